@@ -0,0 +1,226 @@
+//! Vault backups.
+//!
+//! Before every save, the previous store is copied to `<db>.bak` next to the
+//! password database and, if configured, to a timestamped file in a `backups`
+//! directory, of which only the newest few are kept. Backups are encrypted
+//! exactly like the live store, so they can only be checked with a master
+//! password.
+
+use anyhow::Result;
+use chrono::NaiveDateTime;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::lockfile::LOCK_SUFFIX;
+
+/// Name of the backup directory, relative to the database's directory.
+pub const BACKUP_DIR: &str = "backups";
+
+/// Suffix added to the database file name for the copy made before each save.
+pub const PREVIOUS_SUFFIX: &str = ".bak";
+
+/// Outcome of checking a single backup file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BackupStatus {
+    /// The backup decrypted successfully and holds this many credentials.
+    Valid { credentials: usize },
+    /// The backup could not be loaded or decrypted.
+    Invalid { reason: String },
+}
+
+/// Result of verifying one backup file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BackupReport {
+    /// Path to the backup file.
+    pub path: PathBuf,
+    /// Whether the backup could be decrypted.
+    pub status: BackupStatus,
+}
+
+/// Returns the backup directory for a database path.
+pub fn backup_dir_for(db_path: &Path) -> PathBuf {
+    db_path
+        .parent()
+        .unwrap_or_else(|| Path::new("."))
+        .join(BACKUP_DIR)
+}
+
+/// Returns the path of the copy made before the last save (`<db>.bak`).
+pub fn previous_backup_path(db_path: &Path) -> PathBuf {
+    let mut name = db_path.file_name().unwrap_or_default().to_os_string();
+    name.push(PREVIOUS_SUFFIX);
+    db_path.with_file_name(name)
+}
+
+/// Copies the store at `db_path` aside before it is overwritten.
+///
+/// The store is always copied to [`previous_backup_path`]. When `keep` is
+/// non-zero, it is also copied to `<db>.<timestamp>` in the backup directory,
+/// and all but the `keep` newest of those are removed. Nothing is done if
+/// there is no store yet.
+pub fn backup_before_save(db_path: &Path, keep: usize, now: NaiveDateTime) -> Result<()> {
+    if fs::metadata(db_path).map(|m| m.len() == 0).unwrap_or(true) {
+        return Ok(());
+    }
+
+    fs::copy(db_path, previous_backup_path(db_path))?;
+
+    if keep > 0 {
+        let dir = backup_dir_for(db_path);
+        fs::create_dir_all(&dir)?;
+        let name = format!("{}.{}", file_name(db_path), now.format("%Y%m%d_%H%M%S_%3f"));
+        fs::copy(db_path, dir.join(name))?;
+        prune_backups(db_path, keep)?;
+    }
+    Ok(())
+}
+
+/// Removes `<db>.bak` and every timestamped backup of `db_path`.
+pub fn remove_backups(db_path: &Path) -> Result<()> {
+    let previous = previous_backup_path(db_path);
+    if previous.exists() {
+        fs::remove_file(previous)?;
+    }
+    prune_backups(db_path, 0)
+}
+
+/// Removes all but the `keep` newest timestamped backups of `db_path`.
+fn prune_backups(db_path: &Path, keep: usize) -> Result<()> {
+    let prefix = format!("{}.", file_name(db_path));
+    let backups: Vec<PathBuf> = list_backups(&backup_dir_for(db_path))?
+        .into_iter()
+        .filter(|path| {
+            path.file_name()
+                .and_then(|n| n.to_str())
+                .is_some_and(|n| n.starts_with(&prefix))
+        })
+        .collect();
+
+    let excess = backups.len().saturating_sub(keep);
+    for path in &backups[..excess] {
+        fs::remove_file(path)?;
+        log::debug!("Removed old backup: {}", path.display());
+    }
+    Ok(())
+}
+
+/// Returns the database's file name, for naming its backups.
+fn file_name(db_path: &Path) -> String {
+    db_path
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_default()
+}
+
+/// Lists backup files in `dir`, sorted by file name.
+///
+/// A missing directory is treated as having no backups, and lock files are
+/// skipped.
+pub fn list_backups(dir: &Path) -> Result<Vec<PathBuf>> {
+    if !dir.exists() {
+        return Ok(vec![]);
+    }
+
+    let mut files = Vec::new();
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        // A vault opened from the backup directory leaves its lock file there
+        let is_lock = path.to_string_lossy().ends_with(LOCK_SUFFIX);
+        if path.is_file() && !is_lock {
+            files.push(path);
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_backup_dir_for() {
+        let dir = backup_dir_for(Path::new("/home/user/.passmgr/passwords.db"));
+        assert_eq!(dir, PathBuf::from("/home/user/.passmgr/backups"));
+    }
+
+    #[test]
+    fn test_list_backups_missing_dir() {
+        let temp_dir = TempDir::new().unwrap();
+        let backups = list_backups(&temp_dir.path().join("missing")).unwrap();
+        assert!(backups.is_empty());
+    }
+
+    #[test]
+    fn test_list_backups_sorted_files_only() {
+        let temp_dir = TempDir::new().unwrap();
+        fs::write(temp_dir.path().join("b.db"), "b").unwrap();
+        fs::write(temp_dir.path().join("a.db"), "a").unwrap();
+        fs::create_dir(temp_dir.path().join("nested")).unwrap();
+        fs::write(temp_dir.path().join("b.db.lock"), "123").unwrap();
+
+        let backups = list_backups(temp_dir.path()).unwrap();
+        assert_eq!(
+            backups,
+            vec![temp_dir.path().join("a.db"), temp_dir.path().join("b.db")]
+        );
+    }
+
+    fn at(secs: i64) -> NaiveDateTime {
+        chrono::DateTime::from_timestamp(1_700_000_000 + secs, 0)
+            .unwrap()
+            .naive_utc()
+    }
+
+    #[test]
+    fn test_previous_backup_path() {
+        assert_eq!(
+            previous_backup_path(Path::new("/home/user/.passmgr/passwords.db")),
+            PathBuf::from("/home/user/.passmgr/passwords.db.bak")
+        );
+    }
+
+    #[test]
+    fn test_backup_before_save_copies_previous_store() {
+        let temp_dir = TempDir::new().unwrap();
+        let db_path = temp_dir.path().join("passwords.db");
+
+        // Nothing to back up yet
+        backup_before_save(&db_path, 0, at(0)).unwrap();
+        assert!(!previous_backup_path(&db_path).exists());
+
+        fs::write(&db_path, "first").unwrap();
+        backup_before_save(&db_path, 0, at(1)).unwrap();
+        assert_eq!(
+            fs::read_to_string(previous_backup_path(&db_path)).unwrap(),
+            "first"
+        );
+        assert!(!backup_dir_for(&db_path).exists());
+    }
+
+    #[test]
+    fn test_backup_before_save_rotates_timestamped_backups() {
+        let temp_dir = TempDir::new().unwrap();
+        let db_path = temp_dir.path().join("passwords.db");
+        let dir = backup_dir_for(&db_path);
+        fs::create_dir(&dir).unwrap();
+        fs::write(dir.join("manual.db"), "keep me").unwrap();
+
+        for i in 0..4 {
+            fs::write(&db_path, format!("save {}", i)).unwrap();
+            backup_before_save(&db_path, 2, at(i)).unwrap();
+        }
+
+        let backups = list_backups(&dir).unwrap();
+        let contents: Vec<String> = backups
+            .iter()
+            .map(|path| fs::read_to_string(path).unwrap())
+            .collect();
+        assert_eq!(contents, vec!["keep me", "save 2", "save 3"]);
+
+        remove_backups(&db_path).unwrap();
+        assert_eq!(list_backups(&dir).unwrap(), vec![dir.join("manual.db")]);
+        assert!(!previous_backup_path(&db_path).exists());
+    }
+}
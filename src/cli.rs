@@ -0,0 +1,409 @@
+//! Command-line argument parsing for the passmgr binary.
+
+use clap::{Parser, Subcommand};
+use std::io::BufRead;
+
+/// Environment variable holding the master password for non-interactive use.
+pub const MASTER_PASSWORD_ENV: &str = "PASSMGR_MASTER";
+
+/// A secure command-line password manager.
+///
+/// Without a subcommand, passmgr starts the interactive shell.
+#[derive(Debug, Parser)]
+#[command(name = "passmgr", version, about)]
+pub struct Cli {
+    /// Report whether the password database will be upgraded by this version, then exit.
+    #[arg(long)]
+    pub since_version: bool,
+
+    /// Read the master password from the first line of stdin instead of prompting.
+    #[arg(long)]
+    pub password_stdin: bool,
+
+    /// Unlock the vault with its recovery key and set a new master password.
+    #[arg(long)]
+    pub recover: bool,
+
+    /// Disable colored output (also disabled when NO_COLOR is set).
+    #[arg(long)]
+    pub no_color: bool,
+
+    /// Start the shell without the welcome message.
+    #[arg(long)]
+    pub no_welcome: bool,
+
+    /// Open the vault even if another passmgr process appears to have it open.
+    #[arg(long)]
+    pub force: bool,
+
+    /// Print JSON instead of text from commands that support it (get, list,
+    /// search and stats). Secrets are masked unless --reveal is given.
+    #[arg(long, global = true)]
+    pub json: bool,
+
+    /// Print the shell's commands as JSON, for generating documentation such
+    /// as man pages and completion scripts, then exit.
+    #[arg(long)]
+    pub dump_commands: bool,
+
+    /// Use the settings in ~/.passmgr/profiles/NAME.toml instead of config.toml.
+    #[arg(long, value_name = "NAME")]
+    pub profile: Option<String>,
+
+    /// Run a single command and exit instead of starting the shell.
+    #[command(subcommand)]
+    pub action: Option<Action>,
+}
+
+/// One-shot commands, mirroring the shell commands of the same name.
+#[derive(Debug, Clone, PartialEq, Eq, Subcommand)]
+pub enum Action {
+    /// Add a new credential
+    Add {
+        /// Unique identifier for the credential
+        name: String,
+        /// The secret value to store; several words are joined with spaces, as
+        /// in the shell. It can only be left out in the interactive shell,
+        /// which then asks for it
+        secret: Vec<String>,
+    },
+    /// Print a credential's secret, and nothing else
+    Get {
+        /// The name of the credential to retrieve
+        name: String,
+        /// Include the secret in plain text in the JSON output; without --json the
+        /// secret is printed in plain text anyway
+        #[arg(long)]
+        reveal: bool,
+        /// Print a shell-quoted `export VAR=...` line to run with eval
+        #[arg(long, value_name = "VAR", conflicts_with = "json")]
+        export: Option<String>,
+    },
+    /// Delete a credential
+    #[command(visible_alias = "rm")]
+    Remove {
+        /// The name of the credential to delete
+        name: String,
+    },
+    /// Rename a credential, keeping its secret
+    #[command(visible_alias = "mv")]
+    Rename {
+        /// The current name of the credential
+        old: String,
+        /// The new name
+        new: String,
+    },
+    /// Show all stored credential names
+    List {
+        /// Print only the number of credentials
+        #[arg(long)]
+        count_only: bool,
+    },
+    /// Show credential names containing some text, ignoring case
+    #[command(visible_alias = "find")]
+    Search {
+        /// Text to look for in credential names
+        query: String,
+        /// Print only the number of matches
+        #[arg(long)]
+        count_only: bool,
+    },
+    /// Print the number of stored credentials
+    Count,
+    /// Show a summary of the stored credentials
+    Stats,
+}
+
+impl Action {
+    /// Returns the equivalent shell command line, already split into words.
+    pub fn to_args(&self) -> Vec<&str> {
+        match self {
+            Action::Add { name, secret } => {
+                let mut args = vec!["add", name.as_str()];
+                args.extend(secret.iter().map(String::as_str));
+                args
+            }
+            Action::Get {
+                name,
+                reveal,
+                export,
+            } => {
+                let mut args = vec!["get", name.as_str()];
+                if *reveal {
+                    args.push("--reveal");
+                }
+                if let Some(variable) = export {
+                    args.extend(["--export", variable.as_str()]);
+                }
+                args
+            }
+            Action::Remove { name } => vec!["remove", name],
+            Action::Rename { old, new } => vec!["rename", old, new],
+            Action::List { count_only } => with_count_only(vec!["list"], *count_only),
+            Action::Search { query, count_only } => {
+                with_count_only(vec!["search", query], *count_only)
+            }
+            Action::Count => vec!["count"],
+            Action::Stats => vec!["stats"],
+        }
+    }
+}
+
+/// Picks the master password from stdin or the environment, if either supplies one.
+///
+/// `--password-stdin` takes precedence over [`MASTER_PASSWORD_ENV`]. Either is
+/// trimmed of surrounding whitespace, like a prompted password. Returns
+/// `Ok(None)` when the password should be prompted for, and an error when
+/// nothing was supplied and stdin is not a terminal to prompt on.
+pub fn resolve_master_password<R: BufRead>(
+    password_stdin: bool,
+    mut stdin: R,
+    env_password: Option<String>,
+    stdin_is_tty: bool,
+) -> Result<Option<String>, String> {
+    if password_stdin {
+        let mut line = String::new();
+        stdin
+            .read_line(&mut line)
+            .map_err(|e| format!("failed to read master password from stdin: {}", e))?;
+        return Ok(Some(line.trim().to_string()));
+    }
+
+    if let Some(pwd) = env_password {
+        return Ok(Some(pwd.trim().to_string()));
+    }
+
+    if !stdin_is_tty {
+        return Err(format!(
+            "stdin is not a terminal; supply the master password with --password-stdin or {}",
+            MASTER_PASSWORD_ENV
+        ));
+    }
+
+    Ok(None)
+}
+
+/// Appends `--count-only` to a shell command line when requested.
+fn with_count_only(mut args: Vec<&str>, count_only: bool) -> Vec<&str> {
+    if count_only {
+        args.push("--count-only");
+    }
+    args
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_no_args() {
+        let cli = Cli::try_parse_from(["passmgr"]).unwrap();
+        assert!(!cli.since_version);
+        assert!(cli.action.is_none());
+    }
+
+    #[test]
+    fn test_parse_since_version() {
+        let cli = Cli::try_parse_from(["passmgr", "--since-version"]).unwrap();
+        assert!(cli.since_version);
+    }
+
+    #[test]
+    fn test_parse_get() {
+        let cli = Cli::try_parse_from(["passmgr", "get", "github"]).unwrap();
+        let action = cli.action.unwrap();
+        assert_eq!(
+            action,
+            Action::Get {
+                name: "github".to_string(),
+                reveal: false,
+                export: None,
+            }
+        );
+        assert_eq!(action.to_args(), vec!["get", "github"]);
+    }
+
+    #[test]
+    fn test_parse_get_json() {
+        let cli = Cli::try_parse_from(["passmgr", "get", "--json", "--reveal", "github"]).unwrap();
+        assert!(cli.json);
+        assert_eq!(
+            cli.action.unwrap().to_args(),
+            vec!["get", "github", "--reveal"]
+        );
+
+        // --json is global, so it may also come before the command
+        let cli = Cli::try_parse_from(["passmgr", "--json", "get", "--reveal", "github"]).unwrap();
+        assert!(cli.json);
+
+        // clap cannot require a global flag given before the subcommand, so
+        // --reveal is accepted alone; plain `get` output is unmasked anyway
+        let cli = Cli::try_parse_from(["passmgr", "get", "--reveal", "github"]).unwrap();
+        assert!(!cli.json);
+    }
+
+    #[test]
+    fn test_parse_get_export() {
+        let cli = Cli::try_parse_from(["passmgr", "get", "--export", "TOKEN", "github"]).unwrap();
+        assert_eq!(
+            cli.action.unwrap().to_args(),
+            vec!["get", "github", "--export", "TOKEN"]
+        );
+
+        assert!(
+            Cli::try_parse_from(["passmgr", "get", "--export", "TOKEN", "--json", "github"])
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_parse_global_json() {
+        let cli = Cli::try_parse_from(["passmgr", "list", "--json"]).unwrap();
+        assert!(cli.json);
+        assert_eq!(cli.action.unwrap().to_args(), vec!["list"]);
+
+        let cli = Cli::try_parse_from(["passmgr", "--json", "stats"]).unwrap();
+        assert!(cli.json);
+        assert_eq!(cli.action.unwrap().to_args(), vec!["stats"]);
+
+        assert!(!Cli::try_parse_from(["passmgr", "list"]).unwrap().json);
+    }
+
+    #[test]
+    fn test_parse_remove_alias() {
+        let cli = Cli::try_parse_from(["passmgr", "rm", "github"]).unwrap();
+        assert_eq!(cli.action.unwrap().to_args(), vec!["remove", "github"]);
+    }
+
+    #[test]
+    fn test_parse_add_positionally() {
+        let cli = Cli::try_parse_from(["passmgr", "add", "github", "secret"]).unwrap();
+        let action = cli.action.unwrap();
+        assert_eq!(
+            action,
+            Action::Add {
+                name: "github".to_string(),
+                secret: vec!["secret".to_string()],
+            }
+        );
+        assert_eq!(action.to_args(), vec!["add", "github", "secret"]);
+
+        // Extra words are passed on for the shell's `add` to join, as when typed there
+        let cli = Cli::try_parse_from(["passmgr", "add", "email", "two", "words"]).unwrap();
+        assert_eq!(
+            cli.action.unwrap().to_args(),
+            vec!["add", "email", "two", "words"]
+        );
+
+        // Without a secret, the shell's `add` reports that it must be given
+        let cli = Cli::try_parse_from(["passmgr", "add", "github"]).unwrap();
+        assert_eq!(cli.action.unwrap().to_args(), vec!["add", "github"]);
+
+        assert!(Cli::try_parse_from(["passmgr", "add", "--name", "github"]).is_err());
+    }
+
+    #[test]
+    fn test_parse_count() {
+        let cli = Cli::try_parse_from(["passmgr", "count"]).unwrap();
+        assert_eq!(cli.action.unwrap().to_args(), vec!["count"]);
+    }
+
+    #[test]
+    fn test_secret_with_spaces_is_one_argument() {
+        let cli = Cli::try_parse_from(["passmgr", "add", "email", "two words"]).unwrap();
+        assert_eq!(
+            cli.action.unwrap().to_args(),
+            vec!["add", "email", "two words"]
+        );
+    }
+
+    #[test]
+    fn test_parse_search_count_only() {
+        let cli = Cli::try_parse_from(["passmgr", "search", "--count-only", "git"]).unwrap();
+        assert_eq!(
+            cli.action.unwrap().to_args(),
+            vec!["search", "git", "--count-only"]
+        );
+    }
+
+    #[test]
+    fn test_parse_recover() {
+        let cli = Cli::try_parse_from(["passmgr", "--recover"]).unwrap();
+        assert!(cli.recover);
+        assert!(cli.action.is_none());
+    }
+
+    #[test]
+    fn test_parse_no_color() {
+        let cli = Cli::try_parse_from(["passmgr", "--no-color"]).unwrap();
+        assert!(cli.no_color);
+        assert!(cli.action.is_none());
+    }
+
+    #[test]
+    fn test_parse_no_welcome() {
+        let cli = Cli::try_parse_from(["passmgr", "--no-welcome"]).unwrap();
+        assert!(cli.no_welcome);
+        assert!(!Cli::try_parse_from(["passmgr"]).unwrap().no_welcome);
+    }
+
+    #[test]
+    fn test_parse_force() {
+        let cli = Cli::try_parse_from(["passmgr", "--force", "list"]).unwrap();
+        assert!(cli.force);
+        assert!(!Cli::try_parse_from(["passmgr"]).unwrap().force);
+    }
+
+    #[test]
+    fn test_parse_dump_commands() {
+        let cli = Cli::try_parse_from(["passmgr", "--dump-commands"]).unwrap();
+        assert!(cli.dump_commands);
+        assert!(!Cli::try_parse_from(["passmgr"]).unwrap().dump_commands);
+    }
+
+    #[test]
+    fn test_parse_profile() {
+        let cli = Cli::try_parse_from(["passmgr", "--profile", "work", "list"]).unwrap();
+        assert_eq!(cli.profile.as_deref(), Some("work"));
+        assert_eq!(Cli::try_parse_from(["passmgr"]).unwrap().profile, None);
+    }
+
+    #[test]
+    fn test_parse_password_stdin() {
+        let cli = Cli::try_parse_from(["passmgr", "--password-stdin", "get", "github"]).unwrap();
+        assert!(cli.password_stdin);
+    }
+
+    #[test]
+    fn test_resolve_password_from_stdin_first_line() {
+        let input = "hunter2\nlist\n".as_bytes();
+        let pwd = resolve_master_password(true, input, Some("env".to_string()), false).unwrap();
+        assert_eq!(pwd, Some("hunter2".to_string()));
+    }
+
+    #[test]
+    fn test_resolve_password_from_env() {
+        let pwd =
+            resolve_master_password(false, "".as_bytes(), Some("env".to_string()), false).unwrap();
+        assert_eq!(pwd, Some("env".to_string()));
+    }
+
+    #[test]
+    fn test_resolve_password_from_env_is_trimmed() {
+        let pwd = resolve_master_password(false, "".as_bytes(), Some(" env\n".to_string()), false)
+            .unwrap();
+        assert_eq!(pwd, Some("env".to_string()));
+    }
+
+    #[test]
+    fn test_resolve_password_prompts_on_tty() {
+        let pwd = resolve_master_password(false, "".as_bytes(), None, true).unwrap();
+        assert_eq!(pwd, None);
+    }
+
+    #[test]
+    fn test_resolve_password_fails_without_tty() {
+        let err = resolve_master_password(false, "".as_bytes(), None, false).unwrap_err();
+        assert!(err.contains("--password-stdin"));
+    }
+}
@@ -1,34 +1,68 @@
 //! Configuration and path management for passmgr.
 //!
 //! This module handles all file paths used by the application,
-//! including the password database, command history, and log files.
+//! including the password database, command history, and log files,
+//! and loads user settings from `~/.passmgr/config.toml` or, for a named
+//! profile, from `~/.passmgr/profiles/<name>.toml`.
 
 use anyhow::{Result, anyhow};
-use std::path::PathBuf;
+use log::LevelFilter;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use crate::credentials::{CredentialLimits, NameNormalization, SECS_PER_DAY};
+use crate::crypto::Cipher;
+use crate::lockout::{LockoutAction, LockoutPolicy};
+use crate::logging::{self, LogFormat};
+use crate::shell::PROMPT;
+use crate::shell::highlighter::Theme;
+use crate::shell::history::HistoryConfig;
 
 /// The name of the application directory.
 const APP_DIR: &str = ".passmgr";
 
 /// Default history file name.
-const HISTORY_FILE: &str = "history";
+pub const HISTORY_FILE: &str = "history";
 
 /// Default log file name.
 const LOG_FILE: &str = "passmgr.log";
 
+/// Key completion cache file name.
+const KEY_CACHE_FILE: &str = "keys.cache";
+
+/// Config file name.
+const CONFIG_FILE: &str = "config.toml";
+
+/// Directory holding profile config files and profile data directories.
+const PROFILES_DIR: &str = "profiles";
+
 /// Default password database file name.
 const DB_FILE: &str = "passwords.db";
 
+/// Extension of vault files in the application directory.
+const VAULT_EXTENSION: &str = "db";
+
+/// Name of the vault stored in the default database file.
+pub const DEFAULT_VAULT: &str = "default";
+
 /// Maximum number of history entries to keep.
 pub const DEFAULT_HISTORY_SIZE: usize = 1000;
 
+/// Returns the base directory for passmgr data (~/.passmgr), without
+/// creating it.
+pub fn app_dir_path() -> Result<PathBuf> {
+    let home_path =
+        dirs_next::home_dir().ok_or_else(|| anyhow!("Could not determine home directory"))?;
+    Ok(home_path.join(APP_DIR))
+}
+
 /// Returns the base directory for passmgr data (~/.passmgr).
 ///
 /// Creates the directory if it doesn't exist.
 pub fn get_app_dir() -> Result<PathBuf> {
-    let home_path =
-        dirs_next::home_dir().ok_or_else(|| anyhow!("Could not determine home directory"))?;
-
-    let app_dir = home_path.join(APP_DIR);
+    let app_dir = app_dir_path()?;
 
     if !app_dir.exists() {
         std::fs::create_dir_all(&app_dir)?;
@@ -41,6 +75,7 @@ pub fn get_app_dir() -> Result<PathBuf> {
 ///
 /// The database is stored at `~/.passmgr/passwords.db`.
 /// Creates the parent directory and an empty file if they don't exist.
+#[allow(unused)]
 pub fn get_password_db() -> Result<PathBuf> {
     let app_dir = get_app_dir()?;
     let db_path = app_dir.join(DB_FILE);
@@ -52,27 +87,148 @@ pub fn get_password_db() -> Result<PathBuf> {
     Ok(db_path)
 }
 
+/// Finds the named vaults in `dir`.
+///
+/// Every `<name>.db` file is a vault called `<name>`, except the default
+/// database (`passwords.db`), which is called `default`. Results are sorted
+/// by name.
+pub fn discover_vaults(dir: &Path) -> Result<Vec<(String, PathBuf)>> {
+    let mut vaults = Vec::new();
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        if !path.is_file() || path.extension().and_then(|e| e.to_str()) != Some(VAULT_EXTENSION) {
+            continue;
+        }
+
+        let name = if path.file_name().and_then(|n| n.to_str()) == Some(DB_FILE) {
+            DEFAULT_VAULT.to_string()
+        } else {
+            match path.file_stem().and_then(|s| s.to_str()) {
+                Some(stem) => stem.to_string(),
+                None => continue,
+            }
+        };
+        vaults.push((name, path));
+    }
+    vaults.sort();
+    Ok(vaults)
+}
+
 /// Returns the path to the command history file.
 ///
 /// The history is stored at `~/.passmgr/history`.
 /// Creates the parent directory if it doesn't exist.
+#[allow(unused)]
 pub fn get_history_path() -> Result<PathBuf> {
     let app_dir = get_app_dir()?;
-    Ok(app_dir.join(HISTORY_FILE))
+    Ok(HistoryConfig::default_in_dir(&app_dir).path)
 }
 
 /// Returns the path to the log file.
 ///
 /// The log is stored at `~/.passmgr/passmgr.log`.
 /// Creates the parent directory if it doesn't exist.
+#[allow(unused)]
 pub fn get_log_path() -> Result<PathBuf> {
     let app_dir = get_app_dir()?;
     Ok(app_dir.join(LOG_FILE))
 }
 
-/// Application configuration loaded from environment or defaults.
+/// Contents of `config.toml`. Every setting is optional, and unknown keys are
+/// ignored so older builds can read newer files.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct ConfigFile {
+    db_path: Option<PathBuf>,
+    log_path: Option<PathBuf>,
+    log_level: Option<String>,
+    /// `text` or `json`.
+    log_format: Option<String>,
+    /// Log file size in bytes above which it is rotated; 0 disables rotation.
+    log_max_size: Option<u64>,
+    /// Rotated log files to keep.
+    log_max_archives: Option<usize>,
+    /// Seconds of inactivity after which the shell locks; 0 disables it.
+    idle_timeout: Option<u64>,
+    /// Whether the prompt counts down to the idle lock.
+    show_idle_countdown: bool,
+    default_reveal: bool,
+    /// Whether to cache credential names for faster completion at startup.
+    cache_keys: bool,
+    /// Output lines above which the shell uses the pager; 0 disables it.
+    pager_threshold: Option<usize>,
+    /// `none`, `lowercase` or `slugify`.
+    normalize_names: Option<String>,
+    /// Shell prompt template, with `{vault}` and `{count}` placeholders.
+    prompt: Option<String>,
+    /// Whether `remove` asks for confirmation unless given `--force`.
+    confirm_destructive: bool,
+    /// Whether reading a secret records when and how often it was read.
+    track_access: bool,
+    /// Whether `add` warns about weak secrets; on unless set to false.
+    warn_weak_secrets: Option<bool>,
+    /// Timestamped backups to keep in addition to `<db>.bak`.
+    backup_count: usize,
+    /// `chacha20-poly1305` or `aes-256-gcm`.
+    cipher: Option<String>,
+    /// Longest credential name accepted, in bytes.
+    max_key_len: Option<usize>,
+    /// Longest secret accepted, in bytes.
+    max_secret_len: Option<usize>,
+    /// Most credentials a vault can hold.
+    max_credentials: Option<usize>,
+    /// Days after which a secret should be rotated; 0 or absent disables it.
+    rotate_after_days: Option<u64>,
+    history: HistorySection,
+    lockout: LockoutSection,
+    theme: HashMap<String, String>,
+}
+
+/// The `[history]` table of `config.toml`.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct HistorySection {
+    path: Option<PathBuf>,
+    max_entries: Option<usize>,
+    /// Whether a command repeating the previous one is left out.
+    ignore_dups: Option<bool>,
+    /// Whether commands typed with a leading space are left out.
+    ignore_space: Option<bool>,
+}
+
+/// The `[lockout]` table of `config.toml`.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct LockoutSection {
+    /// Failed unlock attempts that trigger the action; 0 or absent disables it.
+    max_attempts: Option<u32>,
+    /// `cooldown` (default) or `wipe`.
+    action: Option<String>,
+    /// Seconds unlocking is refused for with the `cooldown` action.
+    cooldown: Option<u64>,
+}
+
+impl LockoutSection {
+    /// Returns the configured policy, if the lockout is enabled.
+    fn policy(&self) -> Result<Option<LockoutPolicy>, String> {
+        let Some(max_attempts) = self.max_attempts.filter(|&n| n > 0) else {
+            return Ok(None);
+        };
+        let action = match self.action.as_deref().unwrap_or("cooldown").parse()? {
+            LockoutAction::Cooldown(default) => {
+                LockoutAction::Cooldown(self.cooldown.map(Duration::from_secs).unwrap_or(default))
+            }
+            LockoutAction::Wipe => LockoutAction::Wipe,
+        };
+        Ok(Some(LockoutPolicy {
+            max_attempts,
+            action,
+        }))
+    }
+}
+
+/// Application configuration loaded from `config.toml` or defaults.
 #[derive(Debug, Clone)]
-#[allow(unused)]
 pub struct AppConfig {
     /// Path to the password database.
     pub db_path: PathBuf,
@@ -82,19 +238,201 @@ pub struct AppConfig {
     pub log_path: PathBuf,
     /// Maximum number of history entries.
     pub history_size: usize,
+    /// Whether a command repeating the previous one is kept out of history.
+    pub history_ignore_dups: bool,
+    /// Whether commands typed with a leading space are kept out of history.
+    pub history_ignore_space: bool,
+    /// Minimum level written to the log file.
+    pub log_level: LevelFilter,
+    /// Format of the log file.
+    pub log_format: LogFormat,
+    /// Log file size in bytes above which it is rotated (0 = never).
+    pub log_max_size: u64,
+    /// Number of rotated log files to keep.
+    pub log_max_archives: usize,
+    /// Colors for the interactive shell.
+    pub theme: Theme,
+    /// Inactivity after which the shell locks, if any.
+    pub idle_timeout: Option<Duration>,
+    /// Whether the prompt shows the time left before the idle lock.
+    pub show_idle_countdown: bool,
+    /// Whether `get` shows secrets in plain text without `--show`.
+    pub default_reveal: bool,
+    /// Where credential names are cached for completion, if enabled.
+    pub key_cache_path: Option<PathBuf>,
+    /// Output lines above which the shell uses the pager, if any.
+    pub pager_threshold: Option<usize>,
+    /// How credential names are normalized on add and lookup.
+    pub name_normalization: NameNormalization,
+    /// Template the shell prompt is rendered from.
+    pub prompt: String,
+    /// Whether destructive commands ask for confirmation first.
+    pub confirm_destructive: bool,
+    /// Whether reading a secret is recorded, which makes reads save the vault.
+    pub track_access: bool,
+    /// Whether adding a weak secret prints a warning.
+    pub warn_weak_secrets: bool,
+    /// Timestamped backups kept in addition to `<db>.bak`.
+    pub backup_count: usize,
+    /// What happens after repeated failed unlock attempts, if enabled.
+    pub lockout: Option<LockoutPolicy>,
+    /// Cipher the credentials are encrypted with when saved.
+    pub cipher: Cipher,
+    /// Limits on the names and secrets of new credentials.
+    pub credential_limits: CredentialLimits,
+    /// How often secrets should be rotated, unless set per credential.
+    pub rotate_after: Option<Duration>,
+    /// Directory searched for named vaults, and holding the default paths.
+    pub data_dir: PathBuf,
 }
 
 impl AppConfig {
-    /// Loads configuration with default paths.
+    /// Loads configuration from `~/.passmgr/config.toml`, or from the named
+    /// profile's config file.
     ///
-    /// All paths are relative to `~/.passmgr/`.
-    #[allow(unused)]
-    pub fn load() -> Result<Self> {
+    /// Settings missing from the file, or a missing file, fall back to the
+    /// defaults; all default paths are relative to `~/.passmgr/`, or to
+    /// `~/.passmgr/profiles/<name>/` for a profile, which is created if needed.
+    pub fn load(profile: Option<&str>) -> Result<Self> {
+        let app_dir = get_app_dir()?;
+        match profile {
+            Some(name) => {
+                let config = Self::load_profile_from(&app_dir, name)?;
+                std::fs::create_dir_all(&config.data_dir)?;
+                Ok(config)
+            }
+            None => Self::load_from(&app_dir),
+        }
+    }
+
+    /// Loads configuration from `config.toml` in `app_dir`, with default
+    /// paths relative to `app_dir`.
+    pub fn load_from(app_dir: &Path) -> Result<Self> {
+        Self::load_file(&app_dir.join(CONFIG_FILE), app_dir)
+    }
+
+    /// Loads the profile `name` from `profiles/<name>.toml` in `app_dir`, with
+    /// default paths relative to `profiles/<name>/`.
+    ///
+    /// Unlike the top-level config, a profile's file must exist, so a
+    /// mistyped name is an error rather than a new, empty vault.
+    pub fn load_profile_from(app_dir: &Path, name: &str) -> Result<Self> {
+        if name.is_empty() || name.starts_with('.') || name.contains(['/', '\\']) {
+            return Err(anyhow!("Invalid profile name '{}'", name));
+        }
+
+        let profiles_dir = app_dir.join(PROFILES_DIR);
+        let path = profiles_dir.join(format!("{}.toml", name));
+        if !path.exists() {
+            return Err(anyhow!(
+                "Unknown profile '{}': {} does not exist",
+                name,
+                path.display()
+            ));
+        }
+        Self::load_file(&path, &profiles_dir.join(name))
+    }
+
+    /// Loads the config file at `path`, which may be missing, with default
+    /// paths relative to `app_dir`.
+    fn load_file(path: &Path, app_dir: &Path) -> Result<Self> {
+        let file = if path.exists() {
+            let contents = std::fs::read_to_string(path)?;
+            toml::from_str(&contents)
+                .map_err(|e| anyhow!("Invalid config file {}: {}", path.display(), e))?
+        } else {
+            ConfigFile::default()
+        };
+
+        let log_level = match &file.log_level {
+            Some(level) => level.parse().map_err(|_| {
+                anyhow!(
+                    "Invalid config file {}: unknown log level '{}'",
+                    path.display(),
+                    level
+                )
+            })?,
+            None => LevelFilter::Info,
+        };
+
+        let log_format = match &file.log_format {
+            Some(format) => format
+                .parse()
+                .map_err(|e| anyhow!("Invalid config file {}: {}", path.display(), e))?,
+            None => LogFormat::Text,
+        };
+
+        let name_normalization = match &file.normalize_names {
+            Some(mode) => mode
+                .parse()
+                .map_err(|e| anyhow!("Invalid config file {}: {}", path.display(), e))?,
+            None => NameNormalization::None,
+        };
+
+        let cipher = match &file.cipher {
+            Some(name) => name
+                .parse()
+                .map_err(|e| anyhow!("Invalid config file {}: {}", path.display(), e))?,
+            None => Cipher::default(),
+        };
+
+        let lockout = file
+            .lockout
+            .policy()
+            .map_err(|e| anyhow!("Invalid config file {}: {}", path.display(), e))?;
+
+        let theme = Theme::from_roles(&file.theme)
+            .map_err(|e| anyhow!("Invalid config file {}: {}", path.display(), e))?;
+
         Ok(Self {
-            db_path: get_password_db()?,
-            history_path: get_history_path()?,
-            log_path: get_log_path()?,
-            history_size: DEFAULT_HISTORY_SIZE,
+            db_path: file.db_path.unwrap_or_else(|| app_dir.join(DB_FILE)),
+            history_path: file
+                .history
+                .path
+                .unwrap_or_else(|| HistoryConfig::default_in_dir(app_dir).path),
+            log_path: file.log_path.unwrap_or_else(|| app_dir.join(LOG_FILE)),
+            history_size: file.history.max_entries.unwrap_or(DEFAULT_HISTORY_SIZE),
+            history_ignore_dups: file.history.ignore_dups.unwrap_or(true),
+            history_ignore_space: file.history.ignore_space.unwrap_or(true),
+            log_level,
+            log_format,
+            log_max_size: file.log_max_size.unwrap_or(logging::DEFAULT_MAX_SIZE),
+            log_max_archives: file
+                .log_max_archives
+                .unwrap_or(logging::DEFAULT_MAX_ARCHIVES),
+            theme,
+            idle_timeout: file
+                .idle_timeout
+                .filter(|&secs| secs > 0)
+                .map(Duration::from_secs),
+            show_idle_countdown: file.show_idle_countdown,
+            default_reveal: file.default_reveal,
+            key_cache_path: file.cache_keys.then(|| app_dir.join(KEY_CACHE_FILE)),
+            pager_threshold: file.pager_threshold.filter(|&lines| lines > 0),
+            name_normalization,
+            prompt: file.prompt.unwrap_or_else(|| PROMPT.to_string()),
+            confirm_destructive: file.confirm_destructive,
+            track_access: file.track_access,
+            warn_weak_secrets: file.warn_weak_secrets.unwrap_or(true),
+            backup_count: file.backup_count,
+            lockout,
+            cipher,
+            credential_limits: CredentialLimits {
+                max_key_len: file
+                    .max_key_len
+                    .unwrap_or(CredentialLimits::default().max_key_len),
+                max_secret_len: file
+                    .max_secret_len
+                    .unwrap_or(CredentialLimits::default().max_secret_len),
+                max_credentials: file
+                    .max_credentials
+                    .unwrap_or(CredentialLimits::default().max_credentials),
+            },
+            rotate_after: file
+                .rotate_after_days
+                .filter(|&days| days > 0)
+                .map(|days| Duration::from_secs(days.saturating_mul(SECS_PER_DAY))),
+            data_dir: app_dir.to_path_buf(),
         })
     }
 
@@ -106,6 +444,29 @@ impl AppConfig {
             history_path: base_dir.join(HISTORY_FILE),
             log_path: base_dir.join(LOG_FILE),
             history_size: 100,
+            history_ignore_dups: true,
+            history_ignore_space: true,
+            log_level: LevelFilter::Info,
+            log_format: LogFormat::Text,
+            log_max_size: logging::DEFAULT_MAX_SIZE,
+            log_max_archives: logging::DEFAULT_MAX_ARCHIVES,
+            theme: Theme::default(),
+            idle_timeout: None,
+            show_idle_countdown: false,
+            default_reveal: false,
+            key_cache_path: None,
+            pager_threshold: None,
+            name_normalization: NameNormalization::None,
+            prompt: PROMPT.to_string(),
+            confirm_destructive: false,
+            track_access: false,
+            warn_weak_secrets: true,
+            backup_count: 0,
+            lockout: None,
+            cipher: Cipher::default(),
+            credential_limits: CredentialLimits::default(),
+            rotate_after: None,
+            data_dir: base_dir.to_path_buf(),
         }
     }
 }
@@ -137,6 +498,271 @@ mod tests {
         assert_eq!(config.history_size, 100);
     }
 
+    #[test]
+    fn test_load_without_file_uses_defaults() {
+        let temp_dir = TempDir::new().unwrap();
+        let config = AppConfig::load_from(temp_dir.path()).unwrap();
+
+        assert_eq!(config.db_path, temp_dir.path().join(DB_FILE));
+        assert_eq!(config.history_path, temp_dir.path().join(HISTORY_FILE));
+        assert_eq!(config.log_path, temp_dir.path().join(LOG_FILE));
+        assert_eq!(config.history_size, DEFAULT_HISTORY_SIZE);
+        assert!(config.history_ignore_dups);
+        assert!(config.history_ignore_space);
+        assert_eq!(config.log_level, LevelFilter::Info);
+        assert_eq!(config.log_format, LogFormat::Text);
+        assert_eq!(config.log_max_size, logging::DEFAULT_MAX_SIZE);
+        assert_eq!(config.log_max_archives, logging::DEFAULT_MAX_ARCHIVES);
+        assert_eq!(config.theme, Theme::default());
+        assert_eq!(config.idle_timeout, None);
+        assert!(!config.show_idle_countdown);
+        assert!(!config.default_reveal);
+        assert_eq!(config.key_cache_path, None);
+        assert_eq!(config.pager_threshold, None);
+        assert_eq!(config.name_normalization, NameNormalization::None);
+        assert_eq!(config.prompt, "passmgr> ");
+        assert!(!config.confirm_destructive);
+        assert!(!config.track_access);
+        assert!(config.warn_weak_secrets);
+        assert_eq!(config.backup_count, 0);
+        assert_eq!(config.lockout, None);
+        assert_eq!(config.cipher, Cipher::ChaCha20Poly1305);
+        assert_eq!(config.credential_limits, CredentialLimits::default());
+        assert_eq!(config.rotate_after, None);
+    }
+
+    #[test]
+    fn test_load_reads_settings() {
+        let temp_dir = TempDir::new().unwrap();
+        std::fs::write(
+            temp_dir.path().join(CONFIG_FILE),
+            r#"
+db_path = "/tmp/vault.db"
+log_level = "debug"
+log_format = "json"
+log_max_size = 4096
+log_max_archives = 2
+idle_timeout = 300
+show_idle_countdown = true
+default_reveal = true
+cache_keys = true
+pager_threshold = 40
+normalize_names = "slugify"
+prompt = "[{vault}:{count}]> "
+confirm_destructive = true
+track_access = true
+warn_weak_secrets = false
+backup_count = 5
+cipher = "aes-256-gcm"
+max_secret_len = 1024
+max_credentials = 500
+rotate_after_days = 90
+
+[history]
+max_entries = 50
+ignore_space = false
+
+[lockout]
+max_attempts = 5
+cooldown = 60
+
+[theme]
+secret = "none"
+"#,
+        )
+        .unwrap();
+
+        let config = AppConfig::load_from(temp_dir.path()).unwrap();
+        assert_eq!(config.db_path, PathBuf::from("/tmp/vault.db"));
+        assert_eq!(config.history_path, temp_dir.path().join(HISTORY_FILE));
+        assert_eq!(config.history_size, 50);
+        assert!(config.history_ignore_dups);
+        assert!(!config.history_ignore_space);
+        assert_eq!(config.log_level, LevelFilter::Debug);
+        assert_eq!(config.log_format, LogFormat::Json);
+        assert_eq!(config.log_max_size, 4096);
+        assert_eq!(config.log_max_archives, 2);
+        assert_eq!(config.theme.secret, "");
+        assert_eq!(config.idle_timeout, Some(Duration::from_secs(300)));
+        assert!(config.show_idle_countdown);
+        assert!(config.default_reveal);
+        assert_eq!(
+            config.key_cache_path,
+            Some(temp_dir.path().join(KEY_CACHE_FILE))
+        );
+        assert_eq!(config.pager_threshold, Some(40));
+        assert_eq!(config.name_normalization, NameNormalization::Slugify);
+        assert_eq!(config.prompt, "[{vault}:{count}]> ");
+        assert!(config.confirm_destructive);
+        assert!(config.track_access);
+        assert!(!config.warn_weak_secrets);
+        assert_eq!(config.backup_count, 5);
+        assert_eq!(config.cipher, Cipher::Aes256Gcm);
+        assert_eq!(
+            config.credential_limits,
+            CredentialLimits {
+                max_key_len: 256,
+                max_secret_len: 1024,
+                max_credentials: 500,
+            }
+        );
+        assert_eq!(
+            config.rotate_after,
+            Some(Duration::from_secs(90 * 24 * 60 * 60))
+        );
+        assert_eq!(
+            config.lockout,
+            Some(LockoutPolicy {
+                max_attempts: 5,
+                action: LockoutAction::Cooldown(Duration::from_secs(60)),
+            })
+        );
+    }
+
+    #[test]
+    fn test_load_ignores_unknown_fields() {
+        let temp_dir = TempDir::new().unwrap();
+        std::fs::write(
+            temp_dir.path().join(CONFIG_FILE),
+            "future_option = true\n[history]\nmax_entries = 10\nsync = \"cloud\"\n",
+        )
+        .unwrap();
+
+        let config = AppConfig::load_from(temp_dir.path()).unwrap();
+        assert_eq!(config.history_size, 10);
+    }
+
+    #[test]
+    fn test_load_malformed_file_is_an_error() {
+        let temp_dir = TempDir::new().unwrap();
+        std::fs::write(temp_dir.path().join(CONFIG_FILE), "idle_timeout = [").unwrap();
+        let err = AppConfig::load_from(temp_dir.path()).unwrap_err();
+        assert!(err.to_string().contains("Invalid config file"));
+
+        std::fs::write(temp_dir.path().join(CONFIG_FILE), "log_level = \"loud\"").unwrap();
+        let err = AppConfig::load_from(temp_dir.path()).unwrap_err();
+        assert!(err.to_string().contains("unknown log level"));
+
+        std::fs::write(
+            temp_dir.path().join(CONFIG_FILE),
+            "[theme]\nkey = \"sparkly\"",
+        )
+        .unwrap();
+        assert!(AppConfig::load_from(temp_dir.path()).is_err());
+
+        std::fs::write(
+            temp_dir.path().join(CONFIG_FILE),
+            "normalize_names = \"shout\"",
+        )
+        .unwrap();
+        let err = AppConfig::load_from(temp_dir.path()).unwrap_err();
+        assert!(err.to_string().contains("unknown name normalization"));
+
+        std::fs::write(
+            temp_dir.path().join(CONFIG_FILE),
+            "[lockout]\nmax_attempts = 3\naction = \"explode\"",
+        )
+        .unwrap();
+        let err = AppConfig::load_from(temp_dir.path()).unwrap_err();
+        assert!(err.to_string().contains("unknown lockout action"));
+
+        std::fs::write(temp_dir.path().join(CONFIG_FILE), "cipher = \"rot13\"").unwrap();
+        let err = AppConfig::load_from(temp_dir.path()).unwrap_err();
+        assert!(err.to_string().contains("unknown cipher"));
+    }
+
+    #[test]
+    fn test_profiles_resolve_to_distinct_paths() {
+        let temp_dir = TempDir::new().unwrap();
+        let profiles_dir = temp_dir.path().join(PROFILES_DIR);
+        std::fs::create_dir(&profiles_dir).unwrap();
+        std::fs::write(profiles_dir.join("work.toml"), "backup_count = 3\n").unwrap();
+        std::fs::write(
+            profiles_dir.join("personal.toml"),
+            "[history]\nmax_entries = 50\n",
+        )
+        .unwrap();
+
+        let work = AppConfig::load_profile_from(temp_dir.path(), "work").unwrap();
+        let personal = AppConfig::load_profile_from(temp_dir.path(), "personal").unwrap();
+
+        assert_eq!(work.data_dir, profiles_dir.join("work"));
+        assert_eq!(work.db_path, profiles_dir.join("work").join(DB_FILE));
+        assert_eq!(
+            work.history_path,
+            profiles_dir.join("work").join(HISTORY_FILE)
+        );
+        assert_eq!(work.log_path, profiles_dir.join("work").join(LOG_FILE));
+        assert_eq!(work.backup_count, 3);
+
+        assert_eq!(
+            personal.db_path,
+            profiles_dir.join("personal").join(DB_FILE)
+        );
+        assert_eq!(
+            personal.history_path,
+            profiles_dir.join("personal").join(HISTORY_FILE)
+        );
+        assert_eq!(personal.history_size, 50);
+        assert_eq!(personal.backup_count, 0);
+
+        assert_ne!(work.db_path, personal.db_path);
+        assert_ne!(work.history_path, personal.history_path);
+
+        // The top-level config is untouched by profiles
+        let default = AppConfig::load_from(temp_dir.path()).unwrap();
+        assert_eq!(default.db_path, temp_dir.path().join(DB_FILE));
+    }
+
+    #[test]
+    fn test_profile_explicit_paths_are_kept() {
+        let temp_dir = TempDir::new().unwrap();
+        let profiles_dir = temp_dir.path().join(PROFILES_DIR);
+        std::fs::create_dir(&profiles_dir).unwrap();
+        std::fs::write(
+            profiles_dir.join("work.toml"),
+            "db_path = \"/srv/work.db\"\n",
+        )
+        .unwrap();
+
+        let work = AppConfig::load_profile_from(temp_dir.path(), "work").unwrap();
+        assert_eq!(work.db_path, PathBuf::from("/srv/work.db"));
+        assert_eq!(
+            work.history_path,
+            profiles_dir.join("work").join(HISTORY_FILE)
+        );
+    }
+
+    #[test]
+    fn test_unknown_or_invalid_profile_is_an_error() {
+        let temp_dir = TempDir::new().unwrap();
+        let err = AppConfig::load_profile_from(temp_dir.path(), "work").unwrap_err();
+        assert!(err.to_string().contains("Unknown profile 'work'"));
+
+        for name in ["", "../work", ".hidden", "a/b"] {
+            let err = AppConfig::load_profile_from(temp_dir.path(), name).unwrap_err();
+            assert!(err.to_string().contains("Invalid profile name"));
+        }
+    }
+
+    #[test]
+    fn test_discover_vaults() {
+        let temp_dir = TempDir::new().unwrap();
+        std::fs::write(temp_dir.path().join(DB_FILE), "").unwrap();
+        std::fs::write(temp_dir.path().join("work.db"), "").unwrap();
+        std::fs::write(temp_dir.path().join(HISTORY_FILE), "").unwrap();
+        std::fs::write(temp_dir.path().join(LOG_FILE), "").unwrap();
+
+        let vaults = discover_vaults(temp_dir.path()).unwrap();
+        assert_eq!(
+            vaults,
+            vec![
+                (DEFAULT_VAULT.to_string(), temp_dir.path().join(DB_FILE)),
+                ("work".to_string(), temp_dir.path().join("work.db")),
+            ]
+        );
+    }
+
     #[test]
     fn test_default_history_size() {
         assert_eq!(DEFAULT_HISTORY_SIZE, 1000);
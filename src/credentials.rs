@@ -1,18 +1,512 @@
-use std::collections::HashMap;
+use chrono::{DateTime, Utc};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::str::FromStr;
+use std::time::Duration;
 
+use crate::strength::estimate_strength;
+
+/// Summary statistics about a credential set.
+///
+/// Produced by [`Credentials::stats`]; never contains secret values.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct VaultStats {
+    /// Number of stored credentials.
+    pub total: usize,
+    /// Mean secret length in characters.
+    pub average_secret_len: f64,
+    /// Median secret length in characters.
+    pub median_secret_len: f64,
+    /// Number of secrets rated weak by the strength estimator.
+    pub weak_count: usize,
+    /// Number of credentials whose secret is shared with another credential.
+    pub duplicate_count: usize,
+    /// Number of distinct tags given to credentials.
+    pub tag_count: usize,
+    /// When the oldest credential was added, if any credential records it.
+    pub oldest_created_at: Option<DateTime<Utc>>,
+    /// When the newest credential was added, if any credential records it.
+    pub newest_created_at: Option<DateTime<Utc>>,
+    /// When the least recently changed secret was last changed, if any
+    /// credential records it.
+    pub oldest_updated_at: Option<DateTime<Utc>>,
+    /// When a secret was most recently changed, if any credential records it.
+    pub newest_updated_at: Option<DateTime<Utc>>,
+}
+
+/// Character shown in place of each character of a hidden secret.
+const MASK_CHAR: char = '•';
+
+/// Returns a mask with one [`MASK_CHAR`] per character of `secret`.
+///
+/// Characters, not bytes, are counted, so multi-byte secrets are not
+/// over-reported.
+pub fn mask_secret(secret: &str) -> String {
+    std::iter::repeat_n(MASK_CHAR, secret.chars().count()).collect()
+}
+
+/// A read-only view of one credential, for building listings.
+///
+/// Produced by [`Credentials::entries`]. The secret is only available through
+/// [`CredentialView::reveal`]; [`CredentialView::masked_secret`] and the
+/// `Debug` output never contain it.
+#[derive(Clone, PartialEq, Eq)]
+pub struct CredentialView<'a> {
+    /// The credential's name.
+    pub name: &'a str,
+    /// Tags attached to the credential, sorted.
+    pub tags: Vec<String>,
+    /// When the credential was added, if known.
+    pub created_at: Option<DateTime<Utc>>,
+    /// When the secret last changed, if known.
+    pub updated_at: Option<DateTime<Utc>>,
+    /// How often the secret should be rotated, if set for this credential.
+    pub rotate_after: Option<Duration>,
+    secret: &'a str,
+}
+
+impl<'a> CredentialView<'a> {
+    /// Returns the secret masked with [`mask_secret`].
+    pub fn masked_secret(&self) -> String {
+        mask_secret(self.secret)
+    }
+
+    /// Returns the secret in plain text.
+    pub fn reveal(&self) -> &'a str {
+        self.secret
+    }
+}
+
+impl fmt::Debug for CredentialView<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CredentialView")
+            .field("name", &self.name)
+            .field("tags", &self.tags)
+            .field("created_at", &self.created_at)
+            .field("updated_at", &self.updated_at)
+            .field("rotate_after", &self.rotate_after)
+            .field("secret", &self.masked_secret())
+            .finish()
+    }
+}
+
+/// How credential names are normalized before they are stored or looked up.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum NameNormalization {
+    /// Names are used exactly as typed.
+    #[default]
+    None,
+    /// Names are lowercased.
+    Lowercase,
+    /// Names are lowercased, with each run of whitespace replaced by a hyphen.
+    Slugify,
+}
+
+impl NameNormalization {
+    /// Returns `name` normalized, e.g. `My GitHub` becomes `my-github` when
+    /// slugifying.
+    pub fn apply(self, name: &str) -> String {
+        match self {
+            NameNormalization::None => name.to_string(),
+            NameNormalization::Lowercase => name.to_lowercase(),
+            NameNormalization::Slugify => name
+                .split_whitespace()
+                .collect::<Vec<_>>()
+                .join("-")
+                .to_lowercase(),
+        }
+    }
+}
+
+impl FromStr for NameNormalization {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "none" => Ok(NameNormalization::None),
+            "lowercase" => Ok(NameNormalization::Lowercase),
+            "slugify" => Ok(NameNormalization::Slugify),
+            other => Err(format!("unknown name normalization '{}'", other)),
+        }
+    }
+}
+
+/// Default limit on the length of a credential name, in bytes.
+pub const DEFAULT_MAX_KEY_LEN: usize = 256;
+
+/// Default limit on the length of a secret, in bytes.
+pub const DEFAULT_MAX_SECRET_LEN: usize = 4096;
+
+/// Default limit on the number of credentials in a vault.
+pub const DEFAULT_MAX_CREDENTIALS: usize = 100_000;
+
+/// Size limits checked when a credential is added or renamed.
+///
+/// Credentials already in a vault are kept even if they exceed the limits.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CredentialLimits {
+    /// Maximum length of a name, in bytes.
+    pub max_key_len: usize,
+    /// Maximum length of a secret, in bytes.
+    pub max_secret_len: usize,
+    /// Maximum number of credentials, checked when one is added.
+    pub max_credentials: usize,
+}
+
+impl Default for CredentialLimits {
+    fn default() -> Self {
+        Self {
+            max_key_len: DEFAULT_MAX_KEY_LEN,
+            max_secret_len: DEFAULT_MAX_SECRET_LEN,
+            max_credentials: DEFAULT_MAX_CREDENTIALS,
+        }
+    }
+}
+
+/// Why a credential could not be added or renamed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CredentialError {
+    /// A credential with this name already exists.
+    Duplicate(String),
+    /// No credential has this name.
+    NotFound(String),
+    /// The name is empty.
+    EmptyName,
+    /// The name contains control characters.
+    ControlCharacters,
+    /// The name starts or ends with whitespace.
+    SurroundingWhitespace,
+    /// The secret is empty.
+    InvalidSecret,
+    /// The name or secret (`field`) is longer than the configured limit.
+    TooLong {
+        field: &'static str,
+        len: usize,
+        max: usize,
+    },
+    /// The vault already holds the configured maximum of credentials.
+    LimitReached(usize),
+    /// The tag is empty or contains whitespace or control characters.
+    InvalidTag(String),
+}
+
+impl fmt::Display for CredentialError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CredentialError::Duplicate(name) => write!(f, "'{}' already exists.", name),
+            CredentialError::NotFound(name) => write!(f, "'{}' not found", name),
+            CredentialError::EmptyName => write!(f, "Name cannot be empty."),
+            CredentialError::ControlCharacters => {
+                write!(f, "Name cannot contain control characters.")
+            }
+            CredentialError::SurroundingWhitespace => {
+                write!(f, "Name cannot start or end with whitespace.")
+            }
+            CredentialError::InvalidSecret => write!(f, "Secret cannot be empty."),
+            CredentialError::TooLong { field, len, max } => {
+                write!(f, "{} is {} bytes long; the limit is {}.", field, len, max)
+            }
+            CredentialError::LimitReached(max) => {
+                write!(f, "The vault is full; the limit is {} credentials.", max)
+            }
+            CredentialError::InvalidTag(tag) => write!(
+                f,
+                "'{}' is not a valid tag; tags cannot be empty or contain whitespace.",
+                tag
+            ),
+        }
+    }
+}
+
+impl std::error::Error for CredentialError {}
+
+/// Returns whether `text` matches the glob `pattern`, where `*` matches any
+/// run of characters and `?` any single character.
+fn glob_match(pattern: &[char], text: &[char]) -> bool {
+    // Backtrack to the most recent `*`, letting it match one more character
+    let (mut p, mut t) = (0, 0);
+    let mut star: Option<(usize, usize)> = None;
+    while t < text.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                star = Some((p, t));
+                p += 1;
+            }
+            Some(&c) if c == '?' || c == text[t] => {
+                p += 1;
+                t += 1;
+            }
+            _ => match star {
+                Some((star_p, star_t)) => {
+                    p = star_p + 1;
+                    t = star_t + 1;
+                    star = Some((star_p, star_t + 1));
+                }
+                None => return false,
+            },
+        }
+    }
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+/// Seconds in a day, for rotation intervals given in days.
+pub const SECS_PER_DAY: u64 = 24 * 60 * 60;
+
+/// What is recorded about a credential besides its secret.
+///
+/// Credentials added before metadata was recorded have no timestamps.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CredentialMeta {
+    /// When the credential was added.
+    pub created_at: Option<DateTime<Utc>>,
+    /// When the secret last changed, or was marked as rotated.
+    pub updated_at: Option<DateTime<Utc>>,
+    /// How often the secret should be rotated, overriding the default.
+    pub rotate_after: Option<Duration>,
+    /// How many times the secret was read, when access tracking is on.
+    #[serde(default)]
+    pub access_count: u64,
+    /// When the secret was last read, when access tracking is on.
+    pub last_accessed: Option<DateTime<Utc>>,
+    /// Tags attached to the credential, sorted and without duplicates.
+    #[serde(default)]
+    pub tags: Vec<String>,
+}
+
+/// Checks that `tag` is non-empty and is a single word.
+fn check_tag(tag: &str) -> Result<(), CredentialError> {
+    if tag.is_empty() || tag.chars().any(|c| c.is_whitespace() || c.is_control()) {
+        return Err(CredentialError::InvalidTag(tag.to_string()));
+    }
+    Ok(())
+}
+
+/// Inserts `tag` into the sorted `tags`, returning whether it was missing.
+fn insert_tag(tags: &mut Vec<String>, tag: &str) -> bool {
+    match tags.binary_search_by(|t| t.as_str().cmp(tag)) {
+        Ok(_) => false,
+        Err(index) => {
+            tags.insert(index, tag.to_string());
+            true
+        }
+    }
+}
+
+/// Removes `tag` from the sorted `tags`, returning whether it was there.
+fn remove_tag(tags: &mut Vec<String>, tag: &str) -> bool {
+    match tags.binary_search_by(|t| t.as_str().cmp(tag)) {
+        Ok(index) => {
+            tags.remove(index);
+            true
+        }
+        Err(_) => false,
+    }
+}
+
+/// A removed credential kept in the trash until restored or purged.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TrashedCredential {
+    /// The credential's secret.
+    pub secret: String,
+    /// What was recorded about it before it was removed.
+    pub meta: CredentialMeta,
+    /// When it was moved to the trash.
+    pub deleted_at: DateTime<Utc>,
+}
+
+/// How [`Credentials::merge`] resolves a name stored in both sets with
+/// different secrets.
+#[allow(unused)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MergePolicy {
+    /// Keep the existing credential.
+    KeepExisting,
+    /// Replace the existing credential with the incoming one.
+    Overwrite,
+    /// Keep whichever secret was updated more recently. When either has no
+    /// update time, or both were updated at the same time, the existing
+    /// credential is kept and the name reported as conflicted.
+    KeepNewer,
+}
+
+/// What [`Credentials::merge`] did with each incoming credential.
+///
+/// Every incoming name is in exactly one list, and each list is sorted.
+#[allow(unused)]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MergeReport {
+    /// Names that were not stored yet.
+    pub added: Vec<String>,
+    /// Names whose existing credential was replaced.
+    pub overwritten: Vec<String>,
+    /// Names already stored with the same secret, or kept by the policy.
+    pub skipped: Vec<String>,
+    /// Names the policy could not decide on; the existing credential was kept.
+    pub conflicted: Vec<String>,
+}
+
+/// A credential changed differently by both sides of a
+/// [`Credentials::merge3`].
+///
+/// The `Debug` output masks both secrets.
+#[allow(unused)]
+#[derive(Clone, PartialEq, Eq)]
+pub struct Conflict {
+    /// The credential's name.
+    pub name: String,
+    /// The local secret, or `None` if it was deleted locally.
+    pub local: Option<String>,
+    /// The remote secret, or `None` if it was deleted remotely.
+    pub remote: Option<String>,
+}
+
+impl fmt::Debug for Conflict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Conflict")
+            .field("name", &self.name)
+            .field("local", &self.local.as_deref().map(mask_secret))
+            .field("remote", &self.remote.as_deref().map(mask_secret))
+            .finish()
+    }
+}
+
+/// The credentials as serialized inside the encrypted store.
+#[derive(Serialize)]
+struct StoredCredentials<'a> {
+    secrets: &'a HashMap<String, String>,
+    metadata: &'a HashMap<String, CredentialMeta>,
+    trash: &'a HashMap<String, TrashedCredential>,
+}
+
+/// Credentials as found inside an encrypted store, for deserializing.
+///
+/// The formats cannot be confused: a bare map's values are always strings.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum LoadedCredentials {
+    /// Written by [`Credentials::to_json`].
+    WithMetadata {
+        secrets: HashMap<String, String>,
+        #[serde(default)]
+        metadata: HashMap<String, CredentialMeta>,
+        /// Absent before store version 7.
+        #[serde(default)]
+        trash: HashMap<String, TrashedCredential>,
+    },
+    /// A bare map of names to secrets, as written before store version 6.
+    SecretsOnly(HashMap<String, String>),
+}
+
+/// Named secrets with their metadata and trash.
+///
+/// The `Debug` output shows the names but never the secrets, so it is safe
+/// to log.
+#[derive(Clone)]
 pub struct Credentials {
     data: HashMap<String, String>,
+    metadata: HashMap<String, CredentialMeta>,
+    trash: HashMap<String, TrashedCredential>,
+    limits: CredentialLimits,
+    default_rotate_after: Option<Duration>,
 }
 
 impl Credentials {
     pub fn new() -> Self {
+        Self::from_map(HashMap::new())
+    }
+
+    /// Creates credentials from bare secrets, with no metadata.
+    pub fn from_map(data: HashMap<String, String>) -> Self {
         Self {
-            data: HashMap::new(),
+            data,
+            metadata: HashMap::new(),
+            trash: HashMap::new(),
+            limits: CredentialLimits::default(),
+            default_rotate_after: None,
         }
     }
 
-    pub fn from_map(data: HashMap<String, String>) -> Self {
-        Self { data }
+    /// Serializes the secrets, their metadata and the trash, as stored in
+    /// the vault.
+    pub fn to_json(&self) -> serde_json::Result<Vec<u8>> {
+        serde_json::to_vec(&StoredCredentials {
+            secrets: &self.data,
+            metadata: &self.metadata,
+            trash: &self.trash,
+        })
+    }
+
+    /// Deserializes credentials written by [`Credentials::to_json`], or the
+    /// bare map of names to secrets stored by older versions.
+    ///
+    /// Metadata for names without a secret is dropped.
+    pub fn from_json(json: &[u8]) -> serde_json::Result<Self> {
+        match serde_json::from_slice(json)? {
+            LoadedCredentials::WithMetadata {
+                secrets,
+                mut metadata,
+                trash,
+            } => {
+                metadata.retain(|name, _| secrets.contains_key(name));
+                Ok(Self {
+                    metadata,
+                    trash,
+                    ..Self::from_map(secrets)
+                })
+            }
+            LoadedCredentials::SecretsOnly(secrets) => Ok(Self::from_map(secrets)),
+        }
+    }
+
+    /// Returns the limits checked by [`Credentials::add`] and
+    /// [`Credentials::rename`].
+    pub fn limits(&self) -> CredentialLimits {
+        self.limits
+    }
+
+    /// Sets the limits checked by [`Credentials::add`] and
+    /// [`Credentials::rename`].
+    pub fn set_limits(&mut self, limits: CredentialLimits) {
+        self.limits = limits;
+    }
+
+    /// Checks that `name` is within the length limit and wouldn't confuse
+    /// the command parser.
+    fn check_name(&self, name: &str) -> Result<(), CredentialError> {
+        if name.is_empty() {
+            return Err(CredentialError::EmptyName);
+        }
+        if name.chars().any(char::is_control) {
+            return Err(CredentialError::ControlCharacters);
+        }
+        if name.trim() != name {
+            return Err(CredentialError::SurroundingWhitespace);
+        }
+        if name.len() > self.limits.max_key_len {
+            return Err(CredentialError::TooLong {
+                field: "Name",
+                len: name.len(),
+                max: self.limits.max_key_len,
+            });
+        }
+        Ok(())
+    }
+
+    /// Checks that `secret` is not empty and within the length limit.
+    fn check_secret(&self, secret: &str) -> Result<(), CredentialError> {
+        if secret.is_empty() {
+            return Err(CredentialError::InvalidSecret);
+        }
+        if secret.len() > self.limits.max_secret_len {
+            return Err(CredentialError::TooLong {
+                field: "Secret",
+                len: secret.len(),
+                max: self.limits.max_secret_len,
+            });
+        }
+        Ok(())
     }
 
     pub fn to_map(&self) -> &HashMap<String, String> {
@@ -24,26 +518,592 @@ impl Credentials {
         &mut self.data
     }
 
-    pub fn add(&mut self, name: String, secret: String) -> Result<(), String> {
+    /// Adds a credential.
+    ///
+    /// Fails if `name` already exists or is not a valid name, the secret is
+    /// empty, or either breaks the configured [`CredentialLimits`].
+    pub fn add(&mut self, name: String, secret: String) -> Result<(), CredentialError> {
+        self.check_name(&name)?;
+        self.check_secret(&secret)?;
         if self.data.contains_key(&name) {
-            return Err(format!("'{}' already exists.", name));
+            return Err(CredentialError::Duplicate(name));
         }
+        if self.data.len() >= self.limits.max_credentials {
+            return Err(CredentialError::LimitReached(self.limits.max_credentials));
+        }
+        let now = Utc::now();
+        self.metadata.insert(
+            name.clone(),
+            CredentialMeta {
+                created_at: Some(now),
+                updated_at: Some(now),
+                ..CredentialMeta::default()
+            },
+        );
         self.data.insert(name, secret);
         Ok(())
     }
 
+    /// Replaces the secret stored under `name`, recording it as updated.
+    ///
+    /// Fails if there is no such credential, or the secret is empty or
+    /// longer than the configured [`CredentialLimits`].
+    #[allow(unused)]
+    pub fn update(&mut self, name: &str, secret: String) -> Result<(), CredentialError> {
+        self.check_secret(&secret)?;
+        let Some(stored) = self.data.get_mut(name) else {
+            return Err(CredentialError::NotFound(name.to_string()));
+        };
+        stored.clear();
+        *stored = secret;
+        self.metadata
+            .entry(name.to_string())
+            .or_default()
+            .updated_at = Some(Utc::now());
+        Ok(())
+    }
+
     pub fn get(&self, name: &str) -> Option<&String> {
         self.data.get(name)
     }
 
+    /// Returns whether a credential is stored under `name`.
+    pub fn contains_key(&self, name: &str) -> bool {
+        self.data.contains_key(name)
+    }
+
+    /// Returns the secret stored under `name`, first adding the result of
+    /// `f` if there is none.
+    ///
+    /// `f` is only called when `name` is missing, so an existing entry is
+    /// left untouched. The new credential is added as by
+    /// [`Credentials::add`], with the same checks and metadata, and nothing
+    /// is inserted if those fail.
+    #[allow(unused)]
+    pub fn get_or_insert_with(
+        &mut self,
+        name: &str,
+        f: impl FnOnce() -> String,
+    ) -> Result<&String, CredentialError> {
+        if !self.data.contains_key(name) {
+            self.add(name.to_string(), f())?;
+        }
+        Ok(&self.data[name])
+    }
+
     pub fn remove(&mut self, name: &str) -> bool {
+        self.metadata.remove(name);
         self.data.remove(name).is_some()
     }
 
+    /// Moves the credential `name` to the trash, where it stays until
+    /// restored or purged. An older trashed credential of the same name is
+    /// replaced.
+    ///
+    /// Returns whether there was a credential to move.
+    pub fn move_to_trash(&mut self, name: &str, now: DateTime<Utc>) -> bool {
+        let Some(secret) = self.data.remove(name) else {
+            return false;
+        };
+        let meta = self.metadata.remove(name).unwrap_or_default();
+        self.trash.insert(
+            name.to_string(),
+            TrashedCredential {
+                secret,
+                meta,
+                deleted_at: now,
+            },
+        );
+        true
+    }
+
+    /// Moves the credential `name` back from the trash, with its metadata.
+    ///
+    /// Fails if it is not in the trash, or a credential of the same name has
+    /// been added since it was removed.
+    pub fn restore_from_trash(&mut self, name: &str) -> Result<(), CredentialError> {
+        if self.data.contains_key(name) {
+            return Err(CredentialError::Duplicate(name.to_string()));
+        }
+        let Some(trashed) = self.trash.remove(name) else {
+            return Err(CredentialError::NotFound(name.to_string()));
+        };
+        self.metadata.insert(name.to_string(), trashed.meta);
+        self.data.insert(name.to_string(), trashed.secret);
+        Ok(())
+    }
+
+    /// Returns the credentials in the trash, sorted by name.
+    pub fn trashed(&self) -> Vec<(&String, &TrashedCredential)> {
+        let mut trashed: Vec<_> = self.trash.iter().collect();
+        trashed.sort_by(|a, b| a.0.cmp(b.0));
+        trashed
+    }
+
+    /// Deletes everything in the trash for good, returning how many
+    /// credentials were deleted.
+    pub fn purge_trash(&mut self) -> usize {
+        let purged = self.trash.len();
+        self.trash.clear();
+        purged
+    }
+
+    /// Returns what is recorded about `name` besides its secret.
+    pub fn metadata(&self, name: &str) -> Option<&CredentialMeta> {
+        self.metadata.get(name)
+    }
+
+    /// Tags the credential `name` with `tag`.
+    ///
+    /// Returns `Ok(false)` if it already had the tag. Fails if there is no
+    /// such credential or `tag` is not a valid tag.
+    pub fn add_tag(&mut self, name: &str, tag: &str) -> Result<bool, CredentialError> {
+        check_tag(tag)?;
+        if !self.data.contains_key(name) {
+            return Err(CredentialError::NotFound(name.to_string()));
+        }
+        let meta = self.metadata.entry(name.to_string()).or_default();
+        Ok(insert_tag(&mut meta.tags, tag))
+    }
+
+    /// Removes `tag` from the credential `name`.
+    ///
+    /// Returns `Ok(false)` if it did not have the tag. Fails if there is no
+    /// such credential.
+    pub fn remove_tag(&mut self, name: &str, tag: &str) -> Result<bool, CredentialError> {
+        if !self.data.contains_key(name) {
+            return Err(CredentialError::NotFound(name.to_string()));
+        }
+        Ok(self
+            .metadata
+            .get_mut(name)
+            .is_some_and(|meta| remove_tag(&mut meta.tags, tag)))
+    }
+
+    /// Renames the tag `old` to `new` on every credential that has it,
+    /// returning how many credentials were changed.
+    ///
+    /// A credential that already has `new` keeps a single copy of it.
+    /// Credentials in the trash are renamed too, so restoring one doesn't
+    /// bring the old tag back, but they are not counted. Renaming a tag to
+    /// itself changes nothing. Fails without changing anything if `new` is
+    /// not a valid tag.
+    pub fn rename_tag(&mut self, old: &str, new: &str) -> Result<usize, CredentialError> {
+        check_tag(new)?;
+        if old == new {
+            return Ok(0);
+        }
+        let mut renamed = 0;
+        for meta in self.metadata.values_mut() {
+            if remove_tag(&mut meta.tags, old) {
+                insert_tag(&mut meta.tags, new);
+                renamed += 1;
+            }
+        }
+        for trashed in self.trash.values_mut() {
+            if remove_tag(&mut trashed.meta.tags, old) {
+                insert_tag(&mut trashed.meta.tags, new);
+            }
+        }
+        Ok(renamed)
+    }
+
+    /// Returns every tag in use with the number of credentials that have
+    /// it, sorted by tag.
+    pub fn tag_counts(&self) -> Vec<(&String, usize)> {
+        let mut counts: BTreeMap<&String, usize> = BTreeMap::new();
+        for meta in self.metadata.values() {
+            for tag in &meta.tags {
+                *counts.entry(tag).or_default() += 1;
+            }
+        }
+        counts.into_iter().collect()
+    }
+
+    /// Records that the secret stored under `name` was read at `now`.
+    ///
+    /// Returns `false` if there is no such credential.
+    pub fn record_access(&mut self, name: &str, now: DateTime<Utc>) -> bool {
+        if !self.data.contains_key(name) {
+            return false;
+        }
+        let meta = self.metadata.entry(name.to_string()).or_default();
+        meta.access_count = meta.access_count.saturating_add(1);
+        meta.last_accessed = Some(now);
+        true
+    }
+
+    /// Returns the credentials that were ever read, most recently read first.
+    pub fn recently_accessed(&self) -> Vec<(&String, DateTime<Utc>)> {
+        let mut accessed: Vec<_> = self
+            .metadata
+            .iter()
+            .filter_map(|(name, meta)| Some((name, meta.last_accessed?)))
+            .collect();
+        accessed.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+        accessed
+    }
+
+    /// Returns the credentials that were ever read, most often read first.
+    ///
+    /// Ties are sorted by name.
+    pub fn most_accessed(&self) -> Vec<(&String, u64)> {
+        let mut accessed: Vec<_> = self
+            .metadata
+            .iter()
+            .filter(|(_, meta)| meta.access_count > 0)
+            .map(|(name, meta)| (name, meta.access_count))
+            .collect();
+        accessed.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+        accessed
+    }
+
+    /// Records that the secret stored under `name` was rotated at `now`,
+    /// e.g. after changing it with the service it belongs to.
+    ///
+    /// Returns `false` if there is no such credential.
+    pub fn touch(&mut self, name: &str, now: DateTime<Utc>) -> bool {
+        if !self.data.contains_key(name) {
+            return false;
+        }
+        self.metadata
+            .entry(name.to_string())
+            .or_default()
+            .updated_at = Some(now);
+        true
+    }
+
+    /// Sets how often the secret stored under `name` should be rotated;
+    /// `None` falls back to the default.
+    ///
+    /// Returns `false` if there is no such credential.
+    pub fn set_rotate_after(&mut self, name: &str, interval: Option<Duration>) -> bool {
+        if !self.data.contains_key(name) {
+            return false;
+        }
+        self.metadata
+            .entry(name.to_string())
+            .or_default()
+            .rotate_after = interval;
+        true
+    }
+
+    /// Returns the rotation interval used for credentials without their own.
+    pub fn default_rotate_after(&self) -> Option<Duration> {
+        self.default_rotate_after
+    }
+
+    /// Sets the rotation interval used for credentials without their own.
+    pub fn set_default_rotate_after(&mut self, interval: Option<Duration>) {
+        self.default_rotate_after = interval;
+    }
+
+    /// Returns the names, sorted, whose secret was last updated longer ago
+    /// than their rotation interval, as of `now`.
+    ///
+    /// Credentials with no rotation interval, or no recorded update time,
+    /// are never expired.
+    pub fn expired(&self, now: DateTime<Utc>) -> Vec<&String> {
+        let mut names: Vec<&String> = self
+            .data
+            .keys()
+            .filter(|name| {
+                let meta = self.metadata.get(*name);
+                let interval = meta
+                    .and_then(|meta| meta.rotate_after)
+                    .or(self.default_rotate_after);
+                let updated_at = meta.and_then(|meta| meta.updated_at);
+                match (interval, updated_at) {
+                    (Some(interval), Some(updated_at)) => now
+                        .signed_duration_since(updated_at)
+                        .to_std()
+                        .is_ok_and(|age| age > interval),
+                    _ => false,
+                }
+            })
+            .collect();
+        names.sort();
+        names
+    }
+
+    /// Moves the secret stored under `old` to `new`.
+    ///
+    /// Fails without changing anything if `new` is not a valid name or
+    /// already exists, or if `old` does not exist.
+    pub fn rename(&mut self, old: &str, new: &str) -> Result<(), CredentialError> {
+        self.check_name(new)?;
+        if !self.data.contains_key(old) {
+            return Err(CredentialError::NotFound(old.to_string()));
+        }
+        if self.data.contains_key(new) {
+            return Err(CredentialError::Duplicate(new.to_string()));
+        }
+        if let Some(secret) = self.data.remove(old) {
+            self.data.insert(new.to_string(), secret);
+        }
+        if let Some(meta) = self.metadata.remove(old) {
+            self.metadata.insert(new.to_string(), meta);
+        }
+        Ok(())
+    }
+
+    /// Merges the credentials stored in `other` into these, resolving names
+    /// stored in both with different secrets according to `policy`.
+    ///
+    /// Incoming credentials keep their metadata. They are taken as they are,
+    /// like those loaded from a vault, so the limits are not checked. Only
+    /// stored credentials are merged; `other`'s trash is discarded.
+    #[allow(unused)]
+    pub fn merge(&mut self, mut other: Credentials, policy: MergePolicy) -> MergeReport {
+        let mut report = MergeReport::default();
+        let incoming = std::mem::take(&mut other.data);
+
+        for (name, mut secret) in incoming {
+            let meta = other.metadata.remove(&name).unwrap_or_default();
+            let Some(existing) = self.data.get(&name) else {
+                self.metadata.insert(name.clone(), meta);
+                self.data.insert(name.clone(), secret);
+                report.added.push(name);
+                continue;
+            };
+            if *existing == secret {
+                secret.clear();
+                report.skipped.push(name);
+                continue;
+            }
+
+            let existing_updated = self.metadata.get(&name).and_then(|m| m.updated_at);
+            let replace = match policy {
+                MergePolicy::KeepExisting => Some(false),
+                MergePolicy::Overwrite => Some(true),
+                MergePolicy::KeepNewer => match (existing_updated, meta.updated_at) {
+                    (Some(existing), Some(incoming)) if incoming != existing => {
+                        Some(incoming > existing)
+                    }
+                    _ => None,
+                },
+            };
+            match replace {
+                Some(true) => {
+                    self.metadata.insert(name.clone(), meta);
+                    if let Some(mut old) = self.data.insert(name.clone(), secret) {
+                        old.clear();
+                    }
+                    report.overwritten.push(name);
+                }
+                Some(false) => {
+                    secret.clear();
+                    report.skipped.push(name);
+                }
+                None => {
+                    secret.clear();
+                    report.conflicted.push(name);
+                }
+            }
+        }
+        other.wipe();
+
+        report.added.sort();
+        report.overwritten.sort();
+        report.skipped.sort();
+        report.conflicted.sort();
+        report
+    }
+
+    /// Merges the changes `local` and `remote` each made to their common
+    /// ancestor `base`.
+    ///
+    /// A credential changed on one side only takes that side's secret, or
+    /// is removed if that side deleted it; one changed the same way on both
+    /// sides is kept once. Changes are detected by comparing secrets, and
+    /// metadata comes with the secret that is kept.
+    ///
+    /// A credential added, edited or deleted differently on both sides is a
+    /// [`Conflict`]. Until it is resolved, the merged set keeps the local
+    /// secret, or the remote one if it was deleted locally, so no secret is
+    /// lost. The trash of both sides is kept, with the later deletion
+    /// winning, and the limits come from `local`.
+    #[allow(unused)]
+    pub fn merge3(
+        base: &Credentials,
+        local: &Credentials,
+        remote: &Credentials,
+    ) -> (Credentials, Vec<Conflict>) {
+        let mut merged = Credentials {
+            trash: local.trash.clone(),
+            limits: local.limits,
+            default_rotate_after: local.default_rotate_after,
+            ..Credentials::new()
+        };
+        for (name, trashed) in &remote.trash {
+            if merged
+                .trash
+                .get(name)
+                .is_none_or(|kept| trashed.deleted_at > kept.deleted_at)
+            {
+                merged.trash.insert(name.clone(), trashed.clone());
+            }
+        }
+
+        let mut names: Vec<&String> = base
+            .data
+            .keys()
+            .chain(local.data.keys())
+            .chain(remote.data.keys())
+            .collect();
+        names.sort();
+        names.dedup();
+
+        let mut conflicts = Vec::new();
+        for name in names {
+            let (ours, theirs) = (local.get(name), remote.get(name));
+            let ancestor = base.get(name);
+            let keep_local = if ours == theirs || theirs == ancestor {
+                true
+            } else if ours == ancestor {
+                false
+            } else {
+                conflicts.push(Conflict {
+                    name: name.clone(),
+                    local: ours.cloned(),
+                    remote: theirs.cloned(),
+                });
+                ours.is_some()
+            };
+
+            let side = if keep_local { local } else { remote };
+            if let Some(secret) = side.data.get(name) {
+                merged.data.insert(name.clone(), secret.clone());
+                if let Some(meta) = side.metadata.get(name) {
+                    merged.metadata.insert(name.clone(), meta.clone());
+                }
+            }
+        }
+        (merged, conflicts)
+    }
+
     pub fn list(&self) -> Vec<&String> {
         self.data.keys().collect()
     }
 
+    /// Iterates over `(name, secret)` pairs in arbitrary order.
+    #[allow(unused)]
+    pub fn iter(&self) -> impl Iterator<Item = (&String, &String)> {
+        self.data.iter()
+    }
+
+    /// Returns `(name, secret)` pairs sorted by name.
+    ///
+    /// ```
+    /// use passmgr::Credentials;
+    ///
+    /// let mut credentials = Credentials::new();
+    /// credentials.add("github".to_string(), "s3cret".to_string()).unwrap();
+    /// credentials.add("email".to_string(), "hunter2".to_string()).unwrap();
+    ///
+    /// let names: Vec<&str> = credentials
+    ///     .iter_sorted()
+    ///     .into_iter()
+    ///     .map(|(name, _)| name.as_str())
+    ///     .collect();
+    /// assert_eq!(names, ["email", "github"]);
+    /// ```
+    #[allow(unused)]
+    pub fn iter_sorted(&self) -> Vec<(&String, &String)> {
+        let mut pairs: Vec<(&String, &String)> = self.data.iter().collect();
+        pairs.sort_by(|a, b| a.0.cmp(b.0));
+        pairs
+    }
+
+    /// Returns a view of every credential, sorted by name.
+    ///
+    /// ```
+    /// use passmgr::Credentials;
+    ///
+    /// let mut credentials = Credentials::new();
+    /// credentials.add("github".to_string(), "s3cret".to_string()).unwrap();
+    ///
+    /// let entries = credentials.entries();
+    /// assert_eq!(entries[0].name, "github");
+    /// assert_eq!(entries[0].masked_secret(), "••••••");
+    /// ```
+    #[allow(unused)]
+    pub fn entries(&self) -> Vec<CredentialView<'_>> {
+        self.iter_sorted()
+            .into_iter()
+            .map(|(name, secret)| {
+                let meta = self.metadata.get(name).cloned().unwrap_or_default();
+                CredentialView {
+                    name,
+                    tags: meta.tags,
+                    created_at: meta.created_at,
+                    updated_at: meta.updated_at,
+                    rotate_after: meta.rotate_after,
+                    secret,
+                }
+            })
+            .collect()
+    }
+
+    /// Returns the names containing `query`, ignoring case, sorted.
+    pub fn search(&self, query: &str) -> Vec<&String> {
+        let query = query.to_lowercase();
+        let mut names: Vec<&String> = self
+            .data
+            .keys()
+            .filter(|name| name.to_lowercase().contains(&query))
+            .collect();
+        names.sort();
+        names
+    }
+
+    /// Returns the names matching the regular expression `pattern`, sorted.
+    ///
+    /// The pattern is matched anywhere in a name unless anchored with `^` or
+    /// `$`, and is case sensitive unless it starts with `(?i)`. Fails if the
+    /// pattern is not a valid regular expression.
+    pub fn search_regex(&self, pattern: &str) -> Result<Vec<&String>, regex::Error> {
+        let regex = Regex::new(pattern)?;
+        let mut names: Vec<&String> = self
+            .data
+            .keys()
+            .filter(|name| regex.is_match(name))
+            .collect();
+        names.sort();
+        Ok(names)
+    }
+
+    /// Returns the names matching `pattern`, ignoring case, sorted.
+    ///
+    /// A pattern containing `*` (any run of characters) or `?` (any one
+    /// character) must match the whole name; any other pattern matches names
+    /// containing it, as with [`Credentials::search`]. An empty pattern
+    /// matches nothing.
+    pub fn matching(&self, pattern: &str) -> Vec<&String> {
+        if pattern.is_empty() {
+            return Vec::new();
+        }
+        if !pattern.contains(['*', '?']) {
+            return self.search(pattern);
+        }
+        let pattern: Vec<char> = pattern.to_lowercase().chars().collect();
+        let mut names: Vec<&String> = self
+            .data
+            .keys()
+            .filter(|name| {
+                let name: Vec<char> = name.to_lowercase().chars().collect();
+                glob_match(&pattern, &name)
+            })
+            .collect();
+        names.sort();
+        names
+    }
+
+    /// Returns the number of stored credentials.
+    pub fn len(&self) -> usize {
+        self.data.len()
+    }
+
     pub fn is_empty(&self) -> bool {
         self.data.is_empty()
     }
@@ -51,5 +1111,114 @@ impl Credentials {
     #[allow(unused)]
     pub fn clear(&mut self) {
         self.data.clear();
+        self.metadata.clear();
+        self.trash.clear();
+    }
+
+    /// Overwrites every secret, including those in the trash, then removes
+    /// all credentials.
+    pub fn wipe(&mut self) {
+        for secret in self.data.values_mut() {
+            secret.clear();
+        }
+        for trashed in self.trash.values_mut() {
+            trashed.secret.clear();
+        }
+        self.clear();
+    }
+
+    /// Groups credential names that share an identical secret.
+    ///
+    /// Only groups of two or more names are returned. Names within a group are
+    /// sorted, and groups are ordered by their first name.
+    pub fn duplicates(&self) -> Vec<Vec<String>> {
+        let mut by_secret: HashMap<&str, Vec<String>> = HashMap::new();
+        for (name, secret) in &self.data {
+            by_secret
+                .entry(secret.as_str())
+                .or_default()
+                .push(name.clone());
+        }
+
+        let mut groups: Vec<Vec<String>> = by_secret
+            .into_values()
+            .filter(|names| names.len() > 1)
+            .map(|mut names| {
+                names.sort();
+                names
+            })
+            .collect();
+        groups.sort();
+        groups
+    }
+
+    /// Summarizes the stored credentials; those in the trash are not
+    /// counted.
+    pub fn stats(&self) -> VaultStats {
+        let mut lengths: Vec<usize> = self.data.values().map(|s| s.chars().count()).collect();
+        lengths.sort_unstable();
+
+        let total = lengths.len();
+        let average_secret_len = if total == 0 {
+            0.0
+        } else {
+            lengths.iter().sum::<usize>() as f64 / total as f64
+        };
+        let median_secret_len = match total {
+            0 => 0.0,
+            n if n % 2 == 1 => lengths[n / 2] as f64,
+            n => (lengths[n / 2 - 1] + lengths[n / 2]) as f64 / 2.0,
+        };
+
+        let weak_count = self
+            .data
+            .values()
+            .filter(|s| estimate_strength(s).is_weak())
+            .count();
+
+        let duplicate_count = self.duplicates().iter().map(Vec::len).sum();
+
+        let metas = || self.data.keys().filter_map(|name| self.metadata.get(name));
+        let creations = || metas().filter_map(|meta| meta.created_at);
+        let updates = || metas().filter_map(|meta| meta.updated_at);
+
+        VaultStats {
+            total,
+            average_secret_len,
+            median_secret_len,
+            weak_count,
+            duplicate_count,
+            tag_count: self.tag_counts().len(),
+            oldest_created_at: creations().min(),
+            newest_created_at: creations().max(),
+            oldest_updated_at: updates().min(),
+            newest_updated_at: updates().max(),
+        }
+    }
+}
+
+impl Default for Credentials {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl fmt::Debug for Credentials {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut keys = self.list();
+        keys.sort();
+        f.debug_struct("Credentials")
+            .field("count", &keys.len())
+            .field("keys", &keys)
+            .finish()
+    }
+}
+
+impl<'a> IntoIterator for &'a Credentials {
+    type Item = (&'a String, &'a String);
+    type IntoIter = std::collections::hash_map::Iter<'a, String, String>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.data.iter()
     }
 }
@@ -1,11 +1,112 @@
+use aes_gcm::Aes256Gcm;
 use anyhow::{Result, anyhow};
-use argon2::Argon2;
-use chacha20poly1305::aead::Aead;
+use argon2::{Algorithm, Argon2, Params, Version};
+use chacha20poly1305::aead::{Aead, AeadInPlace};
 use chacha20poly1305::{ChaCha20Poly1305, KeyInit, Nonce};
 use rand::{TryRngCore, rngs::OsRng};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
 
+use crate::wordlist::WORDS;
+
+/// Name of the key derivation function, as reported by `info --crypto`.
+pub const KDF_NAME: &str = "Argon2id v19";
+
+/// Authenticated cipher used to encrypt the credentials.
+///
+/// Both take a 32-byte key and a 12-byte nonce, so switching cipher changes
+/// nothing else about the store.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Cipher {
+    /// Fast everywhere, including on hardware without AES instructions.
+    #[default]
+    #[serde(rename = "chacha20-poly1305")]
+    ChaCha20Poly1305,
+    /// Faster on CPUs with AES instructions (AES-NI, ARMv8 crypto extensions).
+    #[serde(rename = "aes-256-gcm")]
+    Aes256Gcm,
+}
+
+impl Cipher {
+    /// Human-readable name, as reported by `info --crypto`.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Cipher::ChaCha20Poly1305 => "ChaCha20-Poly1305",
+            Cipher::Aes256Gcm => "AES-256-GCM",
+        }
+    }
+}
+
+impl FromStr for Cipher {
+    type Err = String;
+
+    /// Parses `chacha20-poly1305` or `aes-256-gcm`, as written in the store.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "chacha20-poly1305" => Ok(Cipher::ChaCha20Poly1305),
+            "aes-256-gcm" => Ok(Cipher::Aes256Gcm),
+            other => Err(format!("unknown cipher '{}'", other)),
+        }
+    }
+}
+
+/// Cipher used to wrap data keys, whatever cipher encrypts the credentials.
+///
+/// A recovery slot is kept unchanged across saves, so its wrapped key must
+/// not depend on the configured cipher.
+pub const KEY_WRAP_CIPHER: Cipher = Cipher::ChaCha20Poly1305;
+
+/// Argon2id cost parameters, stored alongside the salt so a vault can be
+/// unlocked even if the defaults change in a later release.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct KdfParams {
+    /// Memory cost in KiB.
+    pub m_cost: u32,
+    /// Number of iterations.
+    pub t_cost: u32,
+    /// Degree of parallelism.
+    pub p_cost: u32,
+}
+
+impl KdfParams {
+    /// Checks that Argon2id accepts these parameters.
+    pub fn validate(&self) -> Result<()> {
+        Params::new(self.m_cost, self.t_cost, self.p_cost, Some(32))
+            .map(|_| ())
+            .map_err(|e| anyhow!("Invalid Argon2id parameters: {}", e))
+    }
+}
+
+impl Default for KdfParams {
+    fn default() -> Self {
+        Self {
+            m_cost: Params::DEFAULT_M_COST,
+            t_cost: Params::DEFAULT_T_COST,
+            p_cost: Params::DEFAULT_P_COST,
+        }
+    }
+}
+
+#[allow(unused)]
 pub fn derive_key(password: &str, salt: &[u8]) -> Result<[u8; 32]> {
-    let argon2 = Argon2::default();
+    derive_key_with_params(password, salt, &KdfParams::default())
+}
+
+#[cfg(test)]
+thread_local! {
+    /// Key derivations run on this thread, so tests can check that one ran
+    /// without relying on timing.
+    pub static KDF_CALLS: std::cell::Cell<usize> = const { std::cell::Cell::new(0) };
+}
+
+pub fn derive_key_with_params(password: &str, salt: &[u8], params: &KdfParams) -> Result<[u8; 32]> {
+    #[cfg(test)]
+    KDF_CALLS.with(|calls| calls.set(calls.get() + 1));
+
+    let params = Params::new(params.m_cost, params.t_cost, params.p_cost, Some(32))
+        .map_err(|e| anyhow!("Invalid Argon2id parameters: {}", e))?;
+    let argon2 = Argon2::new(Algorithm::Argon2id, Version::V0x13, params);
     let mut key = [0u8; 32];
     argon2
         .hash_password_into(password.as_bytes(), salt, &mut key)
@@ -13,30 +114,451 @@ pub fn derive_key(password: &str, salt: &[u8]) -> Result<[u8; 32]> {
     Ok(key)
 }
 
-pub fn generate_salt() -> Result<[u8; 16]> {
-    let mut salt = [0u8; 16];
+/// Length of an Argon2id salt in bytes.
+pub const SALT_LEN: usize = 16;
+
+/// Length of a nonce in bytes, the same for every [`Cipher`].
+pub const NONCE_LEN: usize = 12;
+
+pub fn generate_salt() -> Result<[u8; SALT_LEN]> {
+    let mut salt = [0u8; SALT_LEN];
     OsRng.try_fill_bytes(&mut salt)?;
     Ok(salt)
 }
 
-pub fn generate_nonce() -> Result<[u8; 12]> {
-    let mut nonce_bytes = [0u8; 12];
+pub fn generate_nonce() -> Result<[u8; NONCE_LEN]> {
+    let mut nonce_bytes = [0u8; NONCE_LEN];
     OsRng.try_fill_bytes(&mut nonce_bytes)?;
     Ok(nonce_bytes)
 }
 
-pub fn encrypt(data: &[u8], key: &[u8; 32], nonce: &[u8; 12]) -> Result<Vec<u8>> {
-    let cipher = ChaCha20Poly1305::new(key.into());
+pub fn generate_data_key() -> Result<[u8; 32]> {
+    let mut key = [0u8; 32];
+    OsRng.try_fill_bytes(&mut key)?;
+    Ok(key)
+}
+
+/// Number of random bytes in a recovery key.
+const RECOVERY_KEY_BYTES: usize = 20;
+
+/// Generates a random recovery key, formatted as dash-separated groups of
+/// four hex digits for easier transcription.
+pub fn generate_recovery_key() -> Result<String> {
+    let mut bytes = [0u8; RECOVERY_KEY_BYTES];
+    OsRng.try_fill_bytes(&mut bytes)?;
+    let hex: String = bytes.iter().map(|b| format!("{:02x}", b)).collect();
+    let groups: Vec<&str> = hex
+        .as_bytes()
+        .chunks(4)
+        .map(|c| std::str::from_utf8(c).unwrap_or_default())
+        .collect();
+    Ok(groups.join("-"))
+}
+
+/// Normalizes a recovery key as typed by the user: dashes and whitespace are
+/// ignored and letters are lowercased.
+pub fn normalize_recovery_key(key: &str) -> String {
+    key.chars()
+        .filter(|c| *c != '-' && !c.is_whitespace())
+        .map(|c| c.to_ascii_lowercase())
+        .collect()
+}
+
+/// Characters of generated passwords: every printable ASCII character
+/// except the space.
+pub const PASSWORD_CHARSET: &str = "ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789\
+                                    !\"#$%&'()*+,-./:;<=>?@[\\]^_`{|}~";
+
+/// Returns a random index below `n` drawn from `rng`, each equally likely.
+///
+/// Reducing a random value with `% n` makes the smallest indices slightly
+/// more likely whenever `n` does not divide 2^32. Instead, a 32-bit value is
+/// scaled into range by multiplying it with `n` and keeping the high half,
+/// and the few values that would make some indices more likely than others
+/// are rejected and drawn again.
+pub fn uniform_index<R>(rng: &mut R, n: usize) -> Result<usize>
+where
+    R: TryRngCore + ?Sized,
+    R::Error: std::error::Error + Send + Sync + 'static,
+{
+    let n = u32::try_from(n).map_err(|_| anyhow!("Cannot pick from {} choices", n))?;
+    if n == 0 {
+        return Err(anyhow!("Cannot pick from an empty set"));
+    }
+    // 2^32 mod n: the number of low halves that occur once too often
+    let threshold = n.wrapping_neg() % n;
+    loop {
+        let product = u64::from(rng.try_next_u32()?) * u64::from(n);
+        if product as u32 >= threshold {
+            return Ok((product >> 32) as usize);
+        }
+    }
+}
+
+/// Returns the entropy in bits of `len` symbols each drawn uniformly from
+/// `charset_size` choices.
+pub fn entropy_bits(len: usize, charset_size: usize) -> f64 {
+    len as f64 * (charset_size as f64).log2()
+}
+
+/// Returns the entropy in bits of `words` words each drawn uniformly from a
+/// list of `wordlist_len` words.
+///
+/// Separators and capitalization add nothing, since they are not random.
+pub fn passphrase_entropy_bits(words: usize, wordlist_len: usize) -> f64 {
+    entropy_bits(words, wordlist_len)
+}
+
+/// Generates a password of `len` characters drawn from
+/// [`PASSWORD_CHARSET`].
+pub fn generate_password(len: usize) -> Result<String> {
+    if len == 0 {
+        return Err(anyhow!("A password needs at least one character"));
+    }
+    let charset = PASSWORD_CHARSET.as_bytes();
+    (0..len)
+        .map(|_| uniform_index(&mut OsRng, charset.len()).map(|i| char::from(charset[i])))
+        .collect()
+}
+
+/// How a generated passphrase is dressed up beyond its words.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PassphraseOptions {
+    /// Capitalize the first letter of each word.
+    pub capitalize: bool,
+    /// Append a random digit after the last word.
+    pub digit: bool,
+}
+
+/// Generates a passphrase of `words` words from the bundled
+/// [word list](crate::wordlist::WORDS), joined by `sep`.
+pub fn generate_passphrase(words: usize, sep: &str, opts: PassphraseOptions) -> Result<String> {
+    if words == 0 {
+        return Err(anyhow!("A passphrase needs at least one word"));
+    }
+    let mut chosen = Vec::with_capacity(words);
+    for _ in 0..words {
+        let word = WORDS[uniform_index(&mut OsRng, WORDS.len())?];
+        chosen.push(if opts.capitalize {
+            let mut chars = word.chars();
+            chars
+                .next()
+                .map(|first| first.to_ascii_uppercase().to_string() + chars.as_str())
+                .unwrap_or_default()
+        } else {
+            word.to_string()
+        });
+    }
+    let mut passphrase = chosen.join(sep);
+    if opts.digit {
+        passphrase.push_str(&uniform_index(&mut OsRng, 10)?.to_string());
+    }
+    Ok(passphrase)
+}
+
+/// Compares two byte strings in time that depends only on their lengths,
+/// not on where they first differ.
+///
+/// Use this whenever secret bytes are compared. The lengths themselves are
+/// not hidden.
+pub fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    let diff = a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y));
+    std::hint::black_box(diff) == 0
+}
+
+/// Encrypted data failed authentication: the key is wrong or the data was
+/// modified. Returned wrapped in [`anyhow::Error`] by [`decrypt`] and
+/// [`decrypt_in_place`]; use `downcast_ref` to detect it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AuthenticationError;
+
+impl fmt::Display for AuthenticationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Decryption failed - wrong key or corrupted data")
+    }
+}
+
+impl std::error::Error for AuthenticationError {}
+
+pub fn encrypt(cipher: Cipher, data: &[u8], key: &[u8; 32], nonce: &[u8; 12]) -> Result<Vec<u8>> {
     let nonce = Nonce::from_slice(nonce);
-    cipher
-        .encrypt(nonce, data)
-        .map_err(|_| anyhow!("Encryption failed"))
+    match cipher {
+        Cipher::ChaCha20Poly1305 => ChaCha20Poly1305::new(key.into()).encrypt(nonce, data),
+        Cipher::Aes256Gcm => Aes256Gcm::new(key.into()).encrypt(nonce, data),
+    }
+    .map_err(|_| anyhow!("Encryption failed"))
 }
 
-pub fn decrypt(encrypted_data: &[u8], key: &[u8; 32], nonce: &[u8; 12]) -> Result<Vec<u8>> {
-    let cipher = ChaCha20Poly1305::new(key.into());
+pub fn decrypt(
+    cipher: Cipher,
+    encrypted_data: &[u8],
+    key: &[u8; 32],
+    nonce: &[u8; 12],
+) -> Result<Vec<u8>> {
     let nonce = Nonce::from_slice(nonce);
-    cipher
-        .decrypt(nonce, encrypted_data)
-        .map_err(|_| anyhow!("Decryption failed - invalid password"))
+    match cipher {
+        Cipher::ChaCha20Poly1305 => {
+            ChaCha20Poly1305::new(key.into()).decrypt(nonce, encrypted_data)
+        }
+        Cipher::Aes256Gcm => Aes256Gcm::new(key.into()).decrypt(nonce, encrypted_data),
+    }
+    .map_err(|_| AuthenticationError.into())
+}
+
+/// Decrypts `buffer` in place, replacing the ciphertext with the plaintext.
+///
+/// Unlike [`decrypt`], no second buffer is allocated, so decrypting a large
+/// vault doesn't hold the ciphertext and plaintext in memory at the same time.
+pub fn decrypt_in_place(
+    cipher: Cipher,
+    buffer: &mut Vec<u8>,
+    key: &[u8; 32],
+    nonce: &[u8; 12],
+) -> Result<()> {
+    let nonce = Nonce::from_slice(nonce);
+    match cipher {
+        Cipher::ChaCha20Poly1305 => {
+            ChaCha20Poly1305::new(key.into()).decrypt_in_place(nonce, b"", buffer)
+        }
+        Cipher::Aes256Gcm => Aes256Gcm::new(key.into()).decrypt_in_place(nonce, b"", buffer),
+    }
+    .map_err(|_| AuthenticationError.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::{RngCore, SeedableRng};
+
+    const KEY: [u8; 32] = [7; 32];
+    const NONCE: [u8; 12] = [3; 12];
+
+    const CIPHERS: [Cipher; 2] = [Cipher::ChaCha20Poly1305, Cipher::Aes256Gcm];
+
+    #[test]
+    fn test_round_trip_with_each_cipher() {
+        let plaintext = b"{\"github\":\"secret\"}";
+        for cipher in CIPHERS {
+            let ciphertext = encrypt(cipher, plaintext, &KEY, &NONCE).unwrap();
+            assert_eq!(
+                decrypt(cipher, &ciphertext, &KEY, &NONCE).unwrap(),
+                plaintext
+            );
+
+            let mut buffer = ciphertext.clone();
+            decrypt_in_place(cipher, &mut buffer, &KEY, &NONCE).unwrap();
+            assert_eq!(buffer, plaintext);
+
+            let err = decrypt(cipher, &ciphertext, &[8; 32], &NONCE).unwrap_err();
+            assert!(err.is::<AuthenticationError>());
+        }
+    }
+
+    #[test]
+    fn test_ciphers_are_not_interchangeable() {
+        let chacha = encrypt(Cipher::ChaCha20Poly1305, b"secret", &KEY, &NONCE).unwrap();
+        let aes = encrypt(Cipher::Aes256Gcm, b"secret", &KEY, &NONCE).unwrap();
+        assert_ne!(chacha, aes);
+
+        let err = decrypt(Cipher::Aes256Gcm, &chacha, &KEY, &NONCE).unwrap_err();
+        assert!(err.is::<AuthenticationError>());
+    }
+
+    #[test]
+    fn test_parse_cipher() {
+        assert_eq!("chacha20-poly1305".parse(), Ok(Cipher::ChaCha20Poly1305));
+        assert_eq!("aes-256-gcm".parse(), Ok(Cipher::Aes256Gcm));
+        assert!("rot13".parse::<Cipher>().is_err());
+        assert_eq!(Cipher::default(), Cipher::ChaCha20Poly1305);
+        assert_eq!(
+            serde_json::to_string(&Cipher::Aes256Gcm).unwrap(),
+            "\"aes-256-gcm\""
+        );
+    }
+
+    #[test]
+    fn test_decrypt_in_place_matches_decrypt() {
+        let plaintext = b"{\"github\":\"secret\"}";
+        let ciphertext = encrypt(Cipher::default(), plaintext, &KEY, &NONCE).unwrap();
+
+        let mut buffer = ciphertext.clone();
+        decrypt_in_place(Cipher::default(), &mut buffer, &KEY, &NONCE).unwrap();
+        assert_eq!(
+            buffer,
+            decrypt(Cipher::default(), &ciphertext, &KEY, &NONCE).unwrap()
+        );
+        assert_eq!(buffer, plaintext);
+    }
+
+    #[test]
+    fn test_decrypt_in_place_reuses_ciphertext_buffer() {
+        let plaintext = vec![b'x'; 4 * 1024 * 1024];
+        let mut buffer = encrypt(Cipher::default(), &plaintext, &KEY, &NONCE).unwrap();
+        let (ptr, capacity) = (buffer.as_ptr(), buffer.capacity());
+
+        decrypt_in_place(Cipher::default(), &mut buffer, &KEY, &NONCE).unwrap();
+
+        // The plaintext lives in the ciphertext's allocation, so no second
+        // copy of the data exists when it's deserialized
+        assert_eq!(buffer.as_ptr(), ptr);
+        assert_eq!(buffer.capacity(), capacity);
+        assert!(buffer == plaintext);
+    }
+
+    #[test]
+    fn test_constant_time_eq() {
+        assert!(constant_time_eq(b"hunter2", b"hunter2"));
+        assert!(constant_time_eq(b"", b""));
+        assert!(!constant_time_eq(b"hunter2", b"hunter3"));
+        assert!(!constant_time_eq(b"hunter2", b"Hunter2"));
+        assert!(!constant_time_eq(b"hunter2", b"hunter22"));
+    }
+
+    #[test]
+    fn test_decrypt_in_place_wrong_key() {
+        let mut buffer = encrypt(Cipher::default(), b"secret", &KEY, &NONCE).unwrap();
+        let err = decrypt_in_place(Cipher::default(), &mut buffer, &[8; 32], &NONCE).unwrap_err();
+        assert!(err.is::<AuthenticationError>());
+    }
+
+    #[test]
+    fn test_generate_password() {
+        assert_eq!(PASSWORD_CHARSET.len(), 94);
+        let password = generate_password(64).unwrap();
+        assert_eq!(password.len(), 64);
+        assert!(password.chars().all(|c| PASSWORD_CHARSET.contains(c)));
+        assert_ne!(password, generate_password(64).unwrap());
+        assert!(generate_password(0).is_err());
+    }
+
+    #[test]
+    fn test_entropy_bits() {
+        let close = |a: f64, b: f64| (a - b).abs() < 0.05;
+        assert!(close(entropy_bits(16, 94), 104.9));
+        assert!(close(entropy_bits(20, PASSWORD_CHARSET.len()), 131.1));
+        assert!(close(entropy_bits(8, 10), 26.6));
+        assert_eq!(entropy_bits(0, 94), 0.0);
+        assert_eq!(entropy_bits(10, 1), 0.0);
+
+        assert_eq!(passphrase_entropy_bits(4, WORDS.len()), 48.0);
+        assert!(close(passphrase_entropy_bits(6, 7776), 77.5));
+    }
+
+    #[test]
+    fn test_generate_passphrase() {
+        let passphrase = generate_passphrase(5, " ", PassphraseOptions::default()).unwrap();
+        let words: Vec<&str> = passphrase.split(' ').collect();
+        assert_eq!(words.len(), 5);
+        assert!(words.iter().all(|word| WORDS.contains(word)));
+
+        let opts = PassphraseOptions {
+            capitalize: true,
+            digit: true,
+        };
+        let passphrase = generate_passphrase(3, "-", opts).unwrap();
+        let (words, digit) = passphrase.split_at(passphrase.len() - 1);
+        assert!(digit.chars().all(|c| c.is_ascii_digit()));
+        let words: Vec<&str> = words.split('-').collect();
+        assert_eq!(words.len(), 3);
+        for word in words {
+            assert!(word.starts_with(|c: char| c.is_ascii_uppercase()));
+            assert!(WORDS.contains(&word.to_lowercase().as_str()));
+        }
+
+        assert!(generate_passphrase(0, "-", PassphraseOptions::default()).is_err());
+    }
+
+    #[test]
+    fn test_word_list() {
+        assert!(WORDS.windows(2).all(|pair| pair[0] < pair[1]));
+        assert!(
+            WORDS.iter().all(|word| (4..=8).contains(&word.len())
+                && word.chars().all(|c| c.is_ascii_lowercase()))
+        );
+    }
+
+    #[test]
+    fn test_uniform_index_uses_full_range() {
+        let mut seen = [false; 7];
+        for _ in 0..1000 {
+            seen[uniform_index(&mut OsRng, seen.len()).unwrap()] = true;
+        }
+        assert!(seen.iter().all(|&hit| hit));
+
+        // The first and last words can both be chosen
+        let (mut lowest, mut highest) = (usize::MAX, 0);
+        for _ in 0..100_000 {
+            let index = uniform_index(&mut OsRng, WORDS.len()).unwrap();
+            lowest = lowest.min(index);
+            highest = highest.max(index);
+        }
+        assert_eq!((lowest, highest), (0, WORDS.len() - 1));
+
+        assert_eq!(uniform_index(&mut OsRng, 1).unwrap(), 0);
+        assert!(uniform_index(&mut OsRng, 0).is_err());
+    }
+
+    /// Yields the given values in turn.
+    struct ScriptedRng(std::vec::IntoIter<u32>);
+
+    impl RngCore for ScriptedRng {
+        fn next_u32(&mut self) -> u32 {
+            self.0.next().expect("ran out of values")
+        }
+
+        fn next_u64(&mut self) -> u64 {
+            u64::from(self.next_u32())
+        }
+
+        fn fill_bytes(&mut self, dst: &mut [u8]) {
+            rand::rand_core::impls::fill_bytes_via_next(self, dst)
+        }
+    }
+
+    #[test]
+    fn test_scripted_rng_fills_bytes() {
+        let mut rng = ScriptedRng(vec![0x0403_0201].into_iter());
+        let mut bytes = [0u8; 4];
+        rng.fill_bytes(&mut bytes);
+        assert_eq!(bytes, [1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn test_uniform_index_rejects_biased_values() {
+        // For 3 choices, 0 is the one value of 2^32 that would favour index 0
+        let mut rng = ScriptedRng(vec![0, 0, u32::MAX, 1 << 31].into_iter());
+        assert_eq!(uniform_index(&mut rng, 3).unwrap(), 2);
+        assert_eq!(uniform_index(&mut rng, 3).unwrap(), 1);
+    }
+
+    #[test]
+    fn test_uniform_index_distribution() {
+        // Chi-square test over a fixed seed, so the test cannot flake
+        let mut rng = StdRng::seed_from_u64(0x5eed);
+        for n in [3usize, 10, 94] {
+            let samples = 2000 * n;
+            let mut counts = vec![0usize; n];
+            for _ in 0..samples {
+                counts[uniform_index(&mut rng, n).unwrap()] += 1;
+            }
+            let expected = (samples / n) as f64;
+            let chi_square: f64 = counts
+                .iter()
+                .map(|&count| (count as f64 - expected).powi(2) / expected)
+                .sum();
+            // Six standard deviations above the mean of the chi-square
+            // distribution with n - 1 degrees of freedom
+            let limit = (n - 1) as f64 + 6.0 * (2.0 * (n - 1) as f64).sqrt();
+            assert!(
+                chi_square < limit,
+                "chi-square {} for n = {}",
+                chi_square,
+                n
+            );
+        }
+    }
 }
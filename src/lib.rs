@@ -3,19 +3,26 @@
 //! This library provides the core functionality for the passmgr password manager,
 //! including credential storage, encryption, and a shell-like interactive interface.
 
+pub mod backup;
 pub mod config;
 pub mod credentials;
 pub mod crypto;
+pub mod lockfile;
+pub mod lockout;
 pub mod logging;
 pub mod manager;
+pub mod pwned;
 pub mod shell;
 pub mod storage;
+pub mod strength;
 pub mod trie;
+pub mod wordlist;
 
 // Re-export commonly used types
 pub use config::AppConfig;
-pub use credentials::Credentials;
-pub use logging::{LogConfig, init_logging};
-pub use manager::Manager;
+pub use credentials::{CredentialError, CredentialView, Credentials};
+pub use logging::{LogConfig, LogFormat, init_logging};
+pub use manager::{LoadError, Manager, ManagerError, VaultInfo, VerifyReport};
 pub use shell::Shell;
+pub use storage::{FileBackend, InMemoryBackend, StorageBackend, StorageError};
 pub use trie::Trie;
@@ -0,0 +1,275 @@
+//! Lock files marking a vault as open in a running passmgr process.
+//!
+//! A save writes the whole vault, so two processes with the same vault open
+//! would silently overwrite each other's changes. While a vault is unlocked,
+//! `<db>.lock` holds the PID of the process that has it open. Another process
+//! finding a lock whose PID is still running refuses to open the vault; a
+//! lock left behind by a process that has exited is stale and is reclaimed.
+
+use std::fmt;
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+/// Appended to the vault's file name to name its lock file.
+pub const LOCK_SUFFIX: &str = ".lock";
+
+/// Why a vault's lock could not be taken.
+#[derive(Debug)]
+pub enum LockError {
+    /// Another running process holds the lock.
+    Held { pid: u32 },
+    /// The lock file could not be read or written.
+    Io(io::Error),
+}
+
+impl fmt::Display for LockError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LockError::Held { pid } => write!(f, "the vault is in use by process {}", pid),
+            LockError::Io(e) => write!(f, "could not create the lock file: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for LockError {}
+
+impl From<io::Error> for LockError {
+    fn from(e: io::Error) -> Self {
+        LockError::Io(e)
+    }
+}
+
+/// Returns the path of the lock file for the vault at `db_path`.
+pub fn lock_path(db_path: &Path) -> PathBuf {
+    let mut name = db_path.file_name().unwrap_or_default().to_os_string();
+    name.push(LOCK_SUFFIX);
+    db_path.with_file_name(name)
+}
+
+/// A held vault lock; the lock file is removed when this is dropped.
+#[derive(Debug)]
+pub struct VaultLock {
+    /// The locked vault.
+    db_path: PathBuf,
+    /// The lock file.
+    path: PathBuf,
+    /// Written to the lock file, so it is only removed by its owner.
+    token: String,
+}
+
+impl VaultLock {
+    /// Takes the lock for the vault at `db_path`.
+    ///
+    /// A lock whose PID is no longer running, or cannot be read, is stale
+    /// and is reclaimed. So is one held by this process, which only happens
+    /// when the same vault is opened again. A lock held by another running
+    /// process is [`LockError::Held`], unless `force` is set, in which case
+    /// it is taken over.
+    pub fn acquire(db_path: &Path, force: bool) -> Result<Self, LockError> {
+        let path = lock_path(db_path);
+        let pid = std::process::id();
+        let token = format!("{:016x}", rand::random::<u64>());
+
+        // A second attempt covers the lock being removed between our failed
+        // create and the removal of the stale one.
+        for _ in 0..2 {
+            match OpenOptions::new().write(true).create_new(true).open(&path) {
+                Ok(mut file) => {
+                    writeln!(file, "{}\n{}", pid, token)?;
+                    file.sync_all()?;
+                    log::debug!("Acquired vault lock: {}", path.display());
+                    return Ok(Self {
+                        db_path: db_path.to_path_buf(),
+                        path,
+                        token,
+                    });
+                }
+                Err(e) if e.kind() == io::ErrorKind::AlreadyExists => {}
+                Err(e) => return Err(e.into()),
+            }
+
+            match read_owner(&path)? {
+                Some((owner, _)) if owner != pid && process_is_running(owner) => {
+                    if !force {
+                        return Err(LockError::Held { pid: owner });
+                    }
+                    log::warn!("Taking over vault lock held by process {}", owner);
+                }
+                Some((owner, _)) => log::info!("Reclaiming stale vault lock of process {}", owner),
+                None => log::info!("Reclaiming unreadable vault lock"),
+            }
+            match fs::remove_file(&path) {
+                Err(e) if e.kind() != io::ErrorKind::NotFound => return Err(e.into()),
+                _ => {}
+            }
+        }
+        Err(LockError::Io(io::Error::new(
+            io::ErrorKind::AlreadyExists,
+            "the lock file was recreated while it was being reclaimed",
+        )))
+    }
+
+    /// Returns the path of the vault this lock is for.
+    pub fn db_path(&self) -> &Path {
+        &self.db_path
+    }
+
+    /// Returns the path of the lock file.
+    #[allow(unused)]
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl Drop for VaultLock {
+    fn drop(&mut self) {
+        // Leave a lock another process has taken over with --force alone
+        match read_owner(&self.path) {
+            Ok(Some((_, token))) if token == self.token => {
+                if let Err(e) = fs::remove_file(&self.path) {
+                    log::warn!("Failed to remove vault lock {}: {}", self.path.display(), e);
+                }
+            }
+            _ => log::debug!("Vault lock no longer ours: {}", self.path.display()),
+        }
+    }
+}
+
+/// Reads the PID and token from the lock file at `path`.
+///
+/// Returns `None` if it is missing or does not hold a PID.
+fn read_owner(path: &Path) -> io::Result<Option<(u32, String)>> {
+    let contents = match fs::read_to_string(path) {
+        Ok(contents) => contents,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(e),
+    };
+    let mut lines = contents.lines();
+    let Some(pid) = lines.next().and_then(|line| line.trim().parse().ok()) else {
+        return Ok(None);
+    };
+    let token = lines.next().unwrap_or_default().trim().to_string();
+    Ok(Some((pid, token)))
+}
+
+/// Returns whether a process with `pid` is running.
+#[cfg(unix)]
+fn process_is_running(pid: u32) -> bool {
+    let Ok(pid) = libc::pid_t::try_from(pid) else {
+        return false;
+    };
+    if pid <= 0 {
+        return false;
+    }
+    // Signal 0 only checks that the process exists and may be signalled; a
+    // process owned by another user exists but refuses with EPERM
+    // SAFETY: kill with signal 0 sends nothing
+    let result = unsafe { libc::kill(pid, 0) };
+    result == 0 || io::Error::last_os_error().raw_os_error() == Some(libc::EPERM)
+}
+
+/// Returns `true`: without a way to check, the lock is assumed to be live.
+#[cfg(not(unix))]
+fn process_is_running(_pid: u32) -> bool {
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    /// A PID above any the kernel hands out.
+    const DEAD_PID: u32 = 0x7fff_fff0;
+
+    fn write_lock(db_path: &Path, pid: u32) {
+        fs::write(lock_path(db_path), format!("{}\nsomeone-else\n", pid)).unwrap();
+    }
+
+    #[test]
+    fn test_lock_path() {
+        assert_eq!(
+            lock_path(Path::new("/vaults/passwords.db")),
+            PathBuf::from("/vaults/passwords.db.lock")
+        );
+    }
+
+    #[test]
+    fn test_acquire_and_release() {
+        let dir = TempDir::new().unwrap();
+        let db_path = dir.path().join("passwords.db");
+
+        let lock = VaultLock::acquire(&db_path, false).unwrap();
+        assert_eq!(lock.db_path(), db_path);
+        let (pid, _) = read_owner(lock.path()).unwrap().unwrap();
+        assert_eq!(pid, std::process::id());
+
+        drop(lock);
+        assert!(!lock_path(&db_path).exists());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_live_lock_is_refused() {
+        let dir = TempDir::new().unwrap();
+        let db_path = dir.path().join("passwords.db");
+        let mut other = std::process::Command::new("sleep")
+            .arg("30")
+            .spawn()
+            .unwrap();
+        write_lock(&db_path, other.id());
+
+        let result = VaultLock::acquire(&db_path, false);
+        assert!(matches!(result, Err(LockError::Held { pid }) if pid == other.id()));
+        // The other process's lock is left alone
+        assert!(lock_path(&db_path).exists());
+
+        other.kill().unwrap();
+        other.wait().unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_force_takes_over_live_lock() {
+        let dir = TempDir::new().unwrap();
+        let db_path = dir.path().join("passwords.db");
+        let mut other = std::process::Command::new("sleep")
+            .arg("30")
+            .spawn()
+            .unwrap();
+        write_lock(&db_path, other.id());
+
+        let lock = VaultLock::acquire(&db_path, true).unwrap();
+        let (pid, _) = read_owner(lock.path()).unwrap().unwrap();
+        assert_eq!(pid, std::process::id());
+
+        other.kill().unwrap();
+        other.wait().unwrap();
+    }
+
+    #[test]
+    fn test_stale_lock_is_reclaimed() {
+        let dir = TempDir::new().unwrap();
+        let db_path = dir.path().join("passwords.db");
+
+        for stale in [format!("{}\ntoken\n", DEAD_PID), "garbage".to_string()] {
+            fs::write(lock_path(&db_path), stale).unwrap();
+            let lock = VaultLock::acquire(&db_path, false).unwrap();
+            let (pid, _) = read_owner(lock.path()).unwrap().unwrap();
+            assert_eq!(pid, std::process::id());
+        }
+    }
+
+    #[test]
+    fn test_drop_leaves_taken_over_lock() {
+        let dir = TempDir::new().unwrap();
+        let db_path = dir.path().join("passwords.db");
+
+        let lock = VaultLock::acquire(&db_path, false).unwrap();
+        write_lock(&db_path, DEAD_PID);
+        drop(lock);
+
+        assert!(lock_path(&db_path).exists());
+    }
+}
@@ -0,0 +1,190 @@
+//! Opt-in lockout after repeated failed unlock attempts.
+//!
+//! The number of consecutive failed master-password attempts, and any
+//! cooldown in effect, are kept in the store header so they survive restarts.
+//! Once the configured number of attempts is reached, unlocking is either
+//! refused for a cooldown period or the vault is wiped.
+//!
+//! The header is not encrypted: anyone who can edit the vault file can also
+//! reset the counter. The lockout slows down guessing through passmgr itself;
+//! it does not replace a strong master password.
+
+use anyhow::{Result, anyhow};
+use std::str::FromStr;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::storage::EncryptedStore;
+
+/// Cooldown used when the action is `cooldown` and no duration is configured.
+pub const DEFAULT_COOLDOWN: Duration = Duration::from_secs(300);
+
+/// What happens once the maximum number of failed attempts is reached.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LockoutAction {
+    /// Refuse to unlock, even with the right password, for this long.
+    Cooldown(Duration),
+    /// Delete the vault and its backups.
+    Wipe,
+}
+
+impl FromStr for LockoutAction {
+    type Err = String;
+
+    /// Parses `cooldown` (with [`DEFAULT_COOLDOWN`]) or `wipe`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "cooldown" => Ok(LockoutAction::Cooldown(DEFAULT_COOLDOWN)),
+            "wipe" => Ok(LockoutAction::Wipe),
+            other => Err(format!("unknown lockout action '{}'", other)),
+        }
+    }
+}
+
+/// How many failed attempts are allowed and what happens after them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LockoutPolicy {
+    /// Consecutive failed attempts that trigger the action.
+    pub max_attempts: u32,
+    /// What to do once `max_attempts` is reached.
+    pub action: LockoutAction,
+}
+
+/// Result of recording a failed attempt.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LockoutOutcome {
+    /// More attempts are allowed.
+    Allowed {
+        /// Attempts left before the action triggers.
+        remaining: u32,
+    },
+    /// Unlocking is refused until this Unix time.
+    LockedUntil(u64),
+    /// The vault must be wiped.
+    Wipe,
+}
+
+impl LockoutPolicy {
+    /// Records a failed attempt in `store`'s header and returns what to do.
+    ///
+    /// When a cooldown starts, the counter is reset so the full number of
+    /// attempts is available once it ends.
+    pub fn record_failure(&self, store: &mut EncryptedStore, now: u64) -> LockoutOutcome {
+        store.failed_attempts = store.failed_attempts.saturating_add(1);
+        if store.failed_attempts < self.max_attempts {
+            return LockoutOutcome::Allowed {
+                remaining: self.max_attempts - store.failed_attempts,
+            };
+        }
+
+        match self.action {
+            LockoutAction::Cooldown(duration) => {
+                let until = now.saturating_add(duration.as_secs());
+                store.failed_attempts = 0;
+                store.locked_until = Some(until);
+                LockoutOutcome::LockedUntil(until)
+            }
+            LockoutAction::Wipe => LockoutOutcome::Wipe,
+        }
+    }
+}
+
+/// Returns how many seconds of cooldown remain, if unlocking is refused.
+pub fn remaining_cooldown(store: &EncryptedStore, now: u64) -> Option<u64> {
+    store
+        .locked_until
+        .filter(|&until| until > now)
+        .map(|until| until - now)
+}
+
+/// Clears the failed attempt counter and any expired cooldown.
+///
+/// Returns whether the header changed and needs to be written back.
+pub fn reset(store: &mut EncryptedStore) -> bool {
+    let changed = store.failed_attempts != 0 || store.locked_until.is_some();
+    store.failed_attempts = 0;
+    store.locked_until = None;
+    changed
+}
+
+/// Returns the current Unix time in seconds.
+pub fn now_secs() -> Result<u64> {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .map_err(|e| anyhow!("System clock is before the Unix epoch: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn store() -> EncryptedStore {
+        serde_json::from_str(
+            r#"{"version":4,"argon2_salt":"","encryption_nonce":"","encrypted_data":""}"#,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_parse_action() {
+        assert_eq!(
+            "cooldown".parse(),
+            Ok(LockoutAction::Cooldown(DEFAULT_COOLDOWN))
+        );
+        assert_eq!("wipe".parse(), Ok(LockoutAction::Wipe));
+        assert!("explode".parse::<LockoutAction>().is_err());
+    }
+
+    #[test]
+    fn test_cooldown_after_max_attempts() {
+        let policy = LockoutPolicy {
+            max_attempts: 3,
+            action: LockoutAction::Cooldown(Duration::from_secs(60)),
+        };
+        let mut store = store();
+
+        assert_eq!(
+            policy.record_failure(&mut store, 1000),
+            LockoutOutcome::Allowed { remaining: 2 }
+        );
+        assert_eq!(
+            policy.record_failure(&mut store, 1000),
+            LockoutOutcome::Allowed { remaining: 1 }
+        );
+        assert_eq!(
+            policy.record_failure(&mut store, 1000),
+            LockoutOutcome::LockedUntil(1060)
+        );
+        assert_eq!(store.failed_attempts, 0);
+
+        assert_eq!(remaining_cooldown(&store, 1010), Some(50));
+        assert_eq!(remaining_cooldown(&store, 1060), None);
+    }
+
+    #[test]
+    fn test_wipe_after_max_attempts() {
+        let policy = LockoutPolicy {
+            max_attempts: 2,
+            action: LockoutAction::Wipe,
+        };
+        let mut store = store();
+
+        assert!(matches!(
+            policy.record_failure(&mut store, 0),
+            LockoutOutcome::Allowed { .. }
+        ));
+        assert_eq!(policy.record_failure(&mut store, 0), LockoutOutcome::Wipe);
+    }
+
+    #[test]
+    fn test_reset() {
+        let mut store = store();
+        assert!(!reset(&mut store));
+
+        store.failed_attempts = 2;
+        store.locked_until = Some(5);
+        assert!(reset(&mut store));
+        assert_eq!(store.failed_attempts, 0);
+        assert_eq!(store.locked_until, None);
+    }
+}
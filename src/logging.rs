@@ -1,15 +1,50 @@
 //! Logging infrastructure for passmgr.
 //!
 //! This module provides structured logging with file output, timestamps,
-//! and configurable log levels.
+//! and configurable log levels. The log file is written as plain text or as
+//! newline-delimited JSON, and is rotated into numbered archives
+//! (`passmgr.log.1`, `passmgr.log.2`, ...) once it grows past a maximum size.
 
 use anyhow::{Result, anyhow};
-use log::LevelFilter;
+use chrono::{DateTime, Local};
+use log::{LevelFilter, Log, Metadata, Record};
 use simplelog::{
     ColorChoice, CombinedLogger, ConfigBuilder, SharedLogger, TermLogger, TerminalMode, WriteLogger,
 };
-use std::fs::OpenOptions;
-use std::path::PathBuf;
+use std::fs::{File, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::Mutex;
+
+/// Format of the records written to the log file.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum LogFormat {
+    /// Human-readable lines.
+    #[default]
+    Text,
+    /// One JSON object per line, with `timestamp`, `level`, `target` and
+    /// `message` fields.
+    Json,
+}
+
+impl FromStr for LogFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "text" => Ok(LogFormat::Text),
+            "json" => Ok(LogFormat::Json),
+            other => Err(format!("unknown log format '{}'", other)),
+        }
+    }
+}
+
+/// Default maximum log file size before rotation.
+pub const DEFAULT_MAX_SIZE: u64 = 10 * 1024 * 1024; // 10 MB
+
+/// Default number of rotated log files to keep.
+pub const DEFAULT_MAX_ARCHIVES: usize = 5;
 
 /// Configuration for the logging system.
 #[derive(Debug, Clone)]
@@ -20,6 +55,10 @@ pub struct LogConfig {
     pub level: LevelFilter,
     /// Maximum log file size in bytes before rotation (0 = no limit).
     pub max_size: u64,
+    /// Number of rotated log files to keep.
+    pub max_archives: usize,
+    /// Format of the log file.
+    pub format: LogFormat,
 }
 
 impl Default for LogConfig {
@@ -27,7 +66,9 @@ impl Default for LogConfig {
         Self {
             path: PathBuf::from("passmgr.log"),
             level: LevelFilter::Info,
-            max_size: 10 * 1024 * 1024, // 10 MB
+            max_size: DEFAULT_MAX_SIZE,
+            max_archives: DEFAULT_MAX_ARCHIVES,
+            format: LogFormat::Text,
         }
     }
 }
@@ -52,13 +93,161 @@ impl LogConfig {
         self.max_size = max_size;
         self
     }
+
+    /// Sets the number of rotated log files to keep.
+    pub fn with_max_archives(mut self, max_archives: usize) -> Self {
+        self.max_archives = max_archives;
+        self
+    }
+
+    /// Sets the format of the log file.
+    pub fn with_format(mut self, format: LogFormat) -> Self {
+        self.format = format;
+        self
+    }
+}
+
+/// Log file that rotates itself once it grows past a maximum size.
+///
+/// Rotation only happens between lines, so a record is never split across
+/// two files; the file may exceed the maximum by up to one record.
+pub struct RotatingFile {
+    path: PathBuf,
+    file: File,
+    len: u64,
+    max_size: u64,
+    max_archives: usize,
+    at_line_start: bool,
+}
+
+impl RotatingFile {
+    /// Opens `path` for appending, rotating it first if it is already
+    /// larger than `max_size` (0 = never rotate).
+    pub fn open(path: PathBuf, max_size: u64, max_archives: usize) -> io::Result<Self> {
+        if max_size > 0
+            && let Ok(metadata) = std::fs::metadata(&path)
+            && metadata.len() > max_size
+        {
+            rotate_log(&path, max_archives)?;
+        }
+        let file = open_append(&path)?;
+        let len = file.metadata()?.len();
+        Ok(Self {
+            path,
+            file,
+            len,
+            max_size,
+            max_archives,
+            at_line_start: true,
+        })
+    }
+
+    /// Moves the current file into the archives and starts a new one.
+    fn rotate(&mut self) -> io::Result<()> {
+        self.file.flush()?;
+        rotate_log(&self.path, self.max_archives)?;
+        self.file = open_append(&self.path)?;
+        self.len = 0;
+        Ok(())
+    }
+}
+
+impl Write for RotatingFile {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if self.max_size > 0 && self.at_line_start && self.len > self.max_size {
+            self.rotate()?;
+        }
+        let written = self.file.write(buf)?;
+        if written > 0 {
+            self.len += written as u64;
+            self.at_line_start = buf[written - 1] == b'\n';
+        }
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.file.flush()
+    }
+}
+
+/// Opens or creates `path` for appending.
+fn open_append(path: &Path) -> io::Result<File> {
+    OpenOptions::new().create(true).append(true).open(path)
+}
+
+/// Logger writing each record as one line of JSON.
+///
+/// Only the record's own fields are written, so it logs nothing the text
+/// logger wouldn't.
+pub struct JsonLogger<W: Write + Send> {
+    level: LevelFilter,
+    writer: Mutex<W>,
+}
+
+impl<W: Write + Send + 'static> JsonLogger<W> {
+    /// Creates a logger writing records at `level` or above to `writer`.
+    pub fn new(level: LevelFilter, writer: W) -> Box<Self> {
+        Box::new(Self {
+            level,
+            writer: Mutex::new(writer),
+        })
+    }
+}
+
+/// Formats a record as a single line of JSON, without the trailing newline.
+///
+/// The message is escaped, so a multi-line message still takes one line.
+pub fn json_record(record: &Record, timestamp: DateTime<Local>) -> String {
+    serde_json::json!({
+        "timestamp": timestamp.to_rfc3339(),
+        "level": record.level().to_string(),
+        "target": record.target(),
+        "message": record.args().to_string(),
+    })
+    .to_string()
+}
+
+impl<W: Write + Send> Log for JsonLogger<W> {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= self.level
+    }
+
+    fn log(&self, record: &Record) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+        let line = json_record(record, Local::now());
+        if let Ok(mut writer) = self.writer.lock() {
+            let _ = writeln!(writer, "{}", line);
+        }
+    }
+
+    fn flush(&self) {
+        if let Ok(mut writer) = self.writer.lock() {
+            let _ = writer.flush();
+        }
+    }
+}
+
+impl<W: Write + Send + 'static> SharedLogger for JsonLogger<W> {
+    fn level(&self) -> LevelFilter {
+        self.level
+    }
+
+    fn config(&self) -> Option<&simplelog::Config> {
+        None
+    }
+
+    fn as_log(self: Box<Self>) -> Box<dyn Log> {
+        self
+    }
 }
 
 /// Initializes the logging system with the given configuration.
 ///
 /// This sets up a combined logger that writes to both:
-/// - Terminal (with colors, at Info level or higher for user feedback)
-/// - Log file (at the configured level, with timestamps)
+/// - Terminal (with colors, at Warn level or higher for user feedback)
+/// - Log file (at the configured level, with timestamps, as text or JSON)
 ///
 /// # Example
 ///
@@ -74,26 +263,14 @@ impl LogConfig {
 /// ```
 pub fn init_logging(config: &LogConfig) -> Result<()> {
     // Ensure the parent directory exists
-    if let Some(parent) = config.path.parent() {
-        if !parent.exists() {
-            std::fs::create_dir_all(parent)?;
-        }
-    }
-
-    // Check if we need to rotate the log file
-    if config.max_size > 0 && config.path.exists() {
-        if let Ok(metadata) = std::fs::metadata(&config.path) {
-            if metadata.len() > config.max_size {
-                rotate_log(&config.path)?;
-            }
-        }
+    if let Some(parent) = config.path.parent()
+        && !parent.exists()
+    {
+        std::fs::create_dir_all(parent)?;
     }
 
-    // Open or create the log file
-    let log_file = OpenOptions::new()
-        .create(true)
-        .append(true)
-        .open(&config.path)
+    // Open or create the log file, rotating it if it is already too large
+    let log_file = RotatingFile::open(config.path.clone(), config.max_size, config.max_archives)
         .map_err(|e| anyhow!("Failed to open log file: {}", e))?;
 
     // Build logger configuration with timestamps
@@ -112,7 +289,10 @@ pub fn init_logging(config: &LogConfig) -> Result<()> {
     let mut loggers: Vec<Box<dyn SharedLogger>> = vec![];
 
     // File logger at configured level
-    loggers.push(WriteLogger::new(config.level, file_config, log_file));
+    match config.format {
+        LogFormat::Text => loggers.push(WriteLogger::new(config.level, file_config, log_file)),
+        LogFormat::Json => loggers.push(JsonLogger::new(config.level, log_file)),
+    }
 
     // Terminal logger at Warn level (only important messages)
     // Only add terminal logger if we're running in a terminal
@@ -139,22 +319,44 @@ fn atty_check() -> bool {
     std::env::var("TERM").is_ok()
 }
 
-/// Rotates the log file by renaming it with a timestamp suffix.
-fn rotate_log(path: &PathBuf) -> Result<()> {
-    let timestamp = chrono::Local::now().format("%Y%m%d_%H%M%S");
-    let rotated_name = format!(
-        "{}.{}",
-        path.file_name()
-            .and_then(|n| n.to_str())
-            .unwrap_or("passmgr.log"),
-        timestamp
-    );
+/// Returns the path of the `n`th archive of the log file at `path`.
+fn archive_path(path: &Path, n: usize) -> PathBuf {
+    let mut name = path
+        .file_name()
+        .map(|name| name.to_os_string())
+        .unwrap_or_else(|| "passmgr.log".into());
+    name.push(format!(".{}", n));
+    path.with_file_name(name)
+}
 
-    let rotated_path = path.with_file_name(rotated_name);
-    std::fs::rename(path, &rotated_path)?;
+/// Rotates the log file into numbered archives.
+///
+/// `passmgr.log.1` becomes `passmgr.log.2` and so on, the current file
+/// becomes `passmgr.log.1`, and archives beyond `max_archives` are deleted.
+/// With `max_archives` 0 the current file is simply deleted.
+///
+/// Nothing is logged here: this runs while the logger holds its lock.
+fn rotate_log(path: &Path, max_archives: usize) -> io::Result<()> {
+    // Delete the oldest archive and any left over from a larger limit
+    let mut n = max_archives.max(1);
+    while archive_path(path, n).exists() {
+        std::fs::remove_file(archive_path(path, n))?;
+        n += 1;
+    }
+    if max_archives == 0 {
+        return match std::fs::remove_file(path) {
+            Err(e) if e.kind() != io::ErrorKind::NotFound => Err(e),
+            _ => Ok(()),
+        };
+    }
 
-    log::info!("Rotated log file to: {}", rotated_path.display());
-    Ok(())
+    for n in (1..max_archives).rev() {
+        let from = archive_path(path, n);
+        if from.exists() {
+            std::fs::rename(from, archive_path(path, n + 1))?;
+        }
+    }
+    std::fs::rename(path, archive_path(path, 1))
 }
 
 /// Log a timed operation.
@@ -191,17 +393,165 @@ mod tests {
         let config = LogConfig::default();
         assert_eq!(config.level, LevelFilter::Info);
         assert_eq!(config.max_size, 10 * 1024 * 1024);
+        assert_eq!(config.max_archives, 5);
+        assert_eq!(config.format, LogFormat::Text);
     }
 
     #[test]
     fn test_log_config_builder() {
         let config = LogConfig::new(PathBuf::from("/tmp/test.log"))
             .with_level(LevelFilter::Trace)
-            .with_max_size(1024);
+            .with_max_size(1024)
+            .with_max_archives(2);
 
         assert_eq!(config.path, PathBuf::from("/tmp/test.log"));
         assert_eq!(config.level, LevelFilter::Trace);
         assert_eq!(config.max_size, 1024);
+        assert_eq!(config.max_archives, 2);
+        assert_eq!(config.with_format(LogFormat::Json).format, LogFormat::Json);
+    }
+
+    #[test]
+    fn test_parse_log_format() {
+        assert_eq!("text".parse(), Ok(LogFormat::Text));
+        assert_eq!("json".parse(), Ok(LogFormat::Json));
+        assert!("xml".parse::<LogFormat>().is_err());
+    }
+
+    #[test]
+    fn test_json_logger_writes_parseable_lines() {
+        let logger = JsonLogger::new(LevelFilter::Info, Vec::new());
+        logger.log(
+            &Record::builder()
+                .args(format_args!("Added credential: {}", "github"))
+                .level(log::Level::Info)
+                .target("passmgr::shell")
+                .build(),
+        );
+        logger.log(
+            &Record::builder()
+                .args(format_args!("two\nlines"))
+                .level(log::Level::Warn)
+                .target("passmgr")
+                .build(),
+        );
+        // Below the configured level
+        logger.log(
+            &Record::builder()
+                .args(format_args!("ignored"))
+                .level(log::Level::Debug)
+                .build(),
+        );
+
+        let output = String::from_utf8(logger.writer.into_inner().unwrap()).unwrap();
+        let lines: Vec<&str> = output.lines().collect();
+        assert_eq!(lines.len(), 2);
+
+        let first: serde_json::Value = serde_json::from_str(lines[0]).unwrap();
+        assert_eq!(first["level"], "INFO");
+        assert_eq!(first["target"], "passmgr::shell");
+        assert_eq!(first["message"], "Added credential: github");
+        assert!(DateTime::parse_from_rfc3339(first["timestamp"].as_str().unwrap()).is_ok());
+
+        let second: serde_json::Value = serde_json::from_str(lines[1]).unwrap();
+        assert_eq!(second["message"], "two\nlines");
+    }
+
+    /// Returns the contents of the log file and its archives, current first.
+    fn log_files(path: &Path) -> Vec<String> {
+        let mut files = vec![std::fs::read_to_string(path).unwrap_or_default()];
+        let mut n = 1;
+        while let Ok(contents) = std::fs::read_to_string(archive_path(path, n)) {
+            files.push(contents);
+            n += 1;
+        }
+        files
+    }
+
+    #[test]
+    fn test_rotate_log_shifts_archives() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let path = temp_dir.path().join("passmgr.log");
+
+        for contents in ["first", "second", "third"] {
+            std::fs::write(&path, contents).unwrap();
+            rotate_log(&path, 3).unwrap();
+        }
+
+        assert!(!path.exists());
+        assert_eq!(log_files(&path), ["", "third", "second", "first"]);
+    }
+
+    #[test]
+    fn test_rotate_log_deletes_oldest_archives() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let path = temp_dir.path().join("passmgr.log");
+        for n in 1..=4 {
+            std::fs::write(archive_path(&path, n), format!("archive {}", n)).unwrap();
+        }
+
+        // Lowering the limit drops every archive beyond it
+        std::fs::write(&path, "current").unwrap();
+        rotate_log(&path, 2).unwrap();
+
+        assert_eq!(log_files(&path), ["", "current", "archive 1"]);
+        assert!(!archive_path(&path, 3).exists());
+        assert!(!archive_path(&path, 4).exists());
+    }
+
+    #[test]
+    fn test_rotate_log_without_archives() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let path = temp_dir.path().join("passmgr.log");
+        std::fs::write(&path, "current").unwrap();
+        std::fs::write(archive_path(&path, 1), "old").unwrap();
+
+        rotate_log(&path, 0).unwrap();
+
+        assert!(!path.exists());
+        assert!(!archive_path(&path, 1).exists());
+    }
+
+    #[test]
+    fn test_rotating_file_rotates_between_lines() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let path = temp_dir.path().join("passmgr.log");
+        let mut file = RotatingFile::open(path.clone(), 10, 2).unwrap();
+
+        // A record written in pieces stays in one file
+        write!(file, "line one ").unwrap();
+        writeln!(file, "continued").unwrap();
+        writeln!(file, "line two").unwrap();
+        writeln!(file, "line three").unwrap();
+        writeln!(file, "line four").unwrap();
+        file.flush().unwrap();
+
+        assert_eq!(
+            log_files(&path),
+            [
+                "line four\n",
+                "line two\nline three\n",
+                "line one continued\n"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_rotating_file_rotates_oversized_file_on_open() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let path = temp_dir.path().join("passmgr.log");
+        std::fs::write(&path, "from the last run\n").unwrap();
+
+        let mut file = RotatingFile::open(path.clone(), 10, 1).unwrap();
+        writeln!(file, "new").unwrap();
+
+        assert_eq!(log_files(&path), ["new\n", "from the last run\n"]);
+
+        // Files within the limit are appended to
+        drop(file);
+        let mut file = RotatingFile::open(path.clone(), 10, 1).unwrap();
+        writeln!(file, "more").unwrap();
+        assert_eq!(log_files(&path), ["new\nmore\n", "from the last run\n"]);
     }
 
     #[test]
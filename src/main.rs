@@ -2,133 +2,204 @@
 //!
 //! This is the main entry point for the passmgr binary.
 
+mod backup;
+mod cli;
 mod config;
 mod credentials;
 mod crypto;
+mod lockfile;
+mod lockout;
 mod logging;
 mod manager;
+mod pwned;
 mod shell;
 mod storage;
+mod strength;
 mod trie;
+mod wordlist;
 
-use config::{get_log_path, get_password_db};
-use log::LevelFilter;
+use backup::previous_backup_path;
+use clap::Parser;
+use cli::{Action, Cli, MASTER_PASSWORD_ENV, resolve_master_password};
+use config::{AppConfig, DEFAULT_VAULT, discover_vaults};
+use crypto::constant_time_eq;
 use logging::{LogConfig, init_logging};
-use manager::Manager;
+use manager::{LoadError, Manager, ManagerError};
+use shell::ShellConfig;
+use shell::command::{CommandRegistry, CommandResult, OutputFormat};
+use shell::commands::register_all;
+use shell::highlighter::{Theme, no_color_requested};
+use shell::history::HistoryConfig;
+use std::io::{self, IsTerminal, Write};
+use std::path::Path;
+use std::process::ExitCode;
 
-fn main() {
-    // Initialize logging
-    if let Ok(log_path) = get_log_path() {
-        let log_config = LogConfig::new(log_path)
-            .with_level(LevelFilter::Info)
-            .with_max_size(100);
-        if let Err(e) = init_logging(&log_config) {
-            eprintln!("Warning: Failed to initialize logging: {}", e);
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+
+    // Needs neither configuration nor a vault
+    if cli.dump_commands {
+        return dump_commands();
+    }
+
+    let config = match AppConfig::load(cli.profile.as_deref()) {
+        Ok(config) => config,
+        Err(e) => {
+            eprintln!("Error: could not load configuration: {}", e);
+            return ExitCode::FAILURE;
         }
+    };
+
+    // Initialize logging
+    let log_config = LogConfig::new(config.log_path.clone())
+        .with_level(config.log_level)
+        .with_format(config.log_format)
+        .with_max_size(config.log_max_size)
+        .with_max_archives(config.log_max_archives);
+    if let Err(e) = init_logging(&log_config) {
+        eprintln!("Warning: Failed to initialize logging: {}", e);
     }
 
     log::info!("Passmgr starting");
-    println!("Welcome to passmgr!");
 
-    let pwd_db = match get_password_db() {
-        Ok(path) => {
-            println!("Using password database at: {}", path.display());
-            log::debug!("Database path: {}", path.display());
-            path
+    let pwd_db = config.db_path.clone();
+    log::debug!("Database path: {}", pwd_db.display());
+
+    let mut manager = Manager::new();
+    match discover_vaults(&config.data_dir) {
+        Ok(vaults) => {
+            for (name, path) in vaults {
+                manager.add_vault(name, path);
+            }
         }
+        Err(e) => log::warn!("Could not discover vaults: {}", e),
+    }
+    manager.add_vault(DEFAULT_VAULT, pwd_db.clone());
+    manager.set_db_path(pwd_db.clone());
+    manager.set_backup_count(config.backup_count);
+    manager.set_lockout_policy(config.lockout);
+    manager.set_force_lock(cli.force);
+    manager.set_cipher(config.cipher);
+    manager.set_credential_limits(config.credential_limits);
+    manager.set_default_rotate_after(config.rotate_after);
+    manager.set_shell_config(shell_config(&config, &cli, manager.is_new_user()));
+
+    if cli.since_version {
+        report_migration(&manager);
+        return ExitCode::SUCCESS;
+    }
+
+    if let Some(action) = &cli.action {
+        return run_one_shot(&mut manager, action, cli.password_stdin);
+    }
+
+    if cli.recover {
+        if let Err(code) = recover_vault(&mut manager) {
+            return code;
+        }
+        if let Err(e) = manager.run() {
+            eprintln!("Error: {}", e);
+            log::error!("Shell error: {}", e);
+            return ExitCode::FAILURE;
+        }
+        log::info!("Passmgr exiting");
+        return ExitCode::SUCCESS;
+    }
+
+    println!("Welcome to passmgr!");
+    println!("Using password database at: {}", pwd_db.display());
+
+    let supplied = match supplied_master_password(cli.password_stdin) {
+        Ok(supplied) => supplied,
         Err(e) => {
-            eprintln!(
-                "Error: could not determine password database location: {}",
-                e
-            );
-            log::error!("Failed to get database path: {}", e);
-            return;
+            eprintln!("Error: {}", e);
+            log::error!("No master password available: {}", e);
+            return ExitCode::FAILURE;
         }
     };
 
-    let mut manager = Manager::new();
-    manager.set_db_path(pwd_db);
-
     if manager.is_new_user() {
         println!("No password database found. Let's set up a new one!");
         println!("Please create a MASTER password to encrypt your credentials.");
-        println!("IMPORTANT: If you forget this password, your data cannot be recovered!");
+        println!(
+            "IMPORTANT: If you forget this password, your data cannot be recovered without a recovery key!"
+        );
 
         log::info!("Setting up new user");
 
-        match rpassword::prompt_password("New Master Password: ") {
-            Ok(pwd) => {
-                let pwd = pwd.trim().to_string();
-                if pwd.is_empty() {
-                    eprintln!("Error: master password cannot be empty");
-                    log::warn!("Empty master password attempted");
-                    return;
-                }
+        let interactive = supplied.is_none();
 
-                match rpassword::prompt_password("Confirm Master Password: ") {
-                    Ok(confirm_pwd) => {
-                        let confirm_pwd = confirm_pwd.trim().to_string();
-                        if pwd != confirm_pwd {
-                            eprintln!("Error: passwords do not match");
-                            log::warn!("Password confirmation failed");
-                            return;
-                        }
-
-                        if let Err(e) = manager.setup_new_user(pwd) {
-                            eprintln!("Error setting up new user: {}", e);
-                            log::error!("Failed to setup new user: {}", e);
-                            return;
-                        }
-
-                        println!("New password database created successfully!");
-                        log::info!("New user setup completed");
-                    }
-                    Err(_) => {
-                        eprintln!("Error: failed to read password confirmation");
-                        log::error!("Failed to read password confirmation");
-                        return;
-                    }
+        let pwd = match supplied {
+            Some(pwd) => pwd,
+            None => match prompt_new_master_password() {
+                Some(pwd) => pwd,
+                None => return ExitCode::FAILURE,
+            },
+        };
+
+        if let Err(e) = manager.setup_new_user(pwd) {
+            eprintln!("Error setting up new user: {}", e);
+            log::error!("Failed to setup new user: {}", e);
+            return ExitCode::FAILURE;
+        }
+
+        println!("New password database created successfully!");
+        log::info!("New user setup completed");
+
+        if interactive
+            && confirm("Generate a recovery key in case you forget your master password? [y/N] ")
+        {
+            match manager.enable_recovery() {
+                Ok(key) => {
+                    println!();
+                    println!("Recovery key: {}", key);
+                    println!();
+                    println!(
+                        "Write this down and keep it somewhere safe. It will not be shown again."
+                    );
+                    println!("Run `passmgr --recover` to unlock your vault with it.");
+                }
+                Err(e) => {
+                    eprintln!("Error generating recovery key: {}", e);
+                    log::error!("Failed to generate recovery key: {}", e);
                 }
-            }
-            Err(_) => {
-                eprintln!("Error: failed to read master password");
-                log::error!("Failed to read master password");
-                return;
             }
         }
     } else {
-        println!("Please enter your MASTER password to unlock your credentials.");
-
-        match rpassword::prompt_password("Master Password: ") {
-            Ok(pwd) => {
-                let pwd = pwd.trim().to_string();
-                if pwd.is_empty() {
-                    eprintln!("Error: master password cannot be empty");
-                    log::warn!("Empty password attempted");
-                    return;
-                }
-
-                match manager.validate_master_password(pwd) {
-                    Ok(true) => {
-                        println!("Password database unlocked successfully!");
-                        log::info!("Database unlocked");
-                    }
-                    Ok(false) => {
-                        eprintln!("Error: invalid master password");
-                        log::warn!("Invalid password attempt");
-                        return;
-                    }
-                    Err(e) => {
-                        eprintln!("Error validating password: {}", e);
-                        log::error!("Password validation error: {}", e);
-                        return;
+        let pwd = match supplied {
+            Some(pwd) => pwd,
+            None => {
+                println!("Please enter your MASTER password to unlock your credentials.");
+                match rpassword::prompt_password("Master Password: ") {
+                    Ok(pwd) => pwd.trim().to_string(),
+                    Err(_) => {
+                        eprintln!("Error: failed to read master password");
+                        log::error!("Failed to read master password");
+                        return ExitCode::FAILURE;
                     }
                 }
             }
-            Err(_) => {
-                eprintln!("Error: failed to read master password");
-                log::error!("Failed to read master password");
-                return;
+        };
+
+        if pwd.is_empty() {
+            eprintln!("Error: master password cannot be empty");
+            log::warn!("Empty password attempted");
+            return ExitCode::FAILURE;
+        }
+
+        match manager.validate_master_password(pwd) {
+            Ok(true) => {
+                println!("Password database unlocked successfully!");
+                log::info!("Database unlocked");
+            }
+            Ok(false) => {
+                eprintln!("Error: invalid master password, please try again");
+                log::warn!("Invalid password attempt");
+                return ExitCode::FAILURE;
+            }
+            Err(e) => {
+                report_unlock_error(&e, manager.db_path());
+                return ExitCode::FAILURE;
             }
         }
     }
@@ -136,7 +207,267 @@ fn main() {
     if let Err(e) = manager.run() {
         eprintln!("Error: {}", e);
         log::error!("Shell error: {}", e);
+        return ExitCode::FAILURE;
     }
 
     log::info!("Passmgr exiting");
+    ExitCode::SUCCESS
+}
+
+/// Unlocks the vault, runs a single command and maps its result to an exit code.
+///
+/// Only the command's own output goes to stdout, so `passmgr get NAME` can be
+/// piped.
+fn run_one_shot(manager: &mut Manager, action: &Action, password_stdin: bool) -> ExitCode {
+    if manager.is_new_user() {
+        eprintln!("Error: no password database found; run passmgr without a command to create one");
+        return ExitCode::FAILURE;
+    }
+
+    let password = match supplied_master_password(password_stdin) {
+        Ok(Some(pwd)) => pwd,
+        Ok(None) => match rpassword::prompt_password("Master Password: ") {
+            Ok(pwd) => pwd.trim().to_string(),
+            Err(_) => {
+                eprintln!("Error: failed to read master password");
+                log::error!("Failed to read master password");
+                return ExitCode::FAILURE;
+            }
+        },
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            log::error!("No master password available: {}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    match manager.validate_master_password(password) {
+        Ok(true) => log::info!("Database unlocked for one-shot command"),
+        Ok(false) => {
+            eprintln!("Error: invalid master password, please try again");
+            log::warn!("Invalid password attempt");
+            return ExitCode::FAILURE;
+        }
+        Err(e) => {
+            report_unlock_error(&e, manager.db_path());
+            return ExitCode::FAILURE;
+        }
+    }
+
+    let result = manager.run_once(&action.to_args());
+    manager.clear_master_password();
+
+    match result {
+        CommandResult::Success(Some(msg)) => {
+            println!("{}", msg);
+            ExitCode::SUCCESS
+        }
+        CommandResult::Json(value) => {
+            println!("{:#}", value);
+            ExitCode::SUCCESS
+        }
+        CommandResult::Success(None)
+        | CommandResult::Continue
+        | CommandResult::Exit
+        | CommandResult::Lock => ExitCode::SUCCESS,
+        CommandResult::Error(msg) => {
+            eprintln!("Error: {}", msg);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// Tells the user why the vault could not be unlocked, other than a wrong
+/// password: a damaged or unreadable file needs a different fix.
+fn report_unlock_error(e: &anyhow::Error, db_path: Option<&Path>) {
+    match e.downcast_ref::<LoadError>() {
+        Some(LoadError::CorruptStore(reason)) => {
+            eprintln!("Error: the password database is damaged ({})", reason);
+            match db_path {
+                Some(path) => eprintln!(
+                    "Re-entering the master password will not help; restore the database from a backup such as {}",
+                    previous_backup_path(path).display()
+                ),
+                None => eprintln!(
+                    "Re-entering the master password will not help; restore the database from a backup"
+                ),
+            }
+        }
+        Some(LoadError::Io(err)) => {
+            eprintln!("Error: could not read the password database: {}", err);
+        }
+        _ => match e.downcast_ref::<ManagerError>() {
+            Some(err @ ManagerError::VaultInUse { .. }) => eprintln!("Error: {}", err),
+            _ => eprintln!("Error validating password: {}", e),
+        },
+    }
+    log::error!("Password validation error: {}", e);
+}
+
+/// Returns the master password supplied via `--password-stdin` or `PASSMGR_MASTER`.
+///
+/// `Ok(None)` means the caller should prompt for it. When nothing was supplied
+/// and stdin is not a terminal, prompting would hang, so this is an error.
+fn supplied_master_password(password_stdin: bool) -> Result<Option<String>, String> {
+    let stdin = io::stdin();
+    let is_tty = stdin.is_terminal();
+    resolve_master_password(
+        password_stdin,
+        stdin.lock(),
+        std::env::var(MASTER_PASSWORD_ENV).ok(),
+        is_tty,
+    )
+}
+
+/// Unlocks the vault with its recovery key, then sets a new master password.
+fn recover_vault(manager: &mut Manager) -> Result<(), ExitCode> {
+    if manager.is_new_user() {
+        eprintln!("Error: no password database found; nothing to recover");
+        return Err(ExitCode::FAILURE);
+    }
+
+    let key = match rpassword::prompt_password("Recovery Key: ") {
+        Ok(key) => key,
+        Err(_) => {
+            eprintln!("Error: failed to read recovery key");
+            log::error!("Failed to read recovery key");
+            return Err(ExitCode::FAILURE);
+        }
+    };
+
+    if let Err(e) = manager.recover(&key) {
+        eprintln!("Error: {}", e);
+        log::warn!("Recovery failed: {}", e);
+        return Err(ExitCode::FAILURE);
+    }
+
+    println!("Vault unlocked with the recovery key. Please choose a new MASTER password.");
+    let pwd = prompt_new_master_password().ok_or(ExitCode::FAILURE)?;
+    if let Err(e) = manager.change_master_password(pwd) {
+        eprintln!("Error setting new master password: {}", e);
+        log::error!("Failed to set master password after recovery: {}", e);
+        return Err(ExitCode::FAILURE);
+    }
+
+    println!("Master password changed. Your recovery key still works.");
+    log::info!("Master password reset with recovery key");
+    Ok(())
+}
+
+/// Asks a yes/no question on stdin, defaulting to no.
+fn confirm(question: &str) -> bool {
+    print!("{}", question);
+    let _ = io::stdout().flush();
+
+    let mut answer = String::new();
+    if io::stdin().read_line(&mut answer).is_err() {
+        return false;
+    }
+    matches!(answer.trim().to_lowercase().as_str(), "y" | "yes")
+}
+
+/// Builds the shell configuration from the loaded config and command line.
+///
+/// `--no-color` and `NO_COLOR` replace the configured theme with the plain one.
+/// A vault about to be created gets the onboarding message unless
+/// `--no-welcome` is given.
+fn shell_config(config: &AppConfig, cli: &Cli, new_user: bool) -> ShellConfig {
+    let theme = if no_color_requested(cli.no_color, std::env::var_os("NO_COLOR")) {
+        Theme::plain()
+    } else {
+        config.theme.clone()
+    };
+
+    ShellConfig {
+        history: HistoryConfig::new(config.history_path.clone())
+            .with_max_entries(config.history_size)
+            .with_ignore_dups(config.history_ignore_dups)
+            .with_ignore_space(config.history_ignore_space),
+        show_welcome: !cli.no_welcome,
+        onboarding: new_user,
+        theme,
+        idle_timeout: config.idle_timeout,
+        show_idle_countdown: config.show_idle_countdown,
+        reveal_secrets: config.default_reveal,
+        key_cache: config.key_cache_path.clone(),
+        pager_threshold: config.pager_threshold,
+        name_normalization: config.name_normalization,
+        prompt: config.prompt.clone(),
+        confirm_destructive: config.confirm_destructive,
+        track_access: config.track_access,
+        warn_weak_secrets: config.warn_weak_secrets,
+        output_format: if cli.json {
+            OutputFormat::Json
+        } else {
+            OutputFormat::Text
+        },
+    }
+}
+
+/// Prints every shell command's name, aliases, usage and description as JSON.
+fn dump_commands() -> ExitCode {
+    let mut registry = CommandRegistry::new();
+    register_all(&mut registry);
+    match serde_json::to_string_pretty(&registry.describe()) {
+        Ok(json) => {
+            println!("{}", json);
+            ExitCode::SUCCESS
+        }
+        Err(e) => {
+            eprintln!("Error: could not describe commands: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// Prompts twice for a new master password, returning it if both entries match.
+fn prompt_new_master_password() -> Option<String> {
+    let pwd = match rpassword::prompt_password("New Master Password: ") {
+        Ok(pwd) => pwd.trim().to_string(),
+        Err(_) => {
+            eprintln!("Error: failed to read master password");
+            log::error!("Failed to read master password");
+            return None;
+        }
+    };
+
+    if pwd.is_empty() {
+        eprintln!("Error: master password cannot be empty");
+        log::warn!("Empty master password attempted");
+        return None;
+    }
+
+    match rpassword::prompt_password("Confirm Master Password: ") {
+        Ok(confirm_pwd) => {
+            if !constant_time_eq(pwd.as_bytes(), confirm_pwd.trim().as_bytes()) {
+                eprintln!("Error: passwords do not match");
+                log::warn!("Password confirmation failed");
+                return None;
+            }
+            Some(pwd)
+        }
+        Err(_) => {
+            eprintln!("Error: failed to read password confirmation");
+            log::error!("Failed to read password confirmation");
+            None
+        }
+    }
+}
+
+/// Prints whether the password database will be upgraded by this build.
+fn report_migration(manager: &Manager) {
+    if manager.is_new_user() {
+        println!("No password database found; nothing to migrate.");
+        return;
+    }
+
+    match manager.migration_plan() {
+        Some((from, to)) => {
+            println!(
+                "Password database uses format version {}; it will be upgraded to version {} on the next save.",
+                from, to
+            );
+        }
+        None => println!("Password database is up to date."),
+    }
 }
@@ -3,29 +3,253 @@
 //! This module handles credential management, encryption, and persistence.
 
 use anyhow::{Result, anyhow};
-use serde_json;
-use std::collections::HashMap;
-use std::fs;
-use std::path::PathBuf;
-
-use crate::config::{DEFAULT_HISTORY_SIZE, get_history_path};
-use crate::credentials::Credentials;
-use crate::crypto::{decrypt, derive_key, encrypt, generate_nonce, generate_salt};
-use crate::shell::history::HistoryConfig;
-use crate::shell::{Shell, ShellConfig};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use crate::backup::{
+    BackupReport, BackupStatus, backup_before_save, backup_dir_for, list_backups,
+    previous_backup_path, remove_backups,
+};
+use crate::credentials::{CredentialLimits, Credentials};
+use crate::crypto::{
+    AuthenticationError, Cipher, KEY_WRAP_CIPHER, KdfParams, decrypt, decrypt_in_place,
+    derive_key_with_params, encrypt, generate_data_key, generate_nonce, generate_recovery_key,
+    generate_salt, normalize_recovery_key,
+};
+use crate::lockfile::{LockError, VaultLock};
+use crate::lockout::{self, LockoutOutcome, LockoutPolicy, now_secs, remaining_cooldown};
+use crate::shell::command::CommandResult;
+use crate::shell::session::Session;
+use crate::shell::suspend::suspend_process;
+use crate::shell::{Shell, ShellConfig, ShellExit, read_master_password, unlock_session};
 use crate::storage::{
-    EncryptedStore, decode_encrypted_data, decode_nonce, decode_salt, encode_encrypted_data,
-    encode_nonce, encode_salt, load_encrypted_store, save_encrypted_store,
+    CryptoInfo, EncryptedStore, FileBackend, RecoverySlot, STORE_VERSION, StorageBackend,
+    WrappedKey, decode_encrypted_data, decode_nonce, decode_salt, decoded_len,
+    encode_encrypted_data, encode_nonce, encode_salt, load_encrypted_store,
 };
 
+/// Errors raised by the manager that callers may want to match on.
+///
+/// These are returned wrapped in [`anyhow::Error`]; use `downcast_ref` to inspect them.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ManagerError {
+    /// The master password is empty.
+    EmptyMasterPassword,
+    /// The vault has no recovery key set up.
+    RecoveryNotEnabled,
+    /// The recovery key does not unlock the vault.
+    InvalidRecoveryKey,
+    /// Too many failed unlock attempts; unlocking is refused for this many seconds.
+    LockedOut { retry_after: u64 },
+    /// Too many failed unlock attempts; the vault and its backups were deleted.
+    VaultWiped,
+    /// The vault is open in another running passmgr process.
+    VaultInUse { pid: u32 },
+}
+
+impl fmt::Display for ManagerError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ManagerError::EmptyMasterPassword => write!(f, "master password cannot be empty"),
+            ManagerError::RecoveryNotEnabled => {
+                write!(f, "no recovery key is set up for this vault")
+            }
+            ManagerError::InvalidRecoveryKey => write!(f, "invalid recovery key"),
+            ManagerError::LockedOut { retry_after } => write!(
+                f,
+                "too many failed unlock attempts; try again in {}s",
+                retry_after
+            ),
+            ManagerError::VaultWiped => {
+                write!(
+                    f,
+                    "too many failed unlock attempts; the vault has been wiped"
+                )
+            }
+            ManagerError::VaultInUse { pid } => write!(
+                f,
+                "the vault is open in another passmgr process (PID {}); close it, \
+                 or pass --force if it is not responding",
+                pid
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ManagerError {}
+
+/// Why a vault could not be unlocked with a master password.
+///
+/// Returned wrapped in [`anyhow::Error`] by [`Manager::validate_master_password`]
+/// for anything but a wrong password; use `downcast_ref` to inspect it.
+#[derive(Debug)]
+pub enum LoadError {
+    /// The master password is wrong.
+    WrongPassword,
+    /// The vault file is damaged or is not a passmgr vault.
+    CorruptStore(String),
+    /// The vault file could not be read.
+    Io(io::Error),
+}
+
+impl LoadError {
+    /// Classifies an error raised while loading or unlocking a store.
+    ///
+    /// I/O errors are [`LoadError::Io`] and anything that isn't already a
+    /// `LoadError` is treated as corruption.
+    fn classify(e: anyhow::Error) -> Self {
+        match e.downcast::<LoadError>() {
+            Ok(e) => e,
+            Err(e) => match e.downcast::<io::Error>() {
+                Ok(e) => LoadError::Io(e),
+                Err(e) => LoadError::CorruptStore(e.to_string()),
+            },
+        }
+    }
+}
+
+impl fmt::Display for LoadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LoadError::WrongPassword => write!(f, "wrong master password"),
+            LoadError::CorruptStore(reason) => write!(f, "vault file is damaged: {}", reason),
+            LoadError::Io(e) => write!(f, "could not read vault file: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for LoadError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            LoadError::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
 /// The password manager.
 pub struct Manager {
     /// Stored credentials.
     credentials: Credentials,
-    /// Path to the password database.
-    pwd_db_path: Option<PathBuf>,
+    /// Where the password database is stored.
+    backend: Option<Box<dyn StorageBackend>>,
     /// Master password (kept only while needed).
     master_password: Option<String>,
+    /// Named vaults that can be switched to, by name.
+    vaults: BTreeMap<String, PathBuf>,
+    /// Data key (and recovery slot) of the unlocked vault.
+    envelope: Option<Envelope>,
+    /// Configuration for the interactive shell, if not the default.
+    shell_config: Option<ShellConfig>,
+    /// Number of timestamped backups kept in addition to `<db>.bak`.
+    backup_count: usize,
+    /// What happens after repeated failed unlock attempts, if anything.
+    lockout: Option<LockoutPolicy>,
+    /// Cipher the credentials are encrypted with on the next save.
+    cipher: Cipher,
+    /// Limits on names and secrets added to the unlocked vault.
+    credential_limits: CredentialLimits,
+    /// Rotation interval for credentials without their own.
+    rotate_after: Option<Duration>,
+    /// Lock file marking the unlocked vault as in use by this process.
+    vault_lock: Option<VaultLock>,
+    /// Whether to take over a vault locked by another running process.
+    force_lock: bool,
+    /// Whether the credentials may differ from the vault file, because they
+    /// were changed and not saved, or saving them failed.
+    unsaved_changes: bool,
+}
+
+/// A configured vault as reported by [`Manager::vaults`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VaultInfo {
+    /// Name used to switch to the vault.
+    pub name: String,
+    /// Path to the vault's encrypted store.
+    pub path: PathBuf,
+    /// Whether this is the vault currently unlocked.
+    pub active: bool,
+}
+
+/// Outcome of one check made by [`Manager::verify`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CheckStatus {
+    /// The check succeeded.
+    Passed,
+    /// The check failed for this reason.
+    Failed(String),
+    /// The check could not run because an earlier one failed.
+    Skipped,
+}
+
+/// A single named check made by [`Manager::verify`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VerifyCheck {
+    /// What was checked.
+    pub name: &'static str,
+    /// Whether it passed.
+    pub status: CheckStatus,
+}
+
+/// Result of checking the vault file with [`Manager::verify`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct VerifyReport {
+    /// Every check, in the order they were made.
+    pub checks: Vec<VerifyCheck>,
+}
+
+impl VerifyReport {
+    /// Returns whether every check passed.
+    pub fn passed(&self) -> bool {
+        self.checks
+            .iter()
+            .all(|check| check.status == CheckStatus::Passed)
+    }
+
+    /// Records the outcome of a check, returning its value if it passed.
+    fn record<T>(&mut self, name: &'static str, result: Result<T>) -> Option<T> {
+        let (status, value) = match result {
+            Ok(value) => (CheckStatus::Passed, Some(value)),
+            Err(e) => (CheckStatus::Failed(e.to_string()), None),
+        };
+        self.checks.push(VerifyCheck { name, status });
+        value
+    }
+}
+
+/// Names of the checks made by [`Manager::verify`], in order.
+const VERIFY_CHECKS: [&str; 6] = [
+    "Store file is readable JSON",
+    "Base64 fields decode",
+    "Nonces are 12 bytes",
+    "Salts are 16 bytes",
+    "Decrypts with the master password",
+    "Credentials deserialize",
+];
+
+/// Keys held in memory while a vault is unlocked.
+struct Envelope {
+    /// Random key the credentials are encrypted with.
+    data_key: [u8; 32],
+    /// The data key wrapped under the recovery key, written back unchanged on save.
+    recovery: Option<RecoverySlot>,
+    /// Argon2id parameters the master password is derived with on save.
+    kdf_params: KdfParams,
+}
+
+impl Envelope {
+    /// Creates an envelope with a fresh data key, no recovery key and the
+    /// default Argon2id parameters.
+    fn generate() -> Result<Self> {
+        Ok(Self {
+            data_key: generate_data_key()?,
+            recovery: None,
+            kdf_params: KdfParams::default(),
+        })
+    }
 }
 
 impl Manager {
@@ -33,142 +257,539 @@ impl Manager {
     pub fn new() -> Self {
         Self {
             credentials: Credentials::new(),
-            pwd_db_path: None,
+            backend: None,
             master_password: None,
+            vaults: BTreeMap::new(),
+            envelope: None,
+            shell_config: None,
+            backup_count: 0,
+            lockout: None,
+            cipher: Cipher::default(),
+            credential_limits: CredentialLimits::default(),
+            rotate_after: None,
+            vault_lock: None,
+            force_lock: false,
+            unsaved_changes: false,
         }
     }
 
     /// Sets the database path.
     pub fn set_db_path(&mut self, path: PathBuf) {
-        self.pwd_db_path = Some(path);
+        self.backend = Some(Box::new(FileBackend::new(path)));
+    }
+
+    /// Stores the database in `backend` instead of a file.
+    ///
+    /// Backups and the lock file are only kept for backends with a path.
+    #[allow(unused)]
+    pub fn set_backend(&mut self, backend: Box<dyn StorageBackend>) {
+        self.backend = Some(backend);
+    }
+
+    /// Returns the backend the database is stored in.
+    fn storage(&self) -> Result<&dyn StorageBackend> {
+        self.backend
+            .as_deref()
+            .ok_or_else(|| anyhow!("Database path not set"))
+    }
+
+    /// Returns the path of the database file, if it is stored in one.
+    pub fn db_path(&self) -> Option<&Path> {
+        self.backend.as_deref()?.path()
+    }
+
+    /// Sets how many timestamped backups to keep, in addition to the copy
+    /// of the previous store that is always made before saving.
+    pub fn set_backup_count(&mut self, count: usize) {
+        self.backup_count = count;
+    }
+
+    /// Sets what happens after repeated failed master-password attempts.
+    ///
+    /// Without a policy, failed attempts are not counted.
+    pub fn set_lockout_policy(&mut self, policy: Option<LockoutPolicy>) {
+        self.lockout = policy;
+    }
+
+    /// Sets the cipher used to encrypt the credentials.
+    ///
+    /// Vaults are always read with the cipher they were written with; the
+    /// new cipher takes effect when the vault is next saved.
+    pub fn set_cipher(&mut self, cipher: Cipher) {
+        self.cipher = cipher;
+    }
+
+    /// Sets the limits on names and secrets added from now on.
+    pub fn set_credential_limits(&mut self, limits: CredentialLimits) {
+        self.credential_limits = limits;
+        self.credentials.set_limits(limits);
+    }
+
+    /// Sets how often secrets should be rotated, for credentials without
+    /// their own interval.
+    pub fn set_default_rotate_after(&mut self, interval: Option<Duration>) {
+        self.rotate_after = interval;
+        self.credentials.set_default_rotate_after(interval);
+    }
+
+    /// Sets whether to open a vault even if another running process has it
+    /// open, taking over its lock.
+    pub fn set_force_lock(&mut self, force: bool) {
+        self.force_lock = force;
+    }
+
+    /// Takes the lock for the vault at `path`, unless this manager already
+    /// holds it.
+    ///
+    /// The lock is returned rather than kept, so callers only replace the
+    /// current one once the vault has actually been opened.
+    fn claim_vault(&self, path: &Path) -> Result<Option<VaultLock>> {
+        if let Some(lock) = &self.vault_lock
+            && lock.db_path() == path
+        {
+            return Ok(None);
+        }
+        match VaultLock::acquire(path, self.force_lock) {
+            Ok(lock) => Ok(Some(lock)),
+            Err(LockError::Held { pid }) => Err(ManagerError::VaultInUse { pid }.into()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Takes the lock for the current vault, if it is stored in a file.
+    fn claim_current(&self) -> Result<Option<VaultLock>> {
+        match self.db_path() {
+            Some(path) => self.claim_vault(path),
+            None => Ok(None),
+        }
+    }
+
+    /// Keeps `claim` as the held vault lock, releasing the previous one.
+    fn keep_claim(&mut self, claim: Option<VaultLock>) {
+        if claim.is_some() {
+            self.vault_lock = claim;
+        }
+    }
+
+    /// Replaces the in-memory credentials, applying the configured limits
+    /// and rotation interval.
+    fn set_credentials(&mut self, mut credentials: Credentials) {
+        credentials.set_limits(self.credential_limits);
+        credentials.set_default_rotate_after(self.rotate_after);
+        self.credentials = credentials;
+    }
+
+    /// Sets the configuration used by the interactive shell.
+    pub fn set_shell_config(&mut self, config: ShellConfig) {
+        self.shell_config = Some(config);
+    }
+
+    /// Registers a named vault, replacing any vault with the same name.
+    pub fn add_vault(&mut self, name: impl Into<String>, path: PathBuf) {
+        self.vaults.insert(name.into(), path);
+    }
+
+    /// Lists the configured vaults in name order.
+    pub fn vaults(&self) -> Vec<VaultInfo> {
+        self.vaults
+            .iter()
+            .map(|(name, path)| VaultInfo {
+                name: name.clone(),
+                path: path.clone(),
+                active: self.db_path() == Some(path.as_path()),
+            })
+            .collect()
+    }
+
+    /// Returns the name of the vault currently in use, if it is a configured vault.
+    #[allow(unused)]
+    pub fn active_vault(&self) -> Option<&str> {
+        let current = self.db_path()?;
+        self.vaults
+            .iter()
+            .find(|(_, path)| *path == current)
+            .map(|(name, _)| name.as_str())
+    }
+
+    /// Switches to another configured vault, unlocking it with `password`.
+    ///
+    /// A vault whose file is missing or empty is initialized with `password`.
+    /// On success the previous vault's credentials and master password are
+    /// cleared from memory; on failure the current vault stays unlocked.
+    ///
+    /// A wrong password counts against the lockout policy of the vault being
+    /// switched to, as with [`Manager::validate_master_password`], and a vault
+    /// file that is damaged or cannot be read is an error holding a
+    /// [`LoadError`].
+    pub fn switch_vault(&mut self, name: &str, password: String) -> Result<()> {
+        if password.is_empty() {
+            return Err(ManagerError::EmptyMasterPassword.into());
+        }
+
+        let path = self
+            .vaults
+            .get(name)
+            .cloned()
+            .ok_or_else(|| anyhow!("Unknown vault: '{}'", name))?;
+
+        let claim = self.claim_vault(&path)?;
+        let backend = FileBackend::new(path);
+        let is_new = !backend.exists();
+        let (credentials, envelope) = if is_new {
+            (Credentials::new(), Envelope::generate()?)
+        } else {
+            unlock_with_lockout(&backend, None, &password, self.lockout)?
+                .ok_or_else(|| anyhow!("Invalid master password for vault '{}'", name))?
+        };
+
+        self.credentials.clear();
+        self.clear_master_password();
+
+        self.set_credentials(credentials);
+        self.backend = Some(Box::new(backend));
+        self.master_password = Some(password);
+        self.envelope = Some(envelope);
+        self.keep_claim(claim);
+
+        if is_new {
+            self.save_credentials()?;
+        }
+
+        log::info!("Switched to vault '{}'", name);
+        Ok(())
     }
 
     /// Checks if this is a new user (no existing database).
     pub fn is_new_user(&self) -> bool {
-        match &self.pwd_db_path {
-            Some(path) => {
-                !path.exists() || fs::metadata(path).map(|m| m.len() == 0).unwrap_or(true)
-            }
-            None => true,
-        }
+        self.backend
+            .as_ref()
+            .is_none_or(|backend| !backend.exists())
     }
 
     /// Sets up a new user with the given master password.
     pub fn setup_new_user(&mut self, master_password: String) -> Result<()> {
-        if self.pwd_db_path.is_none() {
-            return Err(anyhow!("Database path not set"));
+        if master_password.is_empty() {
+            return Err(ManagerError::EmptyMasterPassword.into());
         }
 
+        self.storage()?;
+        let claim = self.claim_current()?;
+
         self.master_password = Some(master_password);
-        self.credentials = Credentials::new();
+        self.set_credentials(Credentials::new());
+        self.envelope = Some(Envelope::generate()?);
 
         // Save empty credentials to create the file
-        self.save_credentials()
+        self.save_credentials()?;
+        self.keep_claim(claim);
+        Ok(())
     }
 
     /// Validates the master password by attempting to load credentials.
+    ///
+    /// Returns `Ok(false)` only if the password is wrong; a vault file that
+    /// is damaged or cannot be read is an error holding a [`LoadError`].
+    ///
+    /// Checking a password costs one Argon2id derivation, which dwarfs
+    /// everything else here. Paths that fail before deriving a key (no vault
+    /// file, a store that doesn't parse) run a throwaway derivation first, so
+    /// how long a failure takes doesn't reveal which check failed. The
+    /// password itself is only ever checked through AEAD authentication,
+    /// never compared byte by byte.
+    ///
+    /// With a lockout policy set, failed attempts are counted in the store
+    /// header, and once the limit is reached this returns
+    /// [`ManagerError::LockedOut`] or wipes the vault and returns
+    /// [`ManagerError::VaultWiped`]. A successful unlock resets the count.
+    ///
+    /// The vault's lock file is taken first; if another running process
+    /// holds it, this returns [`ManagerError::VaultInUse`].
     pub fn validate_master_password(&mut self, password: String) -> Result<bool> {
-        let path = self
-            .pwd_db_path
-            .as_ref()
-            .ok_or_else(|| anyhow!("Database path not set"))?;
+        if password.is_empty() {
+            return Err(ManagerError::EmptyMasterPassword.into());
+        }
 
-        if !path.exists() {
+        if !self.storage()?.exists() {
+            equalize_kdf_timing(&password);
             return Ok(false);
         }
 
-        // Try to load credentials with the provided password
-        match self.load_credentials_with_password(password.clone()) {
-            Ok(_) => {
+        let claim = self.claim_current()?;
+        let unlocked = match self.lockout {
+            Some(policy) => self.validate_with_lockout(password, policy),
+            // Try to load credentials with the provided password
+            None => match self.load_credentials_with_password(password.clone()) {
+                Ok(()) => {
+                    self.master_password = Some(password);
+                    Ok(true)
+                }
+                Err(LoadError::WrongPassword) => Ok(false),
+                Err(e) => Err(e.into()),
+            },
+        };
+        if let Ok(true) = unlocked {
+            self.keep_claim(claim);
+        }
+        unlocked
+    }
+
+    /// Validates the master password, enforcing `policy`.
+    fn validate_with_lockout(&mut self, password: String, policy: LockoutPolicy) -> Result<bool> {
+        match unlock_with_lockout(self.storage()?, None, &password, Some(policy))? {
+            Some((credentials, envelope)) => {
+                self.set_credentials(credentials);
+                self.envelope = Some(envelope);
                 self.master_password = Some(password);
+                log::info!("Loaded {} credentials", self.credentials.len());
                 Ok(true)
             }
-            Err(_) => Ok(false),
+            None => Ok(false),
         }
     }
 
-    /// Loads credentials using the provided password.
-    fn load_credentials_with_password(&mut self, password: String) -> Result<()> {
-        let path = self
-            .pwd_db_path
+    /// Loads credentials from the store at `path` using the provided password.
+    fn load_credentials_with_password(&mut self, password: String) -> Result<(), LoadError> {
+        let store = self
+            .storage()
+            .and_then(|storage| storage.load())
+            .map_err(|e| {
+                equalize_kdf_timing(&password);
+                LoadError::classify(e)
+            })?;
+        let (credentials, envelope) =
+            unlock_store(&store, &password).map_err(LoadError::classify)?;
+        self.set_credentials(credentials);
+        self.envelope = Some(envelope);
+        self.unsaved_changes = false;
+
+        log::info!("Loaded {} credentials", self.credentials.len());
+        Ok(())
+    }
+
+    /// Generates a recovery key that can unlock the vault instead of the master password.
+    ///
+    /// The store must be unlocked with the master password. The returned key
+    /// is not stored anywhere and should be shown to the user once. Calling
+    /// this again replaces the previous recovery key, which stops working.
+    pub fn enable_recovery(&mut self) -> Result<String> {
+        if self.master_password.is_none() {
+            return Err(anyhow!("Master password not set"));
+        }
+        let data_key = self
+            .envelope
             .as_ref()
-            .ok_or_else(|| anyhow!("Database path not set"))?;
+            .map(|envelope| envelope.data_key)
+            .ok_or_else(|| anyhow!("Vault is locked"))?;
 
-        let store = load_encrypted_store(path)?;
+        let recovery_key = generate_recovery_key()?;
 
-        // Decode salt from base64
-        let salt = decode_salt(&store.argon2_salt)?;
+        let salt = generate_salt()?;
+        let params = KdfParams::default();
+        let key = derive_key_with_params(&normalize_recovery_key(&recovery_key), &salt, &params)?;
+        let recovery = RecoverySlot {
+            argon2_salt: encode_salt(&salt),
+            kdf_params: params,
+            key: wrap_key(&data_key, &key)?,
+        };
 
-        // Derive key from password using Argon2id
-        let key = derive_key(&password, &salt)?;
+        let kdf_params = self
+            .envelope
+            .as_ref()
+            .map(|envelope| envelope.kdf_params)
+            .unwrap_or_default();
+        let previous = self.envelope.replace(Envelope {
+            data_key,
+            recovery: Some(recovery),
+            kdf_params,
+        });
+        if let Err(e) = self.save_credentials() {
+            self.envelope = previous;
+            return Err(e);
+        }
 
-        // Decode nonce and encrypted data from base64
-        let nonce_bytes = decode_nonce(&store.encryption_nonce)?;
-        let encrypted_data = decode_encrypted_data(&store.encrypted_data)?;
+        log::info!("Recovery key generated");
+        Ok(recovery_key)
+    }
 
-        // Decrypt the data
-        let nonce_array: [u8; 12] = nonce_bytes
-            .try_into()
-            .map_err(|_| anyhow!("Invalid nonce length"))?;
-        let decrypted_data = decrypt(&encrypted_data, &key, &nonce_array)?;
+    /// Unlocks the vault with its recovery key instead of the master password.
+    ///
+    /// The master password is not recovered: set a new one with
+    /// [`Manager::change_master_password`] before saving.
+    pub fn recover(&mut self, recovery_key: &str) -> Result<()> {
+        let store = self.storage()?.load()?;
+        let claim = self.claim_current()?;
+        let (credentials, envelope) = recover_store(&store, recovery_key)?;
 
-        // Deserialize the decrypted data
-        let credentials_map: HashMap<String, String> = serde_json::from_slice(&decrypted_data)?;
-        self.credentials = Credentials::from_map(credentials_map);
+        self.clear_master_password();
+        self.set_credentials(credentials);
+        self.envelope = Some(envelope);
+        self.keep_claim(claim);
 
-        log::info!("Loaded {} credentials", self.credentials.list().len());
+        log::info!("Vault unlocked with recovery key");
         Ok(())
     }
 
-    /// Saves credentials to disk.
-    pub fn save_credentials(&self) -> Result<()> {
-        let path = self
-            .pwd_db_path
-            .as_ref()
-            .ok_or_else(|| anyhow!("Database path not set"))?;
+    /// Replaces the master password.
+    ///
+    /// The store must already be unlocked, with the master password or the
+    /// recovery key. Only the data key is re-wrapped under the new password;
+    /// the encrypted credentials on disk are left untouched, so save any
+    /// pending changes first. Stores written before data keys were introduced
+    /// are re-encrypted in full instead.
+    pub fn change_master_password(&mut self, new_password: String) -> Result<()> {
+        if new_password.is_empty() {
+            return Err(ManagerError::EmptyMasterPassword.into());
+        }
 
-        let password = self
-            .master_password
-            .as_ref()
-            .ok_or_else(|| anyhow!("Master password not set"))?;
+        let Some(envelope) = &self.envelope else {
+            return Err(anyhow!("Master password not set"));
+        };
 
-        // Generate salt for Argon2id
-        let salt = generate_salt()?;
+        let storage = self.storage()?;
 
-        // Derive encryption key from master password using Argon2id
-        let key = derive_key(password, &salt)?;
+        match storage.load() {
+            Ok(mut store) if store.data_key.is_some() => {
+                let salt = generate_salt()?;
+                let params = envelope.kdf_params;
+                let key = derive_key_with_params(&new_password, &salt, &params)?;
 
-        // Serialize credentials to JSON
-        let credentials_map = self.credentials.to_map();
-        let credentials_json = serde_json::to_vec(credentials_map)?;
+                store.version = STORE_VERSION;
+                store.argon2_salt = encode_salt(&salt);
+                store.kdf_params = Some(params);
+                store.data_key = Some(wrap_key(&envelope.data_key, &key)?);
+                save_with_backup(storage, &store, self.backup_count)?;
 
-        // Generate nonce for encryption
-        let nonce_bytes = generate_nonce()?;
+                self.replace_master_password(new_password);
+            }
+            _ => {
+                self.replace_master_password(new_password);
+                self.save_credentials()?;
+            }
+        }
 
-        // Encrypt the credentials
-        let encrypted_data = encrypt(&credentials_json, &key, &nonce_bytes)?;
+        log::info!("Master password changed");
+        Ok(())
+    }
 
-        // Create the encrypted store
-        let store = EncryptedStore {
-            version: 1,
-            argon2_salt: encode_salt(&salt),
-            encryption_nonce: encode_nonce(&nonce_bytes),
-            encrypted_data: encode_encrypted_data(&encrypted_data),
-        };
+    /// Wipes the current master password and stores a new one.
+    fn replace_master_password(&mut self, new_password: String) {
+        if let Some(ref mut pwd) = self.master_password {
+            pwd.clear();
+        }
+        self.master_password = Some(new_password);
+    }
+
+    /// Saves credentials to disk.
+    pub fn save_credentials(&mut self) -> Result<()> {
+        save_credentials_impl(
+            self.backend.as_deref(),
+            &self.master_password,
+            self.envelope.as_ref(),
+            &self.credentials,
+            self.backup_count,
+            self.cipher,
+        )?;
+        self.unsaved_changes = false;
+        Ok(())
+    }
+
+    /// Re-encrypts the vault with new Argon2id parameters, keeping the
+    /// master password.
+    ///
+    /// A key is derived from the master password with `params` and a fresh
+    /// salt, the data key is wrapped under it, and the parameters are stored
+    /// so later unlocks and saves use them. Nothing changes if saving fails.
+    #[allow(unused)]
+    pub fn reencrypt_with_params(&mut self, params: KdfParams) -> Result<()> {
+        let credentials = std::mem::take(&mut self.credentials);
+        let result = self.reencrypt_credentials_with_params(params, &credentials);
+        self.set_credentials(credentials);
+        result
+    }
 
-        // Write to file
-        save_encrypted_store(path, &store)?;
+    /// Saves `credentials` under a key derived with `params`, keeping
+    /// `params` for later saves only if that succeeds.
+    fn reencrypt_credentials_with_params(
+        &mut self,
+        params: KdfParams,
+        credentials: &Credentials,
+    ) -> Result<()> {
+        params.validate()?;
+        let envelope = self
+            .envelope
+            .as_mut()
+            .ok_or_else(|| anyhow!("Vault is locked"))?;
+        let previous = std::mem::replace(&mut envelope.kdf_params, params);
+
+        if let Err(e) = save_credentials_impl(
+            self.backend.as_deref(),
+            &self.master_password,
+            self.envelope.as_ref(),
+            credentials,
+            self.backup_count,
+            self.cipher,
+        ) {
+            if let Some(envelope) = &mut self.envelope {
+                envelope.kdf_params = previous;
+            }
+            return Err(e);
+        }
 
-        log::info!("Saved {} credentials", self.credentials.list().len());
+        log::info!(
+            "Re-encrypted vault with m_cost={} KiB, t_cost={}, p_cost={}",
+            params.m_cost,
+            params.t_cost,
+            params.p_cost
+        );
         Ok(())
     }
 
-    /// Clears the master password from memory.
+    /// Reports whether the on-disk store would be upgraded by this build.
+    ///
+    /// Returns `Some((on_disk, target))` when the store was written with an older
+    /// format version, and `None` when it is current or there is nothing to migrate.
+    /// Nothing is written to disk.
+    pub fn migration_plan(&self) -> Option<(u8, u8)> {
+        if self.is_new_user() {
+            return None;
+        }
+
+        match self.backend.as_deref()?.load() {
+            Ok(store) if store.version < STORE_VERSION => Some((store.version, STORE_VERSION)),
+            Ok(_) => None,
+            Err(e) => {
+                log::warn!("Could not read store version: {}", e);
+                None
+            }
+        }
+    }
+
+    /// Describes how the current vault file is encrypted, without key material.
+    pub fn crypto_info(&self) -> Result<CryptoInfo> {
+        self.storage()?.load()?.crypto_info()
+    }
+
+    /// Clears the master password, and any data key, from memory.
     pub fn clear_master_password(&mut self) {
         if let Some(ref mut pwd) = self.master_password {
             pwd.clear();
         }
         self.master_password = None;
+
+        if let Some(ref mut envelope) = self.envelope {
+            envelope.data_key = [0u8; 32];
+        }
+        self.envelope = None;
+    }
+
+    /// Clears the credentials, master password and data key from memory.
+    ///
+    /// The vault file is untouched; unlock it again with
+    /// [`Manager::validate_master_password`].
+    pub fn lock(&mut self) {
+        self.credentials.wipe();
+        self.clear_master_password();
+        log::info!("Vault locked");
     }
 
     /// Returns a reference to credentials.
@@ -180,125 +801,692 @@ impl Manager {
     /// Returns a mutable reference to credentials.
     #[allow(unused)]
     pub fn credentials_mut(&mut self) -> &mut Credentials {
+        self.unsaved_changes = true;
         &mut self.credentials
     }
 
-    /// Runs the interactive shell.
-    pub fn run(&mut self) -> Result<()> {
-        // Configure history
-        let history_path = get_history_path().unwrap_or_else(|_| PathBuf::from("history"));
-        let history_config =
-            HistoryConfig::new(history_path).with_max_entries(DEFAULT_HISTORY_SIZE);
-
-        let shell_config = ShellConfig {
-            history: history_config,
-            show_welcome: true,
-        };
-
-        let shell = Shell::with_config(shell_config);
-
-        // We need to clone the necessary data for the save closure
-        let pwd_db_path = self.pwd_db_path.clone();
-        let master_password = self.master_password.clone();
-
-        // Run shell with save callback
-        shell.run_with_save(&mut self.credentials, |credentials| {
-            save_credentials_impl(&pwd_db_path, &master_password, credentials)
-        })?;
+    /// Returns whether the credentials have changes that are not in the
+    /// vault file.
+    pub fn has_unsaved_changes(&self) -> bool {
+        self.unsaved_changes
+    }
 
-        // Clear password on exit
-        self.clear_master_password();
+    /// Re-reads the vault file and decrypts it with the current master
+    /// password, replacing the credentials in memory.
+    ///
+    /// Use this when the file was changed by something else, such as a sync
+    /// client. Unsaved changes are discarded, with a warning logged. If the
+    /// file no longer opens with the master password, e.g. because it was
+    /// changed elsewhere, the current credentials are kept.
+    pub fn reload(&mut self) -> Result<()> {
+        let password = self
+            .master_password
+            .as_ref()
+            .ok_or_else(|| anyhow!("Vault is locked"))?;
+
+        let store = self.storage()?.load()?;
+        let (credentials, envelope) =
+            unlock_store(&store, password).map_err(|e| match LoadError::classify(e) {
+                LoadError::WrongPassword => {
+                    anyhow!("The vault file no longer opens with the current master password")
+                }
+                e => e.into(),
+            })?;
+
+        if self.unsaved_changes {
+            log::warn!("Discarding unsaved changes to reload the vault");
+        }
+        self.set_credentials(credentials);
+        self.envelope = Some(envelope);
+        self.unsaved_changes = false;
 
+        log::info!("Reloaded {} credentials", self.credentials.len());
         Ok(())
     }
-}
 
-/// Internal function to save credentials (used by closure).
-fn save_credentials_impl(
-    pwd_db_path: &Option<PathBuf>,
-    master_password: &Option<String>,
-    credentials: &Credentials,
-) -> Result<()> {
-    let path = pwd_db_path
-        .as_ref()
-        .ok_or_else(|| anyhow!("Database path not set"))?;
+    /// Attempts to decrypt every backup with the current master password.
+    ///
+    /// Returns one report for `<db>.bak`, if present, followed by one per file
+    /// in the backup directory, in file name order.
+    /// Files that cannot be read or decrypted are reported, not treated as errors.
+    pub fn verify_backups(&self) -> Result<Vec<BackupReport>> {
+        verify_backups_impl(self.db_path(), &self.master_password)
+    }
 
-    let password = master_password
-        .as_ref()
-        .ok_or_else(|| anyhow!("Master password not set"))?;
+    /// Checks that the vault file on disk is intact, without modifying it.
+    ///
+    /// The store is re-read and checked step by step: its base64 fields
+    /// decode, nonces and salts have the right length, and it decrypts with
+    /// the in-memory master password into valid credentials. Once a check
+    /// fails, the checks that depend on it are reported as skipped.
+    pub fn verify(&self) -> Result<VerifyReport> {
+        let storage = self.storage()?;
+        let password = self
+            .master_password
+            .as_ref()
+            .ok_or_else(|| anyhow!("Master password not set"))?;
 
-    // Generate salt for Argon2id
-    let salt = generate_salt()?;
+        let mut report = VerifyReport::default();
+        let _ = run_verify_checks(&mut report, storage, password);
+        for name in &VERIFY_CHECKS[report.checks.len()..] {
+            report.checks.push(VerifyCheck {
+                name,
+                status: CheckStatus::Skipped,
+            });
+        }
 
-    // Derive encryption key from master password using Argon2id
-    let key = derive_key(password, &salt)?;
+        log::info!(
+            "Verified vault: {}",
+            if report.passed() { "ok" } else { "failed" }
+        );
+        Ok(report)
+    }
 
-    // Serialize credentials to JSON
-    let credentials_map = credentials.to_map();
-    let credentials_json = serde_json::to_vec(credentials_map)?;
+    /// Replaces the credentials with those of a backup and saves them.
+    ///
+    /// `name` is the file name of a backup, as reported by
+    /// [`Manager::verify_backups`]. The backup must decrypt with `password`;
+    /// its credentials are then saved under the current master password, which
+    /// backs up the store being replaced first.
+    ///
+    /// A wrong password counts against the vault's lockout policy, like a
+    /// failed unlock.
+    pub fn restore_backup(&mut self, name: &str, password: String) -> Result<()> {
+        let path = self
+            .db_path()
+            .ok_or_else(|| anyhow!("Backups are only kept for vaults stored in a file"))?;
+
+        let previous = previous_backup_path(path);
+        let backup = if previous.file_name().is_some_and(|n| n == name) {
+            previous
+        } else if Path::new(name).file_name().is_some_and(|n| n == name) {
+            backup_dir_for(path).join(name)
+        } else {
+            return Err(anyhow!("Invalid backup name: '{}'", name));
+        };
+        if !backup.is_file() {
+            return Err(anyhow!("No backup named '{}'", name));
+        }
 
-    // Generate nonce for encryption
-    let nonce_bytes = generate_nonce()?;
+        let store = load_encrypted_store(&backup).map_err(LoadError::classify)?;
+        let (credentials, _) =
+            unlock_with_lockout(self.storage()?, Some(&store), &password, self.lockout)?
+                .ok_or_else(|| anyhow!("Could not decrypt backup '{}' with that password", name))?;
+
+        save_credentials_impl(
+            self.backend.as_deref(),
+            &self.master_password,
+            self.envelope.as_ref(),
+            &credentials,
+            self.backup_count,
+            self.cipher,
+        )?;
+        self.set_credentials(credentials);
+
+        log::info!("Restored backup {}", backup.display());
+        Ok(())
+    }
 
-    // Encrypt the credentials
-    let encrypted_data = encrypt(&credentials_json, &key, &nonce_bytes)?;
+    /// Executes a single shell command against the unlocked vault.
+    ///
+    /// `args` is the command name followed by its arguments. Changes are saved
+    /// before returning.
+    pub fn run_once(&mut self, args: &[&str]) -> CommandResult {
+        let shell = self.shell();
+        let mut credentials = std::mem::take(&mut self.credentials);
+        let result = shell.run_once(args, &mut credentials, self);
+        self.set_credentials(credentials);
+        result
+    }
 
-    // Create the encrypted store
-    let store = EncryptedStore {
-        version: 1,
-        argon2_salt: encode_salt(&salt),
-        encryption_nonce: encode_nonce(&nonce_bytes),
-        encrypted_data: encode_encrypted_data(&encrypted_data),
-    };
+    /// Returns the shell that [`run`](Self::run) and
+    /// [`run_once`](Self::run_once) dispatch commands through.
+    fn shell(&self) -> Shell {
+        Shell::with_config(self.shell_config.clone().unwrap_or_default())
+    }
 
-    // Write to file
-    save_encrypted_store(path, &store)?;
+    /// Runs the interactive shell.
+    pub fn run(&mut self) -> Result<()> {
+        let shell = self.shell();
+
+        loop {
+            // The shell borrows the credentials while the manager acts as its
+            // session, so move them out for the duration of the run
+            let mut credentials = std::mem::take(&mut self.credentials);
+            let result = shell.run_with_session(&mut credentials, self);
+            self.set_credentials(credentials);
+
+            match result? {
+                ShellExit::Quit => break,
+                ShellExit::Suspended => {
+                    self.lock();
+                    suspend_process()?;
+                    self.reauthenticate(&mut read_master_password)?;
+                }
+            }
+        }
 
-    log::info!("Saved {} credentials", credentials.list().len());
-    Ok(())
-}
+        // Clear password on exit
+        self.clear_master_password();
 
-impl Default for Manager {
-    fn default() -> Self {
-        Self::new()
+        Ok(())
+    }
+
+    /// Asks for the master password after a resume until the vault unlocks.
+    ///
+    /// Gives up as the shell's `lock` command does, after a few wrong
+    /// passwords or once the vault is locked out.
+    fn reauthenticate(
+        &mut self,
+        read_password: &mut dyn FnMut(&str) -> Option<String>,
+    ) -> Result<()> {
+        println!("Vault locked. Enter your MASTER password to unlock it again.");
+        let credentials = unlock_session(self, read_password)?;
+        self.set_credentials(credentials);
+        Ok(())
     }
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use tempfile::TempDir;
+impl Session for Manager {
+    fn save(&mut self, credentials: &Credentials) -> Result<()> {
+        let saved = save_credentials_impl(
+            self.backend.as_deref(),
+            &self.master_password,
+            self.envelope.as_ref(),
+            credentials,
+            self.backup_count,
+            self.cipher,
+        );
+        self.unsaved_changes = saved.is_err();
+        saved
+    }
 
-    fn setup_manager() -> (Manager, TempDir) {
-        let temp_dir = TempDir::new().unwrap();
-        let db_path = temp_dir.path().join("test.db");
+    fn verify_backups(&self) -> Result<Vec<BackupReport>> {
+        Manager::verify_backups(self)
+    }
 
-        let mut manager = Manager::new();
-        manager.set_db_path(db_path);
+    fn restore_backup(&mut self, name: &str, password: String) -> Result<Credentials> {
+        Manager::restore_backup(self, name, password)?;
+        Ok(std::mem::take(&mut self.credentials))
+    }
 
-        (manager, temp_dir)
+    fn vaults(&self) -> Result<Vec<VaultInfo>> {
+        Ok(Manager::vaults(self))
     }
 
-    #[test]
-    fn test_new_manager() {
-        let manager = Manager::new();
-        assert!(manager.pwd_db_path.is_none());
-        assert!(manager.master_password.is_none());
-        assert!(manager.credentials.is_empty());
+    fn switch_vault(&mut self, name: &str, password: String) -> Result<Credentials> {
+        Manager::switch_vault(self, name, password)?;
+        Ok(std::mem::take(&mut self.credentials))
     }
 
-    #[test]
-    fn test_is_new_user() {
-        let (manager, _temp_dir) = setup_manager();
-        assert!(manager.is_new_user());
+    fn reload(&mut self) -> Result<Credentials> {
+        Manager::reload(self)?;
+        Ok(std::mem::take(&mut self.credentials))
     }
 
-    #[test]
-    fn test_setup_new_user() {
-        let (mut manager, _temp_dir) = setup_manager();
+    fn has_unsaved_changes(&self) -> bool {
+        Manager::has_unsaved_changes(self)
+    }
 
-        let result = manager.setup_new_user("test_password".to_string());
-        assert!(result.is_ok());
+    fn crypto_info(&self) -> Result<CryptoInfo> {
+        Manager::crypto_info(self)
+    }
+
+    fn reencrypt_with_params(
+        &mut self,
+        params: KdfParams,
+        credentials: &Credentials,
+    ) -> Result<()> {
+        self.reencrypt_credentials_with_params(params, credentials)
+    }
+
+    fn verify(&self) -> Result<VerifyReport> {
+        Manager::verify(self)
+    }
+
+    fn lock(&mut self) -> Result<()> {
+        Manager::lock(self);
+        Ok(())
+    }
+
+    fn unlock(&mut self, password: String) -> Result<Option<Credentials>> {
+        if self.validate_master_password(password)? {
+            Ok(Some(std::mem::take(&mut self.credentials)))
+        } else {
+            Ok(None)
+        }
+    }
+}
+
+/// Unlocks a store with `password`, enforcing `policy` against the vault
+/// kept in `storage`.
+///
+/// The store unlocked is the vault itself, or `other` if given, such as one of
+/// its backups; either way a cooldown in effect is read from, and failed
+/// attempts are recorded in, the vault's header. Returns `Ok(None)` if the
+/// password is wrong, and an error holding a [`LoadError`] if a store is
+/// damaged or cannot be read.
+fn unlock_with_lockout(
+    storage: &dyn StorageBackend,
+    other: Option<&EncryptedStore>,
+    password: &str,
+    policy: Option<LockoutPolicy>,
+) -> Result<Option<(Credentials, Envelope)>> {
+    let mut vault = storage.load().map_err(|e| {
+        equalize_kdf_timing(password);
+        LoadError::classify(e)
+    })?;
+    let Some(policy) = policy else {
+        return match unlock_store(other.unwrap_or(&vault), password).map_err(LoadError::classify) {
+            Ok(unlocked) => Ok(Some(unlocked)),
+            Err(LoadError::WrongPassword) => Ok(None),
+            Err(e) => Err(e.into()),
+        };
+    };
+
+    let now = now_secs()?;
+    if let Some(retry_after) = remaining_cooldown(&vault, now) {
+        log::warn!("Unlock refused, locked out for another {}s", retry_after);
+        return Err(ManagerError::LockedOut { retry_after }.into());
+    }
+
+    match unlock_store(other.unwrap_or(&vault), password).map_err(LoadError::classify) {
+        Ok(unlocked) => {
+            if lockout::reset(&mut vault) {
+                storage.save(&vault)?;
+            }
+            Ok(Some(unlocked))
+        }
+        Err(LoadError::WrongPassword) => match policy.record_failure(&mut vault, now) {
+            LockoutOutcome::Allowed { remaining } => {
+                storage.save(&vault)?;
+                log::warn!("Failed unlock attempt, {} left before lockout", remaining);
+                Ok(None)
+            }
+            LockoutOutcome::LockedUntil(until) => {
+                storage.save(&vault)?;
+                log::warn!(
+                    "Too many failed unlock attempts, locked out until {}",
+                    until
+                );
+                Err(ManagerError::LockedOut {
+                    retry_after: until - now,
+                }
+                .into())
+            }
+            LockoutOutcome::Wipe => {
+                storage.remove()?;
+                if let Some(path) = storage.path() {
+                    remove_backups(path)?;
+                }
+                log::warn!(
+                    "Vault wiped after {} failed unlock attempts",
+                    policy.max_attempts
+                );
+                Err(ManagerError::VaultWiped.into())
+            }
+        },
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Decrypts an encrypted store with the given master password.
+fn decrypt_store(store: &EncryptedStore, password: &str) -> Result<Credentials> {
+    unlock_store(store, password).map(|(credentials, _)| credentials)
+}
+
+/// Decrypts an encrypted store with the given master password, also returning
+/// its data key.
+///
+/// Stores written before data keys were introduced get a fresh data key,
+/// which is used from the next save on.
+///
+/// A failure to authenticate whatever the password-derived key decrypts
+/// first is reported as [`LoadError::WrongPassword`]. Once the data key has
+/// been authenticated, the password is known to be right, so a failure to
+/// decrypt the credentials means the file is damaged.
+fn unlock_store(store: &EncryptedStore, password: &str) -> Result<(Credentials, Envelope)> {
+    // Decode salt from base64
+    let salt = decode_salt("argon2_salt", &store.argon2_salt)?;
+
+    // Derive key from password using Argon2id (version 1 stores used the defaults)
+    let params = store.kdf_params.unwrap_or_default();
+    let key = derive_key_with_params(password, &salt, &params)?;
+
+    match &store.data_key {
+        Some(wrapped) => {
+            let data_key = unwrap_key(wrapped, "data_key.nonce", &key)
+                .map_err(wrong_password_on_auth_failure)?;
+            let credentials = decrypt_credentials(store, &data_key).map_err(|e| {
+                if e.is::<AuthenticationError>() {
+                    LoadError::CorruptStore("credentials failed authentication".to_string()).into()
+                } else {
+                    e
+                }
+            })?;
+            let envelope = Envelope {
+                data_key,
+                recovery: store.recovery.clone(),
+                kdf_params: params,
+            };
+            Ok((credentials, envelope))
+        }
+        None => {
+            let credentials =
+                decrypt_credentials(store, &key).map_err(wrong_password_on_auth_failure)?;
+            Ok((credentials, Envelope::generate()?))
+        }
+    }
+}
+
+/// Runs a key derivation whose result is discarded.
+///
+/// Called where password validation fails before deriving a key, so that it
+/// takes about as long as a failure caused by a wrong password.
+fn equalize_kdf_timing(password: &str) {
+    let _ = derive_key_with_params(password, &[0u8; 16], &KdfParams::default());
+}
+
+/// Turns an [`AuthenticationError`] into [`LoadError::WrongPassword`],
+/// leaving other errors unchanged.
+fn wrong_password_on_auth_failure(e: anyhow::Error) -> anyhow::Error {
+    if e.is::<AuthenticationError>() {
+        LoadError::WrongPassword.into()
+    } else {
+        e
+    }
+}
+
+/// Decrypts a store's data key with its recovery key, then the credentials.
+fn recover_store(store: &EncryptedStore, recovery_key: &str) -> Result<(Credentials, Envelope)> {
+    let recovery = store
+        .recovery
+        .as_ref()
+        .ok_or(ManagerError::RecoveryNotEnabled)?;
+
+    let salt = decode_salt("recovery.argon2_salt", &recovery.argon2_salt)?;
+    let key = derive_key_with_params(
+        &normalize_recovery_key(recovery_key),
+        &salt,
+        &recovery.kdf_params,
+    )?;
+    let data_key = unwrap_key(&recovery.key, "recovery.key.nonce", &key).map_err(|e| {
+        if e.is::<AuthenticationError>() {
+            ManagerError::InvalidRecoveryKey.into()
+        } else {
+            e
+        }
+    })?;
+
+    let credentials = decrypt_credentials(store, &data_key)?;
+    let envelope = Envelope {
+        data_key,
+        recovery: Some(recovery.clone()),
+        kdf_params: store.kdf_params.unwrap_or_default(),
+    };
+    Ok((credentials, envelope))
+}
+
+/// Runs [`VERIFY_CHECKS`] in order, stopping at the first failure.
+fn run_verify_checks(
+    report: &mut VerifyReport,
+    storage: &dyn StorageBackend,
+    password: &str,
+) -> Option<()> {
+    let store = report.record(VERIFY_CHECKS[0], storage.load())?;
+
+    let (salts, nonces) = report.record(VERIFY_CHECKS[1], decode_store_fields(&store))?;
+
+    report.record(
+        VERIFY_CHECKS[2],
+        match nonces.iter().find(|&&len| len != 12) {
+            Some(len) => Err(anyhow!("found a {}-byte nonce", len)),
+            None => Ok(()),
+        },
+    )?;
+    report.record(
+        VERIFY_CHECKS[3],
+        match salts.iter().find(|&&len| len != 16) {
+            Some(len) => Err(anyhow!("found a {}-byte salt", len)),
+            None => Ok(()),
+        },
+    )?;
+
+    let plaintext = report.record(VERIFY_CHECKS[4], decrypt_with_password(&store, password))?;
+
+    report.record(
+        VERIFY_CHECKS[5],
+        Credentials::from_json(&plaintext).map_err(Into::into),
+    )?;
+    Some(())
+}
+
+/// Decodes every base64 field of a store, returning the lengths of its
+/// salts and of its nonces.
+fn decode_store_fields(store: &EncryptedStore) -> Result<(Vec<usize>, Vec<usize>)> {
+    decoded_len(&store.encrypted_data)?;
+    let mut salts = vec![decoded_len(&store.argon2_salt)?];
+    let mut nonces = vec![decoded_len(&store.encryption_nonce)?];
+    if let Some(wrapped) = &store.data_key {
+        nonces.push(decoded_len(&wrapped.nonce)?);
+        decoded_len(&wrapped.wrapped_key)?;
+    }
+    if let Some(recovery) = &store.recovery {
+        salts.push(decoded_len(&recovery.argon2_salt)?);
+        nonces.push(decoded_len(&recovery.key.nonce)?);
+        decoded_len(&recovery.key.wrapped_key)?;
+    }
+    Ok((salts, nonces))
+}
+
+/// Decrypts a store's serialized credentials with the master password.
+fn decrypt_with_password(store: &EncryptedStore, password: &str) -> Result<Vec<u8>> {
+    let salt = decode_salt("argon2_salt", &store.argon2_salt)?;
+    let params = store.kdf_params.unwrap_or_default();
+    let mut key = derive_key_with_params(password, &salt, &params)?;
+    if let Some(wrapped) = &store.data_key {
+        key = unwrap_key(wrapped, "data_key.nonce", &key)?;
+    }
+    decrypt_payload(store, &key)
+}
+
+/// Decrypts a store's serialized credentials with the key they were encrypted under.
+fn decrypt_payload(store: &EncryptedStore, key: &[u8; 32]) -> Result<Vec<u8>> {
+    // Decode nonce and encrypted data from base64
+    let nonce_array = decode_nonce("encryption_nonce", &store.encryption_nonce)?;
+    let mut data = decode_encrypted_data(&store.encrypted_data)?;
+
+    // Decrypt in place so the ciphertext is gone before deserializing
+    decrypt_in_place(store.cipher, &mut data, key, &nonce_array)?;
+    Ok(data)
+}
+
+/// Decrypts a store's credentials with the key they were encrypted under.
+fn decrypt_credentials(store: &EncryptedStore, key: &[u8; 32]) -> Result<Credentials> {
+    let data = decrypt_payload(store, key)?;
+
+    // Deserialize the decrypted data
+    Ok(Credentials::from_json(&data)?)
+}
+
+/// Encrypts a data key under `key`.
+fn wrap_key(data_key: &[u8; 32], key: &[u8; 32]) -> Result<WrappedKey> {
+    let nonce = generate_nonce()?;
+    let wrapped = encrypt(KEY_WRAP_CIPHER, data_key, key, &nonce)?;
+    Ok(WrappedKey {
+        nonce: encode_nonce(&nonce),
+        wrapped_key: encode_encrypted_data(&wrapped),
+    })
+}
+
+/// Decrypts a data key wrapped with [`wrap_key`]; `nonce_field` names its
+/// nonce in errors.
+fn unwrap_key(wrapped: &WrappedKey, nonce_field: &'static str, key: &[u8; 32]) -> Result<[u8; 32]> {
+    let nonce = decode_nonce(nonce_field, &wrapped.nonce)?;
+    let encrypted = decode_encrypted_data(&wrapped.wrapped_key)?;
+    decrypt(KEY_WRAP_CIPHER, &encrypted, key, &nonce)?
+        .try_into()
+        .map_err(|_| anyhow!("Invalid data key length"))
+}
+
+/// Internal function to verify backups.
+fn verify_backups_impl(
+    db_path: Option<&Path>,
+    master_password: &Option<String>,
+) -> Result<Vec<BackupReport>> {
+    let path =
+        db_path.ok_or_else(|| anyhow!("Backups are only kept for vaults stored in a file"))?;
+
+    let password = master_password
+        .as_ref()
+        .ok_or_else(|| anyhow!("Master password not set"))?;
+
+    let previous = Some(previous_backup_path(path)).filter(|bak| bak.is_file());
+    let reports = previous
+        .into_iter()
+        .chain(list_backups(&backup_dir_for(path))?)
+        .map(|path| {
+            let status = match load_encrypted_store(&path)
+                .and_then(|store| decrypt_store(&store, password))
+            {
+                Ok(credentials) => BackupStatus::Valid {
+                    credentials: credentials.len(),
+                },
+                Err(e) => {
+                    log::warn!("Backup {} failed verification: {}", path.display(), e);
+                    BackupStatus::Invalid {
+                        reason: e.to_string(),
+                    }
+                }
+            };
+            BackupReport { path, status }
+        })
+        .collect();
+
+    Ok(reports)
+}
+
+/// Internal function to save credentials (used by the session).
+///
+/// The credentials are encrypted with `cipher` under the envelope's data key,
+/// which is stored wrapped under the password-derived key alongside the
+/// recovery slot.
+fn save_credentials_impl(
+    storage: Option<&dyn StorageBackend>,
+    master_password: &Option<String>,
+    envelope: Option<&Envelope>,
+    credentials: &Credentials,
+    backup_count: usize,
+    cipher: Cipher,
+) -> Result<()> {
+    let storage = storage.ok_or_else(|| anyhow!("Database path not set"))?;
+
+    let password = master_password
+        .as_ref()
+        .ok_or_else(|| anyhow!("Master password not set"))?;
+
+    let envelope = envelope.ok_or_else(|| anyhow!("Vault is locked"))?;
+
+    // Generate salt for Argon2id
+    let salt = generate_salt()?;
+
+    // Derive the key-wrapping key from master password using Argon2id
+    let params = envelope.kdf_params;
+    let key = derive_key_with_params(password, &salt, &params)?;
+
+    // Serialize credentials and their metadata to JSON
+    let credentials_json = credentials.to_json()?;
+
+    // Generate nonce for encryption
+    let nonce_bytes = generate_nonce()?;
+
+    // Encrypt the credentials under the data key
+    let encrypted_data = encrypt(cipher, &credentials_json, &envelope.data_key, &nonce_bytes)?;
+
+    // Create the encrypted store
+    let store = EncryptedStore {
+        version: STORE_VERSION,
+        argon2_salt: encode_salt(&salt),
+        encryption_nonce: encode_nonce(&nonce_bytes),
+        encrypted_data: encode_encrypted_data(&encrypted_data),
+        cipher,
+        kdf_params: Some(params),
+        data_key: Some(wrap_key(&envelope.data_key, &key)?),
+        recovery: envelope.recovery.clone(),
+        failed_attempts: 0,
+        locked_until: None,
+    };
+
+    save_with_backup(storage, &store, backup_count)?;
+
+    log::info!("Saved {} credentials", credentials.len());
+    Ok(())
+}
+
+/// Saves `store`, first backing up the vault it replaces if it is stored
+/// in a file.
+fn save_with_backup(
+    storage: &dyn StorageBackend,
+    store: &EncryptedStore,
+    backup_count: usize,
+) -> Result<()> {
+    // Keep the previous store in case this write goes wrong
+    if let Some(path) = storage.path() {
+        backup_before_save(path, backup_count, chrono::Local::now().naive_local())
+            .map_err(|e| anyhow!("Could not back up vault before saving: {}", e))?;
+    }
+    storage.save(store)
+}
+
+impl Default for Manager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crypto::KDF_CALLS;
+    use crate::lockfile::lock_path;
+    use crate::lockout::LockoutAction;
+    use crate::shell::command::CommandRegistry;
+    use crate::shell::commands::register_all;
+    use crate::storage::save_encrypted_store;
+    use std::collections::HashMap;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn setup_manager() -> (Manager, TempDir) {
+        let temp_dir = TempDir::new().unwrap();
+        let db_path = temp_dir.path().join("test.db");
+
+        let mut manager = Manager::new();
+        manager.set_db_path(db_path);
+
+        (manager, temp_dir)
+    }
+
+    #[test]
+    fn test_new_manager() {
+        let manager = Manager::new();
+        assert!(manager.backend.is_none());
+        assert!(manager.master_password.is_none());
+        assert!(manager.credentials.is_empty());
+    }
+
+    #[test]
+    fn test_is_new_user() {
+        let (manager, _temp_dir) = setup_manager();
+        assert!(manager.is_new_user());
+    }
+
+    #[test]
+    fn test_setup_new_user() {
+        let (mut manager, _temp_dir) = setup_manager();
+
+        let result = manager.setup_new_user("test_password".to_string());
+        assert!(result.is_ok());
         assert!(!manager.is_new_user());
     }
 
@@ -317,7 +1505,7 @@ mod tests {
 
         // Test with wrong password
         let mut manager2 = Manager::new();
-        manager2.set_db_path(manager.pwd_db_path.clone().unwrap());
+        manager2.set_db_path(manager.db_path().unwrap().to_path_buf());
 
         let result = manager2.validate_master_password("wrong_password".to_string());
         assert!(result.is_ok());
@@ -341,7 +1529,7 @@ mod tests {
 
         // Create new manager and load
         let mut manager2 = Manager::new();
-        manager2.set_db_path(manager.pwd_db_path.clone().unwrap());
+        manager2.set_db_path(manager.db_path().unwrap().to_path_buf());
         let valid = manager2
             .validate_master_password("test_password".to_string())
             .unwrap();
@@ -358,13 +1546,1250 @@ mod tests {
     }
 
     #[test]
-    fn test_clear_master_password() {
+    fn test_migration_plan_old_store() {
+        let (mut manager, _temp_dir) = setup_manager();
+        manager.setup_new_user("test_password".to_string()).unwrap();
+
+        // Rewrite the store as a version 1 file (no KDF params in the header)
+        let path = manager.db_path().unwrap().to_path_buf();
+        let mut store = load_encrypted_store(&path).unwrap();
+        store.version = 1;
+        store.kdf_params = None;
+        save_encrypted_store(&path, &store).unwrap();
+
+        assert_eq!(manager.migration_plan(), Some((1, STORE_VERSION)));
+
+        // Reporting must not touch the file
+        assert_eq!(load_encrypted_store(&path).unwrap().version, 1);
+
+        // A version 1 store still unlocks with the default parameters
+        let mut manager2 = Manager::new();
+        manager2.set_db_path(path);
+        assert!(
+            manager2
+                .validate_master_password("test_password".to_string())
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn test_migration_plan_current_store() {
         let (mut manager, _temp_dir) = setup_manager();
+        assert_eq!(manager.migration_plan(), None);
 
         manager.setup_new_user("test_password".to_string()).unwrap();
-        assert!(manager.master_password.is_some());
+        assert_eq!(manager.migration_plan(), None);
+    }
 
-        manager.clear_master_password();
+    #[test]
+    fn test_setup_rejects_empty_password() {
+        let (mut manager, _temp_dir) = setup_manager();
+
+        let err = manager.setup_new_user(String::new()).unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<ManagerError>(),
+            Some(&ManagerError::EmptyMasterPassword)
+        );
+        assert!(manager.is_new_user());
         assert!(manager.master_password.is_none());
     }
+
+    #[test]
+    fn test_validate_rejects_empty_password() {
+        let (mut manager, _temp_dir) = setup_manager();
+        manager.setup_new_user("test_password".to_string()).unwrap();
+
+        let err = manager.validate_master_password(String::new()).unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<ManagerError>(),
+            Some(&ManagerError::EmptyMasterPassword)
+        );
+    }
+
+    #[test]
+    fn test_change_master_password() {
+        let (mut manager, _temp_dir) = setup_manager();
+        manager.setup_new_user("old_password".to_string()).unwrap();
+        manager
+            .credentials_mut()
+            .add("key".to_string(), "value".to_string())
+            .unwrap();
+        manager.save_credentials().unwrap();
+
+        manager
+            .change_master_password("new_password".to_string())
+            .unwrap();
+
+        let path = manager.db_path().unwrap().to_path_buf();
+        let mut manager2 = Manager::new();
+        manager2.set_db_path(path.clone());
+        assert!(
+            !manager2
+                .validate_master_password("old_password".to_string())
+                .unwrap()
+        );
+        assert!(
+            manager2
+                .validate_master_password("new_password".to_string())
+                .unwrap()
+        );
+        assert_eq!(
+            manager2.credentials().get("key"),
+            Some(&"value".to_string())
+        );
+    }
+
+    #[test]
+    fn test_change_master_password_backs_up_vault() {
+        let (mut manager, _temp_dir) = setup_manager();
+        manager.set_backup_count(2);
+        manager.setup_new_user("old_password".to_string()).unwrap();
+        manager
+            .credentials_mut()
+            .add("key".to_string(), "value".to_string())
+            .unwrap();
+        manager.save_credentials().unwrap();
+        let path = manager.db_path().unwrap().to_path_buf();
+        let backups = list_backups(&backup_dir_for(&path)).unwrap().len();
+
+        manager
+            .change_master_password("new_password".to_string())
+            .unwrap();
+
+        // The vault as it was before the change opens with the old password
+        let backup = load_encrypted_store(&previous_backup_path(&path)).unwrap();
+        assert!(unlock_store(&backup, "old_password").is_ok());
+        assert_eq!(
+            list_backups(&backup_dir_for(&path)).unwrap().len(),
+            backups + 1
+        );
+    }
+
+    #[test]
+    fn test_change_master_password_rejects_empty() {
+        let (mut manager, _temp_dir) = setup_manager();
+        manager.setup_new_user("old_password".to_string()).unwrap();
+
+        let err = manager.change_master_password(String::new()).unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<ManagerError>(),
+            Some(&ManagerError::EmptyMasterPassword)
+        );
+
+        // The old password must still unlock the store
+        let mut manager2 = Manager::new();
+        manager2.set_db_path(manager.db_path().unwrap().to_path_buf());
+        assert!(
+            manager2
+                .validate_master_password("old_password".to_string())
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn test_switch_vault() {
+        let (mut manager, temp_dir) = setup_manager();
+        manager.setup_new_user("personal".to_string()).unwrap();
+        manager
+            .credentials_mut()
+            .add("github".to_string(), "secret".to_string())
+            .unwrap();
+        manager.save_credentials().unwrap();
+
+        let work_path = temp_dir.path().join("work.db");
+        manager.add_vault("default", temp_dir.path().join("test.db"));
+        manager.add_vault("work", work_path.clone());
+        assert_eq!(manager.active_vault(), Some("default"));
+
+        // Switching to a vault with no file initializes it
+        manager.switch_vault("work", "work".to_string()).unwrap();
+        assert_eq!(manager.active_vault(), Some("work"));
+        assert!(manager.credentials().is_empty());
+        assert!(!manager.is_new_user());
+
+        manager
+            .switch_vault("default", "personal".to_string())
+            .unwrap();
+        assert!(manager.credentials().get("github").is_some());
+    }
+
+    #[test]
+    fn test_unlocked_vault_is_locked_on_disk() {
+        let (mut manager, temp_dir) = setup_manager();
+        let db_path = temp_dir.path().join("test.db");
+        let work_path = temp_dir.path().join("work.db");
+        manager.setup_new_user("personal".to_string()).unwrap();
+        assert!(lock_path(&db_path).exists());
+
+        // Switching vaults moves the lock
+        manager.add_vault("work", work_path.clone());
+        manager.switch_vault("work", "work".to_string()).unwrap();
+        assert!(!lock_path(&db_path).exists());
+        assert!(lock_path(&work_path).exists());
+
+        // A failed unlock leaves no lock behind
+        let mut other = Manager::new();
+        other.set_db_path(db_path.clone());
+        assert!(!other.validate_master_password("wrong".to_string()).unwrap());
+        assert!(!lock_path(&db_path).exists());
+
+        drop(manager);
+        assert!(!lock_path(&work_path).exists());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_vault_in_use_by_another_process() {
+        let (mut manager, temp_dir) = setup_manager();
+        let db_path = temp_dir.path().join("test.db");
+        manager.setup_new_user("password".to_string()).unwrap();
+        drop(manager);
+
+        let mut holder = std::process::Command::new("sleep")
+            .arg("30")
+            .spawn()
+            .unwrap();
+        fs::write(lock_path(&db_path), format!("{}\ntoken\n", holder.id())).unwrap();
+
+        let mut manager = Manager::new();
+        manager.set_db_path(db_path.clone());
+        let err = manager
+            .validate_master_password("password".to_string())
+            .unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<ManagerError>(),
+            Some(&ManagerError::VaultInUse { pid: holder.id() })
+        );
+        assert!(manager.credentials().is_empty());
+
+        manager.set_force_lock(true);
+        assert!(
+            manager
+                .validate_master_password("password".to_string())
+                .unwrap()
+        );
+
+        holder.kill().unwrap();
+        holder.wait().unwrap();
+    }
+
+    #[test]
+    fn test_switch_vault_failures_keep_current() {
+        let (mut manager, temp_dir) = setup_manager();
+        manager.setup_new_user("personal".to_string()).unwrap();
+        manager
+            .credentials_mut()
+            .add("github".to_string(), "secret".to_string())
+            .unwrap();
+        manager.save_credentials().unwrap();
+        manager.add_vault("default", temp_dir.path().join("test.db"));
+        manager.add_vault("work", temp_dir.path().join("work.db"));
+        manager.switch_vault("work", "work".to_string()).unwrap();
+        manager
+            .switch_vault("default", "personal".to_string())
+            .unwrap();
+
+        assert!(manager.switch_vault("home", "x".to_string()).is_err());
+        assert!(manager.switch_vault("work", "wrong".to_string()).is_err());
+        let err = manager.switch_vault("work", String::new()).unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<ManagerError>(),
+            Some(&ManagerError::EmptyMasterPassword)
+        );
+
+        assert_eq!(manager.active_vault(), Some("default"));
+        assert!(manager.credentials().get("github").is_some());
+    }
+
+    #[test]
+    fn test_run_once_saves_changes() {
+        let (mut manager, temp_dir) = setup_manager();
+        manager.setup_new_user("password".to_string()).unwrap();
+
+        let result = manager.run_once(&["add", "github", "secret"]);
+        assert!(matches!(result, CommandResult::Success(_)));
+
+        let mut reloaded = Manager::new();
+        reloaded.set_db_path(temp_dir.path().join("test.db"));
+        assert!(
+            reloaded
+                .validate_master_password("password".to_string())
+                .unwrap()
+        );
+        assert_eq!(
+            reloaded.credentials().get("github"),
+            Some(&"secret".to_string())
+        );
+
+        match reloaded.run_once(&["get", "github"]) {
+            CommandResult::Success(Some(secret)) => assert_eq!(secret, "secret"),
+            _ => panic!("Expected the secret"),
+        }
+        assert!(matches!(
+            reloaded.run_once(&["get", "missing"]),
+            CommandResult::Error(_)
+        ));
+    }
+
+    #[test]
+    fn test_shell_sees_loaded_credentials_and_saves_with_master_password() {
+        let (mut manager, temp_dir) = setup_manager();
+        manager.setup_new_user("password".to_string()).unwrap();
+        manager.run_once(&["add", "github", "secret"]);
+
+        let mut reloaded = Manager::new();
+        reloaded.set_db_path(temp_dir.path().join("test.db"));
+        assert!(
+            reloaded
+                .validate_master_password("password".to_string())
+                .unwrap()
+        );
+
+        // Suggestions come from the key trie, built from the loaded credentials
+        match reloaded.run_once(&["get", "githb"]) {
+            CommandResult::Error(msg) => {
+                assert_eq!(msg, "'githb' not found. Did you mean 'github'?")
+            }
+            _ => panic!("Expected a suggestion"),
+        }
+
+        // Changes are saved under the same master password
+        reloaded.run_once(&["rename", "github", "gitlab"]);
+        let mut again = Manager::new();
+        again.set_db_path(temp_dir.path().join("test.db"));
+        assert!(!again.validate_master_password("wrong".to_string()).unwrap());
+        assert!(
+            again
+                .validate_master_password("password".to_string())
+                .unwrap()
+        );
+        assert_eq!(
+            again.credentials().get("gitlab"),
+            Some(&"secret".to_string())
+        );
+    }
+
+    #[test]
+    fn test_reload_after_external_change() {
+        let (mut manager, temp_dir) = setup_manager();
+        manager.setup_new_user("password".to_string()).unwrap();
+        manager.run_once(&["add", "github", "secret"]);
+
+        // Another copy of the vault, e.g. synced from elsewhere, replaces the file
+        let mut other = Manager::new();
+        other.set_db_path(temp_dir.path().join("test.db"));
+        assert!(
+            other
+                .validate_master_password("password".to_string())
+                .unwrap()
+        );
+        other
+            .credentials_mut()
+            .add("email".to_string(), "other".to_string())
+            .unwrap();
+        other.save_credentials().unwrap();
+
+        match manager.run_once(&["reload"]) {
+            CommandResult::Success(Some(msg)) => {
+                assert_eq!(msg, "Reloaded 2 credentials from disk")
+            }
+            _ => panic!("Expected success"),
+        }
+        assert_eq!(
+            manager.credentials().get("email"),
+            Some(&"other".to_string())
+        );
+        // The key trie is rebuilt from the reloaded credentials
+        assert!(matches!(
+            manager.run_once(&["get", "email"]),
+            CommandResult::Success(_)
+        ));
+    }
+
+    #[test]
+    fn test_reload_discards_unsaved_changes() {
+        let (mut manager, _temp_dir) = setup_manager();
+        manager.setup_new_user("password".to_string()).unwrap();
+        assert!(!manager.has_unsaved_changes());
+
+        manager
+            .credentials_mut()
+            .add("github".to_string(), "secret".to_string())
+            .unwrap();
+        assert!(manager.has_unsaved_changes());
+
+        manager.reload().unwrap();
+        assert!(!manager.has_unsaved_changes());
+        assert!(manager.credentials().is_empty());
+    }
+
+    #[test]
+    fn test_reload_with_changed_master_password() {
+        let (mut manager, temp_dir) = setup_manager();
+        manager.setup_new_user("password".to_string()).unwrap();
+        manager.run_once(&["add", "github", "secret"]);
+
+        let mut other = Manager::new();
+        other.set_db_path(temp_dir.path().join("test.db"));
+        assert!(
+            other
+                .validate_master_password("password".to_string())
+                .unwrap()
+        );
+        other.change_master_password("changed".to_string()).unwrap();
+
+        let err = manager.reload().unwrap_err();
+        assert!(err.to_string().contains("current master password"));
+        assert!(manager.credentials().contains_key("github"));
+    }
+
+    #[test]
+    fn test_commands_dispatch_through_the_registry() {
+        let (mut manager, _temp_dir) = setup_manager();
+        manager.setup_new_user("password".to_string()).unwrap();
+
+        // Aliases are resolved by the registry, not by the clap subcommands
+        assert!(matches!(
+            manager.run_once(&["a", "github", "secret"]),
+            CommandResult::Success(_)
+        ));
+        assert!(manager.credentials().contains_key("github"));
+
+        let mut registry = CommandRegistry::new();
+        register_all(&mut registry);
+        match manager.run_once(&["help"]) {
+            CommandResult::Success(Some(help)) => {
+                for command in registry.commands() {
+                    assert!(help.contains(command.name()), "{}", command.name());
+                }
+            }
+            _ => panic!("Expected help"),
+        }
+
+        match manager.run_once(&["frobnicate"]) {
+            CommandResult::Error(msg) => assert!(msg.starts_with("Unknown command: 'frobnicate'")),
+            _ => panic!("Expected an unknown command"),
+        }
+    }
+
+    #[test]
+    fn test_clear_master_password() {
+        let (mut manager, _temp_dir) = setup_manager();
+
+        manager.setup_new_user("test_password".to_string()).unwrap();
+        assert!(manager.master_password.is_some());
+
+        manager.clear_master_password();
+        assert!(manager.master_password.is_none());
+    }
+
+    #[test]
+    fn test_lock_clears_secrets_until_unlocked() {
+        let (mut manager, _temp_dir) = setup_manager();
+        manager.setup_new_user("test_password".to_string()).unwrap();
+        manager
+            .credentials_mut()
+            .add("github".to_string(), "secret".to_string())
+            .unwrap();
+        manager.save_credentials().unwrap();
+
+        manager.lock();
+        assert!(manager.master_password.is_none());
+        assert!(manager.envelope.is_none());
+        assert!(manager.credentials().is_empty());
+        assert!(manager.save_credentials().is_err());
+
+        assert!(
+            !manager
+                .validate_master_password("wrong".to_string())
+                .unwrap()
+        );
+        assert!(manager.credentials().is_empty());
+
+        assert!(
+            manager
+                .validate_master_password("test_password".to_string())
+                .unwrap()
+        );
+        assert_eq!(
+            manager.credentials().get("github"),
+            Some(&"secret".to_string())
+        );
+    }
+
+    #[test]
+    fn test_password_and_recovery_key_both_unlock() {
+        let (mut manager, _temp_dir) = setup_manager();
+        manager.setup_new_user("password".to_string()).unwrap();
+        let recovery_key = manager.enable_recovery().unwrap();
+
+        // Saving after enabling recovery must keep both unlock paths working
+        manager
+            .credentials_mut()
+            .add("github".to_string(), "secret".to_string())
+            .unwrap();
+        manager.save_credentials().unwrap();
+
+        let path = manager.db_path().unwrap().to_path_buf();
+        let mut by_password = Manager::new();
+        by_password.set_db_path(path.clone());
+        assert!(
+            by_password
+                .validate_master_password("password".to_string())
+                .unwrap()
+        );
+        assert_eq!(
+            by_password.credentials().get("github"),
+            Some(&"secret".to_string())
+        );
+
+        let mut by_recovery = Manager::new();
+        by_recovery.set_db_path(path);
+        by_recovery.recover(&recovery_key).unwrap();
+        assert_eq!(
+            by_recovery.credentials().get("github"),
+            Some(&"secret".to_string())
+        );
+    }
+
+    #[test]
+    fn test_recovery_key_ignores_dashes_and_case() {
+        let (mut manager, _temp_dir) = setup_manager();
+        manager.setup_new_user("password".to_string()).unwrap();
+        let recovery_key = manager.enable_recovery().unwrap();
+
+        let mut recovered = Manager::new();
+        recovered.set_db_path(manager.db_path().unwrap().to_path_buf());
+        recovered
+            .recover(&recovery_key.replace('-', " ").to_uppercase())
+            .unwrap();
+    }
+
+    #[test]
+    fn test_wrong_recovery_key_fails() {
+        let (mut manager, _temp_dir) = setup_manager();
+        manager.setup_new_user("password".to_string()).unwrap();
+        manager.enable_recovery().unwrap();
+
+        let mut recovered = Manager::new();
+        recovered.set_db_path(manager.db_path().unwrap().to_path_buf());
+        let err = recovered.recover("0000-0000-0000").unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<ManagerError>(),
+            Some(&ManagerError::InvalidRecoveryKey)
+        );
+        assert!(recovered.envelope.is_none());
+    }
+
+    #[test]
+    fn test_recover_without_recovery_key() {
+        let (mut manager, _temp_dir) = setup_manager();
+        manager.setup_new_user("password".to_string()).unwrap();
+
+        let mut recovered = Manager::new();
+        recovered.set_db_path(manager.db_path().unwrap().to_path_buf());
+        let err = recovered.recover("0000-0000").unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<ManagerError>(),
+            Some(&ManagerError::RecoveryNotEnabled)
+        );
+    }
+
+    #[test]
+    fn test_recover_then_set_new_password() {
+        let (mut manager, _temp_dir) = setup_manager();
+        manager.setup_new_user("forgotten".to_string()).unwrap();
+        manager
+            .credentials_mut()
+            .add("key".to_string(), "value".to_string())
+            .unwrap();
+        let recovery_key = manager.enable_recovery().unwrap();
+
+        let path = manager.db_path().unwrap().to_path_buf();
+        let mut recovered = Manager::new();
+        recovered.set_db_path(path.clone());
+        recovered.recover(&recovery_key).unwrap();
+        recovered
+            .change_master_password("new_password".to_string())
+            .unwrap();
+
+        let mut reloaded = Manager::new();
+        reloaded.set_db_path(path.clone());
+        assert!(
+            !reloaded
+                .validate_master_password("forgotten".to_string())
+                .unwrap()
+        );
+        assert!(
+            reloaded
+                .validate_master_password("new_password".to_string())
+                .unwrap()
+        );
+        assert_eq!(
+            reloaded.credentials().get("key"),
+            Some(&"value".to_string())
+        );
+
+        // The recovery key keeps working after the password change
+        let mut again = Manager::new();
+        again.set_db_path(path);
+        again.recover(&recovery_key).unwrap();
+    }
+
+    #[test]
+    fn test_change_password_only_rewraps_data_key() {
+        let (mut manager, _temp_dir) = setup_manager();
+        manager.setup_new_user("old_password".to_string()).unwrap();
+        manager
+            .credentials_mut()
+            .add("key".to_string(), "value".to_string())
+            .unwrap();
+        manager.save_credentials().unwrap();
+
+        let path = manager.db_path().unwrap().to_path_buf();
+        let before = load_encrypted_store(&path).unwrap();
+
+        manager
+            .change_master_password("new_password".to_string())
+            .unwrap();
+
+        let after = load_encrypted_store(&path).unwrap();
+        assert_eq!(after.encrypted_data, before.encrypted_data);
+        assert_eq!(after.encryption_nonce, before.encryption_nonce);
+        assert_ne!(after.data_key, before.data_key);
+        assert_ne!(after.argon2_salt, before.argon2_salt);
+
+        let mut reloaded = Manager::new();
+        reloaded.set_db_path(path);
+        assert!(
+            !reloaded
+                .validate_master_password("old_password".to_string())
+                .unwrap()
+        );
+        assert!(
+            reloaded
+                .validate_master_password("new_password".to_string())
+                .unwrap()
+        );
+        assert_eq!(
+            reloaded.credentials().get("key"),
+            Some(&"value".to_string())
+        );
+    }
+
+    #[test]
+    fn test_store_without_data_key_is_upgraded_on_save() {
+        let (mut manager, _temp_dir) = setup_manager();
+        let path = manager.db_path().unwrap().to_path_buf();
+
+        // Write a version 2 store, encrypted directly under the password key
+        let salt = generate_salt().unwrap();
+        let params = KdfParams::default();
+        let key = derive_key_with_params("password", &salt, &params).unwrap();
+        let nonce = generate_nonce().unwrap();
+        let data = serde_json::to_vec(&HashMap::from([("key", "value")])).unwrap();
+        let store = EncryptedStore {
+            version: 2,
+            argon2_salt: encode_salt(&salt),
+            encryption_nonce: encode_nonce(&nonce),
+            encrypted_data: encode_encrypted_data(
+                &encrypt(Cipher::ChaCha20Poly1305, &data, &key, &nonce).unwrap(),
+            ),
+            cipher: Cipher::ChaCha20Poly1305,
+            kdf_params: Some(params),
+            data_key: None,
+            recovery: None,
+            failed_attempts: 0,
+            locked_until: None,
+        };
+        save_encrypted_store(&path, &store).unwrap();
+
+        assert!(
+            manager
+                .validate_master_password("password".to_string())
+                .unwrap()
+        );
+        assert_eq!(manager.credentials().get("key"), Some(&"value".to_string()));
+
+        manager.save_credentials().unwrap();
+        let upgraded = load_encrypted_store(&path).unwrap();
+        assert_eq!(upgraded.version, STORE_VERSION);
+        assert!(upgraded.data_key.is_some());
+
+        let mut reloaded = Manager::new();
+        reloaded.set_db_path(path);
+        assert!(
+            reloaded
+                .validate_master_password("password".to_string())
+                .unwrap()
+        );
+        assert_eq!(
+            reloaded.credentials().get("key"),
+            Some(&"value".to_string())
+        );
+    }
+
+    #[test]
+    fn test_vault_round_trip_with_each_cipher() {
+        for cipher in [Cipher::ChaCha20Poly1305, Cipher::Aes256Gcm] {
+            let (mut manager, _temp_dir) = setup_manager();
+            manager.set_cipher(cipher);
+            manager.setup_new_user("password".to_string()).unwrap();
+            manager
+                .credentials_mut()
+                .add("github".to_string(), "secret".to_string())
+                .unwrap();
+            manager.save_credentials().unwrap();
+
+            let path = manager.db_path().unwrap().to_path_buf();
+            assert_eq!(load_encrypted_store(&path).unwrap().cipher, cipher);
+
+            // The store is read with its own cipher, whatever is configured
+            let mut reloaded = Manager::new();
+            reloaded.set_db_path(path);
+            assert!(
+                reloaded
+                    .validate_master_password("password".to_string())
+                    .unwrap()
+            );
+            assert_eq!(
+                reloaded.credentials().get("github"),
+                Some(&"secret".to_string())
+            );
+            assert_eq!(reloaded.crypto_info().unwrap().cipher, cipher.name());
+        }
+    }
+
+    #[test]
+    fn test_changing_cipher_reencrypts_on_save() {
+        let (mut manager, _temp_dir) = setup_manager();
+        manager.setup_new_user("password".to_string()).unwrap();
+        let recovery_key = manager.enable_recovery().unwrap();
+        let path = manager.db_path().unwrap().to_path_buf();
+        assert_eq!(
+            load_encrypted_store(&path).unwrap().cipher,
+            Cipher::ChaCha20Poly1305
+        );
+
+        manager.set_cipher(Cipher::Aes256Gcm);
+        manager.save_credentials().unwrap();
+        let store = load_encrypted_store(&path).unwrap();
+        assert_eq!(store.cipher, Cipher::Aes256Gcm);
+
+        // The recovery slot still unwraps the data key
+        let mut recovered = Manager::new();
+        recovered.set_db_path(path);
+        recovered.recover(&recovery_key).unwrap();
+        assert_eq!(recovered.credentials().list().len(), 0);
+    }
+
+    #[test]
+    fn test_store_without_cipher_is_chacha20() {
+        let (mut manager, _temp_dir) = setup_manager();
+        manager.setup_new_user("password".to_string()).unwrap();
+        let path = manager.db_path().unwrap().to_path_buf();
+
+        // Strip the field, as in a version 4 store
+        let mut json: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+        json.as_object_mut().unwrap().remove("cipher");
+        json["version"] = 4.into();
+        fs::write(&path, json.to_string()).unwrap();
+
+        let store = load_encrypted_store(&path).unwrap();
+        assert_eq!(store.cipher, Cipher::ChaCha20Poly1305);
+        assert!(
+            manager
+                .validate_master_password("password".to_string())
+                .unwrap()
+        );
+        assert_eq!(manager.migration_plan(), Some((4, STORE_VERSION)));
+    }
+
+    #[test]
+    fn test_credential_limits_apply_after_unlock() {
+        let (mut manager, _temp_dir) = setup_manager();
+        let limits = CredentialLimits {
+            max_key_len: 4,
+            max_secret_len: 4,
+            ..CredentialLimits::default()
+        };
+        manager.set_credential_limits(limits);
+        manager.setup_new_user("password".to_string()).unwrap();
+        assert!(
+            manager
+                .credentials_mut()
+                .add("long".to_string(), "12345".to_string())
+                .is_err()
+        );
+
+        manager
+            .validate_master_password("password".to_string())
+            .unwrap();
+        assert_eq!(manager.credentials().limits(), limits);
+    }
+
+    #[test]
+    fn test_crypto_info_for_new_vault() {
+        let (mut manager, _temp_dir) = setup_manager();
+        manager.setup_new_user("password".to_string()).unwrap();
+
+        let info = manager.crypto_info().unwrap();
+        assert_eq!(
+            info,
+            CryptoInfo {
+                version: STORE_VERSION,
+                kdf: "Argon2id v19",
+                kdf_params: KdfParams::default(),
+                salt_len: 16,
+                cipher: "ChaCha20-Poly1305",
+                key_cipher: "ChaCha20-Poly1305",
+                nonce_len: 12,
+                // 32-byte key plus the 16-byte Poly1305 tag
+                wrapped_key_len: Some(48),
+                recovery_key: false,
+            }
+        );
+
+        manager.enable_recovery().unwrap();
+        assert!(manager.crypto_info().unwrap().recovery_key);
+    }
+
+    #[test]
+    fn test_reencrypt_with_params() {
+        let (mut manager, _temp_dir) = setup_manager();
+        manager.setup_new_user("password".to_string()).unwrap();
+        manager
+            .credentials_mut()
+            .add("key".to_string(), "value".to_string())
+            .unwrap();
+        manager.save_credentials().unwrap();
+        let recovery_key = manager.enable_recovery().unwrap();
+
+        let params = KdfParams {
+            m_cost: 32768,
+            t_cost: 3,
+            p_cost: 1,
+        };
+        manager.reencrypt_with_params(params).unwrap();
+        assert_eq!(manager.crypto_info().unwrap().kdf_params, params);
+
+        // Later saves keep the new parameters
+        manager
+            .credentials_mut()
+            .add("other".to_string(), "value".to_string())
+            .unwrap();
+        manager.save_credentials().unwrap();
+        assert_eq!(manager.crypto_info().unwrap().kdf_params, params);
+
+        let path = manager.db_path().unwrap().to_path_buf();
+        let mut manager2 = Manager::new();
+        manager2.set_db_path(path.clone());
+        assert!(
+            manager2
+                .validate_master_password("password".to_string())
+                .unwrap()
+        );
+        assert_eq!(manager2.credentials().list().len(), 2);
+
+        let mut manager3 = Manager::new();
+        manager3.set_db_path(path);
+        manager3.recover(&recovery_key).unwrap();
+        assert_eq!(
+            manager3.credentials().get("key"),
+            Some(&"value".to_string())
+        );
+    }
+
+    #[test]
+    fn test_reencrypt_with_invalid_params_keeps_vault() {
+        let (mut manager, _temp_dir) = setup_manager();
+        manager.setup_new_user("password".to_string()).unwrap();
+
+        let params = KdfParams {
+            p_cost: 0,
+            ..KdfParams::default()
+        };
+        assert!(manager.reencrypt_with_params(params).is_err());
+        assert_eq!(
+            manager.crypto_info().unwrap().kdf_params,
+            KdfParams::default()
+        );
+    }
+
+    #[test]
+    fn test_large_vault_round_trip() {
+        let (mut manager, _temp_dir) = setup_manager();
+        manager.setup_new_user("password".to_string()).unwrap();
+
+        // Large enough that the encrypted payload is several megabytes
+        for i in 0..50_000 {
+            manager
+                .credentials_mut()
+                .add(format!("service-{:05}", i), format!("secret-{:0>64}", i))
+                .unwrap();
+        }
+        manager.save_credentials().unwrap();
+
+        let mut reloaded = Manager::new();
+        reloaded.set_db_path(manager.db_path().unwrap().to_path_buf());
+        assert!(
+            reloaded
+                .validate_master_password("password".to_string())
+                .unwrap()
+        );
+        assert_eq!(reloaded.credentials().list().len(), 50_000);
+        assert_eq!(
+            reloaded.credentials().get("service-49999"),
+            Some(&format!("secret-{:0>64}", 49_999))
+        );
+    }
+
+    fn locked_down_manager(action: LockoutAction) -> (Manager, TempDir) {
+        let (mut manager, temp_dir) = setup_manager();
+        manager.setup_new_user("password".to_string()).unwrap();
+        manager.set_lockout_policy(Some(LockoutPolicy {
+            max_attempts: 3,
+            action,
+        }));
+        (manager, temp_dir)
+    }
+
+    /// Returns a password source answering with `passwords` in turn.
+    fn scripted(passwords: &[&str]) -> impl FnMut(&str) -> Option<String> {
+        let mut passwords: Vec<String> = passwords.iter().rev().map(|p| p.to_string()).collect();
+        move |_| passwords.pop()
+    }
+
+    #[test]
+    fn test_reauthenticate_retries_wrong_passwords() {
+        let (mut manager, _temp_dir) =
+            locked_down_manager(LockoutAction::Cooldown(Duration::from_secs(600)));
+        manager
+            .credentials_mut()
+            .add("key".to_string(), "value".to_string())
+            .unwrap();
+        manager.save_credentials().unwrap();
+        let path = manager.db_path().unwrap().to_path_buf();
+
+        manager.lock();
+        manager
+            .reauthenticate(&mut scripted(&["wrong", "password"]))
+            .unwrap();
+        assert_eq!(manager.credentials().get("key"), Some(&"value".to_string()));
+        assert_eq!(load_encrypted_store(&path).unwrap().failed_attempts, 0);
+
+        // Wrong passwords still count against the lockout
+        manager.lock();
+        let err = manager
+            .reauthenticate(&mut scripted(&["a", "b", "c", "password"]))
+            .unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<ManagerError>(),
+            Some(ManagerError::LockedOut { .. })
+        ));
+        assert!(manager.credentials().is_empty());
+    }
+
+    #[test]
+    fn test_lockout_cooldown_after_failed_attempts() {
+        let (mut manager, _temp_dir) =
+            locked_down_manager(LockoutAction::Cooldown(Duration::from_secs(600)));
+        let path = manager.db_path().unwrap().to_path_buf();
+
+        for attempt in 1..3 {
+            assert!(
+                !manager
+                    .validate_master_password("wrong".to_string())
+                    .unwrap()
+            );
+            // The count survives restarts
+            assert_eq!(
+                load_encrypted_store(&path).unwrap().failed_attempts,
+                attempt
+            );
+        }
+
+        let err = manager
+            .validate_master_password("wrong".to_string())
+            .unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<ManagerError>(),
+            Some(ManagerError::LockedOut { retry_after }) if *retry_after <= 600
+        ));
+
+        // Even the right password is refused during the cooldown
+        let err = manager
+            .validate_master_password("password".to_string())
+            .unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<ManagerError>(),
+            Some(ManagerError::LockedOut { .. })
+        ));
+
+        // Once the cooldown has passed, the right password unlocks and resets it
+        let mut store = load_encrypted_store(&path).unwrap();
+        store.locked_until = Some(1);
+        save_encrypted_store(&path, &store).unwrap();
+        assert!(
+            manager
+                .validate_master_password("password".to_string())
+                .unwrap()
+        );
+        let store = load_encrypted_store(&path).unwrap();
+        assert_eq!(store.failed_attempts, 0);
+        assert_eq!(store.locked_until, None);
+    }
+
+    #[test]
+    fn test_switch_vault_counts_failed_attempts() {
+        let (mut manager, temp_dir) =
+            locked_down_manager(LockoutAction::Cooldown(Duration::from_secs(600)));
+        let work_path = temp_dir.path().join("work.db");
+        manager.add_vault("default", temp_dir.path().join("test.db"));
+        manager.add_vault("work", work_path.clone());
+        manager.switch_vault("work", "work".to_string()).unwrap();
+        manager
+            .switch_vault("default", "password".to_string())
+            .unwrap();
+
+        for attempt in 1..3 {
+            let err = manager
+                .switch_vault("work", "wrong".to_string())
+                .unwrap_err();
+            assert!(err.to_string().contains("Invalid master password"));
+            assert_eq!(
+                load_encrypted_store(&work_path).unwrap().failed_attempts,
+                attempt
+            );
+        }
+        let err = manager
+            .switch_vault("work", "wrong".to_string())
+            .unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<ManagerError>(),
+            Some(ManagerError::LockedOut { .. })
+        ));
+
+        // The cooldown applies to the right password too
+        let err = manager
+            .switch_vault("work", "work".to_string())
+            .unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<ManagerError>(),
+            Some(ManagerError::LockedOut { .. })
+        ));
+        assert_eq!(manager.active_vault(), Some("default"));
+    }
+
+    #[test]
+    fn test_switch_vault_reports_damaged_vault() {
+        let (mut manager, temp_dir) = setup_manager();
+        manager.setup_new_user("personal".to_string()).unwrap();
+        let work_path = temp_dir.path().join("work.db");
+        fs::write(&work_path, "not a store").unwrap();
+        manager.add_vault("work", work_path);
+
+        let err = manager
+            .switch_vault("work", "work".to_string())
+            .unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<LoadError>(),
+            Some(LoadError::CorruptStore(_))
+        ));
+        assert!(!err.to_string().contains("Invalid master password"));
+    }
+
+    #[test]
+    fn test_restore_backup_counts_failed_attempts() {
+        let (mut manager, _temp_dir) =
+            locked_down_manager(LockoutAction::Cooldown(Duration::from_secs(600)));
+        let path = manager.db_path().unwrap().to_path_buf();
+        manager.save_credentials().unwrap();
+        let backup = previous_backup_path(&path);
+        let name = backup.file_name().unwrap().to_str().unwrap();
+
+        for attempt in 1..3 {
+            let err = manager
+                .restore_backup(name, "wrong".to_string())
+                .unwrap_err();
+            assert!(err.to_string().contains("Could not decrypt"));
+            assert_eq!(
+                load_encrypted_store(&path).unwrap().failed_attempts,
+                attempt
+            );
+        }
+        let err = manager
+            .restore_backup(name, "wrong".to_string())
+            .unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<ManagerError>(),
+            Some(ManagerError::LockedOut { .. })
+        ));
+    }
+
+    #[test]
+    fn test_lockout_wipes_vault_after_failed_attempts() {
+        let (mut manager, _temp_dir) = locked_down_manager(LockoutAction::Wipe);
+        let path = manager.db_path().unwrap().to_path_buf();
+        manager.save_credentials().unwrap();
+        assert!(previous_backup_path(&path).exists());
+
+        for _ in 1..3 {
+            assert!(
+                !manager
+                    .validate_master_password("wrong".to_string())
+                    .unwrap()
+            );
+        }
+        let err = manager
+            .validate_master_password("wrong".to_string())
+            .unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<ManagerError>(),
+            Some(&ManagerError::VaultWiped)
+        );
+
+        assert!(!path.exists());
+        assert!(!previous_backup_path(&path).exists());
+        assert!(manager.is_new_user());
+    }
+
+    #[test]
+    fn test_lockout_correct_password_resets_counter() {
+        let (mut manager, _temp_dir) = locked_down_manager(LockoutAction::Wipe);
+        let path = manager.db_path().unwrap().to_path_buf();
+
+        for _ in 0..2 {
+            assert!(
+                !manager
+                    .validate_master_password("wrong".to_string())
+                    .unwrap()
+            );
+        }
+        assert!(
+            manager
+                .validate_master_password("password".to_string())
+                .unwrap()
+        );
+        assert_eq!(load_encrypted_store(&path).unwrap().failed_attempts, 0);
+
+        // The full number of attempts is available again
+        for _ in 0..2 {
+            assert!(
+                !manager
+                    .validate_master_password("wrong".to_string())
+                    .unwrap()
+            );
+        }
+        assert!(path.exists());
+    }
+
+    #[test]
+    fn test_failed_attempts_not_counted_without_policy() {
+        let (mut manager, _temp_dir) = setup_manager();
+        manager.setup_new_user("password".to_string()).unwrap();
+        let path = manager.db_path().unwrap().to_path_buf();
+
+        for _ in 0..5 {
+            assert!(
+                !manager
+                    .validate_master_password("wrong".to_string())
+                    .unwrap()
+            );
+        }
+        assert_eq!(load_encrypted_store(&path).unwrap().failed_attempts, 0);
+    }
+
+    #[test]
+    fn test_wrong_password_is_not_an_error() {
+        let (mut manager, _temp_dir) = setup_manager();
+        manager.setup_new_user("password".to_string()).unwrap();
+
+        assert!(
+            !manager
+                .validate_master_password("wrong".to_string())
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn test_corrupted_store_is_an_error() {
+        let (mut manager, _temp_dir) = setup_manager();
+        manager.setup_new_user("password".to_string()).unwrap();
+        let path = manager.db_path().unwrap().to_path_buf();
+        let original = fs::read_to_string(&path).unwrap();
+
+        // Tampered credentials under an intact data key
+        let mut store: serde_json::Value = serde_json::from_str(&original).unwrap();
+        store["encrypted_data"] = serde_json::Value::String("AAAAAAAAAAAAAAAAAAAAAAAA".to_string());
+        fs::write(&path, store.to_string()).unwrap();
+        let err = manager
+            .validate_master_password("password".to_string())
+            .unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<LoadError>(),
+            Some(LoadError::CorruptStore(_))
+        ));
+
+        // Not a store at all
+        fs::write(&path, "{\"version\": 4,").unwrap();
+        let err = manager
+            .validate_master_password("password".to_string())
+            .unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<LoadError>(),
+            Some(LoadError::CorruptStore(_))
+        ));
+    }
+
+    #[test]
+    fn test_unreadable_store_is_an_error() {
+        let (mut manager, temp_dir) = setup_manager();
+        // A directory exists but cannot be read as a file
+        manager.set_db_path(temp_dir.path().to_path_buf());
+
+        let err = manager
+            .validate_master_password("password".to_string())
+            .unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<LoadError>(),
+            Some(LoadError::Io(_))
+        ));
+    }
+
+    /// Key derivations run on this thread while validating `password`.
+    fn kdf_calls_validating(manager: &mut Manager, password: &str) -> usize {
+        let before = KDF_CALLS.with(|calls| calls.get());
+        let _ = manager.validate_master_password(password.to_string());
+        KDF_CALLS.with(|calls| calls.get()) - before
+    }
+
+    #[test]
+    fn test_every_validation_failure_derives_a_key() {
+        // Missing vault
+        let (mut manager, _temp_dir) = setup_manager();
+        assert!(
+            !manager
+                .validate_master_password("password".to_string())
+                .unwrap()
+        );
+        assert_eq!(kdf_calls_validating(&mut manager, "password"), 1);
+
+        // Wrong password
+        manager.setup_new_user("password".to_string()).unwrap();
+        assert_eq!(kdf_calls_validating(&mut manager, "wrong"), 1);
+
+        // Store that doesn't parse
+        let path = manager.db_path().unwrap().to_path_buf();
+        fs::write(&path, "not a store").unwrap();
+        assert_eq!(kdf_calls_validating(&mut manager, "password"), 1);
+
+        // The same with a lockout policy
+        manager.set_lockout_policy(Some(LockoutPolicy {
+            max_attempts: 3,
+            action: LockoutAction::Wipe,
+        }));
+        assert_eq!(kdf_calls_validating(&mut manager, "password"), 1);
+    }
 }
@@ -0,0 +1,343 @@
+//! Breach checks against the Have I Been Pwned password corpus.
+//!
+//! Secrets are checked with the k-anonymity range API: only the first five
+//! hex characters of a secret's SHA-1 hash are sent, the service answers with
+//! every hash suffix it knows for that prefix, and the match is made locally.
+//! Neither the secret nor its full hash leaves the machine.
+//!
+//! Fetching a range is behind the [`RangeSource`] trait so the comparison can
+//! run offline. The HTTP client is only built with the `hibp` feature, since
+//! it is the one part of passmgr that talks to the network. Without it, a
+//! downloaded hash dump can be searched with [`HashDb`] instead.
+
+use std::cmp::Ordering;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, Seek, SeekFrom};
+use std::path::Path;
+
+use anyhow::Result;
+use sha1::{Digest, Sha1};
+
+/// Number of hash characters sent to the range API.
+pub const PREFIX_LEN: usize = 5;
+
+/// Endpoint the prefix is appended to.
+#[allow(unused)]
+pub const RANGE_URL: &str = "https://api.pwnedpasswords.com/range/";
+
+/// Fetches the hash suffixes known for a prefix.
+pub trait RangeSource {
+    /// Returns the range for `prefix` as served by the API: one
+    /// `SUFFIX:COUNT` line per known hash.
+    fn fetch_range(&self, prefix: &str) -> Result<String>;
+}
+
+/// Returns the uppercase hex SHA-1 hash of `secret`, as used by the API.
+pub fn sha1_hex(secret: &str) -> String {
+    Sha1::digest(secret.as_bytes())
+        .iter()
+        .map(|byte| format!("{:02X}", byte))
+        .collect()
+}
+
+/// Returns how often `suffix` appears in `range`, or 0 if it does not.
+///
+/// Lines that cannot be parsed are skipped. Padding entries, which the API
+/// adds with a count of 0, never match a real secret's count.
+pub fn count_in_range(range: &str, suffix: &str) -> u64 {
+    range
+        .lines()
+        .filter_map(|line| line.trim().split_once(':'))
+        .find(|(candidate, _)| candidate.eq_ignore_ascii_case(suffix))
+        .and_then(|(_, count)| count.trim().parse().ok())
+        .unwrap_or(0)
+}
+
+/// Returns how many times `secret` appears in known breaches.
+///
+/// Only the hash prefix is passed to `source`.
+pub fn breach_count(secret: &str, source: &dyn RangeSource) -> Result<u64> {
+    let hash = sha1_hex(secret);
+    let (prefix, suffix) = hash.split_at(PREFIX_LEN);
+    let range = source.fetch_range(prefix)?;
+    Ok(count_in_range(&range, suffix))
+}
+
+/// A local Have I Been Pwned hash dump, sorted by hash.
+///
+/// Each line is `HASH:COUNT` with the full 40-character SHA-1 hash, as in the
+/// dumps ordered by hash. Lookups binary search the file through seeks, so
+/// only a few lines are read however large it is.
+pub struct HashDb {
+    file: File,
+    len: u64,
+}
+
+impl HashDb {
+    /// Opens the dump at `path`.
+    pub fn open(path: &Path) -> io::Result<Self> {
+        let file = File::open(path)?;
+        let len = file.metadata()?.len();
+        Ok(Self { file, len })
+    }
+
+    /// Returns whether the dump lists `sha1_hex`, in either case.
+    pub fn contains(&self, sha1_hex: &str) -> io::Result<bool> {
+        let target = sha1_hex.to_ascii_uppercase();
+        let mut reader = BufReader::new(&self.file);
+        let mut line = String::new();
+
+        // Search the lines starting in [lo, hi)
+        let (mut lo, mut hi) = (0, self.len);
+        while lo < hi {
+            let mid = lo + (hi - lo) / 2;
+            let start = Self::next_line_start(&mut reader, mid)?;
+            if start >= hi {
+                hi = mid;
+                continue;
+            }
+            line.clear();
+            let read = reader.read_line(&mut line)?;
+            let hash = line.split(':').next().unwrap_or("").trim();
+            match hash.to_ascii_uppercase().as_str().cmp(&target) {
+                Ordering::Equal => return Ok(true),
+                Ordering::Less => lo = start + read as u64,
+                Ordering::Greater => hi = mid,
+            }
+        }
+        Ok(false)
+    }
+
+    /// Moves `reader` to the first line starting at or after `offset`,
+    /// returning that line's offset.
+    fn next_line_start(reader: &mut BufReader<&File>, offset: u64) -> io::Result<u64> {
+        if offset == 0 {
+            reader.seek(SeekFrom::Start(0))?;
+            return Ok(0);
+        }
+        // A line starts at `offset` only if the byte before it ends a line
+        reader.seek(SeekFrom::Start(offset - 1))?;
+        let mut skipped = Vec::new();
+        let read = reader.read_until(b'\n', &mut skipped)?;
+        Ok(offset - 1 + read as u64)
+    }
+
+    /// Returns whether `secret` appears in the dump.
+    pub fn contains_secret(&self, secret: &str) -> io::Result<bool> {
+        self.contains(&sha1_hex(secret))
+    }
+}
+
+/// Fetches ranges from the Have I Been Pwned API over HTTPS.
+#[cfg(feature = "hibp")]
+pub struct HttpRangeSource {
+    agent: ureq::Agent,
+}
+
+#[cfg(feature = "hibp")]
+impl HttpRangeSource {
+    /// Seconds a request may take before it is abandoned.
+    const TIMEOUT_SECS: u64 = 10;
+
+    /// Creates a client with a request timeout.
+    pub fn new() -> Self {
+        let config = ureq::Agent::config_builder()
+            .timeout_global(Some(std::time::Duration::from_secs(Self::TIMEOUT_SECS)))
+            .user_agent(concat!("passmgr/", env!("CARGO_PKG_VERSION")))
+            .build();
+        Self {
+            agent: config.into(),
+        }
+    }
+}
+
+#[cfg(feature = "hibp")]
+impl Default for HttpRangeSource {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(feature = "hibp")]
+impl RangeSource for HttpRangeSource {
+    fn fetch_range(&self, prefix: &str) -> Result<String> {
+        let mut response = self
+            .agent
+            .get(format!("{}{}", RANGE_URL, prefix))
+            // Pads the response so its size does not hint at the prefix
+            .header("Add-Padding", "true")
+            .call()?;
+        Ok(response.body_mut().read_to_string()?)
+    }
+}
+
+/// Returns the range source to check secrets with.
+#[cfg(feature = "hibp")]
+pub fn default_source() -> Option<Box<dyn RangeSource>> {
+    Some(Box::new(HttpRangeSource::new()))
+}
+
+/// Returns `None`: this build cannot reach the network.
+#[cfg(not(feature = "hibp"))]
+pub fn default_source() -> Option<Box<dyn RangeSource>> {
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::anyhow;
+    use std::cell::RefCell;
+
+    /// Serves a fixed range and records the prefixes asked for.
+    struct MockSource {
+        range: &'static str,
+        prefixes: RefCell<Vec<String>>,
+    }
+
+    impl MockSource {
+        fn new(range: &'static str) -> Self {
+            Self {
+                range,
+                prefixes: RefCell::new(Vec::new()),
+            }
+        }
+    }
+
+    impl RangeSource for MockSource {
+        fn fetch_range(&self, prefix: &str) -> Result<String> {
+            self.prefixes.borrow_mut().push(prefix.to_string());
+            Ok(self.range.to_string())
+        }
+    }
+
+    struct OfflineSource;
+
+    impl RangeSource for OfflineSource {
+        fn fetch_range(&self, _prefix: &str) -> Result<String> {
+            Err(anyhow!("connection refused"))
+        }
+    }
+
+    #[test]
+    fn test_sha1_hex() {
+        assert_eq!(
+            sha1_hex("password"),
+            "5BAA61E4C9B93F3F0682250B6CF8331B7EE68FD8"
+        );
+        assert_eq!(sha1_hex(""), "DA39A3EE5E6B4B0D3255BFEF95601890AFD80709");
+    }
+
+    #[test]
+    fn test_count_in_range() {
+        let range = "003D68EB55068C33ACE09247EE4C639306B:3\r\n\
+                     1E4C9B93F3F0682250B6CF8331B7EE68FD8:10434004\r\n\
+                     not a hash line\r\n\
+                     FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFF:0";
+        assert_eq!(
+            count_in_range(range, "1E4C9B93F3F0682250B6CF8331B7EE68FD8"),
+            10434004
+        );
+        assert_eq!(
+            count_in_range(range, "1e4c9b93f3f0682250b6cf8331b7ee68fd8"),
+            10434004
+        );
+        assert_eq!(
+            count_in_range(range, "0000000000000000000000000000000000"),
+            0
+        );
+        assert_eq!(
+            count_in_range(range, "FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFF"),
+            0
+        );
+        assert_eq!(count_in_range("", "1E4C9"), 0);
+    }
+
+    #[test]
+    fn test_breach_count_sends_only_the_prefix() {
+        let source = MockSource::new("1E4C9B93F3F0682250B6CF8331B7EE68FD8:42\n");
+
+        assert_eq!(breach_count("password", &source).unwrap(), 42);
+        assert_eq!(breach_count("not in the range", &source).unwrap(), 0);
+        let prefixes = source.prefixes.borrow();
+        assert_eq!(prefixes[0], "5BAA6");
+        assert!(prefixes.iter().all(|prefix| prefix.len() == PREFIX_LEN));
+    }
+
+    /// Writes a sorted dump of the hashes of `secrets`, with Windows line
+    /// endings as in the published files.
+    fn write_dump(dir: &Path, secrets: &[&str]) -> std::path::PathBuf {
+        let mut hashes: Vec<String> = secrets.iter().map(|s| sha1_hex(s)).collect();
+        hashes.sort();
+        let contents: String = hashes
+            .iter()
+            .enumerate()
+            .map(|(i, hash)| format!("{}:{}\r\n", hash, i + 1))
+            .collect();
+        let path = dir.join("pwned-passwords.txt");
+        std::fs::write(&path, contents).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_hash_db_finds_every_entry() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let secrets: Vec<String> = (0..200).map(|i| format!("secret{}", i)).collect();
+        let refs: Vec<&str> = secrets.iter().map(String::as_str).collect();
+        let db = HashDb::open(&write_dump(dir.path(), &refs)).unwrap();
+
+        for secret in &secrets {
+            assert!(db.contains_secret(secret).unwrap(), "{} not found", secret);
+        }
+        for i in 200..400 {
+            assert!(!db.contains_secret(&format!("secret{}", i)).unwrap());
+        }
+    }
+
+    #[test]
+    fn test_hash_db_boundaries() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let db = HashDb::open(&write_dump(dir.path(), &["password", "123456", "qwerty"])).unwrap();
+
+        let mut hashes: Vec<String> = ["password", "123456", "qwerty"]
+            .iter()
+            .map(|s| sha1_hex(s))
+            .collect();
+        hashes.sort();
+        // First and last lines, and lowercase lookups
+        assert!(db.contains(&hashes[0]).unwrap());
+        assert!(db.contains(&hashes[2]).unwrap());
+        assert!(db.contains(&hashes[1].to_ascii_lowercase()).unwrap());
+        // Before the first and after the last line
+        assert!(!db.contains(&"0".repeat(40)).unwrap());
+        assert!(!db.contains(&"F".repeat(40)).unwrap());
+    }
+
+    #[test]
+    fn test_hash_db_edge_files() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("dump.txt");
+
+        std::fs::write(&path, "").unwrap();
+        assert!(
+            !HashDb::open(&path)
+                .unwrap()
+                .contains_secret("password")
+                .unwrap()
+        );
+
+        // A single line without a trailing newline
+        std::fs::write(&path, format!("{}:3", sha1_hex("password"))).unwrap();
+        let db = HashDb::open(&path).unwrap();
+        assert!(db.contains_secret("password").unwrap());
+        assert!(!db.contains_secret("123456").unwrap());
+
+        assert!(HashDb::open(&dir.path().join("missing.txt")).is_err());
+    }
+
+    #[test]
+    fn test_breach_count_reports_source_errors() {
+        let err = breach_count("password", &OfflineSource).unwrap_err();
+        assert_eq!(err.to_string(), "connection refused");
+    }
+}
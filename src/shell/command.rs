@@ -2,11 +2,16 @@
 //!
 //! This module defines the command system that replaces clap for the REPL.
 
+use serde::Serialize;
 use std::collections::HashMap;
 use std::fmt;
 use std::sync::Arc;
 
-use crate::credentials::Credentials;
+use crate::credentials::{Credentials, NameNormalization};
+use crate::pwned::RangeSource;
+use crate::shell::history::CommandHistory;
+use crate::shell::session::Session;
+use crate::shell::undo::{Change, UndoStack};
 use crate::trie::Trie;
 
 /// Result of executing a command.
@@ -16,8 +21,12 @@ pub enum CommandResult {
     Success(Option<String>),
     /// Command failed with error message.
     Error(String),
+    /// Command executed successfully with machine-readable output.
+    Json(serde_json::Value),
     /// Signal to exit the shell.
     Exit,
+    /// Signal to lock the vault until the master password is entered again.
+    Lock,
     /// Continue without output.
     Continue,
 }
@@ -34,12 +43,200 @@ impl CommandResult {
         CommandResult::Success(None)
     }
 
+    /// Creates a success result with JSON output.
+    pub fn json(value: serde_json::Value) -> Self {
+        CommandResult::Json(value)
+    }
+
     /// Creates an error result.
     pub fn error(msg: impl Into<String>) -> Self {
         CommandResult::Error(msg.into())
     }
 }
 
+/// Removes `flag` from `args`, returning whether it was present and the remaining arguments.
+///
+/// Commands use this for boolean options such as `--count-only`, which may
+/// appear anywhere among the positional arguments.
+pub fn take_flag<'a>(args: &[&'a str], flag: &str) -> (bool, Vec<&'a str>) {
+    let rest: Vec<&str> = args.iter().copied().filter(|a| *a != flag).collect();
+    (rest.len() != args.len(), rest)
+}
+
+/// Checks the number of `args` against the command's [`Command::min_args`]
+/// and [`Command::max_args`].
+///
+/// The dispatcher runs this before [`Command::execute`], so commands can
+/// rely on the argument count being in range.
+pub fn check_arg_count(cmd: &dyn Command, args: &[&str]) -> Result<(), CommandResult> {
+    if args.len() < cmd.min_args() {
+        return Err(CommandResult::error(format!(
+            "Usage: {}\nMissing required arguments",
+            cmd.usage()
+        )));
+    }
+    if cmd.max_args().is_some_and(|max| args.len() > max) {
+        return Err(CommandResult::error(format!(
+            "Usage: {}\nToo many arguments",
+            cmd.usage()
+        )));
+    }
+    Ok(())
+}
+
+/// Removes `option` and the value following it from `args`, returning the value
+/// and the remaining arguments.
+///
+/// Fails if `option` is the last argument or appears more than once.
+pub fn take_option<'a>(
+    args: &[&'a str],
+    option: &str,
+) -> Result<(Option<&'a str>, Vec<&'a str>), String> {
+    let mut value = None;
+    let mut rest = Vec::with_capacity(args.len());
+    let mut iter = args.iter().copied();
+    while let Some(arg) = iter.next() {
+        if arg != option {
+            rest.push(arg);
+            continue;
+        }
+        if value.is_some() {
+            return Err(format!("{} given more than once", option));
+        }
+        match iter.next() {
+            Some(v) => value = Some(v),
+            None => return Err(format!("{} requires a value", option)),
+        }
+    }
+    Ok((value, rest))
+}
+
+/// Splits a command line into words, honoring quotes and backslash escapes.
+///
+/// Words are split as by [`shell_words::split`], except that `#` does not
+/// start a comment: it is an ordinary character wherever it appears, so
+/// `add github #hunter2` keeps its secret. Fails on an unclosed quote.
+pub fn split_line(line: &str) -> Result<Vec<String>, String> {
+    const UNCLOSED: &str = "missing closing quote";
+
+    let mut words = Vec::new();
+    let mut word: Option<String> = None;
+    let mut chars = line.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            ' ' | '\t' | '\n' => words.extend(word.take()),
+            '\\' => match chars.next() {
+                Some('\n') => {}
+                Some(c) => word.get_or_insert_default().push(c),
+                None => word.get_or_insert_default().push('\\'),
+            },
+            '\'' => {
+                let word = word.get_or_insert_default();
+                loop {
+                    match chars.next() {
+                        Some('\'') => break,
+                        Some(c) => word.push(c),
+                        None => return Err(UNCLOSED.to_string()),
+                    }
+                }
+            }
+            '"' => {
+                let word = word.get_or_insert_default();
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some('\\') => match chars.next() {
+                            Some('\n') => {}
+                            Some(c @ ('$' | '`' | '"' | '\\')) => word.push(c),
+                            Some(c) => {
+                                word.push('\\');
+                                word.push(c);
+                            }
+                            None => return Err(UNCLOSED.to_string()),
+                        },
+                        Some(c) => word.push(c),
+                        None => return Err(UNCLOSED.to_string()),
+                    }
+                }
+            }
+            c => word.get_or_insert_default().push(c),
+        }
+    }
+    words.extend(word);
+    Ok(words)
+}
+
+/// Expands a secret given on the command line.
+///
+/// `@PATH` reads the secret from the file at `PATH`, without its trailing
+/// newline, so long secrets such as API tokens stay out of the shell
+/// history. A secret that really starts with `@` is written `\@`; any other
+/// value, including a lone `@`, is used as is.
+pub fn expand_secret(arg: &str) -> Result<String, String> {
+    if let Some(literal) = arg.strip_prefix("\\@") {
+        return Ok(format!("@{}", literal));
+    }
+    let Some(path) = arg.strip_prefix('@').filter(|path| !path.is_empty()) else {
+        return Ok(arg.to_string());
+    };
+    let mut secret = std::fs::read_to_string(path)
+        .map_err(|e| format!("Could not read the secret from '{}': {}", path, e))?;
+    if secret.ends_with('\n') {
+        secret.pop();
+        if secret.ends_with('\r') {
+            secret.pop();
+        }
+    }
+    if secret.is_empty() {
+        return Err(format!("'{}' is empty", path));
+    }
+    Ok(secret)
+}
+
+/// Maximum edit distance for "did you mean" suggestions.
+const SUGGESTION_DISTANCE: usize = 2;
+
+/// Maximum number of "did you mean" suggestions.
+const MAX_SUGGESTIONS: usize = 3;
+
+/// Builds the error for a credential name that does not exist, suggesting
+/// similar names from `key_trie` if there are any.
+pub fn not_found(name: &str, key_trie: &Trie) -> CommandResult {
+    // Allow fewer edits for short names so suggestions stay plausible
+    let max_distance = SUGGESTION_DISTANCE.min(name.chars().count() / 3);
+    let suggestions: Vec<String> = key_trie
+        .closest(name, max_distance)
+        .into_iter()
+        .take(MAX_SUGGESTIONS)
+        .map(|s| format!("'{}'", s))
+        .collect();
+
+    if suggestions.is_empty() {
+        CommandResult::error(format!("'{}' not found", name))
+    } else {
+        CommandResult::error(format!(
+            "'{}' not found. Did you mean {}?",
+            name,
+            suggestions.join(", ")
+        ))
+    }
+}
+
+/// How commands that support it format their output.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum OutputFormat {
+    /// Text meant for people.
+    #[default]
+    Text,
+    /// JSON meant for scripts, as if `--json` was given to every command
+    /// that takes it.
+    Json,
+}
+
+/// Reads a line of input after showing a prompt, returning `None` if input
+/// was cancelled or could not be read.
+pub type LinePrompt<'a> = dyn FnMut(&str) -> Option<String> + 'a;
+
 /// Context available to commands during execution.
 pub struct ShellContext<'a> {
     /// Mutable reference to credentials.
@@ -50,6 +247,32 @@ pub struct ShellContext<'a> {
     pub registry: Option<&'a CommandRegistry>,
     /// The key trie for completions (updated on credential changes).
     pub key_trie: &'a mut Trie,
+    /// Host session for operations beyond the in-memory credentials.
+    pub session: Option<&'a mut dyn Session>,
+    /// Whether `get` prints secrets in plain text rather than masked.
+    pub reveal_secrets: bool,
+    /// How credential names typed by the user are normalized.
+    pub name_normalization: NameNormalization,
+    /// The interactive shell's command history, if there is one.
+    pub history: Option<&'a mut dyn CommandHistory>,
+    /// Whether destructive commands ask for confirmation before running.
+    pub confirm_destructive: bool,
+    /// Reads the user's answer to a question, when input can be read.
+    pub prompt: Option<&'a mut LinePrompt<'a>>,
+    /// Reads a secret without echoing it, when input can be read.
+    pub secret_reader: Option<&'a mut LinePrompt<'a>>,
+    /// Changes made this session that `undo` can revert, if it is available.
+    pub undo: Option<&'a mut UndoStack>,
+    /// Whether reading a secret is recorded in its metadata.
+    pub track_access: bool,
+    /// Output format of commands that can print JSON.
+    pub output_format: OutputFormat,
+    /// Whether `add` warns when the new secret is weak.
+    pub warn_weak_secrets: bool,
+    /// Warnings to show alongside the command's result.
+    pub warnings: Vec<String>,
+    /// Where `pwned` looks up breached secrets, if this build can.
+    pub breach_source: Option<&'a dyn RangeSource>,
 }
 
 impl<'a> ShellContext<'a> {
@@ -60,6 +283,19 @@ impl<'a> ShellContext<'a> {
             modified: false,
             registry: None,
             key_trie,
+            session: None,
+            reveal_secrets: false,
+            name_normalization: NameNormalization::None,
+            history: None,
+            confirm_destructive: false,
+            prompt: None,
+            secret_reader: None,
+            undo: None,
+            track_access: false,
+            output_format: OutputFormat::Text,
+            warn_weak_secrets: false,
+            warnings: Vec::new(),
+            breach_source: None,
         }
     }
 
@@ -69,6 +305,137 @@ impl<'a> ShellContext<'a> {
         self
     }
 
+    /// Sets the host session used by commands that touch the vault file.
+    pub fn with_session(mut self, session: &'a mut dyn Session) -> Self {
+        self.session = Some(session);
+        self
+    }
+
+    /// Sets the command history shown and cleared by `history`.
+    pub fn with_history(mut self, history: &'a mut dyn CommandHistory) -> Self {
+        self.history = Some(history);
+        self
+    }
+
+    /// Sets whether `get` prints secrets in plain text by default.
+    pub fn with_reveal_secrets(mut self, reveal: bool) -> Self {
+        self.reveal_secrets = reveal;
+        self
+    }
+
+    /// Sets how credential names are normalized on add and lookup.
+    pub fn with_name_normalization(mut self, normalization: NameNormalization) -> Self {
+        self.name_normalization = normalization;
+        self
+    }
+
+    /// Sets whether destructive commands ask for confirmation first.
+    pub fn with_confirm_destructive(mut self, confirm: bool) -> Self {
+        self.confirm_destructive = confirm;
+        self
+    }
+
+    /// Sets how commands read answers from the user.
+    pub fn with_prompt(mut self, prompt: &'a mut LinePrompt<'a>) -> Self {
+        self.prompt = Some(prompt);
+        self
+    }
+
+    /// Sets how commands read secrets from the user.
+    pub fn with_secret_reader(mut self, reader: &'a mut LinePrompt<'a>) -> Self {
+        self.secret_reader = Some(reader);
+        self
+    }
+
+    /// Sets the stack changes are recorded on for `undo`.
+    pub fn with_undo(mut self, undo: &'a mut UndoStack) -> Self {
+        self.undo = Some(undo);
+        self
+    }
+
+    /// Sets whether reading a secret is recorded in its metadata.
+    pub fn with_track_access(mut self, track: bool) -> Self {
+        self.track_access = track;
+        self
+    }
+
+    /// Sets whether `add` warns when the new secret is weak.
+    pub fn with_warn_weak_secrets(mut self, warn: bool) -> Self {
+        self.warn_weak_secrets = warn;
+        self
+    }
+
+    /// Sets where `pwned` looks up breached secrets.
+    pub fn with_breach_source(mut self, source: &'a dyn RangeSource) -> Self {
+        self.breach_source = Some(source);
+        self
+    }
+
+    /// Sets the output format of commands that can print JSON.
+    pub fn with_output_format(mut self, format: OutputFormat) -> Self {
+        self.output_format = format;
+        self
+    }
+
+    /// Returns whether a command should print JSON, given whether `--json`
+    /// was passed to it.
+    pub fn wants_json(&self, flag: bool) -> bool {
+        flag || self.output_format == OutputFormat::Json
+    }
+
+    /// Records that the secret of `name` was read, if access is tracked.
+    ///
+    /// This changes the credentials, so they are saved afterwards.
+    pub fn record_access(&mut self, name: &str) {
+        if self.track_access && self.credentials.record_access(name, chrono::Utc::now()) {
+            self.mark_modified();
+        }
+    }
+
+    /// Records a change to the credentials so `undo` can revert it.
+    pub fn record_change(&mut self, change: Change) {
+        if let Some(undo) = self.undo.as_deref_mut() {
+            undo.push(change);
+        }
+    }
+
+    /// Forgets recorded changes, for when the credentials are replaced as a
+    /// whole and earlier changes no longer apply to them.
+    pub fn forget_changes(&mut self) {
+        if let Some(undo) = self.undo.as_deref_mut() {
+            undo.clear();
+        }
+    }
+
+    /// Queues a warning to be shown after the command's result.
+    pub fn warn(&mut self, msg: impl Into<String>) {
+        self.warnings.push(msg.into());
+    }
+
+    /// Asks a yes/no `question`, returning `Some(true)` for `y` or `yes`.
+    ///
+    /// Any other answer, or a cancelled prompt, is a no. Returns `None` when
+    /// there is no way to ask.
+    pub fn confirm(&mut self, question: &str) -> Option<bool> {
+        let prompt = self.prompt.as_deref_mut()?;
+        let answer = prompt(&format!("{} [y/N] ", question)).unwrap_or_default();
+        Some(matches!(answer.trim().to_lowercase().as_str(), "y" | "yes"))
+    }
+
+    /// Reads a secret after showing `prompt`, without echoing it.
+    ///
+    /// Returns `None` when there is no way to read one or reading was
+    /// cancelled.
+    pub fn read_secret(&mut self, prompt: &str) -> Option<String> {
+        let reader = self.secret_reader.as_deref_mut()?;
+        reader(prompt)
+    }
+
+    /// Normalizes a credential name typed by the user.
+    pub fn normalize_name(&self, name: &str) -> String {
+        self.name_normalization.apply(name)
+    }
+
     /// Marks credentials as modified.
     pub fn mark_modified(&mut self) {
         self.modified = true;
@@ -97,6 +464,9 @@ pub trait Command: Send + Sync {
     }
 
     /// Executes the command with the given arguments.
+    ///
+    /// The argument count has already been checked against
+    /// [`Command::min_args`] and [`Command::max_args`].
     fn execute(&self, args: &[&str], ctx: &mut ShellContext) -> CommandResult;
 
     /// Returns completions for the command's arguments.
@@ -114,7 +484,6 @@ pub trait Command: Send + Sync {
     }
 
     /// Returns the maximum number of arguments (None = unlimited).
-    #[allow(unused)]
     fn max_args(&self) -> Option<usize> {
         None
     }
@@ -129,6 +498,20 @@ impl fmt::Debug for dyn Command {
     }
 }
 
+/// What the registry knows about a command, for generated documentation
+/// such as man pages and completion scripts.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct CommandInfo {
+    /// The primary name.
+    pub name: String,
+    /// Other names the command is run by.
+    pub aliases: Vec<String>,
+    /// One-line usage, as shown by `help`.
+    pub usage: String,
+    /// Short description, as listed by `help`.
+    pub description: String,
+}
+
 /// Registry of all available commands.
 pub struct CommandRegistry {
     /// Commands indexed by their primary name.
@@ -186,6 +569,22 @@ impl CommandRegistry {
         self.commands.values()
     }
 
+    /// Describes every registered command, sorted by name.
+    pub fn describe(&self) -> Vec<CommandInfo> {
+        let mut infos: Vec<CommandInfo> = self
+            .commands
+            .values()
+            .map(|command| CommandInfo {
+                name: command.name().to_string(),
+                aliases: command.aliases().iter().map(|a| a.to_string()).collect(),
+                usage: command.usage().to_string(),
+                description: command.description().to_string(),
+            })
+            .collect();
+        infos.sort_by(|a, b| a.name.cmp(&b.name));
+        infos
+    }
+
     /// Returns all command names (primary names only).
     #[allow(unused)]
     pub fn names(&self) -> Vec<&str> {
@@ -272,6 +671,133 @@ mod tests {
         assert!(completions.contains(&"tst".to_string()));
     }
 
+    #[test]
+    fn test_registry_describe() {
+        let mut registry = CommandRegistry::new();
+        crate::shell::commands::register_all(&mut registry);
+
+        let infos = registry.describe();
+        assert_eq!(infos.len(), registry.len());
+        assert!(infos.windows(2).all(|pair| pair[0].name < pair[1].name));
+        for (name, aliases) in [
+            ("add", &["a", "new", "set"][..]),
+            ("get", &["g", "show"]),
+            ("remove", &["rm", "delete", "del"]),
+            ("list", &["ls", "l"]),
+            ("help", &["h", "?"]),
+            ("quit", &["exit", "q"]),
+        ] {
+            let info = infos.iter().find(|info| info.name == name).unwrap();
+            assert_eq!(info.aliases, aliases);
+            assert!(info.usage.starts_with(name));
+            assert!(!info.description.is_empty());
+        }
+
+        let json = serde_json::to_value(&infos).unwrap();
+        let get = json
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|info| info["name"] == "get")
+            .unwrap();
+        assert_eq!(get["aliases"], serde_json::json!(["g", "show"]));
+        assert_eq!(get["description"], "Get a credential by name");
+    }
+
+    #[test]
+    fn test_take_flag() {
+        let (present, rest) = take_flag(&["--count-only", "git"], "--count-only");
+        assert!(present);
+        assert_eq!(rest, vec!["git"]);
+
+        let (present, rest) = take_flag(&["git"], "--count-only");
+        assert!(!present);
+        assert_eq!(rest, vec!["git"]);
+    }
+
+    #[test]
+    fn test_take_option() {
+        let (value, rest) = take_option(&["--limit", "5", "git"], "--limit").unwrap();
+        assert_eq!(value, Some("5"));
+        assert_eq!(rest, vec!["git"]);
+
+        let (value, rest) = take_option(&["git"], "--limit").unwrap();
+        assert_eq!(value, None);
+        assert_eq!(rest, vec!["git"]);
+
+        assert!(take_option(&["git", "--limit"], "--limit").is_err());
+        assert!(take_option(&["--limit", "1", "--limit", "2"], "--limit").is_err());
+    }
+
+    #[test]
+    fn test_split_line() {
+        let split = |line| split_line(line).unwrap();
+        assert_eq!(
+            split("add github hunter2"),
+            vec!["add", "github", "hunter2"]
+        );
+        assert_eq!(split("  get\t'my email'  "), vec!["get", "my email"]);
+        assert_eq!(
+            split(r#"add "a \"b\"" c\ d"#),
+            vec!["add", "a \"b\"", "c d"]
+        );
+        assert_eq!(split(r#"add k "\n" ''"#), vec!["add", "k", "\\n", ""]);
+        assert!(split("").is_empty());
+
+        // Unlike in a shell, '#' never starts a comment
+        assert_eq!(split("add k #abc"), vec!["add", "k", "#abc"]);
+        assert_eq!(split("add k # a#b"), vec!["add", "k", "#", "a#b"]);
+        assert_eq!(split("# note"), vec!["#", "note"]);
+
+        assert!(split_line("add 'k").is_err());
+        assert!(split_line("add \"k\\\"").is_err());
+    }
+
+    #[test]
+    fn test_expand_secret() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let token = dir.path().join("token");
+        std::fs::write(&token, "ghp_abc123\n").unwrap();
+        let token = format!("@{}", token.display());
+
+        assert_eq!(expand_secret("hunter2").unwrap(), "hunter2");
+        assert_eq!(expand_secret("a@b").unwrap(), "a@b");
+        assert_eq!(expand_secret("@").unwrap(), "@");
+        assert_eq!(expand_secret(&token).unwrap(), "ghp_abc123");
+        // Escaped, the rest is taken literally rather than read
+        assert_eq!(expand_secret("\\@home").unwrap(), "@home");
+        assert_eq!(
+            expand_secret(&format!("\\{}", token)).unwrap(),
+            token.as_str()
+        );
+    }
+
+    #[test]
+    fn test_expand_secret_trims_one_line_ending() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("secret");
+        let arg = format!("@{}", path.display());
+
+        for (contents, secret) in [
+            ("plain", "plain"),
+            ("windows\r\n", "windows"),
+            ("two lines\n\n", "two lines\n"),
+            ("  spaced  \n", "  spaced  "),
+        ] {
+            std::fs::write(&path, contents).unwrap();
+            assert_eq!(expand_secret(&arg).unwrap(), secret);
+        }
+
+        std::fs::write(&path, "\n").unwrap();
+        assert!(expand_secret(&arg).unwrap_err().contains("is empty"));
+        let missing = format!("@{}", dir.path().join("missing").display());
+        assert!(
+            expand_secret(&missing)
+                .unwrap_err()
+                .starts_with("Could not read the secret from")
+        );
+    }
+
     #[test]
     fn test_command_result() {
         let success = CommandResult::success("done");
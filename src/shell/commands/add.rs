@@ -1,10 +1,35 @@
 //! Add command implementation.
 
-use crate::shell::command::{Command, CommandResult, ShellContext};
+use crate::credentials::CredentialError;
+use crate::shell::command::{Command, CommandResult, ShellContext, expand_secret};
+use crate::shell::undo::Change;
+use crate::strength::estimate_strength;
 
 /// Command to add a new credential.
 pub struct AddCommand;
 
+impl AddCommand {
+    /// Asks for the secret twice without echoing it, so it stays out of the
+    /// terminal's history and scrollback.
+    fn prompt_secret(&self, ctx: &mut ShellContext) -> Result<String, String> {
+        if ctx.secret_reader.is_none() {
+            return Err(format!(
+                "Usage: {}\nThe secret can only be omitted in the interactive shell",
+                self.usage()
+            ));
+        }
+        let secret = ctx.read_secret("Secret: ").ok_or("Cancelled")?;
+        if secret.is_empty() {
+            return Err("Secret cannot be empty".to_string());
+        }
+        let confirmation = ctx.read_secret("Confirm secret: ").ok_or("Cancelled")?;
+        if confirmation != secret {
+            return Err("Secrets do not match".to_string());
+        }
+        Ok(secret)
+    }
+}
+
 impl Command for AddCommand {
     fn name(&self) -> &str {
         "add"
@@ -19,43 +44,68 @@ impl Command for AddCommand {
     }
 
     fn usage(&self) -> &str {
-        "add <name> <secret>"
+        "add <name> [<secret>]"
     }
 
     fn help(&self) -> &str {
         "Add a new credential to the store.\n\n\
          Arguments:\n  \
            <name>   - Unique identifier for the credential\n  \
-           <secret> - The secret value to store\n\n\
+           <secret> - The secret value to store. If omitted, it is asked for\n             \
+                      twice without being shown, which keeps it out of the\n             \
+                      terminal history. @PATH reads it from the file\n             \
+                      at PATH, without its trailing newline; a secret\n             \
+                      that starts with @ is written '\\@...', quoted\n\n\
          Examples:\n  \
            add github mypassword123\n  \
-           add \"my email\" \"secret with spaces\""
+           add api-token @/home/me/token.txt\n  \
+           add \"my email\" \"secret with spaces\"\n  \
+           add github"
     }
 
     fn execute(&self, args: &[&str], ctx: &mut ShellContext) -> CommandResult {
-        if args.len() < 2 {
-            return CommandResult::error(format!(
-                "Usage: {}\nMissing required arguments",
-                self.usage()
-            ));
+        let name = ctx.normalize_name(args[0]);
+        if name.is_empty() {
+            return CommandResult::error("Credential name cannot be empty");
         }
-
-        let name = args[0].to_string();
-        let secret = args[1..].join(" ");
+        let secret = if args.len() > 1 {
+            match expand_secret(&args[1..].join(" ")) {
+                Ok(secret) => secret,
+                Err(e) => return CommandResult::error(e),
+            }
+        } else {
+            // Don't ask for a secret that cannot be stored
+            if ctx.credentials.contains_key(&name) {
+                return CommandResult::error(CredentialError::Duplicate(name).to_string());
+            }
+            match self.prompt_secret(ctx) {
+                Ok(secret) => secret,
+                Err(e) => return CommandResult::error(e),
+            }
+        };
 
         log::debug!("Adding credential: {}", name);
 
+        let report = ctx.warn_weak_secrets.then(|| estimate_strength(&secret));
         match ctx.credentials.add(name.clone(), secret) {
             Ok(_) => {
                 // Update the key trie for autocomplete
                 ctx.key_trie.insert(&name);
                 ctx.mark_modified();
+                ctx.record_change(Change::Added(vec![name.clone()]));
                 log::info!("Added credential: {}", name);
+                if let Some(report) = report.filter(|r| r.is_weak()) {
+                    ctx.warn(format!(
+                        "Warning: the secret for '{}' is {}",
+                        name,
+                        report.strength.label()
+                    ));
+                }
                 CommandResult::success(format!("Added '{}'", name))
             }
             Err(e) => {
                 log::warn!("Failed to add credential '{}': {}", name, e);
-                CommandResult::error(e)
+                CommandResult::error(e.to_string())
             }
         }
     }
@@ -66,7 +116,7 @@ impl Command for AddCommand {
     }
 
     fn min_args(&self) -> usize {
-        2
+        1
     }
 
     fn max_args(&self) -> Option<usize> {
@@ -78,7 +128,10 @@ impl Command for AddCommand {
 mod tests {
     use super::*;
     use crate::credentials::Credentials;
+    use crate::shell::command::check_arg_count;
     use crate::trie::Trie;
+    use std::cell::RefCell;
+    use std::rc::Rc;
 
     #[test]
     fn test_add_command_success() {
@@ -99,15 +152,108 @@ mod tests {
 
     #[test]
     fn test_add_command_missing_args() {
+        let result = check_arg_count(&AddCommand, &[]);
+        assert!(matches!(result, Err(CommandResult::Error(_))));
+    }
+
+    #[test]
+    fn test_add_command_without_secret_reader() {
         let mut credentials = Credentials::new();
         let mut trie = Trie::new();
         let mut ctx = ShellContext::new(&mut credentials, &mut trie);
 
-        let cmd = AddCommand;
-        let result = cmd.execute(&["only_name"], &mut ctx);
+        match AddCommand.execute(&["only_name"], &mut ctx) {
+            CommandResult::Error(msg) => assert!(msg.starts_with("Usage: ")),
+            _ => panic!("Expected error"),
+        }
+        assert!(!ctx.modified);
+    }
+
+    /// Prompts shown by a scripted secret reader.
+    type Prompts = Rc<RefCell<Vec<String>>>;
+
+    /// Returns a secret reader answering with `answers` in turn, and the
+    /// prompts it was shown.
+    fn scripted(answers: &[&str]) -> (impl FnMut(&str) -> Option<String>, Prompts) {
+        let mut answers: Vec<String> = answers.iter().rev().map(|a| a.to_string()).collect();
+        let prompts = Rc::new(RefCell::new(Vec::new()));
+        let shown = Rc::clone(&prompts);
+        let reader = move |prompt: &str| {
+            shown.borrow_mut().push(prompt.to_string());
+            answers.pop()
+        };
+        (reader, prompts)
+    }
+
+    #[test]
+    fn test_add_command_prompts_for_secret() {
+        let mut credentials = Credentials::new();
+        let mut trie = Trie::new();
+        let (mut reader, prompts) = scripted(&["hunter 2", "hunter 2"]);
+        let mut ctx =
+            ShellContext::new(&mut credentials, &mut trie).with_secret_reader(&mut reader);
+
+        let result = AddCommand.execute(&["github"], &mut ctx);
+
+        assert!(matches!(result, CommandResult::Success(_)));
+        assert!(ctx.modified);
+        assert!(ctx.key_trie.contains("github"));
+        assert_eq!(*prompts.borrow(), vec!["Secret: ", "Confirm secret: "]);
+        assert_eq!(credentials.get("github"), Some(&"hunter 2".to_string()));
+    }
+
+    #[test]
+    fn test_add_command_rejects_mismatched_confirmation() {
+        for answers in [
+            &["hunter2", "hunter3"][..],
+            &["hunter2"][..],
+            &[""][..],
+            &[][..],
+        ] {
+            let mut credentials = Credentials::new();
+            let mut trie = Trie::new();
+            let (mut reader, _) = scripted(answers);
+            let mut ctx =
+                ShellContext::new(&mut credentials, &mut trie).with_secret_reader(&mut reader);
+
+            let result = AddCommand.execute(&["github"], &mut ctx);
+
+            assert!(matches!(result, CommandResult::Error(_)), "{:?}", answers);
+            assert!(!ctx.modified);
+            assert!(credentials.is_empty());
+        }
+    }
+
+    #[test]
+    fn test_add_command_checks_duplicate_before_prompting() {
+        let mut credentials = Credentials::new();
+        credentials
+            .add("github".to_string(), "old".to_string())
+            .unwrap();
+        let mut trie = Trie::new();
+        let (mut reader, prompts) = scripted(&["new", "new"]);
+        let mut ctx =
+            ShellContext::new(&mut credentials, &mut trie).with_secret_reader(&mut reader);
+
+        let result = AddCommand.execute(&["github"], &mut ctx);
 
         assert!(matches!(result, CommandResult::Error(_)));
-        assert!(!ctx.modified);
+        assert!(prompts.borrow().is_empty());
+        assert_eq!(credentials.get("github"), Some(&"old".to_string()));
+    }
+
+    #[test]
+    fn test_add_command_secret_argument_skips_reader() {
+        let mut credentials = Credentials::new();
+        let mut trie = Trie::new();
+        let (mut reader, prompts) = scripted(&[]);
+        let mut ctx =
+            ShellContext::new(&mut credentials, &mut trie).with_secret_reader(&mut reader);
+
+        let result = AddCommand.execute(&["github", "hunter2"], &mut ctx);
+
+        assert!(matches!(result, CommandResult::Success(_)));
+        assert!(prompts.borrow().is_empty());
     }
 
     #[test]
@@ -140,4 +286,74 @@ mod tests {
             Some(&"secret with spaces".to_string())
         );
     }
+
+    #[test]
+    fn test_add_command_reads_secret_from_file() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("token");
+        std::fs::write(&path, "ghp_abc123\n").unwrap();
+        let from_file = format!("@{}", path.display());
+        let missing = format!("@{}", dir.path().join("missing").display());
+
+        let mut credentials = Credentials::new();
+        let mut trie = Trie::new();
+        let mut ctx = ShellContext::new(&mut credentials, &mut trie);
+
+        let result = AddCommand.execute(&["token", &from_file], &mut ctx);
+        assert!(matches!(result, CommandResult::Success(_)));
+        let result = AddCommand.execute(&["handle", "\\@me"], &mut ctx);
+        assert!(matches!(result, CommandResult::Success(_)));
+        let result = AddCommand.execute(&["other", &missing], &mut ctx);
+        assert!(matches!(result, CommandResult::Error(_)));
+
+        assert_eq!(credentials.get("token"), Some(&"ghp_abc123".to_string()));
+        assert_eq!(credentials.get("handle"), Some(&"@me".to_string()));
+        assert!(!credentials.contains_key("other"));
+    }
+
+    #[test]
+    fn test_add_command_warns_about_weak_secret() {
+        let mut credentials = Credentials::new();
+        let mut trie = Trie::new();
+        let mut ctx = ShellContext::new(&mut credentials, &mut trie).with_warn_weak_secrets(true);
+
+        let result = AddCommand.execute(&["bank", "123456"], &mut ctx);
+
+        assert!(matches!(result, CommandResult::Success(Some(_))));
+        assert_eq!(
+            ctx.warnings,
+            vec!["Warning: the secret for 'bank' is very weak".to_string()]
+        );
+        assert!(ctx.credentials.contains_key("bank"));
+    }
+
+    #[test]
+    fn test_add_command_strong_secret_has_no_warning() {
+        let mut credentials = Credentials::new();
+        let mut trie = Trie::new();
+        let mut ctx = ShellContext::new(&mut credentials, &mut trie).with_warn_weak_secrets(true);
+
+        let result = AddCommand.execute(
+            &["bank", "correct", "horse", "battery", "staple", "7!"],
+            &mut ctx,
+        );
+
+        assert!(matches!(result, CommandResult::Success(Some(_))));
+        assert!(ctx.warnings.is_empty());
+    }
+
+    #[test]
+    fn test_add_command_weak_secret_warning_disabled() {
+        let mut credentials = Credentials::new();
+        let mut trie = Trie::new();
+        let mut ctx = ShellContext::new(&mut credentials, &mut trie).with_warn_weak_secrets(false);
+
+        AddCommand.execute(&["bank", "123456"], &mut ctx);
+        assert!(ctx.warnings.is_empty());
+
+        // A failed add does not warn either
+        ctx.warn_weak_secrets = true;
+        AddCommand.execute(&["bank", "123456"], &mut ctx);
+        assert!(ctx.warnings.is_empty());
+    }
 }
@@ -0,0 +1,281 @@
+//! Batch command implementation.
+
+use std::io::{BufRead, IsTerminal};
+
+use crate::credentials::CredentialError;
+use crate::shell::command::{Command, CommandResult, ShellContext, split_line, take_option};
+use crate::shell::undo::Change;
+
+/// Command to add many credentials at once.
+pub struct BatchCommand;
+
+/// Outcome of adding a batch of credentials.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct BatchReport {
+    /// Names that were added, in input order.
+    pub added: Vec<String>,
+    /// Names skipped because they already exist.
+    pub duplicates: Vec<String>,
+    /// Lines that could not be added: 1-based line number and reason.
+    pub failed: Vec<(usize, String)>,
+}
+
+impl BatchReport {
+    /// Summarizes the report, e.g. "Added 3 credentials".
+    pub fn summary(&self) -> String {
+        let mut lines = vec![format!("Added {} credentials", self.added.len())];
+        if !self.duplicates.is_empty() {
+            lines.push(format!(
+                "Skipped {} that already exist: {}",
+                self.duplicates.len(),
+                self.duplicates.join(", ")
+            ));
+        }
+        for (line, reason) in &self.failed {
+            lines.push(format!("Line {}: {}", line, reason));
+        }
+        lines.join("\n")
+    }
+}
+
+/// Adds one credential per `name secret` line, continuing past failures.
+///
+/// Names and secrets are split like shell arguments, so quoted names may
+/// contain spaces; everything after the name is the secret. Blank lines and
+/// lines starting with `#` are skipped.
+pub fn add_batch<I, S>(lines: I, ctx: &mut ShellContext) -> BatchReport
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<str>,
+{
+    let mut report = BatchReport::default();
+    for (index, line) in lines.into_iter().enumerate() {
+        let line = line.as_ref().trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let parts = match split_line(line) {
+            Ok(parts) => parts,
+            Err(e) => {
+                report
+                    .failed
+                    .push((index + 1, format!("Parse error: {}", e)));
+                continue;
+            }
+        };
+        let [name, secret @ ..] = parts.as_slice() else {
+            continue;
+        };
+        if secret.is_empty() {
+            report
+                .failed
+                .push((index + 1, "Expected a name and a secret".to_string()));
+            continue;
+        }
+
+        let name = ctx.normalize_name(name);
+        match ctx.credentials.add(name.clone(), secret.join(" ")) {
+            Ok(()) => {
+                ctx.key_trie.insert(&name);
+                report.added.push(name);
+            }
+            Err(CredentialError::Duplicate(name)) => report.duplicates.push(name),
+            Err(e) => report.failed.push((index + 1, e.to_string())),
+        }
+    }
+
+    if !report.added.is_empty() {
+        ctx.mark_modified();
+        ctx.record_change(Change::Added(report.added.clone()));
+    }
+    log::info!(
+        "Batch added {} credentials, skipped {} duplicates, {} failed",
+        report.added.len(),
+        report.duplicates.len(),
+        report.failed.len()
+    );
+    report
+}
+
+impl Command for BatchCommand {
+    fn name(&self) -> &str {
+        "batch"
+    }
+
+    fn description(&self) -> &str {
+        "Add many credentials at once"
+    }
+
+    fn usage(&self) -> &str {
+        "batch [--file <path>]"
+    }
+
+    fn help(&self) -> &str {
+        "Add one credential per line of 'name secret'.\n\n\
+         Without --file, lines are read from the terminal (or stdin) until an\n\
+         empty line or end of input. Names with spaces must be quoted, as for\n\
+         'add'; the rest of the line is the secret. Lines starting with '#' are\n\
+         ignored. Names that already exist are skipped and reported, and lines\n\
+         that cannot be added don't stop the others.\n\n\
+         Options:\n  \
+           --file <path> - Read the lines from a file instead\n\n\
+         Examples:\n  \
+           batch\n  \
+           batch --file new-accounts.txt"
+    }
+
+    fn execute(&self, args: &[&str], ctx: &mut ShellContext) -> CommandResult {
+        let (file, rest) = match take_option(args, "--file") {
+            Ok(parsed) => parsed,
+            Err(e) => return CommandResult::error(e),
+        };
+        if let Some(arg) = rest.first() {
+            return CommandResult::error(format!(
+                "Unexpected argument: '{}'\nUsage: {}",
+                arg,
+                self.usage()
+            ));
+        }
+
+        let report = match file {
+            Some(path) => match std::fs::read_to_string(path) {
+                Ok(contents) => add_batch(contents.lines(), ctx),
+                Err(e) => return CommandResult::error(format!("Could not read '{}': {}", path, e)),
+            },
+            None => {
+                let stdin = std::io::stdin();
+                if stdin.is_terminal() {
+                    println!("Enter one 'name secret' per line; finish with an empty line.");
+                }
+                let lines = stdin
+                    .lock()
+                    .lines()
+                    .map_while(Result::ok)
+                    .take_while(|line| !line.trim().is_empty());
+                add_batch(lines, ctx)
+            }
+        };
+
+        if report.failed.is_empty() {
+            CommandResult::success(report.summary())
+        } else {
+            CommandResult::error(report.summary())
+        }
+    }
+
+    fn min_args(&self) -> usize {
+        0
+    }
+
+    fn max_args(&self) -> Option<usize> {
+        Some(2)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::credentials::Credentials;
+    use crate::trie::Trie;
+
+    #[test]
+    fn test_add_batch_reports_each_outcome() {
+        let mut credentials = Credentials::new();
+        credentials
+            .add("github".to_string(), "old".to_string())
+            .unwrap();
+        let mut trie = Trie::new();
+        let mut ctx = ShellContext::new(&mut credentials, &mut trie);
+
+        let report = add_batch(
+            [
+                "# accounts to import",
+                "email hunter2",
+                "",
+                "github new",
+                "\"my bank\" secret with spaces",
+                "lonely",
+                "\"unclosed quote",
+                "email again",
+            ],
+            &mut ctx,
+        );
+
+        assert_eq!(report.added, ["email", "my bank"]);
+        assert_eq!(report.duplicates, ["github", "email"]);
+        assert_eq!(report.failed.len(), 2);
+        assert_eq!(
+            report.failed[0],
+            (6, "Expected a name and a secret".to_string())
+        );
+        assert_eq!(report.failed[1].0, 7);
+
+        assert!(ctx.modified);
+        assert_eq!(ctx.credentials.get("github"), Some(&"old".to_string()));
+        assert_eq!(
+            ctx.credentials.get("my bank"),
+            Some(&"secret with spaces".to_string())
+        );
+        assert!(ctx.key_trie.contains("my bank"));
+        assert_eq!(
+            report.summary(),
+            "Added 2 credentials\n\
+             Skipped 2 that already exist: github, email\n\
+             Line 6: Expected a name and a secret\n\
+             Line 7: Parse error: missing closing quote"
+        );
+    }
+
+    #[test]
+    fn test_add_batch_keeps_hash_in_secrets() {
+        let mut credentials = Credentials::new();
+        let mut trie = Trie::new();
+        let mut ctx = ShellContext::new(&mut credentials, &mut trie);
+
+        let report = add_batch(["# skipped", "wifi #abc", "  # also skipped"], &mut ctx);
+
+        assert_eq!(report.added, ["wifi"]);
+        assert!(report.failed.is_empty());
+        assert_eq!(ctx.credentials.get("wifi"), Some(&"#abc".to_string()));
+    }
+
+    #[test]
+    fn test_add_batch_applies_name_rules() {
+        let mut credentials = Credentials::new();
+        let mut trie = Trie::new();
+        let mut ctx = ShellContext::new(&mut credentials, &mut trie);
+
+        let report = add_batch(["\"bad\tname\" secret"], &mut ctx);
+
+        assert!(report.added.is_empty());
+        assert_eq!(
+            report.failed,
+            [(1, "Name cannot contain control characters.".to_string())]
+        );
+        assert!(!ctx.modified);
+    }
+
+    #[test]
+    fn test_batch_from_file() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let path = temp_dir.path().join("creds.txt");
+        std::fs::write(&path, "github s3cret\n\nemail hunter2\n").unwrap();
+        let mut credentials = Credentials::new();
+        let mut trie = Trie::new();
+        let mut ctx = ShellContext::new(&mut credentials, &mut trie);
+
+        let result = BatchCommand.execute(&["--file", path.to_str().unwrap()], &mut ctx);
+
+        match result {
+            CommandResult::Success(Some(msg)) => assert_eq!(msg, "Added 2 credentials"),
+            _ => panic!("Expected success"),
+        }
+        assert_eq!(ctx.credentials.len(), 2);
+
+        let missing = temp_dir.path().join("missing.txt");
+        assert!(matches!(
+            BatchCommand.execute(&["--file", missing.to_str().unwrap()], &mut ctx),
+            CommandResult::Error(_)
+        ));
+    }
+}
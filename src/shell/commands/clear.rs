@@ -0,0 +1,84 @@
+//! Clear command implementation.
+
+use std::io::{self, IsTerminal, Write};
+
+use crate::shell::command::{Command, CommandResult, ShellContext};
+
+/// ANSI sequence that clears the screen and moves the cursor to the top left.
+const CLEAR_SCREEN: &str = "\x1b[2J\x1b[H";
+
+/// Command to clear the terminal screen.
+pub struct ClearCommand;
+
+/// Returns the sequence to write to clear the screen, which is empty when
+/// stdout is not a terminal.
+fn clear_sequence(is_terminal: bool) -> &'static str {
+    if is_terminal { CLEAR_SCREEN } else { "" }
+}
+
+impl Command for ClearCommand {
+    fn name(&self) -> &str {
+        "clear"
+    }
+
+    fn aliases(&self) -> &[&str] {
+        &["cls"]
+    }
+
+    fn description(&self) -> &str {
+        "Clear the terminal screen"
+    }
+
+    fn usage(&self) -> &str {
+        "clear"
+    }
+
+    fn help(&self) -> &str {
+        "Clear the terminal screen, e.g. to remove secrets shown by 'get'.\n\
+         Does nothing when output is not a terminal.\n\n\
+         Examples:\n  \
+           clear\n  \
+           cls"
+    }
+
+    fn execute(&self, _args: &[&str], _ctx: &mut ShellContext) -> CommandResult {
+        let mut stdout = io::stdout();
+        let sequence = clear_sequence(stdout.is_terminal());
+        if !sequence.is_empty() {
+            let _ = stdout.write_all(sequence.as_bytes());
+            let _ = stdout.flush();
+        }
+        CommandResult::Continue
+    }
+
+    fn min_args(&self) -> usize {
+        0
+    }
+
+    fn max_args(&self) -> Option<usize> {
+        Some(0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::credentials::Credentials;
+    use crate::trie::Trie;
+
+    #[test]
+    fn test_clear_command() {
+        let mut credentials = Credentials::new();
+        let mut trie = Trie::new();
+        let mut ctx = ShellContext::new(&mut credentials, &mut trie);
+
+        let result = ClearCommand.execute(&[], &mut ctx);
+        assert!(matches!(result, CommandResult::Continue));
+    }
+
+    #[test]
+    fn test_clear_sequence() {
+        assert_eq!(clear_sequence(true), "\x1b[2J\x1b[H");
+        assert_eq!(clear_sequence(false), "");
+    }
+}
@@ -0,0 +1,125 @@
+//! Count command implementation.
+
+use crate::shell::command::{Command, CommandResult, ShellContext, take_option};
+
+/// Command to print the number of stored credentials.
+pub struct CountCommand;
+
+impl Command for CountCommand {
+    fn name(&self) -> &str {
+        "count"
+    }
+
+    fn description(&self) -> &str {
+        "Print the number of stored credentials"
+    }
+
+    fn usage(&self) -> &str {
+        "count [--tag <tag>]"
+    }
+
+    fn help(&self) -> &str {
+        "Print how many credentials are stored, as a plain number.\n\n\
+         Handy in scripts, e.g. 'passmgr count' in one-shot mode.\n\n\
+         Options:\n  \
+           --tag <tag> - Only count credentials with this tag\n\n\
+         Examples:\n  \
+           count\n  \
+           count --tag work"
+    }
+
+    fn execute(&self, args: &[&str], ctx: &mut ShellContext) -> CommandResult {
+        let (tag, rest) = match take_option(args, "--tag") {
+            Ok(parsed) => parsed,
+            Err(e) => return CommandResult::error(e),
+        };
+        if let Some(arg) = rest.first() {
+            return CommandResult::error(format!(
+                "Unexpected argument: '{}'\nUsage: {}",
+                arg,
+                self.usage()
+            ));
+        }
+
+        log::debug!("Counting credentials");
+        let count = match tag {
+            Some(tag) => ctx
+                .credentials
+                .tag_counts()
+                .into_iter()
+                .find(|(t, _)| t.as_str() == tag)
+                .map_or(0, |(_, count)| count),
+            None => ctx.credentials.len(),
+        };
+        CommandResult::success(count.to_string())
+    }
+
+    fn min_args(&self) -> usize {
+        0
+    }
+
+    fn max_args(&self) -> Option<usize> {
+        Some(2)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::credentials::Credentials;
+    use crate::trie::Trie;
+
+    #[test]
+    fn test_count_command() {
+        let mut credentials = Credentials::new();
+        let mut trie = Trie::new();
+        let mut ctx = ShellContext::new(&mut credentials, &mut trie);
+
+        match CountCommand.execute(&[], &mut ctx) {
+            CommandResult::Success(Some(msg)) => assert_eq!(msg, "0"),
+            _ => panic!("Expected count"),
+        }
+
+        for name in ["github", "email", "aws"] {
+            ctx.credentials
+                .add(name.to_string(), "secret".to_string())
+                .unwrap();
+        }
+        match CountCommand.execute(&[], &mut ctx) {
+            CommandResult::Success(Some(msg)) => assert_eq!(msg, "3"),
+            _ => panic!("Expected count"),
+        }
+    }
+
+    #[test]
+    fn test_count_command_by_tag() {
+        let mut credentials = Credentials::new();
+        for name in ["github", "email", "aws"] {
+            credentials
+                .add(name.to_string(), "secret".to_string())
+                .unwrap();
+        }
+        credentials.add_tag("github", "work").unwrap();
+        credentials.add_tag("aws", "work").unwrap();
+        credentials.add_tag("email", "home").unwrap();
+        let mut trie = Trie::new();
+        let mut ctx = ShellContext::new(&mut credentials, &mut trie);
+
+        for (tag, expected) in [("work", "2"), ("home", "1"), ("unused", "0")] {
+            match CountCommand.execute(&["--tag", tag], &mut ctx) {
+                CommandResult::Success(Some(msg)) => assert_eq!(msg, expected),
+                _ => panic!("Expected count"),
+            }
+        }
+        for args in [&["--tag"][..], &["work"], &["--tag", "work", "extra"]] {
+            assert!(
+                matches!(
+                    CountCommand.execute(args, &mut ctx),
+                    CommandResult::Error(_)
+                ),
+                "{:?} should fail",
+                args
+            );
+        }
+    }
+}
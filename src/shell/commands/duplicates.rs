@@ -0,0 +1,116 @@
+//! Duplicates command implementation.
+
+use crate::shell::command::{Command, CommandResult, ShellContext};
+
+/// Command to report credentials that share the same secret.
+pub struct DuplicatesCommand;
+
+impl Command for DuplicatesCommand {
+    fn name(&self) -> &str {
+        "duplicates"
+    }
+
+    fn aliases(&self) -> &[&str] {
+        &["dups"]
+    }
+
+    fn description(&self) -> &str {
+        "List credentials that reuse the same secret"
+    }
+
+    fn usage(&self) -> &str {
+        "duplicates"
+    }
+
+    fn help(&self) -> &str {
+        "Find credentials that share an identical secret.\n\n\
+         Each line lists a group of credential names using the same secret.\n\
+         The secrets themselves are never shown.\n\n\
+         Examples:\n  \
+           duplicates\n  \
+           dups"
+    }
+
+    fn execute(&self, _args: &[&str], ctx: &mut ShellContext) -> CommandResult {
+        log::debug!("Checking for reused secrets");
+
+        let groups = ctx.credentials.duplicates();
+        if groups.is_empty() {
+            return CommandResult::success("No reused secrets found.");
+        }
+
+        let output = groups
+            .iter()
+            .map(|names| names.join(", "))
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        log::info!("Found {} groups of reused secrets", groups.len());
+        CommandResult::success(output)
+    }
+
+    fn min_args(&self) -> usize {
+        0
+    }
+
+    fn max_args(&self) -> Option<usize> {
+        Some(0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::credentials::Credentials;
+    use crate::trie::Trie;
+
+    #[test]
+    fn test_duplicates_command_none() {
+        let mut credentials = Credentials::new();
+        credentials
+            .add("github".to_string(), "secret1".to_string())
+            .unwrap();
+        let mut trie = Trie::new();
+        let mut ctx = ShellContext::new(&mut credentials, &mut trie);
+
+        let cmd = DuplicatesCommand;
+        let result = cmd.execute(&[], &mut ctx);
+
+        match result {
+            CommandResult::Success(Some(msg)) => assert!(msg.contains("No reused secrets")),
+            _ => panic!("Expected success message"),
+        }
+    }
+
+    #[test]
+    fn test_duplicates_command_groups() {
+        let mut credentials = Credentials::new();
+        credentials
+            .add("github".to_string(), "shared".to_string())
+            .unwrap();
+        credentials
+            .add("email".to_string(), "shared".to_string())
+            .unwrap();
+        credentials
+            .add("aws".to_string(), "shared".to_string())
+            .unwrap();
+        credentials
+            .add("bank".to_string(), "unique".to_string())
+            .unwrap();
+        let mut trie = Trie::new();
+        let mut ctx = ShellContext::new(&mut credentials, &mut trie);
+
+        let cmd = DuplicatesCommand;
+        let result = cmd.execute(&[], &mut ctx);
+
+        match result {
+            CommandResult::Success(Some(msg)) => {
+                assert_eq!(msg, "aws, email, github");
+                assert!(!msg.contains("shared"));
+                assert!(!msg.contains("bank"));
+            }
+            _ => panic!("Expected success with groups"),
+        }
+        assert!(!ctx.modified);
+    }
+}
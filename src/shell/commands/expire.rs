@@ -0,0 +1,149 @@
+//! Expire command implementation.
+
+use std::time::Duration;
+
+use crate::credentials::SECS_PER_DAY;
+use crate::shell::command::{Command, CommandResult, ShellContext, not_found};
+
+/// Command to set how often a credential should be rotated.
+pub struct ExpireCommand;
+
+impl Command for ExpireCommand {
+    fn name(&self) -> &str {
+        "expire"
+    }
+
+    fn description(&self) -> &str {
+        "Set how often a credential should be rotated"
+    }
+
+    fn usage(&self) -> &str {
+        "expire <name> <days|default>"
+    }
+
+    fn help(&self) -> &str {
+        "Set the rotation interval of one credential, in days.\n\n\
+         Once its secret is older than this, the credential is listed by\n\
+         'expired'. 'default' removes the credential's own interval, so the\n\
+         'rotate_after_days' setting from the config file applies again.\n\n\
+         Arguments:\n  \
+           <name>         - The name of the credential\n  \
+           <days|default> - Days between rotations, or 'default'\n\n\
+         Examples:\n  \
+           expire bank 90\n  \
+           expire github default"
+    }
+
+    fn execute(&self, args: &[&str], ctx: &mut ShellContext) -> CommandResult {
+        let name = ctx.normalize_name(args[0]);
+        let days = match args[1] {
+            "default" => None,
+            days => match days.parse::<u64>() {
+                Ok(days) if days > 0 => Some(days),
+                _ => {
+                    return CommandResult::error(format!(
+                        "Expected a positive number of days or 'default', got '{}'",
+                        days
+                    ));
+                }
+            },
+        };
+        let interval = days.map(|days| Duration::from_secs(days.saturating_mul(SECS_PER_DAY)));
+
+        if !ctx.credentials.set_rotate_after(&name, interval) {
+            return not_found(&name, ctx.key_trie);
+        }
+        ctx.mark_modified();
+        log::info!("Set rotation interval of credential: {}", name);
+        match days {
+            Some(days) => {
+                CommandResult::success(format!("'{}' should be rotated every {} days", name, days))
+            }
+            None => {
+                CommandResult::success(format!("'{}' uses the default rotation interval", name))
+            }
+        }
+    }
+
+    fn completions(&self, arg_index: usize, partial: &str, ctx: &ShellContext) -> Vec<String> {
+        match arg_index {
+            0 => ctx.key_trie.completions(partial),
+            1 if "default".starts_with(partial) => vec!["default".to_string()],
+            _ => vec![],
+        }
+    }
+
+    fn min_args(&self) -> usize {
+        2
+    }
+
+    fn max_args(&self) -> Option<usize> {
+        Some(2)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::credentials::Credentials;
+    use crate::trie::Trie;
+
+    fn setup() -> (Credentials, Trie) {
+        let mut credentials = Credentials::new();
+        credentials
+            .add("bank".to_string(), "secret".to_string())
+            .unwrap();
+        let mut trie = Trie::new();
+        trie.insert("bank");
+        (credentials, trie)
+    }
+
+    #[test]
+    fn test_expire_sets_and_clears_interval() {
+        let (mut credentials, mut trie) = setup();
+        let mut ctx = ShellContext::new(&mut credentials, &mut trie);
+
+        let result = ExpireCommand.execute(&["bank", "90"], &mut ctx);
+        match result {
+            CommandResult::Success(Some(msg)) => {
+                assert_eq!(msg, "'bank' should be rotated every 90 days")
+            }
+            _ => panic!("Expected success"),
+        }
+        assert!(ctx.modified);
+        assert_eq!(
+            ctx.credentials.metadata("bank").unwrap().rotate_after,
+            Some(Duration::from_secs(90 * SECS_PER_DAY))
+        );
+
+        let result = ExpireCommand.execute(&["bank", "default"], &mut ctx);
+        assert!(matches!(result, CommandResult::Success(Some(_))));
+        assert_eq!(ctx.credentials.metadata("bank").unwrap().rotate_after, None);
+    }
+
+    #[test]
+    fn test_expire_rejects_bad_days() {
+        let (mut credentials, mut trie) = setup();
+        let mut ctx = ShellContext::new(&mut credentials, &mut trie);
+
+        for days in ["0", "-5", "soon"] {
+            let result = ExpireCommand.execute(&["bank", days], &mut ctx);
+            assert!(matches!(result, CommandResult::Error(_)));
+        }
+        assert!(!ctx.modified);
+    }
+
+    #[test]
+    fn test_expire_missing_credential() {
+        let (mut credentials, mut trie) = setup();
+        let mut ctx = ShellContext::new(&mut credentials, &mut trie);
+
+        let result = ExpireCommand.execute(&["bnk", "30"], &mut ctx);
+        match result {
+            CommandResult::Error(msg) => {
+                assert_eq!(msg, "'bnk' not found. Did you mean 'bank'?")
+            }
+            _ => panic!("Expected error"),
+        }
+    }
+}
@@ -0,0 +1,101 @@
+//! Expired command implementation.
+
+use chrono::{DateTime, Utc};
+
+use crate::credentials::Credentials;
+use crate::shell::command::{Command, CommandResult, ShellContext};
+
+/// Command to list credentials that are due for rotation.
+pub struct ExpiredCommand;
+
+/// Lists the credentials due for rotation as of `now`, with the age of each
+/// secret.
+fn format_expired(credentials: &Credentials, now: DateTime<Utc>) -> String {
+    let names = credentials.expired(now);
+    if names.is_empty() {
+        return "No credentials are due for rotation.".to_string();
+    }
+    names
+        .iter()
+        .map(
+            |name| match credentials.metadata(name).and_then(|meta| meta.updated_at) {
+                Some(updated_at) => format!(
+                    "{} (last rotated {} days ago)",
+                    name,
+                    now.signed_duration_since(updated_at).num_days()
+                ),
+                None => name.to_string(),
+            },
+        )
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+impl Command for ExpiredCommand {
+    fn name(&self) -> &str {
+        "expired"
+    }
+
+    fn description(&self) -> &str {
+        "List credentials due for rotation"
+    }
+
+    fn usage(&self) -> &str {
+        "expired"
+    }
+
+    fn help(&self) -> &str {
+        "List the credentials whose secret is older than their rotation interval.\n\n\
+         The interval is set per credential with 'expire', or for all\n\
+         credentials with 'rotate_after_days' in the config file. A secret's\n\
+         age counts from when it was added, replaced, or marked as rotated\n\
+         with 'touch'. Credentials stored before passmgr recorded timestamps\n\
+         are never listed until they are touched.\n\n\
+         Examples:\n  \
+           expired\n  \
+           list --expired"
+    }
+
+    fn execute(&self, _args: &[&str], ctx: &mut ShellContext) -> CommandResult {
+        log::debug!("Listing expired credentials");
+        CommandResult::success(format_expired(ctx.credentials, Utc::now()))
+    }
+
+    fn min_args(&self) -> usize {
+        0
+    }
+
+    fn max_args(&self) -> Option<usize> {
+        Some(0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::credentials::SECS_PER_DAY;
+    use chrono::TimeZone;
+    use std::time::Duration;
+
+    #[test]
+    fn test_format_expired() {
+        let now = Utc.with_ymd_and_hms(2026, 6, 1, 0, 0, 0).unwrap();
+        let mut credentials = Credentials::new();
+        for (name, days) in [("bank", 120), ("email", 10), ("wifi", 95)] {
+            credentials
+                .add(name.to_string(), "secret".to_string())
+                .unwrap();
+            credentials.touch(name, now - chrono::Duration::days(days));
+        }
+        assert_eq!(
+            format_expired(&credentials, now),
+            "No credentials are due for rotation."
+        );
+
+        credentials.set_default_rotate_after(Some(Duration::from_secs(90 * SECS_PER_DAY)));
+        assert_eq!(
+            format_expired(&credentials, now),
+            "bank (last rotated 120 days ago)\nwifi (last rotated 95 days ago)"
+        );
+    }
+}
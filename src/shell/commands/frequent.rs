@@ -0,0 +1,125 @@
+//! Frequent command implementation.
+
+use crate::shell::command::{Command, CommandResult, ShellContext};
+
+use super::recent::{nothing_recorded, parse_limit};
+
+/// Command to list the most often read credentials.
+pub struct FrequentCommand;
+
+impl Command for FrequentCommand {
+    fn name(&self) -> &str {
+        "frequent"
+    }
+
+    fn description(&self) -> &str {
+        "List the most often read credentials"
+    }
+
+    fn usage(&self) -> &str {
+        "frequent [N]"
+    }
+
+    fn help(&self) -> &str {
+        "List the credentials whose secret was read most often with 'get' or\n\
+         'reveal', with the number of reads. Reads are only recorded with\n\
+         'track_access' set in the config file.\n\n\
+         Arguments:\n  \
+           [N] - How many credentials to list (default: 10)\n\n\
+         Examples:\n  \
+           frequent\n  \
+           frequent 3"
+    }
+
+    fn execute(&self, args: &[&str], ctx: &mut ShellContext) -> CommandResult {
+        let limit = match parse_limit(args) {
+            Ok(limit) => limit,
+            Err(e) => return CommandResult::error(e),
+        };
+
+        log::debug!("Listing most often read credentials");
+        let accessed = ctx.credentials.most_accessed();
+        if accessed.is_empty() {
+            return CommandResult::success(nothing_recorded(ctx.track_access));
+        }
+        let lines: Vec<String> = accessed
+            .into_iter()
+            .take(limit)
+            .map(|(name, count)| match count {
+                1 => format!("{} (read once)", name),
+                count => format!("{} (read {} times)", name, count),
+            })
+            .collect();
+        CommandResult::success(lines.join("\n"))
+    }
+
+    fn min_args(&self) -> usize {
+        0
+    }
+
+    fn max_args(&self) -> Option<usize> {
+        Some(1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::credentials::Credentials;
+    use crate::shell::commands::GetCommand;
+    use crate::trie::Trie;
+
+    fn setup() -> Credentials {
+        let mut credentials = Credentials::new();
+        for name in ["github", "email", "bank"] {
+            credentials
+                .add(name.to_string(), "secret".to_string())
+                .unwrap();
+        }
+        credentials
+    }
+
+    #[test]
+    fn test_get_increments_access_count() {
+        let mut credentials = setup();
+        let mut trie = Trie::new();
+        let mut ctx = ShellContext::new(&mut credentials, &mut trie).with_track_access(true);
+
+        GetCommand.execute(&["github"], &mut ctx);
+        assert!(ctx.modified);
+        GetCommand.execute(&["github", "email"], &mut ctx);
+        GetCommand.execute(&["--json", "github"], &mut ctx);
+        GetCommand.execute(&["missing"], &mut ctx);
+
+        let meta = ctx.credentials.metadata("github").unwrap();
+        assert_eq!(meta.access_count, 3);
+        assert!(meta.last_accessed.is_some());
+
+        match FrequentCommand.execute(&[], &mut ctx) {
+            CommandResult::Success(Some(msg)) => {
+                assert_eq!(msg, "github (read 3 times)\nemail (read once)")
+            }
+            _ => panic!("Expected success"),
+        }
+        match FrequentCommand.execute(&["1"], &mut ctx) {
+            CommandResult::Success(Some(msg)) => assert_eq!(msg, "github (read 3 times)"),
+            _ => panic!("Expected success"),
+        }
+    }
+
+    #[test]
+    fn test_get_without_tracking_changes_nothing() {
+        let mut credentials = setup();
+        let mut trie = Trie::new();
+        let mut ctx = ShellContext::new(&mut credentials, &mut trie);
+
+        GetCommand.execute(&["github"], &mut ctx);
+
+        assert!(!ctx.modified);
+        assert_eq!(ctx.credentials.metadata("github").unwrap().access_count, 0);
+        match FrequentCommand.execute(&[], &mut ctx) {
+            CommandResult::Success(Some(msg)) => assert!(msg.contains("track_access")),
+            _ => panic!("Expected success"),
+        }
+    }
+}
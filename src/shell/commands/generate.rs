@@ -0,0 +1,228 @@
+//! Generate command implementation.
+
+use crate::crypto::{
+    PASSWORD_CHARSET, PassphraseOptions, entropy_bits, generate_passphrase, generate_password,
+    passphrase_entropy_bits,
+};
+use crate::shell::command::{Command, CommandResult, ShellContext, take_flag, take_option};
+use crate::wordlist::WORDS;
+
+/// Length of a generated password when none is given.
+const DEFAULT_LENGTH: usize = 20;
+
+/// Longest password that can be generated.
+const MAX_LENGTH: usize = 1024;
+
+/// Most words a passphrase can have.
+const MAX_WORDS: usize = 64;
+
+/// Separator between passphrase words when none is given.
+const DEFAULT_SEPARATOR: &str = "-";
+
+/// Command to generate a random password or passphrase.
+pub struct GenerateCommand;
+
+/// Parses a count between 1 and `max`.
+fn parse_count(arg: &str, max: usize) -> Result<usize, String> {
+    match arg.parse() {
+        Ok(count) if (1..=max).contains(&count) => Ok(count),
+        _ => Err(format!(
+            "Expected a number from 1 to {}, got '{}'",
+            max, arg
+        )),
+    }
+}
+
+impl Command for GenerateCommand {
+    fn name(&self) -> &str {
+        "generate"
+    }
+
+    fn aliases(&self) -> &[&str] {
+        &["gen"]
+    }
+
+    fn description(&self) -> &str {
+        "Generate a random password or passphrase"
+    }
+
+    fn usage(&self) -> &str {
+        "generate [LENGTH] | generate --words N [--sep SEP] [--capitalize] [--digit]"
+    }
+
+    fn help(&self) -> &str {
+        "Print a random password of printable ASCII characters, or with\n\
+         --words a passphrase of words from a bundled list of 4096 common\n\
+         English words. Nothing is stored; use 'add' to save the result.\n\n\
+         The estimated entropy is shown after the secret, on standard error,\n\
+         so piping the output passes on only the secret.\n\n\
+         Arguments:\n  \
+           [LENGTH] - Number of characters in the password (default: 20)\n\n\
+         Options:\n  \
+           --words N    - Generate a passphrase of N words instead\n  \
+           --sep SEP    - Put SEP between the words (default: '-')\n  \
+           --capitalize - Capitalize each word\n  \
+           --digit      - Append a random digit\n\n\
+         Examples:\n  \
+           generate\n  \
+           generate 32\n  \
+           generate --words 4\n  \
+           generate --words 5 --sep . --capitalize --digit"
+    }
+
+    fn execute(&self, args: &[&str], ctx: &mut ShellContext) -> CommandResult {
+        let (capitalize, rest) = take_flag(args, "--capitalize");
+        let (digit, rest) = take_flag(&rest, "--digit");
+        let parsed = take_option(&rest, "--words")
+            .and_then(|(words, rest)| Ok((words, take_option(&rest, "--sep")?)));
+        let (words, (sep, rest)) = match parsed {
+            Ok(parsed) => parsed,
+            Err(e) => return CommandResult::error(e),
+        };
+
+        let generated = match (words, rest.as_slice()) {
+            (Some(words), []) => {
+                let words = match parse_count(words, MAX_WORDS) {
+                    Ok(words) => words,
+                    Err(e) => return CommandResult::error(e),
+                };
+                let opts = PassphraseOptions { capitalize, digit };
+                log::debug!("Generating a passphrase of {} words", words);
+                let mut bits = passphrase_entropy_bits(words, WORDS.len());
+                if digit {
+                    bits += entropy_bits(1, 10);
+                }
+                let summary = format!("Generated {}-word passphrase (~{:.0} bits)", words, bits);
+                generate_passphrase(words, sep.unwrap_or(DEFAULT_SEPARATOR), opts)
+                    .map(|secret| (secret, summary))
+            }
+            (None, length) if length.len() <= 1 => {
+                if capitalize || digit || sep.is_some() {
+                    return CommandResult::error(
+                        "--sep, --capitalize and --digit only apply to passphrases (--words)",
+                    );
+                }
+                let length = match length.first() {
+                    Some(arg) => match parse_count(arg, MAX_LENGTH) {
+                        Ok(length) => length,
+                        Err(e) => return CommandResult::error(e),
+                    },
+                    None => DEFAULT_LENGTH,
+                };
+                log::debug!("Generating a password of {} characters", length);
+                let bits = entropy_bits(length, PASSWORD_CHARSET.len());
+                let summary = format!("Generated {}-char password (~{:.0} bits)", length, bits);
+                generate_password(length).map(|secret| (secret, summary))
+            }
+            _ => return CommandResult::error(format!("Usage: {}", self.usage())),
+        };
+
+        match generated {
+            Ok((secret, summary)) => {
+                // Kept off the output, so only the secret goes to a pipe
+                ctx.warn(summary);
+                CommandResult::success(secret)
+            }
+            Err(e) => {
+                log::error!("Failed to generate a secret: {}", e);
+                CommandResult::error(format!("Could not generate a secret: {}", e))
+            }
+        }
+    }
+
+    fn min_args(&self) -> usize {
+        0
+    }
+
+    fn max_args(&self) -> Option<usize> {
+        Some(6)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::credentials::Credentials;
+    use crate::trie::Trie;
+
+    fn run(args: &[&str]) -> CommandResult {
+        run_with_summary(args).0
+    }
+
+    /// Runs the command, also returning the summary it printed.
+    fn run_with_summary(args: &[&str]) -> (CommandResult, Vec<String>) {
+        let mut credentials = Credentials::new();
+        let mut trie = Trie::new();
+        let mut ctx = ShellContext::new(&mut credentials, &mut trie);
+        let result = GenerateCommand.execute(args, &mut ctx);
+        (result, ctx.warnings)
+    }
+
+    fn generated(args: &[&str]) -> String {
+        match run(args) {
+            CommandResult::Success(Some(secret)) => secret,
+            _ => panic!("Expected a generated secret for {:?}", args),
+        }
+    }
+
+    #[test]
+    fn test_generate_password() {
+        let password = generated(&[]);
+        assert_eq!(password.len(), DEFAULT_LENGTH);
+        assert!(password.chars().all(|c| PASSWORD_CHARSET.contains(c)));
+
+        assert_eq!(generated(&["32"]).len(), 32);
+        assert!(!generated(&[]).is_empty());
+    }
+
+    #[test]
+    fn test_generate_passphrase() {
+        let passphrase = generated(&["--words", "4"]);
+        let words: Vec<&str> = passphrase.split('-').collect();
+        assert_eq!(words.len(), 4);
+        assert!(words.iter().all(|word| WORDS.contains(word)));
+
+        let passphrase = generated(&["--capitalize", "--words", "3", "--sep", ".", "--digit"]);
+        assert!(passphrase.ends_with(|c: char| c.is_ascii_digit()));
+        assert_eq!(passphrase.split('.').count(), 3);
+        assert!(passphrase.starts_with(|c: char| c.is_ascii_uppercase()));
+    }
+
+    #[test]
+    fn test_generate_shows_entropy() {
+        for (args, summary) in [
+            (&["16"][..], "Generated 16-char password (~105 bits)"),
+            (&[], "Generated 20-char password (~131 bits)"),
+            (&["--words", "4"], "Generated 4-word passphrase (~48 bits)"),
+            (
+                &["--words", "4", "--digit", "--capitalize"],
+                "Generated 4-word passphrase (~51 bits)",
+            ),
+        ] {
+            let (result, warnings) = run_with_summary(args);
+            assert!(matches!(result, CommandResult::Success(Some(_))));
+            assert_eq!(warnings, vec![summary.to_string()]);
+        }
+    }
+
+    #[test]
+    fn test_generate_rejects_bad_arguments() {
+        for args in [
+            &["0"][..],
+            &["many"],
+            &["1025"],
+            &["16", "32"],
+            &["--words", "0"],
+            &["--words", "4", "16"],
+            &["--words"],
+            &["--digit"],
+            &["12", "--sep", "."],
+        ] {
+            assert!(
+                matches!(run(args), CommandResult::Error(_)),
+                "{:?} should fail",
+                args
+            );
+        }
+    }
+}
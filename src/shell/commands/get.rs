@@ -1,10 +1,112 @@
 //! Get command implementation.
 
-use crate::shell::command::{Command, CommandResult, ShellContext};
+use chrono::{DateTime, Utc};
+use serde_json::json;
+
+use crate::credentials::mask_secret;
+use crate::shell::command::{
+    Command, CommandResult, ShellContext, not_found, take_flag, take_option,
+};
 
 /// Command to retrieve a credential.
 pub struct GetCommand;
 
+/// Looks up a credential, returning its secret in plain text if `reveal` is
+/// set and masked otherwise.
+pub(super) fn show_secret(name: &str, reveal: bool, ctx: &mut ShellContext) -> CommandResult {
+    let name = ctx.normalize_name(name);
+    log::debug!("Getting credential: {}", name);
+
+    match ctx.credentials.get(&name) {
+        Some(secret) => {
+            log::info!("Retrieved credential: {}", name);
+            let shown = if reveal {
+                secret.clone()
+            } else {
+                mask_secret(secret)
+            };
+            ctx.record_access(&name);
+            CommandResult::success(shown)
+        }
+        None => {
+            log::debug!("Credential not found: {}", name);
+            not_found(&name, ctx.key_trie)
+        }
+    }
+}
+
+/// Looks up several credentials, giving one `name: secret` line each.
+///
+/// A missing name gets a `name: not found` line instead of stopping the
+/// lookup, so the result is only an error if none of them was found.
+fn show_secrets(names: &[String], reveal: bool, ctx: &mut ShellContext) -> CommandResult {
+    let mut found = 0;
+    let mut lines = Vec::with_capacity(names.len());
+    for name in names {
+        match ctx.credentials.get(name) {
+            Some(secret) => {
+                found += 1;
+                let secret = if reveal {
+                    secret.clone()
+                } else {
+                    mask_secret(secret)
+                };
+                lines.push(format!("{}: {}", name, secret));
+                ctx.record_access(name);
+            }
+            None => lines.push(format!("{}: not found", name)),
+        }
+    }
+
+    log::info!("Retrieved {} of {} credentials", found, names.len());
+    if found == 0 {
+        CommandResult::error(lines.join("\n"))
+    } else {
+        CommandResult::success(lines.join("\n"))
+    }
+}
+
+/// Returns whether `name` can be used as a shell variable name.
+fn is_variable_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// Formats `secret` as a POSIX shell assignment to `variable`, quoted so that
+/// `eval` sets the variable to the secret exactly.
+pub fn export_line(variable: &str, secret: &str) -> String {
+    format!("export {}={}", variable, shell_words::quote(secret))
+}
+
+/// Describes a credential as a JSON object for scripting.
+///
+/// The secret is masked unless `reveal` is set, and `updated_at` is given
+/// in RFC 3339 format. The vault does not record notes yet, so `note` is
+/// always null; it is included so scripts can rely on the shape.
+pub fn credential_json(
+    name: &str,
+    secret: &str,
+    tags: &[String],
+    updated_at: Option<DateTime<Utc>>,
+    reveal: bool,
+) -> serde_json::Value {
+    let secret = if reveal {
+        secret.to_string()
+    } else {
+        mask_secret(secret)
+    };
+    json!({
+        "name": name,
+        "secret": secret,
+        "tags": tags,
+        "updated_at": updated_at.map(|time| time.to_rfc3339()),
+        "note": null,
+    })
+}
+
 impl Command for GetCommand {
     fn name(&self) -> &str {
         "get"
@@ -19,48 +121,134 @@ impl Command for GetCommand {
     }
 
     fn usage(&self) -> &str {
-        "get <name>"
+        "get [--show] [--json] <name>... | get [--show] --all <pattern> | get --export VAR <name>"
     }
 
     fn help(&self) -> &str {
-        "Retrieve and display a stored credential.\n\n\
+        "Retrieve a stored credential.\n\n\
+         The secret is masked with one '•' per character unless --show is\n\
+         given (or 'default_reveal' is set in the config file).\n\n\
+         With several names, or --all, each credential is printed on its own\n\
+         line as 'name: secret'. Names that are not found are reported on their\n\
+         line and the others are still shown.\n\n\
          Arguments:\n  \
            <name> - The name of the credential to retrieve\n\n\
+         Options:\n  \
+           --show          - Print the secret in plain text (also --reveal)\n  \
+           --all <pattern> - Get every credential whose name contains <pattern>,\n                    \
+                             ignoring case\n  \
+           --json          - Print the credential as a JSON object with name,\n                    \
+                             secret, tags, updated_at and note. The secret is\n                    \
+                             masked unless --show is given, whatever\n                    \
+                             'default_reveal' says. Takes a single name.\n  \
+           --export VAR    - Print 'export VAR=<secret>' with the secret quoted\n                    \
+                             for the shell, to be run with eval. Takes a\n                    \
+                             single name.\n\n\
          Examples:\n  \
            get github\n  \
-           get \"my email\""
+           get --show github\n  \
+           get --json --show github\n  \
+           get \"my email\"\n  \
+           get github gitlab email\n  \
+           get --show --all git\n  \
+           eval \"$(passmgr get --export GITHUB_TOKEN github)\""
     }
 
     fn execute(&self, args: &[&str], ctx: &mut ShellContext) -> CommandResult {
-        if args.is_empty() {
-            return CommandResult::error(format!(
-                "Usage: {}\nMissing credential name",
-                self.usage()
-            ));
+        let (show, rest) = take_flag(args, "--show");
+        let (reveal, rest) = take_flag(&rest, "--reveal");
+        let (as_json, rest) = take_flag(&rest, "--json");
+        let as_json = ctx.wants_json(as_json);
+        let (all, rest) = take_flag(&rest, "--all");
+        let (export, rest) = match take_option(&rest, "--export") {
+            Ok(parsed) => parsed,
+            Err(e) => return CommandResult::error(e),
+        };
+        let reveal = show || reveal;
+
+        if let Some(variable) = export {
+            let [name] = rest.as_slice() else {
+                return CommandResult::error("--export takes a single credential name");
+            };
+            if all || as_json {
+                return CommandResult::error("--export cannot be combined with --all or --json");
+            }
+            if !is_variable_name(variable) {
+                return CommandResult::error(format!(
+                    "'{}' is not a valid shell variable name",
+                    variable
+                ));
+            }
+            let name = ctx.normalize_name(name);
+            let Some(secret) = ctx.credentials.get(&name) else {
+                return not_found(&name, ctx.key_trie);
+            };
+            log::info!("Retrieved credential for export: {}", name);
+            let line = export_line(variable, secret);
+            ctx.record_access(&name);
+            return CommandResult::success(line);
         }
 
-        let name = args[0];
-        log::debug!("Getting credential: {}", name);
+        if all {
+            let [pattern] = rest.as_slice() else {
+                return CommandResult::error(format!(
+                    "Usage: {}\n--all expects one pattern",
+                    self.usage()
+                ));
+            };
+            if as_json {
+                return CommandResult::error("--json takes a single credential name");
+            }
+            let names: Vec<String> = ctx
+                .credentials
+                .search(pattern)
+                .into_iter()
+                .cloned()
+                .collect();
+            if names.is_empty() {
+                return CommandResult::error(format!("No credentials match '{}'", pattern));
+            }
+            return show_secrets(&names, reveal || ctx.reveal_secrets, ctx);
+        }
 
-        match ctx.credentials.get(name) {
-            Some(secret) => {
-                log::info!("Retrieved credential: {}", name);
-                CommandResult::success(secret.clone())
+        let name = match rest.as_slice() {
+            [] => {
+                return CommandResult::error(format!(
+                    "Usage: {}\nExpected at least one credential name",
+                    self.usage()
+                ));
             }
-            None => {
-                log::debug!("Credential not found: {}", name);
-                CommandResult::error(format!("'{}' not found", name))
+            [name] => name,
+            names => {
+                if as_json {
+                    return CommandResult::error("--json takes a single credential name");
+                }
+                let names: Vec<String> =
+                    names.iter().map(|name| ctx.normalize_name(name)).collect();
+                return show_secrets(&names, reveal || ctx.reveal_secrets, ctx);
             }
+        };
+
+        if as_json {
+            let name = ctx.normalize_name(name);
+            return match ctx.credentials.get(&name) {
+                Some(secret) => {
+                    log::info!("Retrieved credential as JSON: {}", name);
+                    let meta = ctx.credentials.metadata(&name).cloned().unwrap_or_default();
+                    let value = credential_json(&name, secret, &meta.tags, meta.updated_at, reveal);
+                    ctx.record_access(&name);
+                    CommandResult::json(value)
+                }
+                None => not_found(&name, ctx.key_trie),
+            };
         }
+
+        show_secret(name, reveal || ctx.reveal_secrets, ctx)
     }
 
-    fn completions(&self, arg_index: usize, partial: &str, ctx: &ShellContext) -> Vec<String> {
-        if arg_index == 0 {
-            // Complete credential names
-            ctx.key_trie.completions(partial)
-        } else {
-            vec![]
-        }
+    fn completions(&self, _arg_index: usize, partial: &str, ctx: &ShellContext) -> Vec<String> {
+        // Every argument may be a credential name
+        ctx.key_trie.completions(partial)
     }
 
     fn min_args(&self) -> usize {
@@ -68,7 +256,7 @@ impl Command for GetCommand {
     }
 
     fn max_args(&self) -> Option<usize> {
-        Some(1)
+        None // Any number of names
     }
 }
 
@@ -77,6 +265,75 @@ mod tests {
     use super::*;
     use crate::credentials::Credentials;
     use crate::trie::Trie;
+    use chrono::TimeZone;
+
+    /// Parses an `export` line the way a POSIX shell would, returning the
+    /// variable and its value.
+    fn parse_export(line: &str) -> (String, String) {
+        let words = shell_words::split(line).unwrap();
+        let [export, assignment] = words.as_slice() else {
+            panic!("Expected two words in {:?}", line);
+        };
+        assert_eq!(export, "export");
+        let (variable, value) = assignment.split_once('=').unwrap();
+        (variable.to_string(), value.to_string())
+    }
+
+    #[test]
+    fn test_export_line_round_trips() {
+        for secret in [
+            "plain",
+            "with spaces",
+            "$HOME and ${PATH}",
+            "it's \"quoted\"",
+            "back\\slash `cmd` $(cmd)",
+            "new\nline",
+            "",
+        ] {
+            let line = export_line("TOKEN", secret);
+            assert_eq!(
+                parse_export(&line),
+                ("TOKEN".to_string(), secret.to_string()),
+                "{}",
+                line
+            );
+        }
+        // Characters the shell would expand are always inside quotes
+        assert_eq!(export_line("TOKEN", "$HOME"), "export TOKEN='$HOME'");
+    }
+
+    #[test]
+    fn test_get_export() {
+        let mut credentials = Credentials::new();
+        credentials
+            .add("github".to_string(), "a b$c'd".to_string())
+            .unwrap();
+        let mut trie = Trie::new();
+        let mut ctx = ShellContext::new(&mut credentials, &mut trie);
+
+        match GetCommand.execute(&["--export", "GITHUB_TOKEN", "github"], &mut ctx) {
+            CommandResult::Success(Some(msg)) => assert_eq!(
+                parse_export(&msg),
+                ("GITHUB_TOKEN".to_string(), "a b$c'd".to_string())
+            ),
+            _ => panic!("Expected success"),
+        }
+
+        for args in [
+            &["--export", "1TOKEN", "github"][..],
+            &["--export", "MY-TOKEN", "github"],
+            &["--export", "TOKEN", "github", "gitlab"],
+            &["--export", "TOKEN", "--json", "github"],
+            &["--export", "TOKEN", "missing"],
+            &["github", "--export"],
+        ] {
+            assert!(
+                matches!(GetCommand.execute(args, &mut ctx), CommandResult::Error(_)),
+                "{:?}",
+                args
+            );
+        }
+    }
 
     #[test]
     fn test_get_command_success() {
@@ -88,7 +345,7 @@ mod tests {
         let mut ctx = ShellContext::new(&mut credentials, &mut trie);
 
         let cmd = GetCommand;
-        let result = cmd.execute(&["test_key"], &mut ctx);
+        let result = cmd.execute(&["--show", "test_key"], &mut ctx);
 
         match result {
             CommandResult::Success(Some(msg)) => assert_eq!(msg, "test_secret"),
@@ -96,6 +353,36 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_get_command_masks_by_default() {
+        let mut credentials = Credentials::new();
+        credentials
+            .add("test_key".to_string(), "test_secret".to_string())
+            .unwrap();
+        let mut trie = Trie::new();
+        let mut ctx = ShellContext::new(&mut credentials, &mut trie);
+
+        match GetCommand.execute(&["test_key"], &mut ctx) {
+            CommandResult::Success(Some(msg)) => assert_eq!(msg, "•••••••••••"),
+            _ => panic!("Expected success with masked secret"),
+        }
+    }
+
+    #[test]
+    fn test_get_command_reveal_by_default() {
+        let mut credentials = Credentials::new();
+        credentials
+            .add("test_key".to_string(), "test_secret".to_string())
+            .unwrap();
+        let mut trie = Trie::new();
+        let mut ctx = ShellContext::new(&mut credentials, &mut trie).with_reveal_secrets(true);
+
+        match GetCommand.execute(&["test_key"], &mut ctx) {
+            CommandResult::Success(Some(msg)) => assert_eq!(msg, "test_secret"),
+            _ => panic!("Expected success with secret"),
+        }
+    }
+
     #[test]
     fn test_get_command_not_found() {
         let mut credentials = Credentials::new();
@@ -136,4 +423,189 @@ mod tests {
         assert!(completions.contains(&"gitlab".to_string()));
         assert!(!completions.contains(&"email".to_string()));
     }
+
+    #[test]
+    fn test_get_command_suggests_similar_key() {
+        let mut credentials = Credentials::new();
+        credentials
+            .add("github".to_string(), "secret".to_string())
+            .unwrap();
+        let mut trie = Trie::new();
+        trie.insert("github");
+        trie.insert("email");
+        let mut ctx = ShellContext::new(&mut credentials, &mut trie);
+
+        match GetCommand.execute(&["githb"], &mut ctx) {
+            CommandResult::Error(msg) => {
+                assert_eq!(msg, "'githb' not found. Did you mean 'github'?")
+            }
+            _ => panic!("Expected error with suggestion"),
+        }
+
+        match GetCommand.execute(&["bank-account"], &mut ctx) {
+            CommandResult::Error(msg) => assert_eq!(msg, "'bank-account' not found"),
+            _ => panic!("Expected error without suggestion"),
+        }
+    }
+
+    #[test]
+    fn test_get_json_masks_secret() {
+        let mut credentials = Credentials::new();
+        credentials
+            .add("github".to_string(), "s3cret".to_string())
+            .unwrap();
+        credentials.touch("github", Utc.with_ymd_and_hms(2026, 1, 2, 3, 4, 5).unwrap());
+        credentials.add_tag("github", "work").unwrap();
+        credentials.add_tag("github", "dev").unwrap();
+        let mut trie = Trie::new();
+        // default_reveal does not apply to JSON output
+        let mut ctx = ShellContext::new(&mut credentials, &mut trie).with_reveal_secrets(true);
+
+        match GetCommand.execute(&["--json", "github"], &mut ctx) {
+            CommandResult::Json(value) => assert_eq!(
+                value,
+                json!({
+                    "name": "github",
+                    "secret": "••••••",
+                    "tags": ["dev", "work"],
+                    "updated_at": "2026-01-02T03:04:05+00:00",
+                    "note": null,
+                })
+            ),
+            _ => panic!("Expected JSON"),
+        }
+    }
+
+    #[test]
+    fn test_get_json_output_format() {
+        let mut credentials = Credentials::new();
+        credentials
+            .add("github".to_string(), "s3cret".to_string())
+            .unwrap();
+        let mut trie = Trie::new();
+        let mut ctx = ShellContext::new(&mut credentials, &mut trie)
+            .with_output_format(crate::shell::command::OutputFormat::Json);
+
+        match GetCommand.execute(&["github", "--reveal"], &mut ctx) {
+            CommandResult::Json(value) => {
+                let parsed: serde_json::Value = serde_json::from_str(&value.to_string()).unwrap();
+                assert_eq!(parsed["name"], "github");
+                assert_eq!(parsed["secret"], "s3cret");
+            }
+            _ => panic!("Expected JSON"),
+        }
+    }
+
+    #[test]
+    fn test_get_json_reveal() {
+        let mut credentials = Credentials::new();
+        credentials
+            .add("github".to_string(), "s3cret".to_string())
+            .unwrap();
+        let mut trie = Trie::new();
+        let mut ctx = ShellContext::new(&mut credentials, &mut trie);
+
+        for flag in ["--reveal", "--show"] {
+            match GetCommand.execute(&["github", "--json", flag], &mut ctx) {
+                CommandResult::Json(value) => {
+                    assert_eq!(value["name"], "github");
+                    assert_eq!(value["secret"], "s3cret");
+                    let mut keys: Vec<&String> = value.as_object().unwrap().keys().collect();
+                    keys.sort();
+                    assert_eq!(keys, ["name", "note", "secret", "tags", "updated_at"]);
+                }
+                _ => panic!("Expected JSON"),
+            }
+        }
+    }
+
+    fn multi_setup() -> (Credentials, Trie) {
+        let mut credentials = Credentials::new();
+        for (name, secret) in [("github", "abc"), ("gitlab", "defg"), ("email", "hi")] {
+            credentials
+                .add(name.to_string(), secret.to_string())
+                .unwrap();
+        }
+        (credentials, Trie::new())
+    }
+
+    #[test]
+    fn test_get_several_names_reports_missing() {
+        let (mut credentials, mut trie) = multi_setup();
+        let mut ctx = ShellContext::new(&mut credentials, &mut trie);
+
+        match GetCommand.execute(&["github", "bank", "email"], &mut ctx) {
+            CommandResult::Success(Some(msg)) => {
+                assert_eq!(msg, "github: •••\nbank: not found\nemail: ••")
+            }
+            _ => panic!("Expected success"),
+        }
+
+        match GetCommand.execute(&["--show", "email", "missing", "github"], &mut ctx) {
+            CommandResult::Success(Some(msg)) => {
+                assert_eq!(msg, "email: hi\nmissing: not found\ngithub: abc")
+            }
+            _ => panic!("Expected success"),
+        }
+    }
+
+    #[test]
+    fn test_get_several_names_none_found() {
+        let (mut credentials, mut trie) = multi_setup();
+        let mut ctx = ShellContext::new(&mut credentials, &mut trie);
+
+        match GetCommand.execute(&["bank", "wifi"], &mut ctx) {
+            CommandResult::Error(msg) => assert_eq!(msg, "bank: not found\nwifi: not found"),
+            _ => panic!("Expected error"),
+        }
+    }
+
+    #[test]
+    fn test_get_all_matching_pattern() {
+        let (mut credentials, mut trie) = multi_setup();
+        let mut ctx = ShellContext::new(&mut credentials, &mut trie);
+
+        match GetCommand.execute(&["--reveal", "--all", "GIT"], &mut ctx) {
+            CommandResult::Success(Some(msg)) => assert_eq!(msg, "github: abc\ngitlab: defg"),
+            _ => panic!("Expected success"),
+        }
+
+        match GetCommand.execute(&["--all", "bank"], &mut ctx) {
+            CommandResult::Error(msg) => assert_eq!(msg, "No credentials match 'bank'"),
+            _ => panic!("Expected error"),
+        }
+        for args in [
+            &["--all"][..],
+            &["--all", "git", "mail"],
+            &["--json", "--all", "git"],
+        ] {
+            assert!(matches!(
+                GetCommand.execute(args, &mut ctx),
+                CommandResult::Error(_)
+            ));
+        }
+    }
+
+    #[test]
+    fn test_get_json_rejects_several_names() {
+        let (mut credentials, mut trie) = multi_setup();
+        let mut ctx = ShellContext::new(&mut credentials, &mut trie);
+
+        assert!(matches!(
+            GetCommand.execute(&["--json", "github", "email"], &mut ctx),
+            CommandResult::Error(_)
+        ));
+    }
+
+    #[test]
+    fn test_get_json_not_found() {
+        let mut credentials = Credentials::new();
+        let mut trie = Trie::new();
+        let mut ctx = ShellContext::new(&mut credentials, &mut trie);
+
+        assert!(matches!(
+            GetCommand.execute(&["--json", "missing"], &mut ctx),
+            CommandResult::Error(_)
+        ));
+    }
 }
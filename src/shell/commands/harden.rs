@@ -0,0 +1,247 @@
+//! Harden command implementation.
+
+use crate::crypto::KdfParams;
+use crate::shell::command::{Command, CommandResult, ShellContext, take_option};
+
+/// Command to change the Argon2id parameters of the vault.
+pub struct HardenCommand;
+
+/// Formats parameters as shown by `info --crypto`.
+fn format_params(params: &KdfParams) -> String {
+    format!(
+        "m_cost={} KiB, t_cost={}, p_cost={}",
+        params.m_cost, params.t_cost, params.p_cost
+    )
+}
+
+/// Parses the value of a cost option such as `--m-cost`.
+fn parse_cost(option: &str, value: &str) -> Result<u32, String> {
+    value
+        .parse()
+        .map_err(|_| format!("{} expects a positive number, got '{}'", option, value))
+}
+
+impl Command for HardenCommand {
+    fn name(&self) -> &str {
+        "harden"
+    }
+
+    fn description(&self) -> &str {
+        "Change the cost of deriving the vault key"
+    }
+
+    fn usage(&self) -> &str {
+        "harden [--m-cost KIB] [--t-cost N] [--p-cost N]"
+    }
+
+    fn help(&self) -> &str {
+        "Re-encrypt the vault with new Argon2id parameters, keeping the master\n\
+         password. Higher costs make guessing the password slower, and make\n\
+         unlocking slower too. Parameters not given keep their current value,\n\
+         as shown by 'info --crypto'.\n\n\
+         Options:\n  \
+           --m-cost KIB - Memory used, in KiB\n  \
+           --t-cost N   - Number of iterations\n  \
+           --p-cost N   - Degree of parallelism\n\n\
+         Examples:\n  \
+           harden --m-cost 65536\n  \
+           harden --t-cost 4 --p-cost 2"
+    }
+
+    fn execute(&self, args: &[&str], ctx: &mut ShellContext) -> CommandResult {
+        let mut rest = args.to_vec();
+        let mut costs = [None; 3];
+        for (cost, option) in costs.iter_mut().zip(["--m-cost", "--t-cost", "--p-cost"]) {
+            let (value, remaining) = match take_option(&rest, option) {
+                Ok(parsed) => parsed,
+                Err(e) => return CommandResult::error(e),
+            };
+            rest = remaining;
+            *cost = match value.map(|v| parse_cost(option, v)).transpose() {
+                Ok(cost) => cost,
+                Err(e) => return CommandResult::error(e),
+            };
+        }
+        if let Some(arg) = rest.first() {
+            return CommandResult::error(format!(
+                "Unexpected argument: '{}'\nUsage: {}",
+                arg,
+                self.usage()
+            ));
+        }
+        if costs.iter().all(Option::is_none) {
+            return CommandResult::error(format!(
+                "Give at least one parameter to change\nUsage: {}",
+                self.usage()
+            ));
+        }
+
+        let Some(session) = ctx.session.as_deref_mut() else {
+            return CommandResult::error(
+                "Changing encryption parameters is not available in this session",
+            );
+        };
+        let current = match session.crypto_info() {
+            Ok(info) => info.kdf_params,
+            Err(e) => {
+                return CommandResult::error(format!("Could not read encryption details: {}", e));
+            }
+        };
+        let [m_cost, t_cost, p_cost] = costs;
+        let params = KdfParams {
+            m_cost: m_cost.unwrap_or(current.m_cost),
+            t_cost: t_cost.unwrap_or(current.t_cost),
+            p_cost: p_cost.unwrap_or(current.p_cost),
+        };
+        if params == current {
+            return CommandResult::success(format!(
+                "The vault already uses {}",
+                format_params(&params)
+            ));
+        }
+
+        log::debug!("Re-encrypting vault with new KDF parameters");
+        match session.reencrypt_with_params(params, ctx.credentials) {
+            Ok(()) => CommandResult::success(format!(
+                "Re-encrypted with {} (was {})",
+                format_params(&params),
+                format_params(&current)
+            )),
+            Err(e) => CommandResult::error(format!("Could not re-encrypt vault: {}", e)),
+        }
+    }
+
+    fn min_args(&self) -> usize {
+        0
+    }
+
+    fn max_args(&self) -> Option<usize> {
+        Some(6)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::credentials::Credentials;
+    use crate::shell::session::Session;
+    use crate::storage::CryptoInfo;
+    use crate::trie::Trie;
+    use anyhow::{Result, anyhow};
+
+    const CURRENT: KdfParams = KdfParams {
+        m_cost: 19456,
+        t_cost: 2,
+        p_cost: 1,
+    };
+
+    /// Session whose vault uses [`CURRENT`] and records the new parameters.
+    #[derive(Default)]
+    struct MockSession {
+        reencrypted: Option<KdfParams>,
+    }
+
+    impl Session for MockSession {
+        fn save(&mut self, _credentials: &Credentials) -> Result<()> {
+            Ok(())
+        }
+
+        fn crypto_info(&self) -> Result<CryptoInfo> {
+            Ok(CryptoInfo {
+                version: 5,
+                kdf: "Argon2id v19",
+                kdf_params: CURRENT,
+                salt_len: 16,
+                cipher: "ChaCha20-Poly1305",
+                key_cipher: "ChaCha20-Poly1305",
+                nonce_len: 12,
+                wrapped_key_len: Some(48),
+                recovery_key: false,
+            })
+        }
+
+        fn reencrypt_with_params(
+            &mut self,
+            params: KdfParams,
+            _credentials: &Credentials,
+        ) -> Result<()> {
+            if params.p_cost == 0 {
+                return Err(anyhow!("Invalid Argon2id parameters"));
+            }
+            self.reencrypted = Some(params);
+            Ok(())
+        }
+    }
+
+    fn run(args: &[&str], session: &mut MockSession) -> CommandResult {
+        let mut credentials = Credentials::new();
+        let mut trie = Trie::new();
+        let mut ctx = ShellContext::new(&mut credentials, &mut trie).with_session(session);
+        HardenCommand.execute(args, &mut ctx)
+    }
+
+    #[test]
+    fn test_harden_keeps_unchanged_parameters() {
+        let mut session = MockSession::default();
+        let result = run(&["--m-cost", "65536"], &mut session);
+
+        match result {
+            CommandResult::Success(Some(msg)) => assert_eq!(
+                msg,
+                "Re-encrypted with m_cost=65536 KiB, t_cost=2, p_cost=1 \
+                 (was m_cost=19456 KiB, t_cost=2, p_cost=1)"
+            ),
+            _ => panic!("Expected success"),
+        }
+        assert_eq!(
+            session.reencrypted,
+            Some(KdfParams {
+                m_cost: 65536,
+                ..CURRENT
+            })
+        );
+    }
+
+    #[test]
+    fn test_harden_with_current_parameters_does_nothing() {
+        let mut session = MockSession::default();
+        let result = run(&["--t-cost", "2"], &mut session);
+        assert!(matches!(result, CommandResult::Success(Some(_))));
+        assert_eq!(session.reencrypted, None);
+    }
+
+    #[test]
+    fn test_harden_rejects_bad_arguments() {
+        for args in [
+            &[][..],
+            &["--m-cost"],
+            &["--m-cost", "lots"],
+            &["--t-cost", "-1"],
+            &["--t-cost", "3", "extra"],
+        ] {
+            let mut session = MockSession::default();
+            assert!(matches!(run(args, &mut session), CommandResult::Error(_)));
+            assert_eq!(session.reencrypted, None);
+        }
+    }
+
+    #[test]
+    fn test_harden_reports_session_errors() {
+        let mut session = MockSession::default();
+        match run(&["--p-cost", "0"], &mut session) {
+            CommandResult::Error(msg) => assert!(msg.starts_with("Could not re-encrypt vault")),
+            _ => panic!("Expected error"),
+        }
+    }
+
+    #[test]
+    fn test_harden_without_session() {
+        let mut credentials = Credentials::new();
+        let mut trie = Trie::new();
+        let mut ctx = ShellContext::new(&mut credentials, &mut trie);
+        assert!(matches!(
+            HardenCommand.execute(&["--t-cost", "3"], &mut ctx),
+            CommandResult::Error(_)
+        ));
+    }
+}
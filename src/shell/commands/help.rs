@@ -152,6 +152,7 @@ mod tests {
                 assert!(msg.contains("remove"));
                 assert!(msg.contains("help"));
                 assert!(msg.contains("quit"));
+                assert!(msg.contains("clear"));
             }
             _ => panic!("Expected success with help text"),
         }
@@ -170,7 +171,7 @@ mod tests {
         match result {
             CommandResult::Success(Some(msg)) => {
                 assert!(msg.contains("ADD"));
-                assert!(msg.contains("add <name> <secret>"));
+                assert!(msg.contains("add <name> [<secret>]"));
             }
             _ => panic!("Expected success with add help"),
         }
@@ -0,0 +1,216 @@
+//! History command implementation.
+
+use crate::shell::command::{Command, CommandResult, ShellContext};
+
+/// Number of entries shown by `history` without arguments.
+const RECENT_ENTRIES: usize = 20;
+
+/// Command to view, search and clear the command history.
+pub struct HistoryCommand;
+
+/// Formats `(index, entry)` pairs one per line, numbered from 1.
+fn numbered<'a>(entries: impl Iterator<Item = (usize, &'a String)>) -> String {
+    entries
+        .map(|(i, entry)| format!("{:>5}  {}", i + 1, entry))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+impl Command for HistoryCommand {
+    fn name(&self) -> &str {
+        "history"
+    }
+
+    fn aliases(&self) -> &[&str] {
+        &["hist"]
+    }
+
+    fn description(&self) -> &str {
+        "Show, search or clear the command history"
+    }
+
+    fn usage(&self) -> &str {
+        "history [clear | search <pattern>]"
+    }
+
+    fn help(&self) -> &str {
+        "Show the most recent commands, numbered from the oldest.\n\n\
+         Subcommands:\n  \
+           clear            - Delete the history, in memory and on disk\n  \
+           search <pattern> - Show every command containing <pattern>, ignoring case\n\n\
+         Examples:\n  \
+           history\n  \
+           history search github\n  \
+           history clear"
+    }
+
+    fn execute(&self, args: &[&str], ctx: &mut ShellContext) -> CommandResult {
+        let Some(history) = ctx.history.as_deref_mut() else {
+            return CommandResult::error("History is not available in this session");
+        };
+
+        match args {
+            [] => {
+                let entries = history.entries();
+                if entries.is_empty() {
+                    return CommandResult::success("History is empty.");
+                }
+                let skip = entries.len().saturating_sub(RECENT_ENTRIES);
+                CommandResult::success(numbered(entries.iter().enumerate().skip(skip)))
+            }
+            ["clear"] => match history.clear() {
+                Ok(()) => CommandResult::success("History cleared."),
+                Err(e) => CommandResult::error(format!("Could not clear history: {}", e)),
+            },
+            ["search", pattern] => {
+                let needle = pattern.to_lowercase();
+                let entries = history.entries();
+                let matches: Vec<(usize, &String)> = entries
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, entry)| entry.to_lowercase().contains(&needle))
+                    .collect();
+                if matches.is_empty() {
+                    CommandResult::success(format!("No history entries match '{}'.", pattern))
+                } else {
+                    CommandResult::success(numbered(matches.into_iter()))
+                }
+            }
+            _ => CommandResult::error(format!("Usage: {}", self.usage())),
+        }
+    }
+
+    fn min_args(&self) -> usize {
+        0
+    }
+
+    fn max_args(&self) -> Option<usize> {
+        Some(2)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::credentials::Credentials;
+    use crate::shell::history::CommandHistory;
+    use crate::trie::Trie;
+
+    /// In-memory history that records whether it was cleared.
+    struct StubHistory {
+        entries: Vec<String>,
+        cleared: bool,
+    }
+
+    impl StubHistory {
+        fn new(entries: &[&str]) -> Self {
+            Self {
+                entries: entries.iter().map(|e| e.to_string()).collect(),
+                cleared: false,
+            }
+        }
+    }
+
+    impl CommandHistory for StubHistory {
+        fn entries(&self) -> Vec<String> {
+            self.entries.clone()
+        }
+
+        fn clear(&mut self) -> anyhow::Result<()> {
+            self.entries.clear();
+            self.cleared = true;
+            Ok(())
+        }
+    }
+
+    fn run(history: &mut StubHistory, args: &[&str]) -> CommandResult {
+        let mut credentials = Credentials::new();
+        let mut trie = Trie::new();
+        let mut ctx = ShellContext::new(&mut credentials, &mut trie).with_history(history);
+        HistoryCommand.execute(args, &mut ctx)
+    }
+
+    #[test]
+    fn test_history_lists_numbered_entries() {
+        let mut history = StubHistory::new(&["list", "get github"]);
+        match run(&mut history, &[]) {
+            CommandResult::Success(Some(msg)) => {
+                assert_eq!(msg, "    1  list\n    2  get github");
+            }
+            _ => panic!("Expected history"),
+        }
+    }
+
+    #[test]
+    fn test_history_shows_only_recent_entries() {
+        let entries: Vec<String> = (0..30).map(|i| format!("get key{}", i)).collect();
+        let entries: Vec<&str> = entries.iter().map(String::as_str).collect();
+        let mut history = StubHistory::new(&entries);
+
+        match run(&mut history, &[]) {
+            CommandResult::Success(Some(msg)) => {
+                assert_eq!(msg.lines().count(), RECENT_ENTRIES);
+                assert_eq!(msg.lines().next(), Some("   11  get key10"));
+            }
+            _ => panic!("Expected history"),
+        }
+    }
+
+    #[test]
+    fn test_history_empty() {
+        let mut history = StubHistory::new(&[]);
+        match run(&mut history, &[]) {
+            CommandResult::Success(Some(msg)) => assert_eq!(msg, "History is empty."),
+            _ => panic!("Expected message"),
+        }
+    }
+
+    #[test]
+    fn test_history_search() {
+        let mut history = StubHistory::new(&["get GitHub", "list", "remove github"]);
+        match run(&mut history, &["search", "github"]) {
+            CommandResult::Success(Some(msg)) => {
+                assert_eq!(msg, "    1  get GitHub\n    3  remove github");
+            }
+            _ => panic!("Expected matches"),
+        }
+        match run(&mut history, &["search", "email"]) {
+            CommandResult::Success(Some(msg)) => assert!(msg.contains("No history entries")),
+            _ => panic!("Expected message"),
+        }
+    }
+
+    #[test]
+    fn test_history_clear() {
+        let mut history = StubHistory::new(&["list"]);
+        let result = run(&mut history, &["clear"]);
+        assert!(matches!(result, CommandResult::Success(Some(_))));
+        assert!(history.cleared);
+        assert!(history.entries.is_empty());
+    }
+
+    #[test]
+    fn test_history_rejects_unknown_subcommand() {
+        let mut history = StubHistory::new(&["list"]);
+        assert!(matches!(
+            run(&mut history, &["wipe"]),
+            CommandResult::Error(_)
+        ));
+        assert!(matches!(
+            run(&mut history, &["search"]),
+            CommandResult::Error(_)
+        ));
+        assert!(!history.cleared);
+    }
+
+    #[test]
+    fn test_history_unavailable_without_shell() {
+        let mut credentials = Credentials::new();
+        let mut trie = Trie::new();
+        let mut ctx = ShellContext::new(&mut credentials, &mut trie);
+        assert!(matches!(
+            HistoryCommand.execute(&[], &mut ctx),
+            CommandResult::Error(_)
+        ));
+    }
+}
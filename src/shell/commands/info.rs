@@ -0,0 +1,215 @@
+//! Info command implementation.
+
+use crate::shell::command::{Command, CommandResult, ShellContext, take_flag};
+use crate::storage::CryptoInfo;
+
+/// Command to show details about the unlocked vault.
+pub struct InfoCommand;
+
+/// Formats encryption details, one `label: value` line each.
+fn format_crypto_info(info: &CryptoInfo) -> String {
+    let data_key = match info.wrapped_key_len {
+        Some(len) => format!(
+            "256-bit, wrapped with {} under the password-derived key ({} bytes)",
+            info.key_cipher, len
+        ),
+        None => "none (data encrypted directly under the password-derived key)".to_string(),
+    };
+    let recovery = if info.recovery_key {
+        "set up"
+    } else {
+        "not set up"
+    };
+
+    [
+        format!("Store format:  version {}", info.version),
+        format!(
+            "KDF:           {} (m_cost={} KiB, t_cost={}, p_cost={})",
+            info.kdf, info.kdf_params.m_cost, info.kdf_params.t_cost, info.kdf_params.p_cost
+        ),
+        format!("Salt:          {} bytes", info.salt_len),
+        format!("Cipher:        {}", info.cipher),
+        format!("Nonce:         {} bytes", info.nonce_len),
+        format!("Data key:      {}", data_key),
+        format!("Recovery key:  {}", recovery),
+    ]
+    .join("\n")
+}
+
+impl Command for InfoCommand {
+    fn name(&self) -> &str {
+        "info"
+    }
+
+    fn description(&self) -> &str {
+        "Show details about the unlocked vault"
+    }
+
+    fn usage(&self) -> &str {
+        "info [--crypto]"
+    }
+
+    fn help(&self) -> &str {
+        "Show the vault in use and how many credentials it holds.\n\n\
+         Options:\n  \
+           --crypto - Also show how the vault file is encrypted (KDF and\n             \
+                      its parameters, cipher, salt and nonce sizes, data key\n             \
+                      wrapping). No key material is shown.\n\n\
+         Examples:\n  \
+           info\n  \
+           info --crypto"
+    }
+
+    fn execute(&self, args: &[&str], ctx: &mut ShellContext) -> CommandResult {
+        let (crypto, rest) = take_flag(args, "--crypto");
+        if let Some(arg) = rest.first() {
+            return CommandResult::error(format!(
+                "Unexpected argument: '{}'\nUsage: {}",
+                arg,
+                self.usage()
+            ));
+        }
+
+        let mut lines = Vec::new();
+        if let Some(session) = ctx.session.as_deref()
+            && let Ok(vaults) = session.vaults()
+            && let Some(vault) = vaults.iter().find(|v| v.active)
+        {
+            lines.push(format!(
+                "Vault:         {} ({})",
+                vault.name,
+                vault.path.display()
+            ));
+        }
+        lines.push(format!("Credentials:   {}", ctx.credentials.len()));
+
+        if crypto {
+            let Some(session) = ctx.session.as_deref() else {
+                return CommandResult::error(
+                    "Encryption details are not available in this session",
+                );
+            };
+            match session.crypto_info() {
+                Ok(info) => lines.push(format_crypto_info(&info)),
+                Err(e) => {
+                    return CommandResult::error(format!(
+                        "Could not read encryption details: {}",
+                        e
+                    ));
+                }
+            }
+        }
+
+        CommandResult::success(lines.join("\n"))
+    }
+
+    fn min_args(&self) -> usize {
+        0
+    }
+
+    fn max_args(&self) -> Option<usize> {
+        Some(1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::credentials::Credentials;
+    use crate::crypto::KdfParams;
+    use crate::shell::session::Session;
+    use crate::trie::Trie;
+    use anyhow::Result;
+
+    struct MockSession {
+        info: CryptoInfo,
+    }
+
+    impl Session for MockSession {
+        fn save(&mut self, _credentials: &Credentials) -> Result<()> {
+            Ok(())
+        }
+
+        fn crypto_info(&self) -> Result<CryptoInfo> {
+            Ok(self.info.clone())
+        }
+    }
+
+    fn envelope_info() -> CryptoInfo {
+        CryptoInfo {
+            version: 4,
+            kdf: "Argon2id v19",
+            kdf_params: KdfParams {
+                m_cost: 19456,
+                t_cost: 2,
+                p_cost: 1,
+            },
+            salt_len: 16,
+            cipher: "ChaCha20-Poly1305",
+            key_cipher: "ChaCha20-Poly1305",
+            nonce_len: 12,
+            wrapped_key_len: Some(48),
+            recovery_key: false,
+        }
+    }
+
+    #[test]
+    fn test_info_counts_credentials() {
+        let mut credentials = Credentials::new();
+        credentials
+            .add("github".to_string(), "secret".to_string())
+            .unwrap();
+        let mut trie = Trie::new();
+        let mut ctx = ShellContext::new(&mut credentials, &mut trie);
+
+        match InfoCommand.execute(&[], &mut ctx) {
+            CommandResult::Success(Some(msg)) => assert_eq!(msg, "Credentials:   1"),
+            _ => panic!("Expected success with info"),
+        }
+    }
+
+    #[test]
+    fn test_info_crypto() {
+        let mut credentials = Credentials::new();
+        let mut trie = Trie::new();
+        let mut session = MockSession {
+            info: envelope_info(),
+        };
+        let mut ctx = ShellContext::new(&mut credentials, &mut trie).with_session(&mut session);
+
+        match InfoCommand.execute(&["--crypto"], &mut ctx) {
+            CommandResult::Success(Some(msg)) => {
+                assert!(msg.contains("Store format:  version 4"));
+                assert!(msg.contains(
+                    "KDF:           Argon2id v19 (m_cost=19456 KiB, t_cost=2, p_cost=1)"
+                ));
+                assert!(msg.contains("Salt:          16 bytes"));
+                assert!(msg.contains("Cipher:        ChaCha20-Poly1305"));
+                assert!(msg.contains("Nonce:         12 bytes"));
+                assert!(msg.contains("under the password-derived key (48 bytes)"));
+                assert!(msg.contains("Recovery key:  not set up"));
+            }
+            _ => panic!("Expected success with crypto info"),
+        }
+    }
+
+    #[test]
+    fn test_info_crypto_without_session() {
+        let mut credentials = Credentials::new();
+        let mut trie = Trie::new();
+        let mut ctx = ShellContext::new(&mut credentials, &mut trie);
+
+        let result = InfoCommand.execute(&["--crypto"], &mut ctx);
+        assert!(matches!(result, CommandResult::Error(_)));
+    }
+
+    #[test]
+    fn test_info_rejects_unknown_argument() {
+        let mut credentials = Credentials::new();
+        let mut trie = Trie::new();
+        let mut ctx = ShellContext::new(&mut credentials, &mut trie);
+
+        let result = InfoCommand.execute(&["--keys"], &mut ctx);
+        assert!(matches!(result, CommandResult::Error(_)));
+    }
+}
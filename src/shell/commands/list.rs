@@ -1,6 +1,141 @@
 //! List command implementation.
 
-use crate::shell::command::{Command, CommandResult, ShellContext};
+use chrono::Utc;
+use serde_json::json;
+
+use crate::shell::command::{Command, CommandResult, ShellContext, take_flag, take_option};
+
+/// A slice of a sorted list, with its position in the whole list.
+#[derive(Debug, PartialEq)]
+pub struct Page<'a, T> {
+    /// The items on this page.
+    pub items: &'a [T],
+    /// Index of the first item on this page in the whole list.
+    pub offset: usize,
+    /// Number of items in the whole list.
+    pub total: usize,
+}
+
+impl<T> Page<'_, T> {
+    /// Describes the page position, e.g. "showing 1-20 of 137".
+    pub fn footer(&self) -> String {
+        if self.items.is_empty() {
+            format!("showing 0 of {}", self.total)
+        } else {
+            format!(
+                "showing {}-{} of {}",
+                self.offset + 1,
+                self.offset + self.items.len(),
+                self.total
+            )
+        }
+    }
+}
+
+/// Returns up to `limit` items starting at `offset`, or all remaining items
+/// if `limit` is `None`.
+///
+/// An offset past the end yields an empty page rather than an error.
+pub fn paginate<T>(items: &[T], offset: usize, limit: Option<usize>) -> Page<'_, T> {
+    let start = offset.min(items.len());
+    let end = match limit {
+        Some(limit) => start.saturating_add(limit).min(items.len()),
+        None => items.len(),
+    };
+    Page {
+        items: &items[start..end],
+        offset,
+        total: items.len(),
+    }
+}
+
+/// Default separator between the parts of a name in `list --tree`.
+pub const TREE_SEPARATOR: &str = "/";
+
+/// A part of a credential name shown by `list --tree`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TreeNode {
+    /// This part of the name, without separators.
+    pub label: String,
+    /// Whether the name up to and including this part is a credential.
+    pub is_credential: bool,
+    /// Parts that follow this one, in the order they were first seen.
+    pub children: Vec<TreeNode>,
+}
+
+impl TreeNode {
+    fn new(label: &str) -> Self {
+        Self {
+            label: label.to_string(),
+            is_credential: false,
+            children: Vec::new(),
+        }
+    }
+}
+
+/// Groups `names` into a tree by splitting them at `separator`.
+///
+/// Siblings keep the order in which they first appear in `names`, so a
+/// sorted list gives a sorted tree. A name with an empty part, such as one
+/// ending in the separator, is kept whole as a top-level entry.
+pub fn build_tree<S: AsRef<str>>(names: &[S], separator: &str) -> Vec<TreeNode> {
+    let mut roots: Vec<TreeNode> = Vec::new();
+    for name in names {
+        let name = name.as_ref();
+        let mut parts: Vec<&str> = name.split(separator).collect();
+        if parts.iter().any(|part| part.is_empty()) {
+            parts = vec![name];
+        }
+
+        let mut level = &mut roots;
+        let last = parts.len() - 1;
+        for (i, part) in parts.into_iter().enumerate() {
+            let index = match level.iter().position(|node| node.label == part) {
+                Some(index) => index,
+                None => {
+                    level.push(TreeNode::new(part));
+                    level.len() - 1
+                }
+            };
+            let node = &mut level[index];
+            if i == last {
+                node.is_credential = true;
+            }
+            level = &mut node.children;
+        }
+    }
+    roots
+}
+
+/// Renders a tree one part per line, indenting children by two spaces.
+///
+/// Groups are followed by the separator; a part that is both a credential
+/// and a group is shown on two lines.
+pub fn render_tree(nodes: &[TreeNode], separator: &str) -> String {
+    fn render(nodes: &[TreeNode], separator: &str, depth: usize, lines: &mut Vec<String>) {
+        let indent = "  ".repeat(depth);
+        for node in nodes {
+            if node.is_credential {
+                lines.push(format!("{}{}", indent, node.label));
+            }
+            if !node.children.is_empty() {
+                lines.push(format!("{}{}{}", indent, node.label, separator));
+                render(&node.children, separator, depth + 1, lines);
+            }
+        }
+    }
+
+    let mut lines = Vec::new();
+    render(nodes, separator, 0, &mut lines);
+    lines.join("\n")
+}
+
+/// Parses the value of a numeric option such as `--limit`.
+fn parse_count(option: &str, value: &str) -> Result<usize, String> {
+    value
+        .parse()
+        .map_err(|_| format!("{} expects a non-negative number, got '{}'", option, value))
+}
 
 /// Command to list all credentials.
 pub struct ListCommand;
@@ -19,34 +154,147 @@ impl Command for ListCommand {
     }
 
     fn usage(&self) -> &str {
-        "list"
+        "list [--count-only] [--expired] [--limit N] [--offset M] [--reverse] [--json | --tree [--separator SEP]]"
     }
 
     fn help(&self) -> &str {
         "Display a list of all stored credential names.\n\n\
          The secrets are not shown, only the names.\n\n\
+         Options:\n  \
+           --count-only - Print only the number of credentials\n  \
+           --expired    - List only credentials due for rotation\n  \
+           --limit N    - Show at most N names\n  \
+           --offset M   - Skip the first M names\n  \
+           --reverse    - Sort names in reverse order\n  \
+           --json       - Print the names, or the count, as JSON\n  \
+           --tree       - Group names into a tree at each '/'\n  \
+           --separator SEP - Split names at SEP instead of '/' with --tree\n\n\
+         When --limit or --offset is given, a footer shows which names are listed.\n\
+         --tree cannot be combined with them.\n\n\
          Examples:\n  \
            list\n  \
-           ls"
+           ls\n  \
+           list --count-only\n  \
+           list --expired\n  \
+           list --limit 20 --offset 40\n  \
+           list --tree\n  \
+           list --json --limit 20\n  \
+           list --tree --separator ."
     }
 
-    fn execute(&self, _args: &[&str], ctx: &mut ShellContext) -> CommandResult {
+    fn execute(&self, args: &[&str], ctx: &mut ShellContext) -> CommandResult {
+        let (count_only, rest) = take_flag(args, "--count-only");
+        let (reverse, rest) = take_flag(&rest, "--reverse");
+        let (expired, rest) = take_flag(&rest, "--expired");
+        let (tree, rest) = take_flag(&rest, "--tree");
+        let (as_json, rest) = take_flag(&rest, "--json");
+        let as_json = ctx.wants_json(as_json);
+        let (separator, rest) = match take_option(&rest, "--separator") {
+            Ok(parsed) => parsed,
+            Err(e) => return CommandResult::error(e),
+        };
+        let (limit, rest) = match take_option(&rest, "--limit") {
+            Ok(parsed) => parsed,
+            Err(e) => return CommandResult::error(e),
+        };
+        let (offset, rest) = match take_option(&rest, "--offset") {
+            Ok(parsed) => parsed,
+            Err(e) => return CommandResult::error(e),
+        };
+        if let Some(arg) = rest.first() {
+            return CommandResult::error(format!(
+                "Unexpected argument: '{}'\nUsage: {}",
+                arg,
+                self.usage()
+            ));
+        }
+
+        let paged = limit.is_some() || offset.is_some();
+        if tree && paged {
+            return CommandResult::error("--tree cannot be combined with --limit or --offset");
+        }
+        if tree && as_json {
+            return CommandResult::error("--tree cannot be combined with --json");
+        }
+        if separator.is_some() && !tree {
+            return CommandResult::error("--separator only applies with --tree");
+        }
+        let separator = separator.unwrap_or(TREE_SEPARATOR);
+        if separator.is_empty() {
+            return CommandResult::error("--separator cannot be empty");
+        }
+        let limit = match limit.map(|v| parse_count("--limit", v)).transpose() {
+            Ok(limit) => limit,
+            Err(e) => return CommandResult::error(e),
+        };
+        let offset = match offset.map(|v| parse_count("--offset", v)).transpose() {
+            Ok(offset) => offset.unwrap_or(0),
+            Err(e) => return CommandResult::error(e),
+        };
+
         log::debug!("Listing credentials");
 
-        if ctx.credentials.is_empty() {
-            return CommandResult::success("No credentials stored.");
+        let mut names: Vec<&String> = if expired {
+            ctx.credentials.expired(Utc::now())
+        } else {
+            ctx.credentials.list()
+        };
+
+        if count_only {
+            return if as_json {
+                CommandResult::json(json!(names.len()))
+            } else {
+                CommandResult::success(names.len().to_string())
+            };
+        }
+
+        if names.is_empty() && !as_json {
+            return CommandResult::success(if expired {
+                "No credentials are due for rotation."
+            } else {
+                "No credentials stored."
+            });
         }
 
-        let mut names: Vec<&String> = ctx.credentials.list();
         names.sort();
+        if reverse {
+            names.reverse();
+        }
+
+        if tree {
+            log::info!("Listed {} credentials as a tree", names.len());
+            return CommandResult::success(render_tree(&build_tree(&names, separator), separator));
+        }
+
+        let page = paginate(&names, offset, limit);
+        if as_json {
+            log::info!(
+                "Listed {} of {} credentials as JSON",
+                page.items.len(),
+                page.total
+            );
+            return CommandResult::json(json!(page.items));
+        }
+        if page.items.is_empty() {
+            return CommandResult::success(format!(
+                "No credentials at offset {} ({}).",
+                offset,
+                page.footer()
+            ));
+        }
 
-        let output = names
+        let mut output = page
+            .items
             .iter()
             .map(|s| s.as_str())
             .collect::<Vec<_>>()
             .join("\n");
+        if paged {
+            output.push('\n');
+            output.push_str(&page.footer());
+        }
 
-        log::info!("Listed {} credentials", names.len());
+        log::info!("Listed {} of {} credentials", page.items.len(), page.total);
         CommandResult::success(output)
     }
 
@@ -55,7 +303,7 @@ impl Command for ListCommand {
     }
 
     fn max_args(&self) -> Option<usize> {
-        Some(0)
+        Some(11)
     }
 }
 
@@ -112,4 +360,346 @@ mod tests {
             _ => panic!("Expected success with list"),
         }
     }
+
+    #[test]
+    fn test_list_order_does_not_depend_on_insertion_order() {
+        // Names are unique, so sorting by name leaves no ties for the
+        // credentials map's iteration order to break
+        let names = ["github", "GitHub", "email", "aws", "aws-prod", "Email"];
+        let mut outputs = Vec::new();
+        for rotation in 0..names.len() {
+            let mut credentials = Credentials::new();
+            for name in names.iter().cycle().skip(rotation).take(names.len()) {
+                credentials
+                    .add(name.to_string(), "same secret".to_string())
+                    .unwrap();
+            }
+            let mut trie = Trie::new();
+            let mut ctx = ShellContext::new(&mut credentials, &mut trie);
+            match ListCommand.execute(&[], &mut ctx) {
+                CommandResult::Success(Some(msg)) => outputs.push(msg),
+                _ => panic!("Expected success with list"),
+            }
+        }
+
+        assert_eq!(outputs[0], "Email\nGitHub\naws\naws-prod\nemail\ngithub");
+        assert!(outputs.iter().all(|output| *output == outputs[0]));
+    }
+
+    #[test]
+    fn test_list_command_count_only() {
+        let mut credentials = Credentials::new();
+        let mut trie = Trie::new();
+        let mut ctx = ShellContext::new(&mut credentials, &mut trie);
+
+        let cmd = ListCommand;
+        match cmd.execute(&["--count-only"], &mut ctx) {
+            CommandResult::Success(Some(msg)) => assert_eq!(msg, "0"),
+            _ => panic!("Expected count"),
+        }
+
+        for name in ["github", "email", "aws"] {
+            ctx.credentials
+                .add(name.to_string(), "secret".to_string())
+                .unwrap();
+        }
+
+        let listed = match cmd.execute(&[], &mut ctx) {
+            CommandResult::Success(Some(msg)) => msg.lines().count(),
+            _ => panic!("Expected list"),
+        };
+        match cmd.execute(&["--count-only"], &mut ctx) {
+            CommandResult::Success(Some(msg)) => assert_eq!(msg, listed.to_string()),
+            _ => panic!("Expected count"),
+        }
+    }
+
+    #[test]
+    fn test_list_command_expired() {
+        let mut credentials = Credentials::new();
+        let long_ago = Utc::now() - chrono::Duration::days(400);
+        for name in ["github", "email", "aws"] {
+            credentials
+                .add(name.to_string(), "secret".to_string())
+                .unwrap();
+        }
+        credentials.touch("github", long_ago);
+        credentials.touch("aws", long_ago);
+        let mut trie = Trie::new();
+        let mut ctx = ShellContext::new(&mut credentials, &mut trie);
+
+        let cmd = ListCommand;
+        match cmd.execute(&["--expired"], &mut ctx) {
+            CommandResult::Success(Some(msg)) => {
+                assert_eq!(msg, "No credentials are due for rotation.")
+            }
+            _ => panic!("Expected success"),
+        }
+
+        ctx.credentials
+            .set_default_rotate_after(Some(std::time::Duration::from_secs(
+                365 * crate::credentials::SECS_PER_DAY,
+            )));
+        match cmd.execute(&["--expired"], &mut ctx) {
+            CommandResult::Success(Some(msg)) => assert_eq!(msg, "aws\ngithub"),
+            _ => panic!("Expected list"),
+        }
+        match cmd.execute(&["--expired", "--count-only"], &mut ctx) {
+            CommandResult::Success(Some(msg)) => assert_eq!(msg, "2"),
+            _ => panic!("Expected count"),
+        }
+    }
+
+    #[test]
+    fn test_list_command_rejects_unknown_argument() {
+        let mut credentials = Credentials::new();
+        let mut trie = Trie::new();
+        let mut ctx = ShellContext::new(&mut credentials, &mut trie);
+
+        let result = ListCommand.execute(&["--verbose"], &mut ctx);
+        assert!(matches!(result, CommandResult::Error(_)));
+    }
+
+    fn credentials_named(count: usize) -> Credentials {
+        let mut credentials = Credentials::new();
+        for i in 0..count {
+            credentials
+                .add(format!("site{:03}", i), "secret".to_string())
+                .unwrap();
+        }
+        credentials
+    }
+
+    #[test]
+    fn test_paginate() {
+        let items: Vec<usize> = (0..137).collect();
+
+        let page = paginate(&items, 0, Some(20));
+        assert_eq!(page.items, &items[0..20]);
+        assert_eq!(page.footer(), "showing 1-20 of 137");
+
+        let page = paginate(&items, 120, Some(20));
+        assert_eq!(page.items, &items[120..137]);
+        assert_eq!(page.footer(), "showing 121-137 of 137");
+
+        let page = paginate(&items, 10, None);
+        assert_eq!(page.items.len(), 127);
+
+        let page = paginate(&items, 200, Some(20));
+        assert!(page.items.is_empty());
+        assert_eq!(page.footer(), "showing 0 of 137");
+
+        let page = paginate(&items, 5, Some(usize::MAX));
+        assert_eq!(page.items.len(), 132);
+
+        let empty: Vec<usize> = Vec::new();
+        assert!(paginate(&empty, 0, Some(5)).items.is_empty());
+    }
+
+    #[test]
+    fn test_list_command_paged() {
+        let mut credentials = credentials_named(5);
+        let mut trie = Trie::new();
+        let mut ctx = ShellContext::new(&mut credentials, &mut trie);
+
+        match ListCommand.execute(&["--limit", "2", "--offset", "1"], &mut ctx) {
+            CommandResult::Success(Some(msg)) => {
+                assert_eq!(msg, "site001\nsite002\nshowing 2-3 of 5");
+            }
+            _ => panic!("Expected a page"),
+        }
+
+        match ListCommand.execute(&["--reverse", "--limit", "2"], &mut ctx) {
+            CommandResult::Success(Some(msg)) => {
+                assert_eq!(msg, "site004\nsite003\nshowing 1-2 of 5");
+            }
+            _ => panic!("Expected a reversed page"),
+        }
+
+        match ListCommand.execute(&["--reverse"], &mut ctx) {
+            CommandResult::Success(Some(msg)) => {
+                assert_eq!(msg.lines().next(), Some("site004"));
+                assert_eq!(msg.lines().count(), 5);
+            }
+            _ => panic!("Expected a reversed list"),
+        }
+    }
+
+    #[test]
+    fn test_list_command_offset_out_of_range() {
+        let mut credentials = credentials_named(3);
+        let mut trie = Trie::new();
+        let mut ctx = ShellContext::new(&mut credentials, &mut trie);
+
+        match ListCommand.execute(&["--offset", "10"], &mut ctx) {
+            CommandResult::Success(Some(msg)) => {
+                assert_eq!(msg, "No credentials at offset 10 (showing 0 of 3).");
+            }
+            _ => panic!("Expected an empty page"),
+        }
+    }
+
+    #[test]
+    fn test_list_command_rejects_bad_paging_values() {
+        let mut credentials = credentials_named(3);
+        let mut trie = Trie::new();
+        let mut ctx = ShellContext::new(&mut credentials, &mut trie);
+
+        for args in [
+            &["--limit", "x"][..],
+            &["--offset", "-1"][..],
+            &["--limit"][..],
+        ] {
+            let result = ListCommand.execute(args, &mut ctx);
+            assert!(matches!(result, CommandResult::Error(_)), "{:?}", args);
+        }
+    }
+
+    fn names(names: &[&str]) -> Vec<String> {
+        names.iter().map(|name| name.to_string()).collect()
+    }
+
+    #[test]
+    fn test_build_tree_groups_by_prefix() {
+        let tree = build_tree(&names(&["aws/dev/key", "aws/prod/key", "github"]), "/");
+
+        assert_eq!(tree.len(), 2);
+        assert_eq!(tree[0].label, "aws");
+        assert!(!tree[0].is_credential);
+        let groups: Vec<&str> = tree[0].children.iter().map(|n| n.label.as_str()).collect();
+        assert_eq!(groups, vec!["dev", "prod"]);
+        assert_eq!(tree[0].children[1].children[0].label, "key");
+        assert!(tree[0].children[1].children[0].is_credential);
+        assert_eq!(tree[1].label, "github");
+        assert!(tree[1].is_credential && tree[1].children.is_empty());
+    }
+
+    #[test]
+    fn test_build_tree_merges_groups_seen_apart() {
+        // "aws-x" sorts between the two "aws/" names
+        let tree = build_tree(&names(&["aws/a", "aws-x", "aws/b", "aws"]), "/");
+
+        let labels: Vec<&str> = tree.iter().map(|n| n.label.as_str()).collect();
+        assert_eq!(labels, vec!["aws", "aws-x"]);
+        assert!(tree[0].is_credential);
+        assert_eq!(tree[0].children.len(), 2);
+    }
+
+    #[test]
+    fn test_build_tree_keeps_names_with_empty_parts() {
+        let tree = build_tree(&names(&["/root", "a//b", "dir/"]), "/");
+        let labels: Vec<&str> = tree.iter().map(|n| n.label.as_str()).collect();
+        assert_eq!(labels, vec!["/root", "a//b", "dir/"]);
+        assert!(
+            tree.iter()
+                .all(|n| n.is_credential && n.children.is_empty())
+        );
+    }
+
+    #[test]
+    fn test_render_tree() {
+        let input = names(&["aws", "aws/dev/key", "aws/prod/key", "github"]);
+        assert_eq!(
+            render_tree(&build_tree(&input, "/"), "/"),
+            "aws\naws/\n  dev/\n    key\n  prod/\n    key\ngithub"
+        );
+
+        let input = names(&["mail.work", "mail.home"]);
+        assert_eq!(
+            render_tree(&build_tree(&input, "."), "."),
+            "mail.\n  work\n  home"
+        );
+        assert_eq!(render_tree(&[], "/"), "");
+    }
+
+    #[test]
+    fn test_list_command_tree() {
+        let mut credentials = Credentials::new();
+        for name in ["github", "aws/prod/key", "aws/dev/key", "aws.eu"] {
+            credentials
+                .add(name.to_string(), "secret".to_string())
+                .unwrap();
+        }
+        let mut trie = Trie::new();
+        let mut ctx = ShellContext::new(&mut credentials, &mut trie);
+
+        match ListCommand.execute(&["--tree"], &mut ctx) {
+            CommandResult::Success(Some(msg)) => assert_eq!(
+                msg,
+                "aws.eu\naws/\n  dev/\n    key\n  prod/\n    key\ngithub"
+            ),
+            _ => panic!("Expected a tree"),
+        }
+        match ListCommand.execute(&["--tree", "--separator", "."], &mut ctx) {
+            CommandResult::Success(Some(msg)) => {
+                assert_eq!(msg, "aws.\n  eu\naws/dev/key\naws/prod/key\ngithub")
+            }
+            _ => panic!("Expected a tree"),
+        }
+        // Plain list stays flat
+        match ListCommand.execute(&[], &mut ctx) {
+            CommandResult::Success(Some(msg)) => {
+                assert_eq!(msg, "aws.eu\naws/dev/key\naws/prod/key\ngithub")
+            }
+            _ => panic!("Expected a list"),
+        }
+    }
+
+    #[test]
+    fn test_list_command_tree_rejects_bad_combinations() {
+        let mut credentials = credentials_named(3);
+        let mut trie = Trie::new();
+        let mut ctx = ShellContext::new(&mut credentials, &mut trie);
+
+        for args in [
+            &["--tree", "--limit", "2"][..],
+            &["--separator", "."][..],
+            &["--tree", "--separator", ""][..],
+            &["--tree", "--separator"][..],
+        ] {
+            let result = ListCommand.execute(args, &mut ctx);
+            assert!(matches!(result, CommandResult::Error(_)), "{:?}", args);
+        }
+    }
+
+    #[test]
+    fn test_list_command_json() {
+        let mut credentials = credentials_named(3);
+        let mut trie = Trie::new();
+        let mut ctx = ShellContext::new(&mut credentials, &mut trie);
+
+        let value = match ListCommand.execute(&["--json"], &mut ctx) {
+            CommandResult::Json(value) => value,
+            _ => panic!("Expected JSON"),
+        };
+        let parsed: Vec<String> = serde_json::from_str(&value.to_string()).unwrap();
+        assert_eq!(parsed, vec!["site000", "site001", "site002"]);
+
+        match ListCommand.execute(&["--json", "--reverse", "--limit", "1"], &mut ctx) {
+            CommandResult::Json(value) => assert_eq!(value, json!(["site002"])),
+            _ => panic!("Expected JSON"),
+        }
+        match ListCommand.execute(&["--json", "--count-only"], &mut ctx) {
+            CommandResult::Json(value) => assert_eq!(value, json!(3)),
+            _ => panic!("Expected JSON"),
+        }
+        assert!(matches!(
+            ListCommand.execute(&["--json", "--tree"], &mut ctx),
+            CommandResult::Error(_)
+        ));
+    }
+
+    #[test]
+    fn test_list_command_json_output_format() {
+        let mut credentials = Credentials::new();
+        let mut trie = Trie::new();
+        let mut ctx = ShellContext::new(&mut credentials, &mut trie)
+            .with_output_format(crate::shell::command::OutputFormat::Json);
+
+        // An empty vault is an empty array, not a message
+        match ListCommand.execute(&[], &mut ctx) {
+            CommandResult::Json(value) => assert_eq!(value, json!([])),
+            _ => panic!("Expected JSON"),
+        }
+    }
 }
@@ -0,0 +1,61 @@
+//! Lock command implementation.
+
+use crate::shell::command::{Command, CommandResult, ShellContext};
+
+/// Command to lock the vault without leaving the shell.
+pub struct LockCommand;
+
+impl Command for LockCommand {
+    fn name(&self) -> &str {
+        "lock"
+    }
+
+    fn description(&self) -> &str {
+        "Lock the vault until the master password is entered again"
+    }
+
+    fn usage(&self) -> &str {
+        "lock"
+    }
+
+    fn help(&self) -> &str {
+        "Clear the credentials and master password from memory, then ask for\n\
+         the master password to unlock the vault again. The shell keeps its\n\
+         history, but changes made before locking can no longer be undone.\n\
+         Pending changes are saved first.\n\n\
+         Examples:\n  \
+           lock"
+    }
+
+    fn execute(&self, _args: &[&str], _ctx: &mut ShellContext) -> CommandResult {
+        log::info!("User requested lock");
+        CommandResult::Lock
+    }
+
+    fn min_args(&self) -> usize {
+        0
+    }
+
+    fn max_args(&self) -> Option<usize> {
+        Some(0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::credentials::Credentials;
+    use crate::trie::Trie;
+
+    #[test]
+    fn test_lock_command() {
+        let mut credentials = Credentials::new();
+        let mut trie = Trie::new();
+        let mut ctx = ShellContext::new(&mut credentials, &mut trie);
+
+        assert!(matches!(
+            LockCommand.execute(&[], &mut ctx),
+            CommandResult::Lock
+        ));
+    }
+}
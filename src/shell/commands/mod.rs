@@ -1,18 +1,78 @@
 //! Individual command implementations.
 
 mod add;
+mod batch;
+mod clear;
+mod count;
+mod duplicates;
+mod expire;
+mod expired;
+mod frequent;
+mod generate;
 mod get;
+mod harden;
 mod help;
+mod history;
+mod info;
 mod list;
+mod lock;
+mod purge;
+mod pwned;
 mod quit;
+mod recent;
+mod reload;
 mod remove;
+mod rename;
+mod replace;
+mod restore;
+mod reveal;
+mod search;
+mod stats;
+mod tag;
+mod touch;
+mod trash;
+mod undo;
+mod use_vault;
+mod vaults;
+mod verify;
+mod verify_backups;
 
 pub use add::AddCommand;
+pub use batch::BatchCommand;
+pub use clear::ClearCommand;
+pub use count::CountCommand;
+pub use duplicates::DuplicatesCommand;
+pub use expire::ExpireCommand;
+pub use expired::ExpiredCommand;
+pub use frequent::FrequentCommand;
+pub use generate::GenerateCommand;
 pub use get::GetCommand;
+pub use harden::HardenCommand;
 pub use help::HelpCommand;
+pub use history::HistoryCommand;
+pub use info::InfoCommand;
 pub use list::ListCommand;
+pub use lock::LockCommand;
+pub use purge::PurgeCommand;
+pub use pwned::PwnedCommand;
 pub use quit::QuitCommand;
+pub use recent::RecentCommand;
+pub use reload::ReloadCommand;
 pub use remove::RemoveCommand;
+pub use rename::RenameCommand;
+pub use replace::ReplaceCommand;
+pub use restore::RestoreCommand;
+pub use reveal::RevealCommand;
+pub use search::SearchCommand;
+pub use stats::StatsCommand;
+pub use tag::TagCommand;
+pub use touch::TouchCommand;
+pub use trash::TrashCommand;
+pub use undo::UndoCommand;
+pub use use_vault::UseCommand;
+pub use vaults::VaultsCommand;
+pub use verify::VerifyCommand;
+pub use verify_backups::VerifyBackupsCommand;
 
 use std::sync::Arc;
 
@@ -21,9 +81,39 @@ use super::command::CommandRegistry;
 /// Registers all built-in commands with the registry.
 pub fn register_all(registry: &mut CommandRegistry) {
     registry.register(Arc::new(AddCommand));
+    registry.register(Arc::new(BatchCommand));
+    registry.register(Arc::new(GenerateCommand));
     registry.register(Arc::new(GetCommand));
+    registry.register(Arc::new(RevealCommand));
     registry.register(Arc::new(RemoveCommand));
+    registry.register(Arc::new(RenameCommand));
+    registry.register(Arc::new(ReplaceCommand));
+    registry.register(Arc::new(UndoCommand));
+    registry.register(Arc::new(TrashCommand));
+    registry.register(Arc::new(PurgeCommand));
     registry.register(Arc::new(ListCommand));
+    registry.register(Arc::new(SearchCommand));
+    registry.register(Arc::new(CountCommand));
+    registry.register(Arc::new(StatsCommand));
+    registry.register(Arc::new(DuplicatesCommand));
+    registry.register(Arc::new(PwnedCommand));
+    registry.register(Arc::new(TagCommand));
+    registry.register(Arc::new(TouchCommand));
+    registry.register(Arc::new(ExpireCommand));
+    registry.register(Arc::new(ExpiredCommand));
+    registry.register(Arc::new(RecentCommand));
+    registry.register(Arc::new(FrequentCommand));
+    registry.register(Arc::new(VerifyCommand));
+    registry.register(Arc::new(VerifyBackupsCommand));
+    registry.register(Arc::new(RestoreCommand));
+    registry.register(Arc::new(InfoCommand));
+    registry.register(Arc::new(HardenCommand));
+    registry.register(Arc::new(VaultsCommand));
+    registry.register(Arc::new(UseCommand));
+    registry.register(Arc::new(ReloadCommand));
+    registry.register(Arc::new(ClearCommand));
+    registry.register(Arc::new(HistoryCommand));
     registry.register(Arc::new(HelpCommand));
+    registry.register(Arc::new(LockCommand));
     registry.register(Arc::new(QuitCommand));
 }
@@ -0,0 +1,139 @@
+//! Purge command implementation.
+
+use crate::shell::command::{Command, CommandResult, ShellContext, take_flag};
+
+/// Command to delete the credentials in the trash for good.
+pub struct PurgeCommand;
+
+impl Command for PurgeCommand {
+    fn name(&self) -> &str {
+        "purge"
+    }
+
+    fn description(&self) -> &str {
+        "Empty the trash for good"
+    }
+
+    fn usage(&self) -> &str {
+        "purge [--force]"
+    }
+
+    fn help(&self) -> &str {
+        "Delete every credential in the trash. They cannot be restored\n\
+         afterwards, not even with 'undo'. With 'confirm_destructive' set in\n\
+         the config file, asks first.\n\n\
+         Options:\n  \
+           --force, -f - Purge without asking for confirmation\n\n\
+         Examples:\n  \
+           purge\n  \
+           purge -f"
+    }
+
+    fn execute(&self, args: &[&str], ctx: &mut ShellContext) -> CommandResult {
+        let (force, rest) = take_flag(args, "--force");
+        let (short_force, rest) = take_flag(&rest, "-f");
+        if let Some(arg) = rest.first() {
+            return CommandResult::error(format!(
+                "Unexpected argument: '{}'\nUsage: {}",
+                arg,
+                self.usage()
+            ));
+        }
+
+        let count = ctx.credentials.trashed().len();
+        if count == 0 {
+            return CommandResult::success("The trash is empty.");
+        }
+
+        if ctx.confirm_destructive && !(force || short_force) {
+            match ctx.confirm(&format!("Delete {} credentials for good?", count)) {
+                Some(true) => {}
+                Some(false) => return CommandResult::success("Nothing purged"),
+                None => {
+                    return CommandResult::error(
+                        "Cannot ask for confirmation here; use 'purge --force'",
+                    );
+                }
+            }
+        }
+
+        let purged = ctx.credentials.purge_trash();
+        ctx.mark_modified();
+        log::info!("Purged {} credentials from the trash", purged);
+        CommandResult::success(format!("Deleted {} credentials for good", purged))
+    }
+
+    fn min_args(&self) -> usize {
+        0
+    }
+
+    fn max_args(&self) -> Option<usize> {
+        Some(2)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::credentials::Credentials;
+    use crate::shell::commands::RemoveCommand;
+    use crate::trie::Trie;
+
+    #[test]
+    fn test_remove_then_purge() {
+        let mut credentials = Credentials::new();
+        credentials
+            .add("github".to_string(), "secret".to_string())
+            .unwrap();
+        let mut trie = Trie::new();
+        trie.insert("github");
+        let mut ctx = ShellContext::new(&mut credentials, &mut trie);
+
+        RemoveCommand.execute(&["github"], &mut ctx);
+        ctx.modified = false;
+        match PurgeCommand.execute(&[], &mut ctx) {
+            CommandResult::Success(Some(msg)) => {
+                assert_eq!(msg, "Deleted 1 credentials for good")
+            }
+            _ => panic!("Expected success"),
+        }
+
+        assert!(ctx.modified);
+        assert!(ctx.credentials.trashed().is_empty());
+        assert!(ctx.credentials.restore_from_trash("github").is_err());
+    }
+
+    #[test]
+    fn test_purge_empty_trash() {
+        let mut credentials = Credentials::new();
+        let mut trie = Trie::new();
+        let mut ctx = ShellContext::new(&mut credentials, &mut trie);
+
+        match PurgeCommand.execute(&[], &mut ctx) {
+            CommandResult::Success(Some(msg)) => assert_eq!(msg, "The trash is empty."),
+            _ => panic!("Expected success"),
+        }
+        assert!(!ctx.modified);
+    }
+
+    #[test]
+    fn test_purge_declined() {
+        let mut credentials = Credentials::new();
+        credentials
+            .add("github".to_string(), "secret".to_string())
+            .unwrap();
+        credentials.move_to_trash("github", chrono::Utc::now());
+        let mut trie = Trie::new();
+        let mut prompt = |_: &str| Some("n".to_string());
+        let mut ctx = ShellContext::new(&mut credentials, &mut trie)
+            .with_confirm_destructive(true)
+            .with_prompt(&mut prompt);
+
+        assert!(matches!(
+            PurgeCommand.execute(&[], &mut ctx),
+            CommandResult::Success(Some(_))
+        ));
+        assert!(!ctx.modified);
+        assert_eq!(ctx.credentials.trashed().len(), 1);
+    }
+}
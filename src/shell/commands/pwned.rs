@@ -0,0 +1,268 @@
+//! Pwned command implementation.
+
+use std::path::Path;
+
+use crate::pwned::{HashDb, breach_count};
+use crate::shell::command::{Command, CommandResult, ShellContext, not_found, take_option};
+
+/// Command to check whether a stored secret appears in known breaches.
+pub struct PwnedCommand;
+
+impl PwnedCommand {
+    /// Checks `secret` against the hash dump at `path`, without the network.
+    fn check_local(&self, name: &str, secret: &str, path: &str) -> CommandResult {
+        let found = HashDb::open(Path::new(path)).and_then(|db| db.contains_secret(secret));
+        match found {
+            Ok(true) => {
+                log::info!("Credential found in local breach list: {}", name);
+                CommandResult::success(format!(
+                    "The secret for '{}' appears in the local breach list; change it",
+                    name
+                ))
+            }
+            Ok(false) => CommandResult::success(format!(
+                "The secret for '{}' was not found in the local breach list",
+                name
+            )),
+            Err(e) => CommandResult::error(format!("Could not read '{}': {}", path, e)),
+        }
+    }
+}
+
+impl Command for PwnedCommand {
+    fn name(&self) -> &str {
+        "pwned"
+    }
+
+    fn description(&self) -> &str {
+        "Check whether a secret appears in known breaches"
+    }
+
+    fn usage(&self) -> &str {
+        "pwned [--local PATH] <name>"
+    }
+
+    fn help(&self) -> &str {
+        "Look up a credential's secret in the Have I Been Pwned password list.\n\n\
+         Only the first 5 characters of the secret's SHA-1 hash are sent to\n\
+         api.pwnedpasswords.com; the matching hashes it returns are compared\n\
+         on this machine, so neither the secret nor its full hash is shared.\n\
+         This is the only command that uses the network, and it is only\n\
+         available when passmgr is built with '--features hibp'.\n\n\
+         With --local, the hash is instead looked up in a downloaded copy of\n\
+         the list, sorted by hash with one 'HASH:COUNT' line per entry. The\n\
+         file is searched in place, so it is never loaded into memory.\n\n\
+         Arguments:\n  \
+           <name> - The name of the credential to check\n\n\
+         Options:\n  \
+           --local PATH - Check against the hash file at PATH, offline\n\n\
+         Examples:\n  \
+           pwned github\n  \
+           pwned --local ~/pwned-passwords-sha1-ordered-by-hash.txt github"
+    }
+
+    fn execute(&self, args: &[&str], ctx: &mut ShellContext) -> CommandResult {
+        let (local, rest) = match take_option(args, "--local") {
+            Ok(parsed) => parsed,
+            Err(e) => return CommandResult::error(e),
+        };
+        let [name] = rest.as_slice() else {
+            return CommandResult::error(format!("Usage: {}", self.usage()));
+        };
+        let name = ctx.normalize_name(name);
+        let Some(secret) = ctx.credentials.get(&name) else {
+            return not_found(&name, ctx.key_trie);
+        };
+        if let Some(path) = local {
+            return self.check_local(&name, secret, path);
+        }
+        let Some(source) = ctx.breach_source else {
+            return CommandResult::error(
+                "Breach checks are not available: passmgr was built without the 'hibp' feature",
+            );
+        };
+
+        log::debug!("Checking credential against breaches: {}", name);
+        match breach_count(secret, source) {
+            Ok(0) => CommandResult::success(format!(
+                "The secret for '{}' was not found in any known breach",
+                name
+            )),
+            Ok(count) => {
+                log::info!("Credential found in breaches: {}", name);
+                CommandResult::success(format!(
+                    "The secret for '{}' appears {} times in known breaches; change it",
+                    name, count
+                ))
+            }
+            Err(e) => {
+                log::warn!("Breach check failed for '{}': {}", name, e);
+                CommandResult::error(format!(
+                    "Could not reach Have I Been Pwned, '{}' was not checked: {}",
+                    name, e
+                ))
+            }
+        }
+    }
+
+    fn completions(&self, _arg_index: usize, partial: &str, ctx: &ShellContext) -> Vec<String> {
+        // The name may come before or after --local
+        ctx.key_trie.completions(partial)
+    }
+
+    fn min_args(&self) -> usize {
+        1
+    }
+
+    fn max_args(&self) -> Option<usize> {
+        Some(3)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::credentials::Credentials;
+    use crate::pwned::RangeSource;
+    use crate::pwned::sha1_hex;
+    use crate::trie::Trie;
+    use anyhow::{Result, anyhow};
+
+    /// Knows the hash of `password`, as served for its prefix `5BAA6`.
+    struct MockSource;
+
+    impl RangeSource for MockSource {
+        fn fetch_range(&self, prefix: &str) -> Result<String> {
+            match prefix {
+                "5BAA6" => Ok("1D2A1E0FD5C2C1F3D1A2D76E1A2D0B1C0A2:3\r\n\
+                               1E4C9B93F3F0682250B6CF8331B7EE68FD8:10434004"
+                    .to_string()),
+                _ => Ok("0018A45C4D1DEF81644B54AB7F969B88D65:1".to_string()),
+            }
+        }
+    }
+
+    struct OfflineSource;
+
+    impl RangeSource for OfflineSource {
+        fn fetch_range(&self, _prefix: &str) -> Result<String> {
+            Err(anyhow!("connection refused"))
+        }
+    }
+
+    fn run(args: &[&str], source: Option<&dyn RangeSource>) -> CommandResult {
+        let mut credentials = Credentials::new();
+        credentials
+            .add("bank".to_string(), "password".to_string())
+            .unwrap();
+        credentials
+            .add("email".to_string(), "a much better secret".to_string())
+            .unwrap();
+        let mut trie = Trie::new();
+        trie.insert("bank");
+        trie.insert("email");
+        let mut ctx = ShellContext::new(&mut credentials, &mut trie);
+        if let Some(source) = source {
+            ctx = ctx.with_breach_source(source);
+        }
+        PwnedCommand.execute(args, &mut ctx)
+    }
+
+    #[test]
+    fn test_pwned_secret() {
+        match run(&["bank"], Some(&MockSource)) {
+            CommandResult::Success(Some(msg)) => assert_eq!(
+                msg,
+                "The secret for 'bank' appears 10434004 times in known breaches; change it"
+            ),
+            _ => panic!("Expected success"),
+        }
+    }
+
+    #[test]
+    fn test_secret_not_pwned() {
+        match run(&["email"], Some(&MockSource)) {
+            CommandResult::Success(Some(msg)) => assert_eq!(
+                msg,
+                "The secret for 'email' was not found in any known breach"
+            ),
+            _ => panic!("Expected success"),
+        }
+    }
+
+    #[test]
+    fn test_pwned_network_error() {
+        match run(&["bank"], Some(&OfflineSource)) {
+            CommandResult::Error(msg) => assert_eq!(
+                msg,
+                "Could not reach Have I Been Pwned, 'bank' was not checked: connection refused"
+            ),
+            _ => panic!("Expected error"),
+        }
+    }
+
+    #[test]
+    fn test_pwned_without_source() {
+        match run(&["bank"], None) {
+            CommandResult::Error(msg) => assert!(msg.contains("'hibp' feature")),
+            _ => panic!("Expected error"),
+        }
+    }
+
+    #[test]
+    fn test_pwned_unknown_name() {
+        match run(&["github"], Some(&MockSource)) {
+            CommandResult::Error(msg) => assert_eq!(msg, "'github' not found"),
+            _ => panic!("Expected error"),
+        }
+    }
+
+    /// Writes a dump with the hashes of a few common passwords.
+    fn write_dump(dir: &Path) -> String {
+        let mut hashes = [sha1_hex("password"), sha1_hex("123456"), sha1_hex("qwerty")];
+        hashes.sort();
+        let contents: String = hashes.iter().map(|h| format!("{}:1\r\n", h)).collect();
+        let path = dir.join("pwned.txt");
+        std::fs::write(&path, contents).unwrap();
+        path.to_str().unwrap().to_string()
+    }
+
+    #[test]
+    fn test_pwned_local() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = write_dump(dir.path());
+
+        // No network source is needed
+        match run(&["--local", &path, "bank"], None) {
+            CommandResult::Success(Some(msg)) => assert_eq!(
+                msg,
+                "The secret for 'bank' appears in the local breach list; change it"
+            ),
+            _ => panic!("Expected success"),
+        }
+        match run(&["email", "--local", &path], None) {
+            CommandResult::Success(Some(msg)) => assert_eq!(
+                msg,
+                "The secret for 'email' was not found in the local breach list"
+            ),
+            _ => panic!("Expected success"),
+        }
+    }
+
+    #[test]
+    fn test_pwned_local_errors() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let missing = dir.path().join("missing.txt");
+        let missing = missing.to_str().unwrap();
+
+        match run(&["--local", missing, "bank"], None) {
+            CommandResult::Error(msg) => {
+                assert!(msg.starts_with(&format!("Could not read '{}'", missing)))
+            }
+            _ => panic!("Expected error"),
+        }
+        for args in [&["bank", "--local"][..], &["--local", missing], &["a", "b"]] {
+            assert!(matches!(run(args, None), CommandResult::Error(_)));
+        }
+    }
+}
@@ -0,0 +1,142 @@
+//! Recent command implementation.
+
+use chrono::{DateTime, Utc};
+
+use crate::credentials::Credentials;
+use crate::shell::command::{Command, CommandResult, ShellContext};
+
+/// Number of credentials `recent` and `frequent` list by default.
+pub(super) const DEFAULT_LIMIT: usize = 10;
+
+/// Command to list the most recently read credentials.
+pub struct RecentCommand;
+
+/// Parses the optional count argument of `recent` and `frequent`.
+pub(super) fn parse_limit(args: &[&str]) -> Result<usize, String> {
+    match args.first() {
+        None => Ok(DEFAULT_LIMIT),
+        Some(arg) => match arg.parse() {
+            Ok(limit) if limit > 0 => Ok(limit),
+            _ => Err(format!("Expected a positive number, got '{}'", arg)),
+        },
+    }
+}
+
+/// Message shown when no reads have been recorded.
+pub(super) fn nothing_recorded(track_access: bool) -> String {
+    if track_access {
+        "No credentials have been read yet.".to_string()
+    } else {
+        "No reads have been recorded. Set 'track_access = true' in the config \
+         file to record them."
+            .to_string()
+    }
+}
+
+/// Lists up to `limit` credentials, most recently read first, with how long
+/// ago each was read.
+fn format_recent(credentials: &Credentials, limit: usize, now: DateTime<Utc>) -> Option<String> {
+    let accessed = credentials.recently_accessed();
+    if accessed.is_empty() {
+        return None;
+    }
+    let lines: Vec<String> = accessed
+        .into_iter()
+        .take(limit)
+        .map(
+            |(name, at)| match now.signed_duration_since(at).num_days() {
+                0 => format!("{} (read today)", name),
+                1 => format!("{} (read 1 day ago)", name),
+                days => format!("{} (read {} days ago)", name, days),
+            },
+        )
+        .collect();
+    Some(lines.join("\n"))
+}
+
+impl Command for RecentCommand {
+    fn name(&self) -> &str {
+        "recent"
+    }
+
+    fn description(&self) -> &str {
+        "List the most recently read credentials"
+    }
+
+    fn usage(&self) -> &str {
+        "recent [N]"
+    }
+
+    fn help(&self) -> &str {
+        "List the credentials whose secret was read most recently with 'get'\n\
+         or 'reveal'. Reads are only recorded with 'track_access' set in the\n\
+         config file.\n\n\
+         Arguments:\n  \
+           [N] - How many credentials to list (default: 10)\n\n\
+         Examples:\n  \
+           recent\n  \
+           recent 3"
+    }
+
+    fn execute(&self, args: &[&str], ctx: &mut ShellContext) -> CommandResult {
+        let limit = match parse_limit(args) {
+            Ok(limit) => limit,
+            Err(e) => return CommandResult::error(e),
+        };
+
+        log::debug!("Listing recently read credentials");
+        match format_recent(ctx.credentials, limit, Utc::now()) {
+            Some(list) => CommandResult::success(list),
+            None => CommandResult::success(nothing_recorded(ctx.track_access)),
+        }
+    }
+
+    fn min_args(&self) -> usize {
+        0
+    }
+
+    fn max_args(&self) -> Option<usize> {
+        Some(1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_format_recent() {
+        let now = Utc.with_ymd_and_hms(2026, 3, 10, 12, 0, 0).unwrap();
+        let mut credentials = Credentials::new();
+        for name in ["github", "email", "bank", "unused"] {
+            credentials
+                .add(name.to_string(), "secret".to_string())
+                .unwrap();
+        }
+        assert_eq!(format_recent(&credentials, 10, now), None);
+
+        credentials.record_access("bank", now - chrono::Duration::days(30));
+        credentials.record_access("github", now - chrono::Duration::days(1));
+        credentials.record_access("email", now);
+
+        assert_eq!(
+            format_recent(&credentials, 10, now).unwrap(),
+            "email (read today)\n\
+             github (read 1 day ago)\n\
+             bank (read 30 days ago)"
+        );
+        assert_eq!(
+            format_recent(&credentials, 1, now).unwrap(),
+            "email (read today)"
+        );
+    }
+
+    #[test]
+    fn test_parse_limit() {
+        assert_eq!(parse_limit(&[]), Ok(DEFAULT_LIMIT));
+        assert_eq!(parse_limit(&["3"]), Ok(3));
+        assert!(parse_limit(&["0"]).is_err());
+        assert!(parse_limit(&["many"]).is_err());
+    }
+}
@@ -0,0 +1,187 @@
+//! Reload command implementation.
+
+use crate::shell::command::{Command, CommandResult, ShellContext};
+
+/// Command to re-read the vault after it was changed outside this session.
+pub struct ReloadCommand;
+
+impl Command for ReloadCommand {
+    fn name(&self) -> &str {
+        "reload"
+    }
+
+    fn description(&self) -> &str {
+        "Re-read the vault from disk"
+    }
+
+    fn usage(&self) -> &str {
+        "reload"
+    }
+
+    fn help(&self) -> &str {
+        "Re-read the vault file and decrypt it with the current master\n\
+         password, replacing the credentials in memory. Use this when the\n\
+         file was changed by something else, such as a sync client.\n\n\
+         Changes are saved after every command, so normally nothing is lost.\n\
+         If a save failed, those changes are discarded with a warning.\n\
+         Changes made before the reload cannot be undone.\n\n\
+         Examples:\n  \
+           reload"
+    }
+
+    fn execute(&self, _args: &[&str], ctx: &mut ShellContext) -> CommandResult {
+        let Some(session) = ctx.session.as_deref_mut() else {
+            return CommandResult::error("Reloading is not available in this session");
+        };
+        let discarded = session.has_unsaved_changes();
+
+        match session.reload() {
+            Ok(credentials) => {
+                *ctx.credentials = credentials;
+                ctx.key_trie.clear();
+                for key in ctx.credentials.list() {
+                    ctx.key_trie.insert(key);
+                }
+                ctx.forget_changes();
+                if discarded {
+                    ctx.warn("Warning: changes that were not saved have been discarded");
+                }
+                log::info!("Reloaded vault from disk");
+                CommandResult::success(format!(
+                    "Reloaded {} credentials from disk",
+                    ctx.credentials.len()
+                ))
+            }
+            Err(e) => {
+                log::warn!("Failed to reload vault: {}", e);
+                CommandResult::error(format!("Could not reload: {}", e))
+            }
+        }
+    }
+
+    fn min_args(&self) -> usize {
+        0
+    }
+
+    fn max_args(&self) -> Option<usize> {
+        Some(0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::credentials::Credentials;
+    use crate::shell::session::Session;
+    use crate::trie::Trie;
+    use anyhow::{Result, anyhow};
+
+    /// Serves `on_disk`, or fails if it is `None`.
+    struct MockSession {
+        on_disk: Option<Credentials>,
+        unsaved: bool,
+    }
+
+    impl Session for MockSession {
+        fn save(&mut self, _credentials: &Credentials) -> Result<()> {
+            Ok(())
+        }
+
+        fn reload(&mut self) -> Result<Credentials> {
+            self.on_disk
+                .take()
+                .ok_or_else(|| anyhow!("vault file is damaged"))
+        }
+
+        fn has_unsaved_changes(&self) -> bool {
+            self.unsaved
+        }
+    }
+
+    fn session(on_disk: &[&str], unsaved: bool) -> MockSession {
+        let mut credentials = Credentials::new();
+        for name in on_disk {
+            credentials
+                .add(name.to_string(), "secret".to_string())
+                .unwrap();
+        }
+        MockSession {
+            on_disk: Some(credentials),
+            unsaved,
+        }
+    }
+
+    fn current_credentials() -> (Credentials, Trie) {
+        let mut credentials = Credentials::new();
+        credentials
+            .add("github".to_string(), "secret".to_string())
+            .unwrap();
+        let mut trie = Trie::new();
+        trie.insert("github");
+        (credentials, trie)
+    }
+
+    #[test]
+    fn test_reload_replaces_credentials_and_trie() {
+        let (mut credentials, mut trie) = current_credentials();
+        let mut session = session(&["github", "email"], false);
+        let mut ctx = ShellContext::new(&mut credentials, &mut trie).with_session(&mut session);
+
+        match ReloadCommand.execute(&[], &mut ctx) {
+            CommandResult::Success(Some(msg)) => {
+                assert_eq!(msg, "Reloaded 2 credentials from disk")
+            }
+            _ => panic!("Expected success"),
+        }
+        assert!(ctx.credentials.contains_key("email"));
+        assert!(ctx.key_trie.contains("email"));
+        assert!(ctx.warnings.is_empty());
+        // The credentials match the file, so there is nothing to save
+        assert!(!ctx.modified);
+    }
+
+    #[test]
+    fn test_reload_warns_about_unsaved_changes() {
+        let (mut credentials, mut trie) = current_credentials();
+        let mut session = session(&[], true);
+        let mut ctx = ShellContext::new(&mut credentials, &mut trie).with_session(&mut session);
+
+        assert!(matches!(
+            ReloadCommand.execute(&[], &mut ctx),
+            CommandResult::Success(Some(_))
+        ));
+        assert!(ctx.credentials.is_empty());
+        assert!(!ctx.key_trie.contains("github"));
+        assert_eq!(ctx.warnings.len(), 1);
+    }
+
+    #[test]
+    fn test_reload_failure_keeps_credentials() {
+        let (mut credentials, mut trie) = current_credentials();
+        let mut session = MockSession {
+            on_disk: None,
+            unsaved: false,
+        };
+        let mut ctx = ShellContext::new(&mut credentials, &mut trie).with_session(&mut session);
+
+        match ReloadCommand.execute(&[], &mut ctx) {
+            CommandResult::Error(msg) => {
+                assert_eq!(msg, "Could not reload: vault file is damaged")
+            }
+            _ => panic!("Expected error"),
+        }
+        assert!(ctx.credentials.contains_key("github"));
+        assert!(ctx.key_trie.contains("github"));
+    }
+
+    #[test]
+    fn test_reload_without_session() {
+        let (mut credentials, mut trie) = current_credentials();
+        let mut ctx = ShellContext::new(&mut credentials, &mut trie);
+
+        assert!(matches!(
+            ReloadCommand.execute(&[], &mut ctx),
+            CommandResult::Error(_)
+        ));
+    }
+}
@@ -1,10 +1,63 @@
 //! Remove command implementation.
 
-use crate::shell::command::{Command, CommandResult, ShellContext};
+use chrono::Utc;
+
+use crate::shell::command::{Command, CommandResult, ShellContext, not_found, take_flag};
+use crate::shell::undo::Change;
 
 /// Command to remove a credential.
 pub struct RemoveCommand;
 
+impl RemoveCommand {
+    /// Moves every credential matching `pattern` to the trash, after asking
+    /// for confirmation unless `force` is set.
+    fn remove_matching(&self, pattern: &str, force: bool, ctx: &mut ShellContext) -> CommandResult {
+        if pattern.is_empty() {
+            return CommandResult::error("The pattern cannot be empty");
+        }
+        let names: Vec<String> = ctx
+            .credentials
+            .matching(pattern)
+            .into_iter()
+            .cloned()
+            .collect();
+        if names.is_empty() {
+            return CommandResult::error(format!("No credentials match '{}'", pattern));
+        }
+
+        // Many credentials may match by mistake, so always ask first
+        let list = names
+            .iter()
+            .map(|name| format!("  {}", name))
+            .collect::<Vec<_>>()
+            .join("\n");
+        if !force {
+            let question = format!("{}\nDelete these {} credentials?", list, names.len());
+            match ctx.confirm(&question) {
+                Some(true) => {}
+                Some(false) => return CommandResult::success("Nothing removed"),
+                None => {
+                    return CommandResult::error(format!(
+                        "Cannot ask for confirmation here; use 'remove --force --all {}'",
+                        pattern
+                    ));
+                }
+            }
+        }
+
+        let now = Utc::now();
+        for name in &names {
+            ctx.credentials.move_to_trash(name, now);
+            ctx.key_trie.remove(name);
+        }
+        ctx.mark_modified();
+        log::info!("Moved {} credentials to the trash", names.len());
+        let message = format!("Moved {} credentials to the trash:\n{}", names.len(), list);
+        ctx.record_change(Change::Trashed(names));
+        CommandResult::success(message)
+    }
+}
+
 impl Command for RemoveCommand {
     fn name(&self) -> &str {
         "remove"
@@ -19,38 +72,72 @@ impl Command for RemoveCommand {
     }
 
     fn usage(&self) -> &str {
-        "remove <name>"
+        "remove [--force] <name> | remove [--force] --all <pattern>"
     }
 
     fn help(&self) -> &str {
-        "Remove a credential from the store.\n\n\
+        "Move a credential to the trash.\n\n\
+         It no longer appears in 'list' or completion, but can be brought\n\
+         back with 'restore <name>' until the trash is emptied with 'purge'.\n\
+         With 'confirm_destructive' set in the config file, asks before\n\
+         removing anything.\n\n\
+         With --all, every credential whose name matches <pattern> is removed\n\
+         after listing them and asking for confirmation, whatever the config\n\
+         file says. A pattern with '*' or '?' must match the whole name, e.g.\n\
+         'test-*'; any other pattern matches names containing it. Case is\n\
+         ignored.\n\n\
          Arguments:\n  \
            <name> - The name of the credential to remove\n\n\
+         Options:\n  \
+           --all <pattern> - Remove every credential matching <pattern>\n  \
+           --force, -f     - Remove without asking for confirmation\n\n\
          Examples:\n  \
            remove github\n  \
-           rm \"old email\""
+           rm -f \"old email\"\n  \
+           remove --all 'test-*'"
     }
 
     fn execute(&self, args: &[&str], ctx: &mut ShellContext) -> CommandResult {
-        if args.is_empty() {
-            return CommandResult::error(format!(
-                "Usage: {}\nMissing credential name",
-                self.usage()
-            ));
+        let (force, rest) = take_flag(args, "--force");
+        let (short_force, rest) = take_flag(&rest, "-f");
+        let force = force || short_force;
+        let (all, rest) = take_flag(&rest, "--all");
+        let [name] = rest[..] else {
+            return CommandResult::error(format!("Usage: {}", self.usage()));
+        };
+        if all {
+            return self.remove_matching(name, force, ctx);
         }
-
-        let name = args[0];
+        let name = ctx.normalize_name(name);
         log::debug!("Removing credential: {}", name);
 
-        if ctx.credentials.remove(name) {
+        if !ctx.credentials.contains_key(&name) {
+            log::debug!("Credential not found for removal: {}", name);
+            return not_found(&name, ctx.key_trie);
+        }
+
+        if ctx.confirm_destructive && !force {
+            match ctx.confirm(&format!("Delete '{}'?", name)) {
+                Some(true) => {}
+                Some(false) => return CommandResult::success("Nothing removed"),
+                None => {
+                    return CommandResult::error(format!(
+                        "Cannot ask for confirmation here; use 'remove --force {}'",
+                        name
+                    ));
+                }
+            }
+        }
+
+        if ctx.credentials.move_to_trash(&name, Utc::now()) {
             // Update the key trie
-            ctx.key_trie.remove(name);
+            ctx.key_trie.remove(&name);
             ctx.mark_modified();
-            log::info!("Removed credential: {}", name);
-            CommandResult::success(format!("Removed '{}'", name))
+            ctx.record_change(Change::Trashed(vec![name.clone()]));
+            log::info!("Moved credential to the trash: {}", name);
+            CommandResult::success(format!("Moved '{}' to the trash", name))
         } else {
-            log::debug!("Credential not found for removal: {}", name);
-            CommandResult::error(format!("'{}' not found", name))
+            not_found(&name, ctx.key_trie)
         }
     }
 
@@ -68,7 +155,7 @@ impl Command for RemoveCommand {
     }
 
     fn max_args(&self) -> Option<usize> {
-        Some(1)
+        Some(4)
     }
 }
 
@@ -76,6 +163,7 @@ impl Command for RemoveCommand {
 mod tests {
     use super::*;
     use crate::credentials::Credentials;
+    use crate::shell::command::check_arg_count;
     use crate::trie::Trie;
 
     #[test]
@@ -95,6 +183,7 @@ mod tests {
         assert!(ctx.modified);
         assert!(credentials.get("test_key").is_none());
         assert!(!trie.contains("test_key"));
+        assert_eq!(credentials.trashed()[0].0, "test_key");
     }
 
     #[test]
@@ -110,15 +199,198 @@ mod tests {
         assert!(!ctx.modified);
     }
 
+    /// Runs `remove` with confirmation enabled, answering `answer` and
+    /// returning the result and the questions asked.
+    fn run_confirmed(args: &[&str], answer: &str) -> (CommandResult, Vec<String>, bool) {
+        let mut credentials = Credentials::new();
+        credentials
+            .add("github".to_string(), "secret".to_string())
+            .unwrap();
+        let mut trie = Trie::new();
+        trie.insert("github");
+
+        let mut questions = Vec::new();
+        let mut prompt = |question: &str| {
+            questions.push(question.to_string());
+            Some(answer.to_string())
+        };
+        let mut ctx = ShellContext::new(&mut credentials, &mut trie)
+            .with_confirm_destructive(true)
+            .with_prompt(&mut prompt);
+        let result = RemoveCommand.execute(args, &mut ctx);
+
+        (result, questions, credentials.contains_key("github"))
+    }
+
+    #[test]
+    fn test_remove_confirmed() {
+        for answer in ["y", "Yes\n"] {
+            let (result, questions, kept) = run_confirmed(&["github"], answer);
+            assert!(matches!(result, CommandResult::Success(_)));
+            assert_eq!(questions, vec!["Delete 'github'? [y/N] "]);
+            assert!(!kept);
+        }
+    }
+
+    #[test]
+    fn test_remove_declined() {
+        for answer in ["n", "", "maybe"] {
+            let (result, questions, kept) = run_confirmed(&["github"], answer);
+            match result {
+                CommandResult::Success(Some(msg)) => assert_eq!(msg, "Nothing removed"),
+                _ => panic!("Expected success"),
+            }
+            assert_eq!(questions.len(), 1);
+            assert!(kept);
+        }
+    }
+
+    #[test]
+    fn test_remove_force_skips_confirmation() {
+        for args in [&["--force", "github"], &["github", "-f"]] {
+            let (result, questions, kept) = run_confirmed(args, "n");
+            assert!(matches!(result, CommandResult::Success(_)));
+            assert!(questions.is_empty());
+            assert!(!kept);
+        }
+    }
+
+    #[test]
+    fn test_remove_missing_key_does_not_ask() {
+        let (result, questions, kept) = run_confirmed(&["gitlab"], "y");
+        assert!(matches!(result, CommandResult::Error(_)));
+        assert!(questions.is_empty());
+        assert!(kept);
+    }
+
+    #[test]
+    fn test_remove_without_prompt_requires_force() {
+        let mut credentials = Credentials::new();
+        credentials
+            .add("github".to_string(), "secret".to_string())
+            .unwrap();
+        let mut trie = Trie::new();
+        let mut ctx = ShellContext::new(&mut credentials, &mut trie).with_confirm_destructive(true);
+
+        assert!(matches!(
+            RemoveCommand.execute(&["github"], &mut ctx),
+            CommandResult::Error(_)
+        ));
+        assert!(!ctx.modified);
+        assert!(matches!(
+            RemoveCommand.execute(&["-f", "github"], &mut ctx),
+            CommandResult::Success(_)
+        ));
+    }
+
+    fn setup_many() -> (Credentials, Trie) {
+        let mut credentials = Credentials::new();
+        let mut trie = Trie::new();
+        for name in ["test-1", "test-2", "Test-3", "github"] {
+            credentials
+                .add(name.to_string(), "secret".to_string())
+                .unwrap();
+            trie.insert(name);
+        }
+        (credentials, trie)
+    }
+
+    #[test]
+    fn test_remove_all_matching() {
+        let (mut credentials, mut trie) = setup_many();
+        let mut questions = Vec::new();
+        let mut prompt = |question: &str| {
+            questions.push(question.to_string());
+            Some("y".to_string())
+        };
+        let mut ctx = ShellContext::new(&mut credentials, &mut trie).with_prompt(&mut prompt);
+
+        match RemoveCommand.execute(&["--all", "test-*"], &mut ctx) {
+            CommandResult::Success(Some(msg)) => assert_eq!(
+                msg,
+                "Moved 3 credentials to the trash:\n  Test-3\n  test-1\n  test-2"
+            ),
+            _ => panic!("Expected success"),
+        }
+        assert!(ctx.modified);
+        assert_eq!(ctx.credentials.list(), vec!["github"]);
+        assert_eq!(ctx.credentials.trashed().len(), 3);
+        for name in ["test-1", "test-2", "Test-3"] {
+            assert!(!ctx.key_trie.contains(name));
+        }
+        assert!(ctx.key_trie.contains("github"));
+        assert_eq!(
+            questions,
+            vec!["  Test-3\n  test-1\n  test-2\nDelete these 3 credentials? [y/N] "]
+        );
+    }
+
+    #[test]
+    fn test_remove_all_declined_or_unconfirmed() {
+        let (mut credentials, mut trie) = setup_many();
+        let mut prompt = |_: &str| Some("n".to_string());
+        let mut ctx = ShellContext::new(&mut credentials, &mut trie).with_prompt(&mut prompt);
+        assert!(matches!(
+            RemoveCommand.execute(&["--all", "test"], &mut ctx),
+            CommandResult::Success(_)
+        ));
+        assert!(!ctx.modified);
+        assert_eq!(ctx.credentials.len(), 4);
+
+        // Without a way to ask, --force is required
+        let (mut credentials, mut trie) = setup_many();
+        let mut ctx = ShellContext::new(&mut credentials, &mut trie);
+        assert!(matches!(
+            RemoveCommand.execute(&["--all", "test"], &mut ctx),
+            CommandResult::Error(_)
+        ));
+        assert_eq!(ctx.credentials.len(), 4);
+        assert!(matches!(
+            RemoveCommand.execute(&["--all", "test", "-f"], &mut ctx),
+            CommandResult::Success(_)
+        ));
+        assert_eq!(ctx.credentials.len(), 1);
+    }
+
+    #[test]
+    fn test_remove_all_rejects_empty_or_unmatched_pattern() {
+        let (mut credentials, mut trie) = setup_many();
+        let mut ctx = ShellContext::new(&mut credentials, &mut trie);
+
+        for args in [&["--all", "", "-f"], &["--all", "bank", "-f"]] {
+            assert!(matches!(
+                RemoveCommand.execute(args, &mut ctx),
+                CommandResult::Error(_)
+            ));
+        }
+        assert_eq!(ctx.credentials.len(), 4);
+    }
+
     #[test]
     fn test_remove_command_missing_args() {
+        let result = check_arg_count(&RemoveCommand, &[]);
+        assert!(matches!(result, Err(CommandResult::Error(_))));
+    }
+
+    #[test]
+    fn test_remove_command_suggests_similar_key() {
         let mut credentials = Credentials::new();
+        credentials
+            .add("gitlab".to_string(), "secret".to_string())
+            .unwrap();
         let mut trie = Trie::new();
+        trie.insert("gitlab");
         let mut ctx = ShellContext::new(&mut credentials, &mut trie);
 
-        let cmd = RemoveCommand;
-        let result = cmd.execute(&[], &mut ctx);
+        match RemoveCommand.execute(&["gtilab"], &mut ctx) {
+            CommandResult::Error(msg) => assert!(msg.contains("Did you mean 'gitlab'?")),
+            _ => panic!("Expected error with suggestion"),
+        }
+        assert!(!ctx.modified);
 
-        assert!(matches!(result, CommandResult::Error(_)));
+        match RemoveCommand.execute(&["xyz"], &mut ctx) {
+            CommandResult::Error(msg) => assert_eq!(msg, "'xyz' not found"),
+            _ => panic!("Expected error without suggestion"),
+        }
     }
 }
@@ -0,0 +1,204 @@
+//! Rename command implementation.
+
+use crate::shell::command::{Command, CommandResult, ShellContext};
+use crate::shell::undo::Change;
+use crate::trie::Trie;
+
+/// Command to rename a credential.
+pub struct RenameCommand;
+
+impl RenameCommand {
+    /// Renames `old` to `new`, keeping the credentials and key trie consistent.
+    ///
+    /// Arguments are validated before anything changes. The credential map is
+    /// updated first; if `update_trie` then fails, the map change is rolled
+    /// back so the store and the completion index never disagree.
+    fn rename_with<F>(
+        &self,
+        old: &str,
+        new: &str,
+        ctx: &mut ShellContext,
+        update_trie: F,
+    ) -> CommandResult
+    where
+        F: FnOnce(&mut Trie, &str, &str) -> Result<(), String>,
+    {
+        if new.is_empty() {
+            return CommandResult::error("New name cannot be empty");
+        }
+        if old == new {
+            return CommandResult::error(format!("'{}' already has that name", old));
+        }
+        if !ctx.credentials.contains_key(old) {
+            return CommandResult::error(format!("'{}' not found", old));
+        }
+        if ctx.credentials.contains_key(new) {
+            return CommandResult::error(format!("'{}' already exists.", new));
+        }
+
+        if let Err(e) = ctx.credentials.rename(old, new) {
+            return CommandResult::error(e.to_string());
+        }
+
+        if let Err(e) = update_trie(ctx.key_trie, old, new) {
+            // Roll back so the map matches the untouched trie
+            if let Err(rollback) = ctx.credentials.rename(new, old) {
+                log::error!("Failed to roll back rename of '{}': {}", old, rollback);
+            }
+            log::warn!("Rename of '{}' to '{}' rolled back: {}", old, new, e);
+            return CommandResult::error(format!("Could not rename '{}': {}", old, e));
+        }
+
+        ctx.mark_modified();
+        ctx.record_change(Change::Renamed {
+            old: old.to_string(),
+            new: new.to_string(),
+        });
+        log::info!("Renamed credential: {} -> {}", old, new);
+        CommandResult::success(format!("Renamed '{}' to '{}'", old, new))
+    }
+}
+
+/// Moves `old` to `new` in the key trie, inserting first so a failure leaves it unchanged.
+fn update_key_trie(trie: &mut Trie, old: &str, new: &str) -> Result<(), String> {
+    trie.try_insert(new)?;
+    trie.remove(old);
+    Ok(())
+}
+
+impl Command for RenameCommand {
+    fn name(&self) -> &str {
+        "rename"
+    }
+
+    fn aliases(&self) -> &[&str] {
+        &["mv"]
+    }
+
+    fn description(&self) -> &str {
+        "Rename a credential"
+    }
+
+    fn usage(&self) -> &str {
+        "rename <old> <new>"
+    }
+
+    fn help(&self) -> &str {
+        "Rename a stored credential, keeping its secret.\n\n\
+         Arguments:\n  \
+           <old> - The current name of the credential\n  \
+           <new> - The new name, which must not already exist\n\n\
+         Examples:\n  \
+           rename github github-personal\n  \
+           mv \"old email\" \"new email\""
+    }
+
+    fn execute(&self, args: &[&str], ctx: &mut ShellContext) -> CommandResult {
+        let old = ctx.normalize_name(args[0]);
+        let new = ctx.normalize_name(args[1]);
+        log::debug!("Renaming credential: {} -> {}", old, new);
+        self.rename_with(&old, &new, ctx, update_key_trie)
+    }
+
+    fn completions(&self, arg_index: usize, partial: &str, ctx: &ShellContext) -> Vec<String> {
+        if arg_index == 0 {
+            // Complete existing credential names
+            ctx.key_trie.completions(partial)
+        } else {
+            vec![]
+        }
+    }
+
+    fn min_args(&self) -> usize {
+        2
+    }
+
+    fn max_args(&self) -> Option<usize> {
+        Some(2)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::credentials::Credentials;
+
+    fn setup() -> (Credentials, Trie) {
+        let mut credentials = Credentials::new();
+        credentials
+            .add("github".to_string(), "secret1".to_string())
+            .unwrap();
+        credentials
+            .add("email".to_string(), "secret2".to_string())
+            .unwrap();
+        let mut trie = Trie::new();
+        trie.insert("github");
+        trie.insert("email");
+        (credentials, trie)
+    }
+
+    #[test]
+    fn test_rename_command() {
+        let (mut credentials, mut trie) = setup();
+        let mut ctx = ShellContext::new(&mut credentials, &mut trie);
+
+        let result = RenameCommand.execute(&["github", "gh"], &mut ctx);
+
+        assert!(matches!(result, CommandResult::Success(Some(_))));
+        assert!(ctx.modified);
+        assert_eq!(ctx.credentials.get("gh"), Some(&"secret1".to_string()));
+        assert!(ctx.credentials.get("github").is_none());
+        assert!(ctx.key_trie.contains("gh"));
+        assert!(!ctx.key_trie.contains("github"));
+    }
+
+    #[test]
+    fn test_rename_validation_changes_nothing() {
+        let (mut credentials, mut trie) = setup();
+        let mut ctx = ShellContext::new(&mut credentials, &mut trie);
+
+        for args in [
+            ["missing", "new"],
+            ["github", "email"],
+            ["github", ""],
+            ["github", "github"],
+        ] {
+            let result = RenameCommand.execute(&args, &mut ctx);
+            assert!(matches!(result, CommandResult::Error(_)), "{:?}", args);
+        }
+
+        assert!(!ctx.modified);
+        assert_eq!(ctx.credentials.list().len(), 2);
+        assert!(ctx.key_trie.contains("github"));
+        assert!(ctx.key_trie.contains("email"));
+    }
+
+    #[test]
+    fn test_rename_rolls_back_on_trie_failure() {
+        let (mut credentials, mut trie) = setup();
+        let mut ctx = ShellContext::new(&mut credentials, &mut trie);
+
+        let result = RenameCommand.rename_with("github", "gh", &mut ctx, |_, _, _| {
+            Err("simulated trie failure".to_string())
+        });
+
+        match result {
+            CommandResult::Error(msg) => assert!(msg.contains("simulated trie failure")),
+            _ => panic!("Expected error"),
+        }
+        assert!(!ctx.modified);
+        assert_eq!(ctx.credentials.get("github"), Some(&"secret1".to_string()));
+        assert!(ctx.credentials.get("gh").is_none());
+        assert!(ctx.key_trie.contains("github"));
+        assert!(!ctx.key_trie.contains("gh"));
+    }
+
+    #[test]
+    fn test_update_key_trie_rejects_empty_name() {
+        let mut trie = Trie::new();
+        trie.insert("github");
+
+        assert!(update_key_trie(&mut trie, "github", "").is_err());
+        assert!(trie.contains("github"));
+    }
+}
@@ -0,0 +1,208 @@
+//! Replace command implementation.
+
+use crate::shell::command::{Command, CommandResult, ShellContext, expand_secret, not_found};
+use crate::shell::undo::Change;
+
+/// Command to replace a credential with a new name and secret.
+pub struct ReplaceCommand;
+
+impl ReplaceCommand {
+    /// Removes `old` and adds `new` with `secret` as one operation.
+    ///
+    /// If `new` cannot be added, `old` is restored with its original secret,
+    /// so a failed replace leaves the credentials and key trie unchanged.
+    fn replace(
+        &self,
+        old: &str,
+        new: &str,
+        secret: String,
+        ctx: &mut ShellContext,
+    ) -> CommandResult {
+        if new.is_empty() {
+            return CommandResult::error("New name cannot be empty");
+        }
+        let Some(previous) = ctx.credentials.get(old).cloned() else {
+            return not_found(old, ctx.key_trie);
+        };
+        ctx.credentials.remove(old);
+
+        if let Err(e) = ctx.credentials.add(new.to_string(), secret) {
+            // Roll back so the old entry is back as it was
+            if let Err(rollback) = ctx.credentials.add(old.to_string(), previous.clone()) {
+                log::error!("Failed to roll back replace of '{}': {}", old, rollback);
+            }
+            log::warn!("Replace of '{}' with '{}' rolled back: {}", old, new, e);
+            return CommandResult::error(format!("Could not replace '{}': {}", old, e));
+        }
+
+        ctx.key_trie.remove(old);
+        ctx.key_trie.insert(new);
+        ctx.mark_modified();
+        ctx.record_change(Change::Replaced {
+            old: old.to_string(),
+            secret: previous,
+            new: new.to_string(),
+        });
+        log::info!("Replaced credential: {} -> {}", old, new);
+        CommandResult::success(format!("Replaced '{}' with '{}'", old, new))
+    }
+}
+
+impl Command for ReplaceCommand {
+    fn name(&self) -> &str {
+        "replace"
+    }
+
+    fn description(&self) -> &str {
+        "Replace a credential with a new name and secret"
+    }
+
+    fn usage(&self) -> &str {
+        "replace <old> <new-name> <new-secret>"
+    }
+
+    fn help(&self) -> &str {
+        "Remove a credential and add a new one in its place, in one step.\n\n\
+         Unlike 'rename', the secret is replaced too. If the new name already\n\
+         belongs to another credential, nothing is changed.\n\n\
+         Arguments:\n  \
+           <old>        - The name of the credential to replace\n  \
+           <new-name>   - The name to store the new secret under\n  \
+           <new-secret> - The new secret value, or @PATH to read it from a file\n\n\
+         Examples:\n  \
+           replace github github-work n3wpassword\n  \
+           replace \"old email\" \"new email\" \"secret with spaces\""
+    }
+
+    fn execute(&self, args: &[&str], ctx: &mut ShellContext) -> CommandResult {
+        let old = ctx.normalize_name(args[0]);
+        let new = ctx.normalize_name(args[1]);
+        let secret = match expand_secret(&args[2..].join(" ")) {
+            Ok(secret) => secret,
+            Err(e) => return CommandResult::error(e),
+        };
+
+        log::debug!("Replacing credential: {} -> {}", old, new);
+        self.replace(&old, &new, secret, ctx)
+    }
+
+    fn completions(&self, arg_index: usize, partial: &str, ctx: &ShellContext) -> Vec<String> {
+        if arg_index == 0 {
+            // Complete existing credential names
+            ctx.key_trie.completions(partial)
+        } else {
+            vec![]
+        }
+    }
+
+    fn min_args(&self) -> usize {
+        3
+    }
+
+    fn max_args(&self) -> Option<usize> {
+        None // Allow spaces in secret
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::credentials::Credentials;
+    use crate::trie::Trie;
+
+    fn setup() -> (Credentials, Trie) {
+        let mut credentials = Credentials::new();
+        credentials
+            .add("github".to_string(), "secret1".to_string())
+            .unwrap();
+        credentials
+            .add("email".to_string(), "secret2".to_string())
+            .unwrap();
+        let mut trie = Trie::new();
+        trie.insert("github");
+        trie.insert("email");
+        (credentials, trie)
+    }
+
+    #[test]
+    fn test_replace_command() {
+        let (mut credentials, mut trie) = setup();
+        let mut ctx = ShellContext::new(&mut credentials, &mut trie);
+
+        let result = ReplaceCommand.execute(&["github", "gitlab", "new", "secret"], &mut ctx);
+
+        assert!(matches!(result, CommandResult::Success(Some(_))));
+        assert!(ctx.modified);
+        assert!(ctx.credentials.get("github").is_none());
+        assert_eq!(
+            ctx.credentials.get("gitlab"),
+            Some(&"new secret".to_string())
+        );
+        assert!(!ctx.key_trie.contains("github"));
+        assert!(ctx.key_trie.contains("gitlab"));
+    }
+
+    #[test]
+    fn test_replace_reads_secret_from_file() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("token");
+        std::fs::write(&path, "rotated\n").unwrap();
+        let from_file = format!("@{}", path.display());
+
+        let (mut credentials, mut trie) = setup();
+        let mut ctx = ShellContext::new(&mut credentials, &mut trie);
+
+        let result = ReplaceCommand.execute(&["github", "github", &from_file], &mut ctx);
+
+        assert!(matches!(result, CommandResult::Success(Some(_))));
+        assert_eq!(ctx.credentials.get("github"), Some(&"rotated".to_string()));
+    }
+
+    #[test]
+    fn test_replace_keeping_name() {
+        let (mut credentials, mut trie) = setup();
+        let mut ctx = ShellContext::new(&mut credentials, &mut trie);
+
+        let result = ReplaceCommand.execute(&["github", "github", "rotated"], &mut ctx);
+
+        assert!(matches!(result, CommandResult::Success(Some(_))));
+        assert_eq!(ctx.credentials.get("github"), Some(&"rotated".to_string()));
+        assert!(ctx.key_trie.contains("github"));
+    }
+
+    #[test]
+    fn test_replace_collision_rolls_back() {
+        let (mut credentials, mut trie) = setup();
+        let mut ctx = ShellContext::new(&mut credentials, &mut trie);
+
+        let result = ReplaceCommand.execute(&["github", "email", "new"], &mut ctx);
+
+        match result {
+            CommandResult::Error(msg) => assert!(msg.contains("already exists")),
+            _ => panic!("Expected error"),
+        }
+        assert!(!ctx.modified);
+        assert_eq!(ctx.credentials.get("github"), Some(&"secret1".to_string()));
+        assert_eq!(ctx.credentials.get("email"), Some(&"secret2".to_string()));
+        assert!(ctx.key_trie.contains("github"));
+        assert!(ctx.key_trie.contains("email"));
+    }
+
+    #[test]
+    fn test_replace_missing_old_key() {
+        let (mut credentials, mut trie) = setup();
+        let mut ctx = ShellContext::new(&mut credentials, &mut trie);
+
+        let result = ReplaceCommand.execute(&["githb", "gitlab", "new"], &mut ctx);
+
+        match result {
+            CommandResult::Error(msg) => {
+                assert_eq!(msg, "'githb' not found. Did you mean 'github'?")
+            }
+            _ => panic!("Expected error"),
+        }
+        assert!(!ctx.modified);
+        assert_eq!(ctx.credentials.list().len(), 2);
+        assert!(ctx.credentials.get("gitlab").is_none());
+    }
+}
@@ -0,0 +1,240 @@
+//! Restore command implementation.
+
+use crate::credentials::CredentialError;
+use crate::shell::command::{Command, CommandResult, ShellContext};
+
+/// Command to bring back a removed credential, or replace the vault's
+/// contents with a backup.
+pub struct RestoreCommand;
+
+impl RestoreCommand {
+    /// Moves `name` back from the trash, or returns `None` if it is not there.
+    fn restore_from_trash(&self, name: &str, ctx: &mut ShellContext) -> Option<CommandResult> {
+        match ctx.credentials.restore_from_trash(name) {
+            Ok(()) => {
+                ctx.key_trie.insert(name);
+                ctx.mark_modified();
+                log::info!("Restored credential from the trash: {}", name);
+                Some(CommandResult::success(format!(
+                    "Restored '{}' from the trash",
+                    name
+                )))
+            }
+            Err(CredentialError::NotFound(_)) => None,
+            Err(e) => Some(CommandResult::error(format!(
+                "Could not restore '{}': {}",
+                name, e
+            ))),
+        }
+    }
+
+    /// Restores `name` with an already-read password and refreshes the key trie.
+    fn restore(&self, name: &str, password: String, ctx: &mut ShellContext) -> CommandResult {
+        let Some(session) = ctx.session.as_deref_mut() else {
+            return CommandResult::error("Backups are not available in this session");
+        };
+
+        match session.restore_backup(name, password) {
+            Ok(credentials) => {
+                *ctx.credentials = credentials;
+                ctx.key_trie.clear();
+                for key in ctx.credentials.list() {
+                    ctx.key_trie.insert(key);
+                }
+                ctx.forget_changes();
+                log::info!("Restored backup: {}", name);
+                CommandResult::success(format!(
+                    "Restored {} credentials from '{}'",
+                    ctx.credentials.len(),
+                    name
+                ))
+            }
+            Err(e) => {
+                log::warn!("Failed to restore backup '{}': {}", name, e);
+                CommandResult::error(format!("Could not restore '{}': {}", name, e))
+            }
+        }
+    }
+}
+
+impl Command for RestoreCommand {
+    fn name(&self) -> &str {
+        "restore"
+    }
+
+    fn description(&self) -> &str {
+        "Restore a removed credential, or the vault from a backup"
+    }
+
+    fn usage(&self) -> &str {
+        "restore <name> | restore <backup>"
+    }
+
+    fn help(&self) -> &str {
+        "Bring back a credential from the trash, as listed by 'trash'.\n\n\
+         If no credential of that name is in the trash, the argument is taken\n\
+         as a backup instead, and every credential is replaced with those\n\
+         stored in it. This prompts for the master password the backup was\n\
+         saved with, and only restores it if it decrypts. The restored\n\
+         credentials are saved under the current master password, and the\n\
+         vault being replaced is backed up first.\n\n\
+         Arguments:\n  \
+           <name>   - The name of a credential in the trash\n  \
+           <backup> - The backup's file name, as shown by 'verify-backups'\n\n\
+         Examples:\n  \
+           restore github\n  \
+           restore passwords.db.bak\n  \
+           restore passwords.db.20260101_120000_000"
+    }
+
+    fn execute(&self, args: &[&str], ctx: &mut ShellContext) -> CommandResult {
+        let name = ctx.normalize_name(args[0]);
+        if let Some(result) = self.restore_from_trash(&name, ctx) {
+            return result;
+        }
+
+        if ctx.session.is_none() {
+            return CommandResult::error("Backups are not available in this session");
+        }
+
+        let password = match rpassword::prompt_password("Master Password for the backup: ") {
+            Ok(pwd) => pwd.trim().to_string(),
+            Err(_) => return CommandResult::error("Failed to read master password"),
+        };
+
+        self.restore(args[0], password, ctx)
+    }
+
+    fn min_args(&self) -> usize {
+        1
+    }
+
+    fn max_args(&self) -> Option<usize> {
+        Some(1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::credentials::Credentials;
+    use crate::shell::commands::RemoveCommand;
+    use crate::shell::session::Session;
+    use crate::trie::Trie;
+    use anyhow::{Result, anyhow};
+
+    struct MockSession;
+
+    impl Session for MockSession {
+        fn save(&mut self, _credentials: &Credentials) -> Result<()> {
+            Ok(())
+        }
+
+        fn restore_backup(&mut self, name: &str, password: String) -> Result<Credentials> {
+            if name != "passwords.db.bak" {
+                return Err(anyhow!("No backup named '{}'", name));
+            }
+            if password != "old-pass" {
+                return Err(anyhow!("wrong password"));
+            }
+            let mut credentials = Credentials::new();
+            credentials
+                .add("email".to_string(), "old".to_string())
+                .unwrap();
+            Ok(credentials)
+        }
+    }
+
+    fn current_credentials() -> (Credentials, Trie) {
+        let mut credentials = Credentials::new();
+        credentials
+            .add("github".to_string(), "new".to_string())
+            .unwrap();
+        let mut trie = Trie::new();
+        trie.insert("github");
+        (credentials, trie)
+    }
+
+    #[test]
+    fn test_restore_replaces_credentials_and_trie() {
+        let (mut credentials, mut trie) = current_credentials();
+        let mut session = MockSession;
+        let mut ctx = ShellContext::new(&mut credentials, &mut trie).with_session(&mut session);
+
+        let result = RestoreCommand.restore("passwords.db.bak", "old-pass".to_string(), &mut ctx);
+
+        match result {
+            CommandResult::Success(Some(msg)) => {
+                assert_eq!(msg, "Restored 1 credentials from 'passwords.db.bak'")
+            }
+            _ => panic!("Expected success"),
+        }
+        assert!(ctx.credentials.get("github").is_none());
+        assert_eq!(ctx.credentials.get("email"), Some(&"old".to_string()));
+        assert!(!ctx.key_trie.contains("github"));
+        assert!(ctx.key_trie.contains("email"));
+        // The session has already saved the restored credentials
+        assert!(!ctx.modified);
+    }
+
+    #[test]
+    fn test_restore_failure_keeps_credentials() {
+        let (mut credentials, mut trie) = current_credentials();
+        let mut session = MockSession;
+        let mut ctx = ShellContext::new(&mut credentials, &mut trie).with_session(&mut session);
+
+        for (name, password) in [("passwords.db.bak", "wrong"), ("missing", "old-pass")] {
+            let result = RestoreCommand.restore(name, password.to_string(), &mut ctx);
+            assert!(matches!(result, CommandResult::Error(_)));
+            assert!(ctx.credentials.get("github").is_some());
+            assert!(ctx.key_trie.contains("github"));
+        }
+    }
+
+    #[test]
+    fn test_remove_then_restore_from_trash() {
+        let (mut credentials, mut trie) = current_credentials();
+        let mut ctx = ShellContext::new(&mut credentials, &mut trie);
+
+        RemoveCommand.execute(&["github"], &mut ctx);
+        assert!(!ctx.credentials.contains_key("github"));
+        ctx.modified = false;
+
+        match RestoreCommand.execute(&["github"], &mut ctx) {
+            CommandResult::Success(Some(msg)) => {
+                assert_eq!(msg, "Restored 'github' from the trash")
+            }
+            _ => panic!("Expected success"),
+        }
+        assert!(ctx.modified);
+        assert_eq!(ctx.credentials.get("github"), Some(&"new".to_string()));
+        assert!(ctx.key_trie.contains("github"));
+        assert!(ctx.credentials.trashed().is_empty());
+    }
+
+    #[test]
+    fn test_restore_from_trash_over_new_credential() {
+        let (mut credentials, mut trie) = current_credentials();
+        credentials.move_to_trash("github", chrono::Utc::now());
+        credentials
+            .add("github".to_string(), "newer".to_string())
+            .unwrap();
+        let mut ctx = ShellContext::new(&mut credentials, &mut trie);
+
+        assert!(matches!(
+            RestoreCommand.execute(&["github"], &mut ctx),
+            CommandResult::Error(_)
+        ));
+        assert_eq!(ctx.credentials.get("github"), Some(&"newer".to_string()));
+        assert_eq!(ctx.credentials.trashed().len(), 1);
+    }
+
+    #[test]
+    fn test_restore_without_session() {
+        let (mut credentials, mut trie) = current_credentials();
+        let mut ctx = ShellContext::new(&mut credentials, &mut trie);
+
+        let result = RestoreCommand.execute(&["passwords.db.bak"], &mut ctx);
+        assert!(matches!(result, CommandResult::Error(_)));
+    }
+}
@@ -0,0 +1,75 @@
+//! Reveal command implementation.
+
+use super::get::show_secret;
+use crate::shell::command::{Command, CommandResult, ShellContext};
+
+/// Command to print a credential's secret in plain text.
+pub struct RevealCommand;
+
+impl Command for RevealCommand {
+    fn name(&self) -> &str {
+        "reveal"
+    }
+
+    fn description(&self) -> &str {
+        "Show a credential's secret in plain text"
+    }
+
+    fn usage(&self) -> &str {
+        "reveal <name>"
+    }
+
+    fn help(&self) -> &str {
+        "Print a stored secret in plain text. Same as 'get --show'.\n\n\
+         Arguments:\n  \
+           <name> - The name of the credential to reveal\n\n\
+         Examples:\n  \
+           reveal github"
+    }
+
+    fn execute(&self, args: &[&str], ctx: &mut ShellContext) -> CommandResult {
+        show_secret(args[0], true, ctx)
+    }
+
+    fn completions(&self, arg_index: usize, partial: &str, ctx: &ShellContext) -> Vec<String> {
+        if arg_index == 0 {
+            ctx.key_trie.completions(partial)
+        } else {
+            vec![]
+        }
+    }
+
+    fn min_args(&self) -> usize {
+        1
+    }
+
+    fn max_args(&self) -> Option<usize> {
+        Some(1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::credentials::Credentials;
+    use crate::trie::Trie;
+
+    #[test]
+    fn test_reveal_command() {
+        let mut credentials = Credentials::new();
+        credentials
+            .add("github".to_string(), "pässwörd".to_string())
+            .unwrap();
+        let mut trie = Trie::new();
+        let mut ctx = ShellContext::new(&mut credentials, &mut trie);
+
+        match RevealCommand.execute(&["github"], &mut ctx) {
+            CommandResult::Success(Some(msg)) => assert_eq!(msg, "pässwörd"),
+            _ => panic!("Expected success with secret"),
+        }
+        assert!(matches!(
+            RevealCommand.execute(&["missing"], &mut ctx),
+            CommandResult::Error(_)
+        ));
+    }
+}
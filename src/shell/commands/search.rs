@@ -0,0 +1,230 @@
+//! Search command implementation.
+
+use serde_json::json;
+
+use crate::shell::command::{Command, CommandResult, ShellContext, take_flag};
+
+/// Command to find credentials by name.
+pub struct SearchCommand;
+
+impl Command for SearchCommand {
+    fn name(&self) -> &str {
+        "search"
+    }
+
+    fn aliases(&self) -> &[&str] {
+        &["find"]
+    }
+
+    fn description(&self) -> &str {
+        "Find credentials whose name contains a query"
+    }
+
+    fn usage(&self) -> &str {
+        "search [--count-only] [--regex] [--json] <query>"
+    }
+
+    fn help(&self) -> &str {
+        "List credential names containing the query, ignoring case.\n\n\
+         The secrets are not shown, only the names.\n\n\
+         With --regex the query is a regular expression instead, matched\n\
+         anywhere in the name unless anchored with ^ or $. It is case\n\
+         sensitive unless it starts with (?i).\n\n\
+         Arguments:\n  \
+           <query> - Text to look for in credential names\n\n\
+         Options:\n  \
+           --count-only - Print only the number of matches\n  \
+           --regex      - Treat the query as a regular expression\n  \
+           --json       - Print the names, or the count, as JSON\n\n\
+         Examples:\n  \
+           search git\n  \
+           find mail\n  \
+           search --count-only work\n  \
+           search --regex '^aws-.*-prod$'"
+    }
+
+    fn execute(&self, args: &[&str], ctx: &mut ShellContext) -> CommandResult {
+        let (count_only, rest) = take_flag(args, "--count-only");
+        let (regex, rest) = take_flag(&rest, "--regex");
+        let (as_json, rest) = take_flag(&rest, "--json");
+        let as_json = ctx.wants_json(as_json);
+        let query = match rest.as_slice() {
+            [query] => *query,
+            [] => {
+                return CommandResult::error(format!("Usage: {}\nMissing query", self.usage()));
+            }
+            _ => {
+                return CommandResult::error(format!(
+                    "Usage: {}\nToo many arguments",
+                    self.usage()
+                ));
+            }
+        };
+
+        log::debug!("Searching credentials for: {}", query);
+        let matches = if regex {
+            match ctx.credentials.search_regex(query) {
+                Ok(matches) => matches,
+                Err(e) => {
+                    return CommandResult::error(format!("Invalid regular expression: {}", e));
+                }
+            }
+        } else {
+            ctx.credentials.search(query)
+        };
+
+        if count_only {
+            return if as_json {
+                CommandResult::json(json!(matches.len()))
+            } else {
+                CommandResult::success(matches.len().to_string())
+            };
+        }
+
+        if as_json {
+            log::info!("Search matched {} credentials", matches.len());
+            return CommandResult::json(json!(matches));
+        }
+
+        if matches.is_empty() {
+            return CommandResult::success(format!("No credentials match '{}'.", query));
+        }
+
+        let output = matches
+            .iter()
+            .map(|s| s.as_str())
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        log::info!("Search matched {} credentials", matches.len());
+        CommandResult::success(output)
+    }
+
+    fn min_args(&self) -> usize {
+        1
+    }
+
+    fn max_args(&self) -> Option<usize> {
+        Some(4)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::credentials::Credentials;
+    use crate::trie::Trie;
+
+    fn setup() -> Credentials {
+        let mut credentials = Credentials::new();
+        for name in [
+            "github",
+            "GitLab",
+            "email",
+            "work-git",
+            "aws-eu-prod",
+            "aws-us-prod",
+            "aws-us-staging",
+            "old-aws-eu-prod",
+        ] {
+            credentials
+                .add(name.to_string(), "secret".to_string())
+                .unwrap();
+        }
+        credentials
+    }
+
+    #[test]
+    fn test_search_command_matches() {
+        let mut credentials = setup();
+        let mut trie = Trie::new();
+        let mut ctx = ShellContext::new(&mut credentials, &mut trie);
+
+        match SearchCommand.execute(&["git"], &mut ctx) {
+            CommandResult::Success(Some(msg)) => {
+                let lines: Vec<&str> = msg.lines().collect();
+                assert_eq!(lines, vec!["GitLab", "github", "work-git"]);
+            }
+            _ => panic!("Expected matches"),
+        }
+    }
+
+    #[test]
+    fn test_search_command_count_only_matches_listing() {
+        let mut credentials = setup();
+        let mut trie = Trie::new();
+        let mut ctx = ShellContext::new(&mut credentials, &mut trie);
+
+        for query in ["git", "mail", "nothing"] {
+            let listed = match SearchCommand.execute(&[query], &mut ctx) {
+                CommandResult::Success(Some(msg)) if msg.starts_with("No credentials") => 0,
+                CommandResult::Success(Some(msg)) => msg.lines().count(),
+                _ => panic!("Expected matches"),
+            };
+            match SearchCommand.execute(&["--count-only", query], &mut ctx) {
+                CommandResult::Success(Some(msg)) => assert_eq!(msg, listed.to_string()),
+                _ => panic!("Expected count"),
+            }
+        }
+    }
+
+    #[test]
+    fn test_search_command_missing_query() {
+        let mut credentials = setup();
+        let mut trie = Trie::new();
+        let mut ctx = ShellContext::new(&mut credentials, &mut trie);
+
+        let result = SearchCommand.execute(&["--count-only"], &mut ctx);
+        assert!(matches!(result, CommandResult::Error(_)));
+    }
+
+    #[test]
+    fn test_search_command_regex_anchored() {
+        let mut credentials = setup();
+        let mut trie = Trie::new();
+        let mut ctx = ShellContext::new(&mut credentials, &mut trie);
+
+        match SearchCommand.execute(&["--regex", "^aws-.*-prod$"], &mut ctx) {
+            CommandResult::Success(Some(msg)) => {
+                let lines: Vec<&str> = msg.lines().collect();
+                assert_eq!(lines, vec!["aws-eu-prod", "aws-us-prod"]);
+            }
+            _ => panic!("Expected matches"),
+        }
+        match SearchCommand.execute(&["--count-only", "--regex", "git$"], &mut ctx) {
+            CommandResult::Success(Some(msg)) => assert_eq!(msg, "1"),
+            _ => panic!("Expected count"),
+        }
+    }
+
+    #[test]
+    fn test_search_command_invalid_regex() {
+        let mut credentials = setup();
+        let mut trie = Trie::new();
+        let mut ctx = ShellContext::new(&mut credentials, &mut trie);
+
+        match SearchCommand.execute(&["--regex", "aws-(prod"], &mut ctx) {
+            CommandResult::Error(msg) => assert!(msg.starts_with("Invalid regular expression")),
+            _ => panic!("Expected error"),
+        }
+    }
+
+    #[test]
+    fn test_search_command_json() {
+        let mut credentials = setup();
+        let mut trie = Trie::new();
+        let mut ctx = ShellContext::new(&mut credentials, &mut trie);
+
+        match SearchCommand.execute(&["--json", "--regex", "^aws-us"], &mut ctx) {
+            CommandResult::Json(value) => {
+                let parsed: Vec<String> = serde_json::from_str(&value.to_string()).unwrap();
+                assert_eq!(parsed, vec!["aws-us-prod", "aws-us-staging"]);
+            }
+            _ => panic!("Expected JSON"),
+        }
+        match SearchCommand.execute(&["--json", "nothing"], &mut ctx) {
+            CommandResult::Json(value) => assert_eq!(value, json!([])),
+            _ => panic!("Expected JSON"),
+        }
+    }
+}
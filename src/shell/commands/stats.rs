@@ -0,0 +1,251 @@
+//! Stats command implementation.
+
+use chrono::{DateTime, Utc};
+
+use crate::shell::command::{Command, CommandResult, ShellContext, take_flag};
+
+/// Command to summarize the credential store.
+pub struct StatsCommand;
+
+impl Command for StatsCommand {
+    fn name(&self) -> &str {
+        "stats"
+    }
+
+    fn description(&self) -> &str {
+        "Show a summary of the stored credentials"
+    }
+
+    fn usage(&self) -> &str {
+        "stats [--json]"
+    }
+
+    fn help(&self) -> &str {
+        "Display statistics about the credential store.\n\n\
+         Shows the number of credentials, average and median secret length,\n\
+         how many secrets are weak or reused, how many tags are in use, when\n\
+         the oldest and newest credentials were added, and the dates of the\n\
+         oldest and newest secret changes. Secrets are never shown.\n\n\
+         Options:\n  \
+           --json - Print the statistics as a JSON object\n\n\
+         Examples:\n  \
+           stats\n  \
+           stats --json"
+    }
+
+    fn execute(&self, args: &[&str], ctx: &mut ShellContext) -> CommandResult {
+        let (as_json, rest) = take_flag(args, "--json");
+        if let Some(arg) = rest.first() {
+            return CommandResult::error(format!(
+                "Usage: {}\nUnexpected argument: '{}'",
+                self.usage(),
+                arg
+            ));
+        }
+
+        log::debug!("Computing credential statistics");
+
+        let stats = ctx.credentials.stats();
+        if ctx.wants_json(as_json) {
+            return match serde_json::to_value(&stats) {
+                Ok(value) => CommandResult::json(value),
+                Err(e) => CommandResult::error(format!("Could not format statistics: {}", e)),
+            };
+        }
+
+        let date = |time: Option<DateTime<Utc>>| {
+            time.map_or_else(
+                || "-".to_string(),
+                |time| time.format("%Y-%m-%d").to_string(),
+            )
+        };
+        let output = format!(
+            "Credentials:     {}\n\
+             Average length:  {:.1}\n\
+             Median length:   {:.1}\n\
+             Weak secrets:    {}\n\
+             Reused secrets:  {}\n\
+             Tags:            {}\n\
+             Oldest entry:    {}\n\
+             Newest entry:    {}\n\
+             Oldest update:   {}\n\
+             Newest update:   {}",
+            stats.total,
+            stats.average_secret_len,
+            stats.median_secret_len,
+            stats.weak_count,
+            stats.duplicate_count,
+            stats.tag_count,
+            date(stats.oldest_created_at),
+            date(stats.newest_created_at),
+            date(stats.oldest_updated_at),
+            date(stats.newest_updated_at)
+        );
+
+        CommandResult::success(output)
+    }
+
+    fn min_args(&self) -> usize {
+        0
+    }
+
+    fn max_args(&self) -> Option<usize> {
+        Some(1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::credentials::Credentials;
+    use crate::trie::Trie;
+
+    #[test]
+    fn test_stats_command_empty() {
+        let mut credentials = Credentials::new();
+        let mut trie = Trie::new();
+        let mut ctx = ShellContext::new(&mut credentials, &mut trie);
+
+        let cmd = StatsCommand;
+        let result = cmd.execute(&[], &mut ctx);
+
+        match result {
+            CommandResult::Success(Some(msg)) => {
+                assert!(msg.contains("Credentials:     0"));
+                assert!(msg.contains("Tags:            0"));
+                assert!(msg.contains("Oldest entry:    -"));
+                assert!(msg.contains("Oldest update:   -"));
+            }
+            _ => panic!("Expected success with stats"),
+        }
+        assert!(!ctx.modified);
+    }
+
+    #[test]
+    fn test_stats_command_does_not_reveal_secrets() {
+        let mut credentials = Credentials::new();
+        credentials
+            .add("github".to_string(), "shared-secret".to_string())
+            .unwrap();
+        credentials
+            .add("gitlab".to_string(), "shared-secret".to_string())
+            .unwrap();
+        credentials
+            .add("email".to_string(), "123456".to_string())
+            .unwrap();
+        let mut trie = Trie::new();
+        let mut ctx = ShellContext::new(&mut credentials, &mut trie);
+
+        let cmd = StatsCommand;
+        let result = cmd.execute(&[], &mut ctx);
+
+        match result {
+            CommandResult::Success(Some(msg)) => {
+                assert!(msg.contains("Credentials:     3"));
+                assert!(msg.contains("Reused secrets:  2"));
+                assert!(msg.contains("Weak secrets:    1"));
+                assert!(!msg.contains("shared-secret"));
+                assert!(!msg.contains("123456"));
+            }
+            _ => panic!("Expected success with stats"),
+        }
+        assert!(!ctx.modified);
+    }
+
+    #[test]
+    fn test_stats_command_json() {
+        let mut credentials = Credentials::new();
+        for (name, secret) in [
+            ("github", "shared"),
+            ("gitlab", "shared"),
+            ("email", "abcd"),
+        ] {
+            credentials
+                .add(name.to_string(), secret.to_string())
+                .unwrap();
+        }
+        let mut trie = Trie::new();
+        let mut ctx = ShellContext::new(&mut credentials, &mut trie);
+
+        let value = match StatsCommand.execute(&["--json"], &mut ctx) {
+            CommandResult::Json(value) => value,
+            _ => panic!("Expected JSON"),
+        };
+        let parsed: serde_json::Value = serde_json::from_str(&value.to_string()).unwrap();
+        assert_eq!(parsed["total"], 3);
+        assert_eq!(parsed["duplicate_count"], 2);
+        assert_eq!(parsed["median_secret_len"], 6.0);
+        assert!(parsed["average_secret_len"].is_f64());
+        assert!(parsed["weak_count"].is_u64());
+        assert_eq!(parsed["tag_count"], 0);
+        assert!(parsed["oldest_created_at"].is_string());
+        assert!(parsed["newest_created_at"].is_string());
+        assert!(parsed["oldest_updated_at"].is_string());
+        assert!(parsed["newest_updated_at"].is_string());
+        assert!(!value.to_string().contains("shared"));
+    }
+
+    #[test]
+    fn test_stats_command_shows_update_range() {
+        let mut credentials = Credentials::new();
+        for name in ["github", "email"] {
+            credentials
+                .add(name.to_string(), "secret".to_string())
+                .unwrap();
+        }
+        credentials.touch("github", "2024-03-01T12:00:00Z".parse().unwrap());
+        credentials.touch("email", "2026-01-02T08:00:00Z".parse().unwrap());
+        let mut trie = Trie::new();
+        let mut ctx = ShellContext::new(&mut credentials, &mut trie);
+
+        match StatsCommand.execute(&[], &mut ctx) {
+            CommandResult::Success(Some(msg)) => {
+                assert!(msg.contains("Oldest update:   2024-03-01"));
+                assert!(msg.contains("Newest update:   2026-01-02"));
+            }
+            _ => panic!("Expected success with stats"),
+        }
+    }
+
+    #[test]
+    fn test_stats_command_shows_entry_range_and_tags() {
+        let mut credentials = Credentials::from_json(
+            br#"{
+                "secrets": {"github": "secret", "email": "secret"},
+                "metadata": {
+                    "github": {"created_at": "2023-05-01T09:00:00Z", "tags": ["work"]},
+                    "email": {"created_at": "2025-11-20T18:00:00Z", "tags": ["home", "work"]}
+                }
+            }"#,
+        )
+        .unwrap();
+        let mut trie = Trie::new();
+        let mut ctx = ShellContext::new(&mut credentials, &mut trie);
+
+        match StatsCommand.execute(&[], &mut ctx) {
+            CommandResult::Success(Some(msg)) => {
+                assert!(msg.contains("Tags:            2"));
+                assert!(msg.contains("Oldest entry:    2023-05-01"));
+                assert!(msg.contains("Newest entry:    2025-11-20"));
+            }
+            _ => panic!("Expected success with stats"),
+        }
+    }
+
+    #[test]
+    fn test_stats_command_json_output_format() {
+        let mut credentials = Credentials::new();
+        let mut trie = Trie::new();
+        let mut ctx = ShellContext::new(&mut credentials, &mut trie)
+            .with_output_format(crate::shell::command::OutputFormat::Json);
+
+        assert!(matches!(
+            StatsCommand.execute(&[], &mut ctx),
+            CommandResult::Json(_)
+        ));
+        assert!(matches!(
+            StatsCommand.execute(&["--verbose"], &mut ctx),
+            CommandResult::Error(_)
+        ));
+    }
+}
@@ -0,0 +1,312 @@
+//! Tag command implementation.
+
+use crate::credentials::CredentialError;
+use crate::shell::command::{Command, CommandResult, ShellContext, not_found};
+use crate::shell::undo::Change;
+
+/// Command to tag credentials and manage the tags in use.
+pub struct TagCommand;
+
+/// Subcommands of `tag`, in the order they are completed.
+const SUBCOMMANDS: [&str; 4] = ["add", "list", "remove", "rename"];
+
+impl TagCommand {
+    /// Lists every tag in use with the number of credentials that have it.
+    fn list(&self, ctx: &ShellContext) -> CommandResult {
+        let counts = ctx.credentials.tag_counts();
+        if counts.is_empty() {
+            return CommandResult::success("No credentials are tagged.");
+        }
+        CommandResult::success(
+            counts
+                .iter()
+                .map(|(tag, count)| format!("{} ({})", tag, count))
+                .collect::<Vec<_>>()
+                .join("\n"),
+        )
+    }
+
+    /// Adds or removes `tag` on the credential `name`.
+    fn update(&self, name: &str, tag: &str, add: bool, ctx: &mut ShellContext) -> CommandResult {
+        let name = ctx.normalize_name(name);
+        let changed = if add {
+            ctx.credentials.add_tag(&name, tag)
+        } else {
+            ctx.credentials.remove_tag(&name, tag)
+        };
+        match (changed, add) {
+            (Ok(true), true) => {
+                ctx.mark_modified();
+                ctx.record_change(Change::Tagged {
+                    name: name.clone(),
+                    tag: tag.to_string(),
+                });
+                log::info!("Tagged credential: {}", name);
+                CommandResult::success(format!("Tagged '{}' with '{}'", name, tag))
+            }
+            (Ok(true), false) => {
+                ctx.mark_modified();
+                ctx.record_change(Change::Untagged {
+                    name: name.clone(),
+                    tag: tag.to_string(),
+                });
+                log::info!("Untagged credential: {}", name);
+                CommandResult::success(format!("Removed tag '{}' from '{}'", tag, name))
+            }
+            (Ok(false), true) => {
+                CommandResult::success(format!("'{}' is already tagged '{}'", name, tag))
+            }
+            (Ok(false), false) => {
+                CommandResult::error(format!("'{}' is not tagged '{}'", name, tag))
+            }
+            (Err(CredentialError::NotFound(_)), _) => not_found(&name, ctx.key_trie),
+            (Err(e), _) => CommandResult::error(e.to_string()),
+        }
+    }
+
+    /// Renames the tag `old` to `new` on every credential.
+    fn rename(&self, old: &str, new: &str, ctx: &mut ShellContext) -> CommandResult {
+        if old == new {
+            return CommandResult::error(format!("The tag is already called '{}'", new));
+        }
+        // Note which credentials had the old tag, so undo can put it back
+        let mut renamed = Vec::new();
+        let mut merged = Vec::new();
+        for entry in ctx.credentials.entries() {
+            if !entry.tags.iter().any(|t| t == old) {
+                continue;
+            }
+            if entry.tags.iter().any(|t| t == new) {
+                merged.push(entry.name.to_string());
+            } else {
+                renamed.push(entry.name.to_string());
+            }
+        }
+
+        match ctx.credentials.rename_tag(old, new) {
+            Ok(0) => CommandResult::error(format!("No credential is tagged '{}'", old)),
+            Ok(count) => {
+                ctx.mark_modified();
+                ctx.record_change(Change::TagRenamed {
+                    old: old.to_string(),
+                    new: new.to_string(),
+                    renamed,
+                    merged,
+                });
+                log::info!("Renamed a tag on {} credentials", count);
+                let noun = if count == 1 {
+                    "credential"
+                } else {
+                    "credentials"
+                };
+                CommandResult::success(format!(
+                    "Renamed tag '{}' to '{}' on {} {}",
+                    old, new, count, noun
+                ))
+            }
+            Err(e) => CommandResult::error(e.to_string()),
+        }
+    }
+}
+
+impl Command for TagCommand {
+    fn name(&self) -> &str {
+        "tag"
+    }
+
+    fn description(&self) -> &str {
+        "Tag credentials and rename tags"
+    }
+
+    fn usage(&self) -> &str {
+        "tag add|remove <name> <tag> | tag rename <old> <new> | tag [list]"
+    }
+
+    fn help(&self) -> &str {
+        "Attach tags to credentials, e.g. to group them by project.\n\n\
+         A tag is a single word. Tags are shown by 'get --json', and 'tag list'\n\
+         (or just 'tag') shows every tag with the number of credentials that\n\
+         have it. 'tag rename' renames a tag on every credential at once; a\n\
+         credential that already has the new tag keeps a single copy of it.\n\n\
+         Subcommands:\n  \
+           add <name> <tag>    - Tag a credential\n  \
+           remove <name> <tag> - Remove a tag from a credential\n  \
+           rename <old> <new>  - Rename a tag on every credential\n  \
+           list                - List the tags in use\n\n\
+         Examples:\n  \
+           tag add github work\n  \
+           tag rename work job\n  \
+           tag"
+    }
+
+    fn execute(&self, args: &[&str], ctx: &mut ShellContext) -> CommandResult {
+        match args {
+            [] | ["list"] => self.list(ctx),
+            ["add", name, tag] => self.update(name, tag, true, ctx),
+            ["remove", name, tag] => self.update(name, tag, false, ctx),
+            ["rename", old, new] => self.rename(old, new, ctx),
+            _ => CommandResult::error(format!("Usage: {}", self.usage())),
+        }
+    }
+
+    fn completions(&self, arg_index: usize, partial: &str, ctx: &ShellContext) -> Vec<String> {
+        match arg_index {
+            0 => SUBCOMMANDS
+                .iter()
+                .filter(|sub| sub.starts_with(partial))
+                .map(|sub| sub.to_string())
+                .collect(),
+            1 => ctx.key_trie.completions(partial),
+            _ => vec![],
+        }
+    }
+
+    fn min_args(&self) -> usize {
+        0
+    }
+
+    fn max_args(&self) -> Option<usize> {
+        Some(3)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::credentials::Credentials;
+    use crate::shell::commands::{AddCommand, UndoCommand};
+    use crate::shell::undo::UndoStack;
+    use crate::trie::Trie;
+
+    /// Runs the command, returning its message or panicking if it failed.
+    fn run(args: &[&str], ctx: &mut ShellContext) -> String {
+        match TagCommand.execute(args, ctx) {
+            CommandResult::Success(Some(msg)) => msg,
+            _ => panic!("Expected {:?} to succeed", args),
+        }
+    }
+
+    fn tagged_credentials() -> Credentials {
+        let mut credentials = Credentials::new();
+        for name in ["github", "gitlab", "bank"] {
+            credentials
+                .add(name.to_string(), "secret".to_string())
+                .unwrap();
+        }
+        credentials
+    }
+
+    #[test]
+    fn test_tag_add_list_and_remove() {
+        let mut credentials = tagged_credentials();
+        let mut trie = Trie::new();
+        let mut ctx = ShellContext::new(&mut credentials, &mut trie);
+
+        assert_eq!(run(&[], &mut ctx), "No credentials are tagged.");
+        assert_eq!(
+            run(&["add", "github", "work"], &mut ctx),
+            "Tagged 'github' with 'work'"
+        );
+        assert!(ctx.modified);
+        TagCommand.execute(&["add", "gitlab", "work"], &mut ctx);
+        TagCommand.execute(&["add", "bank", "home"], &mut ctx);
+        assert_eq!(
+            run(&["add", "github", "work"], &mut ctx),
+            "'github' is already tagged 'work'"
+        );
+        assert_eq!(run(&["list"], &mut ctx), "home (1)\nwork (2)");
+
+        assert_eq!(
+            run(&["remove", "bank", "home"], &mut ctx),
+            "Removed tag 'home' from 'bank'"
+        );
+        assert!(matches!(
+            TagCommand.execute(&["remove", "bank", "home"], &mut ctx),
+            CommandResult::Error(_)
+        ));
+        assert_eq!(
+            ctx.credentials.metadata("bank").unwrap().tags,
+            Vec::<String>::new()
+        );
+    }
+
+    #[test]
+    fn test_tag_rename() {
+        let mut credentials = tagged_credentials();
+        credentials.add_tag("github", "work").unwrap();
+        credentials.add_tag("gitlab", "work").unwrap();
+        credentials.add_tag("gitlab", "job").unwrap();
+        let mut trie = Trie::new();
+        let mut ctx = ShellContext::new(&mut credentials, &mut trie);
+
+        assert_eq!(
+            run(&["rename", "work", "job"], &mut ctx),
+            "Renamed tag 'work' to 'job' on 2 credentials"
+        );
+        assert!(ctx.modified);
+        assert_eq!(ctx.credentials.metadata("gitlab").unwrap().tags, ["job"]);
+        assert_eq!(run(&["list"], &mut ctx), "job (2)");
+
+        assert!(matches!(
+            TagCommand.execute(&["rename", "work", "job"], &mut ctx),
+            CommandResult::Error(_)
+        ));
+    }
+
+    #[test]
+    fn test_tag_changes_can_be_undone() {
+        let mut credentials = tagged_credentials();
+        credentials.add_tag("gitlab", "work").unwrap();
+        credentials.add_tag("gitlab", "job").unwrap();
+        let mut trie = Trie::new();
+        let mut undo = UndoStack::default();
+        let mut ctx = ShellContext::new(&mut credentials, &mut trie).with_undo(&mut undo);
+
+        AddCommand.execute(&["foo", "x"], &mut ctx);
+        run(&["add", "foo", "work"], &mut ctx);
+        run(&["remove", "gitlab", "job"], &mut ctx);
+        run(&["rename", "work", "job"], &mut ctx);
+
+        UndoCommand.execute(&[], &mut ctx);
+        assert_eq!(ctx.credentials.metadata("foo").unwrap().tags, ["work"]);
+        UndoCommand.execute(&[], &mut ctx);
+        assert_eq!(
+            ctx.credentials.metadata("gitlab").unwrap().tags,
+            ["job", "work"]
+        );
+        // The tag is undone before the credential it was added to
+        UndoCommand.execute(&[], &mut ctx);
+        assert!(ctx.credentials.contains_key("foo"));
+        assert_eq!(
+            ctx.credentials.metadata("foo").unwrap().tags,
+            Vec::<String>::new()
+        );
+        UndoCommand.execute(&[], &mut ctx);
+        assert!(!ctx.credentials.contains_key("foo"));
+    }
+
+    #[test]
+    fn test_tag_rejects_bad_input() {
+        let mut credentials = tagged_credentials();
+        credentials.add_tag("github", "work").unwrap();
+        let mut trie = Trie::new();
+        let mut ctx = ShellContext::new(&mut credentials, &mut trie);
+
+        for args in [
+            &["add", "missing", "work"][..],
+            &["add", "github", "two words"],
+            &["add", "github", ""],
+            &["rename", "work", ""],
+            &["rename", "work", "work"],
+            &["add", "github"],
+            &["bogus"],
+        ] {
+            assert!(
+                matches!(TagCommand.execute(args, &mut ctx), CommandResult::Error(_)),
+                "{:?} should fail",
+                args
+            );
+        }
+        assert!(!ctx.modified);
+    }
+}
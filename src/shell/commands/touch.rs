@@ -0,0 +1,103 @@
+//! Touch command implementation.
+
+use chrono::Utc;
+
+use crate::shell::command::{Command, CommandResult, ShellContext, not_found};
+
+/// Command to mark a credential's secret as just rotated.
+pub struct TouchCommand;
+
+impl Command for TouchCommand {
+    fn name(&self) -> &str {
+        "touch"
+    }
+
+    fn description(&self) -> &str {
+        "Mark a credential as just rotated"
+    }
+
+    fn usage(&self) -> &str {
+        "touch <name>"
+    }
+
+    fn help(&self) -> &str {
+        "Record that a credential's secret was rotated now, without changing it.\n\n\
+         Use this after changing a password on the service itself when the\n\
+         stored secret stays the same, e.g. a key that was re-issued. The\n\
+         credential is then no longer listed by 'expired' until its rotation\n\
+         interval passes again.\n\n\
+         Arguments:\n  \
+           <name> - The name of the credential\n\n\
+         Examples:\n  \
+           touch github"
+    }
+
+    fn execute(&self, args: &[&str], ctx: &mut ShellContext) -> CommandResult {
+        let name = ctx.normalize_name(args[0]);
+
+        if ctx.credentials.touch(&name, Utc::now()) {
+            ctx.mark_modified();
+            log::info!("Marked credential as rotated: {}", name);
+            CommandResult::success(format!("Marked '{}' as rotated", name))
+        } else {
+            not_found(&name, ctx.key_trie)
+        }
+    }
+
+    fn completions(&self, arg_index: usize, partial: &str, ctx: &ShellContext) -> Vec<String> {
+        if arg_index == 0 {
+            ctx.key_trie.completions(partial)
+        } else {
+            vec![]
+        }
+    }
+
+    fn min_args(&self) -> usize {
+        1
+    }
+
+    fn max_args(&self) -> Option<usize> {
+        Some(1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::credentials::Credentials;
+    use crate::trie::Trie;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_touch_updates_timestamp() {
+        let mut credentials = Credentials::new();
+        credentials
+            .add("github".to_string(), "secret".to_string())
+            .unwrap();
+        let long_ago = Utc.with_ymd_and_hms(2020, 1, 1, 0, 0, 0).unwrap();
+        credentials.touch("github", long_ago);
+        let mut trie = Trie::new();
+        trie.insert("github");
+        let mut ctx = ShellContext::new(&mut credentials, &mut trie);
+
+        let result = TouchCommand.execute(&["github"], &mut ctx);
+
+        assert!(matches!(result, CommandResult::Success(Some(_))));
+        assert!(ctx.modified);
+        let meta = ctx.credentials.metadata("github").unwrap();
+        assert!(meta.updated_at.unwrap() > long_ago);
+        assert_eq!(ctx.credentials.get("github"), Some(&"secret".to_string()));
+    }
+
+    #[test]
+    fn test_touch_missing_credential() {
+        let mut credentials = Credentials::new();
+        let mut trie = Trie::new();
+        let mut ctx = ShellContext::new(&mut credentials, &mut trie);
+
+        let result = TouchCommand.execute(&["github"], &mut ctx);
+
+        assert!(matches!(result, CommandResult::Error(_)));
+        assert!(!ctx.modified);
+    }
+}
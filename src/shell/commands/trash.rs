@@ -0,0 +1,92 @@
+//! Trash command implementation.
+
+use chrono::{DateTime, Utc};
+
+use crate::credentials::Credentials;
+use crate::shell::command::{Command, CommandResult, ShellContext};
+
+/// Command to list removed credentials that can still be restored.
+pub struct TrashCommand;
+
+/// Lists the credentials in the trash with how long ago each was removed.
+fn format_trash(credentials: &Credentials, now: DateTime<Utc>) -> String {
+    let trashed = credentials.trashed();
+    if trashed.is_empty() {
+        return "The trash is empty.".to_string();
+    }
+    trashed
+        .iter()
+        .map(
+            |(name, trashed)| match now.signed_duration_since(trashed.deleted_at).num_days() {
+                0 => format!("{} (removed today)", name),
+                1 => format!("{} (removed 1 day ago)", name),
+                days => format!("{} (removed {} days ago)", name, days),
+            },
+        )
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+impl Command for TrashCommand {
+    fn name(&self) -> &str {
+        "trash"
+    }
+
+    fn description(&self) -> &str {
+        "List removed credentials"
+    }
+
+    fn usage(&self) -> &str {
+        "trash"
+    }
+
+    fn help(&self) -> &str {
+        "List the credentials moved to the trash by 'remove'.\n\n\
+         They are kept in the vault until the trash is emptied with 'purge',\n\
+         and can be brought back with 'restore <name>'.\n\n\
+         Examples:\n  \
+           trash"
+    }
+
+    fn execute(&self, _args: &[&str], ctx: &mut ShellContext) -> CommandResult {
+        log::debug!("Listing trash");
+        CommandResult::success(format_trash(ctx.credentials, Utc::now()))
+    }
+
+    fn min_args(&self) -> usize {
+        0
+    }
+
+    fn max_args(&self) -> Option<usize> {
+        Some(0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_format_trash() {
+        let now = Utc.with_ymd_and_hms(2026, 3, 10, 12, 0, 0).unwrap();
+        let mut credentials = Credentials::new();
+        assert_eq!(format_trash(&credentials, now), "The trash is empty.");
+
+        for name in ["github", "email", "bank"] {
+            credentials
+                .add(name.to_string(), "secret".to_string())
+                .unwrap();
+        }
+        credentials.move_to_trash("github", now);
+        credentials.move_to_trash("email", now - chrono::Duration::days(1));
+        credentials.move_to_trash("bank", now - chrono::Duration::days(30));
+
+        assert_eq!(
+            format_trash(&credentials, now),
+            "bank (removed 30 days ago)\n\
+             email (removed 1 day ago)\n\
+             github (removed today)"
+        );
+    }
+}
@@ -0,0 +1,150 @@
+//! Undo command implementation.
+
+use crate::shell::command::{Command, CommandResult, ShellContext};
+
+/// Command to revert the last change to the credentials.
+pub struct UndoCommand;
+
+impl Command for UndoCommand {
+    fn name(&self) -> &str {
+        "undo"
+    }
+
+    fn description(&self) -> &str {
+        "Revert the last change to the credentials"
+    }
+
+    fn usage(&self) -> &str {
+        "undo"
+    }
+
+    fn help(&self) -> &str {
+        "Revert the most recent add, remove, rename, replace, tag change or\n\
+         batch made in this session. Run it again to revert earlier changes,\n\
+         up to the last 50. Changes from earlier sessions, and changes made\n\
+         before a 'use' or 'restore', cannot be undone.\n\n\
+         Examples:\n  \
+           undo"
+    }
+
+    fn execute(&self, _args: &[&str], ctx: &mut ShellContext) -> CommandResult {
+        let Some(undo) = ctx.undo.as_deref_mut() else {
+            return CommandResult::error("Undo is not available in this session");
+        };
+        let Some(change) = undo.pop() else {
+            return CommandResult::success("Nothing to undo");
+        };
+
+        log::debug!("Undoing last change");
+        match change.revert(ctx.credentials, ctx.key_trie) {
+            Ok(message) => {
+                ctx.mark_modified();
+                log::info!("{}", message);
+                CommandResult::success(message)
+            }
+            Err(e) => {
+                log::warn!("Failed to undo change: {}", e);
+                CommandResult::error(format!("Could not undo: {}", e))
+            }
+        }
+    }
+
+    fn min_args(&self) -> usize {
+        0
+    }
+
+    fn max_args(&self) -> Option<usize> {
+        Some(0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::credentials::Credentials;
+    use crate::shell::commands::{AddCommand, RemoveCommand};
+    use crate::shell::undo::UndoStack;
+    use crate::trie::Trie;
+
+    #[test]
+    fn test_undo_add() {
+        let mut credentials = Credentials::new();
+        let mut trie = Trie::new();
+        let mut undo = UndoStack::default();
+        let mut ctx = ShellContext::new(&mut credentials, &mut trie).with_undo(&mut undo);
+
+        AddCommand.execute(&["github", "secret"], &mut ctx);
+        ctx.modified = false;
+        let result = UndoCommand.execute(&[], &mut ctx);
+
+        match result {
+            CommandResult::Success(Some(msg)) => assert_eq!(msg, "Undid adding 'github'"),
+            _ => panic!("Expected success"),
+        }
+        assert!(ctx.modified);
+        assert!(!ctx.credentials.contains_key("github"));
+        assert!(!ctx.key_trie.contains("github"));
+    }
+
+    #[test]
+    fn test_undo_remove_restores_secret_and_trie() {
+        let mut credentials = Credentials::new();
+        credentials
+            .add("github".to_string(), "secret".to_string())
+            .unwrap();
+        let mut trie = Trie::new();
+        trie.insert("github");
+        let mut undo = UndoStack::default();
+        let mut ctx = ShellContext::new(&mut credentials, &mut trie).with_undo(&mut undo);
+
+        RemoveCommand.execute(&["github"], &mut ctx);
+        assert!(!ctx.key_trie.contains("github"));
+        let result = UndoCommand.execute(&[], &mut ctx);
+
+        assert!(matches!(result, CommandResult::Success(Some(_))));
+        assert_eq!(ctx.credentials.get("github"), Some(&"secret".to_string()));
+        assert!(ctx.key_trie.contains("github"));
+    }
+
+    #[test]
+    fn test_undo_reverts_in_reverse_order() {
+        let mut credentials = Credentials::new();
+        let mut trie = Trie::new();
+        let mut undo = UndoStack::default();
+        let mut ctx = ShellContext::new(&mut credentials, &mut trie).with_undo(&mut undo);
+
+        AddCommand.execute(&["github", "secret"], &mut ctx);
+        RemoveCommand.execute(&["github"], &mut ctx);
+
+        UndoCommand.execute(&[], &mut ctx);
+        assert!(ctx.credentials.contains_key("github"));
+        UndoCommand.execute(&[], &mut ctx);
+        assert!(!ctx.credentials.contains_key("github"));
+    }
+
+    #[test]
+    fn test_nothing_to_undo() {
+        let mut credentials = Credentials::new();
+        let mut trie = Trie::new();
+        let mut undo = UndoStack::default();
+        let mut ctx = ShellContext::new(&mut credentials, &mut trie).with_undo(&mut undo);
+
+        match UndoCommand.execute(&[], &mut ctx) {
+            CommandResult::Success(Some(msg)) => assert_eq!(msg, "Nothing to undo"),
+            _ => panic!("Expected success"),
+        }
+        assert!(!ctx.modified);
+    }
+
+    #[test]
+    fn test_undo_without_stack() {
+        let mut credentials = Credentials::new();
+        let mut trie = Trie::new();
+        let mut ctx = ShellContext::new(&mut credentials, &mut trie);
+
+        assert!(matches!(
+            UndoCommand.execute(&[], &mut ctx),
+            CommandResult::Error(_)
+        ));
+    }
+}
@@ -0,0 +1,203 @@
+//! Use command implementation.
+
+use crate::shell::command::{Command, CommandResult, ShellContext};
+
+/// Command to switch to another vault.
+pub struct UseCommand;
+
+impl UseCommand {
+    /// Switches to `name` with an already-read password and refreshes the key trie.
+    fn switch(&self, name: &str, password: String, ctx: &mut ShellContext) -> CommandResult {
+        let Some(session) = ctx.session.as_deref_mut() else {
+            return CommandResult::error("Vaults are not available in this session");
+        };
+
+        match session.switch_vault(name, password) {
+            Ok(credentials) => {
+                *ctx.credentials = credentials;
+                ctx.key_trie.clear();
+                for key in ctx.credentials.list() {
+                    ctx.key_trie.insert(key);
+                }
+                ctx.forget_changes();
+                log::info!("Now using vault: {}", name);
+                CommandResult::success(format!("Now using vault '{}'", name))
+            }
+            Err(e) => {
+                log::warn!("Failed to switch to vault '{}': {}", name, e);
+                CommandResult::error(e.to_string())
+            }
+        }
+    }
+}
+
+impl Command for UseCommand {
+    fn name(&self) -> &str {
+        "use"
+    }
+
+    fn description(&self) -> &str {
+        "Switch to another vault"
+    }
+
+    fn usage(&self) -> &str {
+        "use <vault>"
+    }
+
+    fn help(&self) -> &str {
+        "Switch to another configured vault.\n\n\
+         Prompts for the vault's master password. The current vault is locked\n\
+         and its credentials are cleared from memory once the switch succeeds.\n\n\
+         Arguments:\n  \
+           <vault> - The name of the vault, as shown by 'vaults'\n\n\
+         Examples:\n  \
+           use work"
+    }
+
+    fn execute(&self, args: &[&str], ctx: &mut ShellContext) -> CommandResult {
+        let name = args[0];
+        let Some(session) = ctx.session.as_deref() else {
+            return CommandResult::error("Vaults are not available in this session");
+        };
+
+        // Check the name before prompting so typos fail fast
+        match session.vaults() {
+            Ok(vaults) => match vaults.iter().find(|v| v.name == name) {
+                Some(vault) if vault.active => {
+                    return CommandResult::success(format!("Already using vault '{}'", name));
+                }
+                Some(_) => {}
+                None => return CommandResult::error(format!("Unknown vault: '{}'", name)),
+            },
+            Err(e) => return CommandResult::error(format!("Could not list vaults: {}", e)),
+        }
+
+        let password = match rpassword::prompt_password(format!("Master Password for '{}': ", name))
+        {
+            Ok(pwd) => pwd.trim().to_string(),
+            Err(_) => return CommandResult::error("Failed to read master password"),
+        };
+
+        self.switch(name, password, ctx)
+    }
+
+    fn min_args(&self) -> usize {
+        1
+    }
+
+    fn max_args(&self) -> Option<usize> {
+        Some(1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::credentials::Credentials;
+    use crate::manager::VaultInfo;
+    use crate::shell::session::Session;
+    use crate::trie::Trie;
+    use anyhow::{Result, anyhow};
+    use std::path::PathBuf;
+
+    struct MockSession;
+
+    impl Session for MockSession {
+        fn save(&mut self, _credentials: &Credentials) -> Result<()> {
+            Ok(())
+        }
+
+        fn vaults(&self) -> Result<Vec<VaultInfo>> {
+            Ok(vec![
+                VaultInfo {
+                    name: "default".to_string(),
+                    path: PathBuf::from("default.db"),
+                    active: true,
+                },
+                VaultInfo {
+                    name: "work".to_string(),
+                    path: PathBuf::from("work.db"),
+                    active: false,
+                },
+            ])
+        }
+
+        fn switch_vault(&mut self, name: &str, password: String) -> Result<Credentials> {
+            if name != "work" || password != "work-pass" {
+                return Err(anyhow!("Invalid master password for vault '{}'", name));
+            }
+            let mut credentials = Credentials::new();
+            credentials
+                .add("jira".to_string(), "secret".to_string())
+                .unwrap();
+            Ok(credentials)
+        }
+    }
+
+    fn personal_credentials() -> Credentials {
+        let mut credentials = Credentials::new();
+        credentials
+            .add("github".to_string(), "personal".to_string())
+            .unwrap();
+        credentials
+    }
+
+    #[test]
+    fn test_use_unknown_vault() {
+        let mut credentials = personal_credentials();
+        let mut trie = Trie::new();
+        let mut session = MockSession;
+        let mut ctx = ShellContext::new(&mut credentials, &mut trie).with_session(&mut session);
+
+        let result = UseCommand.execute(&["home"], &mut ctx);
+        match result {
+            CommandResult::Error(msg) => assert!(msg.contains("Unknown vault")),
+            _ => panic!("Expected error for unknown vault"),
+        }
+    }
+
+    #[test]
+    fn test_use_active_vault() {
+        let mut credentials = personal_credentials();
+        let mut trie = Trie::new();
+        let mut session = MockSession;
+        let mut ctx = ShellContext::new(&mut credentials, &mut trie).with_session(&mut session);
+
+        let result = UseCommand.execute(&["default"], &mut ctx);
+        assert!(matches!(result, CommandResult::Success(Some(_))));
+        assert!(ctx.credentials.get("github").is_some());
+    }
+
+    #[test]
+    fn test_switch_replaces_credentials_and_trie() {
+        let mut credentials = personal_credentials();
+        let mut trie = Trie::new();
+        trie.insert("github");
+        let mut session = MockSession;
+        let mut ctx = ShellContext::new(&mut credentials, &mut trie).with_session(&mut session);
+
+        let result = UseCommand.switch("work", "work-pass".to_string(), &mut ctx);
+
+        assert!(matches!(result, CommandResult::Success(Some(_))));
+        assert!(ctx.credentials.get("github").is_none());
+        assert!(ctx.credentials.get("jira").is_some());
+        assert!(!ctx.key_trie.contains("github"));
+        assert!(ctx.key_trie.contains("jira"));
+        assert!(!ctx.modified);
+    }
+
+    #[test]
+    fn test_switch_wrong_password_keeps_vault() {
+        let mut credentials = personal_credentials();
+        let mut trie = Trie::new();
+        trie.insert("github");
+        let mut session = MockSession;
+        let mut ctx = ShellContext::new(&mut credentials, &mut trie).with_session(&mut session);
+
+        let result = UseCommand.switch("work", "wrong".to_string(), &mut ctx);
+
+        assert!(matches!(result, CommandResult::Error(_)));
+        assert!(ctx.credentials.get("github").is_some());
+        assert!(ctx.key_trie.contains("github"));
+    }
+}
@@ -0,0 +1,125 @@
+//! Vaults command implementation.
+
+use crate::shell::command::{Command, CommandResult, ShellContext};
+
+/// Command to list the configured vaults.
+pub struct VaultsCommand;
+
+impl Command for VaultsCommand {
+    fn name(&self) -> &str {
+        "vaults"
+    }
+
+    fn description(&self) -> &str {
+        "List configured vaults"
+    }
+
+    fn usage(&self) -> &str {
+        "vaults"
+    }
+
+    fn help(&self) -> &str {
+        "List the vaults that can be switched to with 'use'.\n\n\
+         The vault currently unlocked is marked with '*'.\n\n\
+         Examples:\n  \
+           vaults"
+    }
+
+    fn execute(&self, _args: &[&str], ctx: &mut ShellContext) -> CommandResult {
+        let Some(session) = ctx.session.as_deref() else {
+            return CommandResult::error("Vaults are not available in this session");
+        };
+
+        let vaults = match session.vaults() {
+            Ok(vaults) => vaults,
+            Err(e) => return CommandResult::error(format!("Could not list vaults: {}", e)),
+        };
+
+        if vaults.is_empty() {
+            return CommandResult::success("No vaults configured.");
+        }
+
+        let output = vaults
+            .iter()
+            .map(|vault| {
+                let marker = if vault.active { "*" } else { " " };
+                format!("{} {}  ({})", marker, vault.name, vault.path.display())
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        CommandResult::success(output)
+    }
+
+    fn min_args(&self) -> usize {
+        0
+    }
+
+    fn max_args(&self) -> Option<usize> {
+        Some(0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::credentials::Credentials;
+    use crate::manager::VaultInfo;
+    use crate::shell::session::Session;
+    use crate::trie::Trie;
+    use anyhow::Result;
+    use std::path::PathBuf;
+
+    struct MockSession;
+
+    impl Session for MockSession {
+        fn save(&mut self, _credentials: &Credentials) -> Result<()> {
+            Ok(())
+        }
+
+        fn vaults(&self) -> Result<Vec<VaultInfo>> {
+            Ok(vec![
+                VaultInfo {
+                    name: "default".to_string(),
+                    path: PathBuf::from("/home/user/.passmgr/passwords.db"),
+                    active: true,
+                },
+                VaultInfo {
+                    name: "work".to_string(),
+                    path: PathBuf::from("/home/user/.passmgr/work.db"),
+                    active: false,
+                },
+            ])
+        }
+    }
+
+    #[test]
+    fn test_vaults_command_without_session() {
+        let mut credentials = Credentials::new();
+        let mut trie = Trie::new();
+        let mut ctx = ShellContext::new(&mut credentials, &mut trie);
+
+        let result = VaultsCommand.execute(&[], &mut ctx);
+        assert!(matches!(result, CommandResult::Error(_)));
+    }
+
+    #[test]
+    fn test_vaults_command_marks_active() {
+        let mut credentials = Credentials::new();
+        let mut trie = Trie::new();
+        let mut session = MockSession;
+        let mut ctx = ShellContext::new(&mut credentials, &mut trie).with_session(&mut session);
+
+        let result = VaultsCommand.execute(&[], &mut ctx);
+
+        match result {
+            CommandResult::Success(Some(msg)) => {
+                let lines: Vec<&str> = msg.lines().collect();
+                assert_eq!(lines.len(), 2);
+                assert!(lines[0].starts_with("* default"));
+                assert!(lines[1].starts_with("  work"));
+            }
+            _ => panic!("Expected vault list"),
+        }
+    }
+}
@@ -0,0 +1,180 @@
+//! Verify command implementation.
+
+use crate::manager::{CheckStatus, VerifyReport};
+use crate::shell::command::{Command, CommandResult, ShellContext};
+
+/// Command to check that the vault file on disk is intact.
+pub struct VerifyCommand;
+
+/// Formats a report one check per line, followed by a summary.
+fn format_report(report: &VerifyReport) -> String {
+    let mut lines: Vec<String> = report
+        .checks
+        .iter()
+        .map(|check| match &check.status {
+            CheckStatus::Passed => format!("OK      {}", check.name),
+            CheckStatus::Failed(reason) => format!("FAILED  {} ({})", check.name, reason),
+            CheckStatus::Skipped => format!("SKIPPED {}", check.name),
+        })
+        .collect();
+
+    let failed = report
+        .checks
+        .iter()
+        .filter(|check| matches!(check.status, CheckStatus::Failed(_)))
+        .count();
+    if report.passed() {
+        lines.push(format!("All {} checks passed", report.checks.len()));
+    } else {
+        lines.push(format!(
+            "{} of {} checks failed",
+            failed,
+            report.checks.len()
+        ));
+    }
+    lines.join("\n")
+}
+
+impl Command for VerifyCommand {
+    fn name(&self) -> &str {
+        "verify"
+    }
+
+    fn description(&self) -> &str {
+        "Check that the vault file is intact"
+    }
+
+    fn usage(&self) -> &str {
+        "verify"
+    }
+
+    fn help(&self) -> &str {
+        "Re-read the vault file from disk and check it step by step.\n\n\
+         Checks that the file is valid JSON, its base64 fields decode, nonces\n\
+         and salts have the right length, and that it decrypts with the current\n\
+         master password into valid credentials. Checks that depend on a failed\n\
+         one are skipped. The vault file is never modified.\n\n\
+         Examples:\n  \
+           verify"
+    }
+
+    fn execute(&self, _args: &[&str], ctx: &mut ShellContext) -> CommandResult {
+        let Some(session) = ctx.session.as_deref() else {
+            return CommandResult::error("Verification is not available in this session");
+        };
+
+        log::debug!("Verifying vault");
+
+        match session.verify() {
+            Ok(report) if report.passed() => CommandResult::success(format_report(&report)),
+            Ok(report) => CommandResult::error(format_report(&report)),
+            Err(e) => CommandResult::error(format!("Could not verify vault: {}", e)),
+        }
+    }
+
+    fn min_args(&self) -> usize {
+        0
+    }
+
+    fn max_args(&self) -> Option<usize> {
+        Some(0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::credentials::Credentials;
+    use crate::manager::VerifyCheck;
+    use crate::shell::session::Session;
+    use crate::trie::Trie;
+    use anyhow::Result;
+
+    struct MockSession {
+        report: VerifyReport,
+    }
+
+    impl Session for MockSession {
+        fn save(&mut self, _credentials: &Credentials) -> Result<()> {
+            Ok(())
+        }
+
+        fn verify(&self) -> Result<VerifyReport> {
+            Ok(self.report.clone())
+        }
+    }
+
+    fn run(checks: Vec<VerifyCheck>) -> CommandResult {
+        let mut credentials = Credentials::new();
+        let mut trie = Trie::new();
+        let mut session = MockSession {
+            report: VerifyReport { checks },
+        };
+        let mut ctx = ShellContext::new(&mut credentials, &mut trie).with_session(&mut session);
+        VerifyCommand.execute(&[], &mut ctx)
+    }
+
+    #[test]
+    fn test_verify_all_passed() {
+        let result = run(vec![
+            VerifyCheck {
+                name: "Store file is readable JSON",
+                status: CheckStatus::Passed,
+            },
+            VerifyCheck {
+                name: "Credentials deserialize",
+                status: CheckStatus::Passed,
+            },
+        ]);
+
+        match result {
+            CommandResult::Success(Some(msg)) => assert_eq!(
+                msg,
+                "OK      Store file is readable JSON\n\
+                 OK      Credentials deserialize\n\
+                 All 2 checks passed"
+            ),
+            _ => panic!("Expected success"),
+        }
+    }
+
+    #[test]
+    fn test_verify_reports_failures() {
+        let result = run(vec![
+            VerifyCheck {
+                name: "Store file is readable JSON",
+                status: CheckStatus::Passed,
+            },
+            VerifyCheck {
+                name: "Decrypts with the master password",
+                status: CheckStatus::Failed("Decryption failed".to_string()),
+            },
+            VerifyCheck {
+                name: "Credentials deserialize",
+                status: CheckStatus::Skipped,
+            },
+        ]);
+
+        match result {
+            CommandResult::Error(msg) => assert_eq!(
+                msg,
+                "OK      Store file is readable JSON\n\
+                 FAILED  Decrypts with the master password (Decryption failed)\n\
+                 SKIPPED Credentials deserialize\n\
+                 1 of 3 checks failed"
+            ),
+            _ => panic!("Expected error"),
+        }
+    }
+
+    #[test]
+    fn test_verify_without_session() {
+        let mut credentials = Credentials::new();
+        let mut trie = Trie::new();
+        let mut ctx = ShellContext::new(&mut credentials, &mut trie);
+        assert!(matches!(
+            VerifyCommand.execute(&[], &mut ctx),
+            CommandResult::Error(_)
+        ));
+    }
+}
@@ -0,0 +1,164 @@
+//! Verify-backups command implementation.
+
+use crate::backup::BackupStatus;
+use crate::shell::command::{Command, CommandResult, ShellContext};
+
+/// Command to check that every backup decrypts with the current master password.
+pub struct VerifyBackupsCommand;
+
+impl Command for VerifyBackupsCommand {
+    fn name(&self) -> &str {
+        "verify-backups"
+    }
+
+    fn description(&self) -> &str {
+        "Check that backups decrypt with the current master password"
+    }
+
+    fn usage(&self) -> &str {
+        "verify-backups"
+    }
+
+    fn help(&self) -> &str {
+        "Attempt to decrypt every file in the backup directory.\n\n\
+         Each backup is reported as OK with its credential count, or FAILED\n\
+         with the reason it could not be read. Backups are never modified.\n\n\
+         Examples:\n  \
+           verify-backups"
+    }
+
+    fn execute(&self, _args: &[&str], ctx: &mut ShellContext) -> CommandResult {
+        let Some(session) = ctx.session.as_deref() else {
+            return CommandResult::error("Backups are not available in this session");
+        };
+
+        log::debug!("Verifying backups");
+
+        let reports = match session.verify_backups() {
+            Ok(reports) => reports,
+            Err(e) => return CommandResult::error(format!("Could not verify backups: {}", e)),
+        };
+
+        if reports.is_empty() {
+            return CommandResult::success("No backups found.");
+        }
+
+        let mut lines = Vec::with_capacity(reports.len() + 1);
+        let mut valid = 0;
+        for report in &reports {
+            let name = report
+                .path
+                .file_name()
+                .map(|n| n.to_string_lossy().into_owned())
+                .unwrap_or_else(|| report.path.display().to_string());
+            match &report.status {
+                BackupStatus::Valid { credentials } => {
+                    valid += 1;
+                    lines.push(format!("OK      {} ({} credentials)", name, credentials));
+                }
+                BackupStatus::Invalid { reason } => {
+                    lines.push(format!("FAILED  {} ({})", name, reason));
+                }
+            }
+        }
+        lines.push(format!("{} of {} backups valid", valid, reports.len()));
+
+        log::info!("Verified {} backups, {} valid", reports.len(), valid);
+        CommandResult::success(lines.join("\n"))
+    }
+
+    fn min_args(&self) -> usize {
+        0
+    }
+
+    fn max_args(&self) -> Option<usize> {
+        Some(0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backup::BackupReport;
+    use crate::credentials::Credentials;
+    use crate::shell::session::Session;
+    use crate::trie::Trie;
+    use anyhow::Result;
+    use std::path::PathBuf;
+
+    struct MockSession {
+        reports: Vec<BackupReport>,
+    }
+
+    impl Session for MockSession {
+        fn save(&mut self, _credentials: &Credentials) -> Result<()> {
+            Ok(())
+        }
+
+        fn verify_backups(&self) -> Result<Vec<BackupReport>> {
+            Ok(self.reports.clone())
+        }
+    }
+
+    #[test]
+    fn test_verify_backups_without_session() {
+        let mut credentials = Credentials::new();
+        let mut trie = Trie::new();
+        let mut ctx = ShellContext::new(&mut credentials, &mut trie);
+
+        let cmd = VerifyBackupsCommand;
+        let result = cmd.execute(&[], &mut ctx);
+
+        assert!(matches!(result, CommandResult::Error(_)));
+    }
+
+    #[test]
+    fn test_verify_backups_none_found() {
+        let mut credentials = Credentials::new();
+        let mut trie = Trie::new();
+        let mut session = MockSession { reports: vec![] };
+        let mut ctx = ShellContext::new(&mut credentials, &mut trie).with_session(&mut session);
+
+        let cmd = VerifyBackupsCommand;
+        let result = cmd.execute(&[], &mut ctx);
+
+        match result {
+            CommandResult::Success(Some(msg)) => assert_eq!(msg, "No backups found."),
+            _ => panic!("Expected success message"),
+        }
+    }
+
+    #[test]
+    fn test_verify_backups_report() {
+        let mut credentials = Credentials::new();
+        let mut trie = Trie::new();
+        let mut session = MockSession {
+            reports: vec![
+                BackupReport {
+                    path: PathBuf::from("/tmp/backups/a.db"),
+                    status: BackupStatus::Valid { credentials: 3 },
+                },
+                BackupReport {
+                    path: PathBuf::from("/tmp/backups/b.db"),
+                    status: BackupStatus::Invalid {
+                        reason: "Decryption failed".to_string(),
+                    },
+                },
+            ],
+        };
+        let mut ctx = ShellContext::new(&mut credentials, &mut trie).with_session(&mut session);
+
+        let cmd = VerifyBackupsCommand;
+        let result = cmd.execute(&[], &mut ctx);
+
+        match result {
+            CommandResult::Success(Some(msg)) => {
+                assert!(msg.contains("OK      a.db (3 credentials)"));
+                assert!(msg.contains("FAILED  b.db (Decryption failed)"));
+                assert!(msg.contains("1 of 2 backups valid"));
+            }
+            _ => panic!("Expected success with report"),
+        }
+        assert!(!ctx.modified);
+    }
+}
@@ -4,50 +4,168 @@
 
 use rustyline::Context;
 use rustyline::completion::{Completer, Pair};
+use std::collections::HashMap;
 use std::sync::{Arc, RwLock};
 
 use crate::shell::command::CommandRegistry;
 use crate::trie::Trie;
 
+/// Number of candidates above which a summary line is appended.
+pub const DEFAULT_SUMMARY_THRESHOLD: usize = 20;
+
+/// Number of candidates listed at most; the rest are counted in a last line.
+pub const DEFAULT_MAX_COMPLETIONS: usize = 50;
+
+/// Snapshot of each credential's secret length in characters, shown next to
+/// key completions. The shell refreshes it whenever the credentials change.
+pub type SecretLengths = Arc<RwLock<HashMap<String, usize>>>;
+
+/// Returns the part of a completion's display that is an annotation rather
+/// than the candidate itself, e.g. `(12 chars)` in `github (12 chars)`.
+pub fn split_annotation(display: &str) -> (&str, Option<&str>) {
+    match display.rfind(" (") {
+        Some(i) if display.ends_with(')') => (&display[..i], Some(&display[i + 1..])),
+        _ => (display, None),
+    }
+}
+
 /// Completer that handles both command and argument completion.
 pub struct PassmgrCompleter {
     /// Registry of available commands.
     registry: Arc<CommandRegistry>,
     /// Trie containing credential keys (updated dynamically).
     key_trie: Arc<RwLock<Trie>>,
+    /// Candidate count above which a "N matches" summary is shown.
+    summary_threshold: usize,
+    /// Number of candidates listed at most.
+    max_completions: usize,
+    /// Secret lengths used to annotate key completions.
+    secret_lengths: SecretLengths,
 }
 
 impl PassmgrCompleter {
     /// Creates a new completer.
     pub fn new(registry: Arc<CommandRegistry>, key_trie: Arc<RwLock<Trie>>) -> Self {
-        Self { registry, key_trie }
+        Self {
+            registry,
+            key_trie,
+            summary_threshold: DEFAULT_SUMMARY_THRESHOLD,
+            max_completions: DEFAULT_MAX_COMPLETIONS,
+            secret_lengths: SecretLengths::default(),
+        }
+    }
+
+    /// Annotates key completions with the secret lengths in `lengths`.
+    pub fn with_secret_lengths(mut self, lengths: SecretLengths) -> Self {
+        self.secret_lengths = lengths;
+        self
+    }
+
+    /// Sets the candidate count above which a summary line is shown.
+    #[allow(unused)]
+    pub fn with_summary_threshold(mut self, threshold: usize) -> Self {
+        self.summary_threshold = threshold;
+        self
+    }
+
+    /// Sets the number of candidates listed at most.
+    #[allow(unused)]
+    pub fn with_max_completions(mut self, max: usize) -> Self {
+        self.max_completions = max;
+        self
+    }
+
+    /// Finishes a list of candidates that may have been cut at
+    /// `max_completions` out of `total` matches for `partial`.
+    ///
+    /// A cut list ends with an "… N more" entry whose replacement is only
+    /// `partial`, since the hidden candidates may not share a longer prefix;
+    /// otherwise a summary is added as by [`Self::with_summary`].
+    fn finish(&self, mut pairs: Vec<Pair>, total: usize, partial: &str) -> Vec<Pair> {
+        if total <= pairs.len() {
+            return self.with_summary(pairs);
+        }
+        pairs.push(Pair {
+            display: format!("… {} more", total - pairs.len()),
+            replacement: partial.to_string(),
+        });
+        pairs
+    }
+
+    /// Appends a "N matches" summary entry when there are many candidates.
+    ///
+    /// The summary only changes what is displayed: its replacement is the
+    /// longest common prefix of the real candidates, so selecting it (or
+    /// rustyline's prefix extension) never inserts anything unexpected.
+    fn with_summary(&self, mut pairs: Vec<Pair>) -> Vec<Pair> {
+        if pairs.len() <= self.summary_threshold {
+            return pairs;
+        }
+
+        let mut prefix = pairs[0].replacement.clone();
+        for pair in &pairs[1..] {
+            let common = prefix
+                .char_indices()
+                .zip(pair.replacement.chars())
+                .find(|((_, a), b)| a != b)
+                .map(|((i, _), _)| i)
+                .unwrap_or_else(|| prefix.len().min(pair.replacement.len()));
+            prefix.truncate(common);
+        }
+
+        pairs.push(Pair {
+            display: format!("({} matches, type more to narrow)", pairs.len()),
+            replacement: prefix,
+        });
+        pairs
     }
 
     /// Gets completions for a command name.
     fn complete_command(&self, partial: &str) -> Vec<Pair> {
-        self.registry
-            .completions(partial)
+        let names = self.registry.completions(partial);
+        let total = names.len();
+        let pairs = names
             .into_iter()
+            .take(self.max_completions)
             .map(|s| Pair {
                 display: s.clone(),
                 replacement: s,
             })
-            .collect()
+            .collect();
+        self.finish(pairs, total, partial)
     }
 
     /// Gets completions for a credential key.
+    ///
+    /// Each candidate is displayed with its secret length when known, e.g.
+    /// `github (12 chars)`; only the key itself is inserted.
     fn complete_key(&self, partial: &str) -> Vec<Pair> {
-        match self.key_trie.read() {
-            Ok(trie) => trie
-                .completions(partial)
-                .into_iter()
-                .map(|s| Pair {
-                    display: s.clone(),
+        let Ok(trie) = self.key_trie.read() else {
+            return vec![];
+        };
+        let lengths = self.secret_lengths.read().ok();
+
+        let keys = trie.completions_limited(partial, self.max_completions);
+        let total = if keys.len() < self.max_completions {
+            keys.len()
+        } else {
+            trie.count_with_prefix(partial)
+        };
+        let pairs = keys
+            .into_iter()
+            .map(|s| {
+                let display = match lengths.as_ref().and_then(|l| l.get(&s)) {
+                    Some(1) => format!("{} (1 char)", s),
+                    Some(len) => format!("{} ({} chars)", s, len),
+                    None => s.clone(),
+                };
+                Pair {
+                    display,
                     replacement: s,
-                })
-                .collect(),
-            Err(_) => vec![],
-        }
+                }
+            })
+            .collect();
+        self.finish(pairs, total, partial)
     }
 
     /// Parses the input line to determine completion context.
@@ -113,8 +231,7 @@ impl Completer for PassmgrCompleter {
         match context {
             CompletionContext::Command { partial } => {
                 let start = pos - partial.len();
-                let completions = self.complete_command(partial);
-                Ok((start, completions))
+                Ok((start, self.complete_command(partial)))
             }
             CompletionContext::Argument {
                 command,
@@ -124,7 +241,8 @@ impl Completer for PassmgrCompleter {
                 // Determine what kind of completions based on command
                 let completions = match command {
                     // Commands that complete credential keys
-                    "get" | "g" | "show" | "remove" | "rm" | "delete" | "del" => {
+                    "get" | "g" | "show" | "reveal" | "remove" | "rm" | "delete" | "del"
+                    | "rename" | "mv" | "replace" => {
                         if arg_index == 0 {
                             self.complete_key(partial)
                         } else {
@@ -139,10 +257,24 @@ impl Completer for PassmgrCompleter {
                             vec![]
                         }
                     }
+                    "history" | "hist" => {
+                        if arg_index == 0 {
+                            ["clear", "search"]
+                                .into_iter()
+                                .filter(|s| s.starts_with(partial))
+                                .map(|s| Pair {
+                                    display: s.to_string(),
+                                    replacement: s.to_string(),
+                                })
+                                .collect()
+                        } else {
+                            vec![]
+                        }
+                    }
                     // Add command doesn't complete (new names)
                     "add" | "a" | "new" | "set" => vec![],
-                    // List and quit have no arguments
-                    "list" | "ls" | "l" | "quit" | "exit" | "q" => vec![],
+                    // List, clear and quit have no arguments
+                    "list" | "ls" | "l" | "clear" | "cls" | "quit" | "exit" | "q" => vec![],
                     // Unknown command - no completions
                     _ => vec![],
                 };
@@ -172,13 +304,25 @@ mod tests {
         PassmgrCompleter::new(Arc::new(registry), Arc::new(RwLock::new(key_trie)))
     }
 
+    fn lengths(entries: &[(&str, usize)]) -> SecretLengths {
+        let map = entries
+            .iter()
+            .map(|(name, len)| (name.to_string(), *len))
+            .collect();
+        Arc::new(RwLock::new(map))
+    }
+
     #[test]
     fn test_complete_command_partial() {
         let completer = setup_completer();
-        let completions = completer.complete_command("ge");
+        let completions = completer.complete_command("get");
 
         assert_eq!(completions.len(), 1);
         assert_eq!(completions[0].display, "get");
+
+        let completions = completer.complete_command("ge");
+        assert!(completions.iter().any(|pair| pair.display == "get"));
+        assert!(completions.iter().any(|pair| pair.display == "generate"));
     }
 
     #[test]
@@ -201,6 +345,27 @@ mod tests {
         assert!(displays.contains(&"gitlab"));
     }
 
+    #[test]
+    fn test_summary_added_above_threshold() {
+        let completer = setup_completer().with_summary_threshold(1);
+        let completions = completer.complete_key("gi");
+
+        assert_eq!(completions.len(), 3);
+        let summary = completions.last().unwrap();
+        assert_eq!(summary.display, "(2 matches, type more to narrow)");
+        // Selecting the summary only inserts the common prefix
+        assert_eq!(summary.replacement, "git");
+    }
+
+    #[test]
+    fn test_no_summary_at_or_below_threshold() {
+        let completer = setup_completer().with_summary_threshold(2);
+        let completions = completer.complete_key("gi");
+
+        assert_eq!(completions.len(), 2);
+        assert!(completions.iter().all(|p| !p.display.contains("matches")));
+    }
+
     #[test]
     fn test_parse_context_command() {
         let completer = setup_completer();
@@ -236,4 +401,97 @@ mod tests {
             }
         ));
     }
+
+    #[test]
+    fn test_complete_key_annotates_secret_length() {
+        let completer =
+            setup_completer().with_secret_lengths(lengths(&[("github", 12), ("gitlab", 1)]));
+        let completions = completer.complete_key("gi");
+
+        let displays: Vec<&str> = completions.iter().map(|p| p.display.as_str()).collect();
+        assert!(displays.contains(&"github (12 chars)"));
+        assert!(displays.contains(&"gitlab (1 char)"));
+
+        // Only the key is inserted
+        let replacements: Vec<&str> = completions.iter().map(|p| p.replacement.as_str()).collect();
+        assert!(replacements.contains(&"github"));
+        assert!(replacements.contains(&"gitlab"));
+
+        let completer = completer.with_summary_threshold(1);
+        let summary = completer.complete_key("gi");
+        assert_eq!(summary.last().unwrap().replacement, "git");
+    }
+
+    #[test]
+    fn test_complete_key_without_length_is_plain() {
+        let completer = setup_completer().with_secret_lengths(lengths(&[("github", 12)]));
+        let completions = completer.complete_key("em");
+        assert_eq!(completions[0].display, "email");
+    }
+
+    #[test]
+    fn test_split_annotation() {
+        assert_eq!(
+            split_annotation("github (12 chars)"),
+            ("github", Some("(12 chars)"))
+        );
+        assert_eq!(
+            split_annotation("my (old) key (3 chars)"),
+            ("my (old) key", Some("(3 chars)"))
+        );
+        assert_eq!(split_annotation("github"), ("github", None));
+        assert_eq!(
+            split_annotation("(2 matches, type more to narrow)"),
+            ("(2 matches, type more to narrow)", None)
+        );
+    }
+
+    #[test]
+    fn test_complete_key_capped_with_more_indicator() {
+        let mut registry = CommandRegistry::new();
+        register_all(&mut registry);
+        let mut key_trie = Trie::new();
+        for i in 0..120 {
+            key_trie.insert(&format!("site{:03}", i));
+        }
+        let completer = PassmgrCompleter::new(Arc::new(registry), Arc::new(RwLock::new(key_trie)))
+            .with_max_completions(50);
+
+        let completions = completer.complete_key("site");
+        assert_eq!(completions.len(), 51);
+        assert_eq!(completions[0].replacement, "site000");
+        assert_eq!(completions[49].replacement, "site049");
+        let more = completions.last().unwrap();
+        assert_eq!(more.display, "… 70 more");
+        // Selecting the indicator inserts nothing beyond what was typed
+        assert_eq!(more.replacement, "site");
+
+        // Below the cap, the summary is shown instead
+        let completer = completer.with_summary_threshold(10);
+        let completions = completer.complete_key("site1");
+        assert_eq!(completions.len(), 21);
+        assert_eq!(
+            completions.last().unwrap().display,
+            "(20 matches, type more to narrow)"
+        );
+
+        // Exactly at the cap nothing is hidden
+        let completer = completer.with_max_completions(20);
+        let completions = completer.complete_key("site1");
+        assert!(completions.iter().all(|p| !p.display.starts_with('…')));
+    }
+
+    #[test]
+    fn test_complete_command_capped() {
+        let completer = setup_completer().with_max_completions(3);
+        let total = completer.registry.completions("").len();
+
+        let completions = completer.complete_command("");
+        assert_eq!(completions.len(), 4);
+        assert_eq!(
+            completions.last().unwrap().display,
+            format!("… {} more", total - 3)
+        );
+        assert_eq!(completions.last().unwrap().replacement, "");
+    }
 }
@@ -1,12 +1,18 @@
 //! Syntax and semantic highlighting for the shell.
 //!
-//! Provides colorized output for commands, arguments, and results.
+//! Provides colorized output for commands, arguments, and results. Colors
+//! come from a [`Theme`], which can be customized from the config file or
+//! replaced with [`Theme::plain`] to emit no escape codes at all.
 
+use anyhow::{Result, anyhow};
 use rustyline::highlight::{CmdKind, Highlighter};
 use std::borrow::Cow;
+use std::collections::HashMap;
+use std::ffi::OsString;
 use std::sync::Arc;
 
 use crate::shell::command::CommandRegistry;
+use crate::shell::completer::split_annotation;
 
 /// ANSI color codes for highlighting.
 pub mod colors {
@@ -17,21 +23,17 @@ pub mod colors {
     /// Dim text.
     pub const DIM: &str = "\x1b[2m";
     /// Italic text.
-    #[allow(unused)]
     pub const ITALIC: &str = "\x1b[3m";
     /// Underline text.
-    #[allow(unused)]
     pub const UNDERLINE: &str = "\x1b[4m";
 
     /// Red foreground.
     pub const RED: &str = "\x1b[31m";
     /// Green foreground.
-    #[allow(unused)]
     pub const GREEN: &str = "\x1b[32m";
     /// Yellow foreground.
     pub const YELLOW: &str = "\x1b[33m";
     /// Blue foreground.
-    #[allow(unused)]
     pub const BLUE: &str = "\x1b[34m";
     /// Magenta foreground.
     pub const MAGENTA: &str = "\x1b[35m";
@@ -45,22 +47,179 @@ pub mod colors {
     /// Bright green foreground.
     pub const BRIGHT_GREEN: &str = "\x1b[92m";
     /// Bright yellow foreground.
-    #[allow(unused)]
     pub const BRIGHT_YELLOW: &str = "\x1b[93m";
     /// Bright cyan foreground.
     pub const BRIGHT_CYAN: &str = "\x1b[96m";
 }
 
+/// Maps semantic roles to the escape codes used to display them.
+///
+/// Each field holds the codes emitted before text in that role; an empty
+/// string means the text is shown as-is, without a trailing reset.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Theme {
+    /// Known command names.
+    pub command: String,
+    /// Command names that are not recognised.
+    pub unknown_command: String,
+    /// Credential names.
+    pub key: String,
+    /// Secret values.
+    pub secret: String,
+    /// Command names given as arguments to `help`.
+    pub topic: String,
+    /// Any other argument.
+    pub argument: String,
+    /// The shell prompt.
+    pub prompt: String,
+    /// Inline hints.
+    pub hint: String,
+    /// Completion candidates.
+    pub candidate: String,
+    /// Error messages.
+    pub error: String,
+    /// Warning messages.
+    pub warning: String,
+    /// Success messages.
+    pub success: String,
+    /// Informational messages.
+    pub info: String,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self {
+            command: format!("{}{}", colors::BOLD, colors::CYAN),
+            unknown_command: colors::RED.to_string(),
+            key: colors::MAGENTA.to_string(),
+            secret: colors::DIM.to_string(),
+            topic: colors::YELLOW.to_string(),
+            argument: colors::WHITE.to_string(),
+            prompt: format!("{}{}", colors::BOLD, colors::BRIGHT_GREEN),
+            hint: colors::DIM.to_string(),
+            candidate: colors::BRIGHT_CYAN.to_string(),
+            error: colors::BRIGHT_RED.to_string(),
+            warning: colors::YELLOW.to_string(),
+            success: colors::GREEN.to_string(),
+            info: colors::BLUE.to_string(),
+        }
+    }
+}
+
+impl Theme {
+    /// Returns a theme that emits no escape codes.
+    pub fn plain() -> Self {
+        Self {
+            command: String::new(),
+            unknown_command: String::new(),
+            key: String::new(),
+            secret: String::new(),
+            topic: String::new(),
+            argument: String::new(),
+            prompt: String::new(),
+            hint: String::new(),
+            candidate: String::new(),
+            error: String::new(),
+            warning: String::new(),
+            success: String::new(),
+            info: String::new(),
+        }
+    }
+
+    /// Builds a theme from role names mapped to space-separated style names,
+    /// e.g. `key = "bold yellow"`. Roles that are not mentioned keep their
+    /// default colors.
+    pub fn from_roles(roles: &HashMap<String, String>) -> Result<Self> {
+        let mut theme = Self::default();
+        for (role, style) in roles {
+            theme.set_role(role, style)?;
+        }
+        Ok(theme)
+    }
+
+    /// Sets the style of a role by name.
+    pub fn set_role(&mut self, role: &str, style: &str) -> Result<()> {
+        let codes = parse_style(style)?;
+        let slot = match role {
+            "command" => &mut self.command,
+            "unknown_command" => &mut self.unknown_command,
+            "key" => &mut self.key,
+            "secret" => &mut self.secret,
+            "topic" => &mut self.topic,
+            "argument" => &mut self.argument,
+            "prompt" => &mut self.prompt,
+            "hint" => &mut self.hint,
+            "candidate" => &mut self.candidate,
+            "error" => &mut self.error,
+            "warning" => &mut self.warning,
+            "success" => &mut self.success,
+            "info" => &mut self.info,
+            _ => return Err(anyhow!("Unknown theme role: '{}'", role)),
+        };
+        *slot = codes;
+        Ok(())
+    }
+
+    /// Wraps `text` in the given style, or returns it unchanged if the style is empty.
+    pub fn paint(style: &str, text: &str) -> String {
+        if style.is_empty() {
+            text.to_string()
+        } else {
+            format!("{}{}{}", style, text, colors::RESET)
+        }
+    }
+}
+
+/// Converts space-separated style names into escape codes.
+///
+/// `none` (or an empty string) means no styling.
+fn parse_style(style: &str) -> Result<String> {
+    let mut codes = String::new();
+    for name in style.split_whitespace() {
+        let code = match name {
+            "none" => "",
+            "bold" => colors::BOLD,
+            "dim" => colors::DIM,
+            "italic" => colors::ITALIC,
+            "underline" => colors::UNDERLINE,
+            "red" => colors::RED,
+            "green" => colors::GREEN,
+            "yellow" => colors::YELLOW,
+            "blue" => colors::BLUE,
+            "magenta" => colors::MAGENTA,
+            "cyan" => colors::CYAN,
+            "white" => colors::WHITE,
+            "bright_red" => colors::BRIGHT_RED,
+            "bright_green" => colors::BRIGHT_GREEN,
+            "bright_yellow" => colors::BRIGHT_YELLOW,
+            "bright_cyan" => colors::BRIGHT_CYAN,
+            _ => return Err(anyhow!("Unknown style: '{}'", name)),
+        };
+        codes.push_str(code);
+    }
+    Ok(codes)
+}
+
+/// Returns true if colors should be disabled.
+///
+/// Colors are off when `--no-color` was given or when `NO_COLOR` is set to a
+/// non-empty value (see <https://no-color.org>).
+pub fn no_color_requested(flag: bool, no_color_env: Option<OsString>) -> bool {
+    flag || no_color_env.is_some_and(|v| !v.is_empty())
+}
+
 /// Highlighter for shell input with syntax coloring.
 pub struct PassmgrHighlighter {
     /// Registry to check for valid commands.
     registry: Arc<CommandRegistry>,
+    /// Colors to use.
+    theme: Theme,
 }
 
 impl PassmgrHighlighter {
     /// Creates a new highlighter.
-    pub fn new(registry: Arc<CommandRegistry>) -> Self {
-        Self { registry }
+    pub fn new(registry: Arc<CommandRegistry>, theme: Theme) -> Self {
+        Self { registry, theme }
     }
 
     /// Highlights a line of input.
@@ -84,17 +243,12 @@ impl PassmgrHighlighter {
         result.push_str(leading_ws);
 
         // Highlight the command
-        if is_valid_command {
-            result.push_str(colors::BOLD);
-            result.push_str(colors::CYAN);
-            result.push_str(command);
-            result.push_str(colors::RESET);
+        let style = if is_valid_command {
+            &self.theme.command
         } else {
-            // Invalid command - show in red
-            result.push_str(colors::RED);
-            result.push_str(command);
-            result.push_str(colors::RESET);
-        }
+            &self.theme.unknown_command
+        };
+        result.push_str(&Theme::paint(style, command));
 
         // Find where arguments start
         let cmd_end = line.find(command).unwrap_or(0) + command.len();
@@ -122,26 +276,21 @@ impl PassmgrHighlighter {
             result.push_str(whitespace);
 
             // Color based on command and argument position
-            let color = match command {
+            let style = match command {
                 "add" | "a" | "new" | "set" => {
                     if i == 0 {
-                        colors::MAGENTA // Key name
+                        &self.theme.key
                     } else {
-                        colors::DIM // Secret (dimmed for privacy)
+                        &self.theme.secret
                     }
                 }
-                "get" | "g" | "show" | "remove" | "rm" | "delete" | "del" => {
-                    colors::MAGENTA // Key name
-                }
-                "help" | "h" | "?" => {
-                    colors::YELLOW // Command name for help
-                }
-                _ => colors::WHITE,
+                "get" | "g" | "show" | "reveal" | "remove" | "rm" | "delete" | "del" | "rename"
+                | "mv" => &self.theme.key,
+                "help" | "h" | "?" => &self.theme.topic,
+                _ => &self.theme.argument,
             };
 
-            result.push_str(color);
-            result.push_str(part);
-            result.push_str(colors::RESET);
+            result.push_str(&Theme::paint(style, part));
 
             pos = part_start + part.len();
         }
@@ -165,19 +314,11 @@ impl Highlighter for PassmgrHighlighter {
         prompt: &'p str,
         _default: bool,
     ) -> Cow<'b, str> {
-        // Highlight the prompt with a nice color
-        Cow::Owned(format!(
-            "{}{}{}{}",
-            colors::BOLD,
-            colors::BRIGHT_GREEN,
-            prompt,
-            colors::RESET
-        ))
+        Cow::Owned(Theme::paint(&self.theme.prompt, prompt))
     }
 
     fn highlight_hint<'h>(&self, hint: &'h str) -> Cow<'h, str> {
-        // Dim the hint text
-        Cow::Owned(format!("{}{}{}", colors::DIM, hint, colors::RESET))
+        Cow::Owned(Theme::paint(&self.theme.hint, hint))
     }
 
     fn highlight_candidate<'c>(
@@ -185,13 +326,14 @@ impl Highlighter for PassmgrHighlighter {
         candidate: &'c str,
         _completion: rustyline::CompletionType,
     ) -> Cow<'c, str> {
-        // Highlight completion candidates
-        Cow::Owned(format!(
-            "{}{}{}",
-            colors::BRIGHT_CYAN,
-            candidate,
-            colors::RESET
-        ))
+        // Annotations such as secret lengths are dimmed like hints
+        let (name, annotation) = split_annotation(candidate);
+        let mut painted = Theme::paint(&self.theme.candidate, name);
+        if let Some(annotation) = annotation {
+            painted.push(' ');
+            painted.push_str(&Theme::paint(&self.theme.hint, annotation));
+        }
+        Cow::Owned(painted)
     }
 
     fn highlight_char(&self, _line: &str, _pos: usize, _kind: CmdKind) -> bool {
@@ -201,48 +343,61 @@ impl Highlighter for PassmgrHighlighter {
 }
 
 /// Utilities for semantic highlighting in output.
-pub struct OutputHighlighter;
+pub struct OutputHighlighter {
+    /// Colors to use.
+    theme: Theme,
+}
 
 impl OutputHighlighter {
+    /// Creates an output highlighter using `theme`.
+    pub fn new(theme: Theme) -> Self {
+        Self { theme }
+    }
+
     /// Formats a success message.
     #[allow(unused)]
-    pub fn success(msg: &str) -> String {
-        format!("{}{}{}", colors::GREEN, msg, colors::RESET)
+    pub fn success(&self, msg: &str) -> String {
+        Theme::paint(&self.theme.success, msg)
     }
 
     /// Formats an error message.
-    pub fn error(msg: &str) -> String {
-        format!("{}{}{}", colors::BRIGHT_RED, msg, colors::RESET)
+    pub fn error(&self, msg: &str) -> String {
+        Theme::paint(&self.theme.error, msg)
     }
 
     /// Formats a warning message.
     #[allow(unused)]
-    pub fn warning(msg: &str) -> String {
-        format!("{}{}{}", colors::YELLOW, msg, colors::RESET)
+    pub fn warning(&self, msg: &str) -> String {
+        Theme::paint(&self.theme.warning, msg)
     }
 
     /// Formats a key/credential name.
     #[allow(unused)]
-    pub fn key(name: &str) -> String {
-        format!("{}{}{}", colors::MAGENTA, name, colors::RESET)
+    pub fn key(&self, name: &str) -> String {
+        Theme::paint(&self.theme.key, name)
     }
 
     /// Formats a secret (dimmed for less visibility).
     #[allow(unused)]
-    pub fn secret(secret: &str) -> String {
-        format!("{}{}{}", colors::DIM, secret, colors::RESET)
+    pub fn secret(&self, secret: &str) -> String {
+        Theme::paint(&self.theme.secret, secret)
     }
 
     /// Formats a command name.
     #[allow(unused)]
-    pub fn command(cmd: &str) -> String {
-        format!("{}{}{}{}", colors::BOLD, colors::CYAN, cmd, colors::RESET)
+    pub fn command(&self, cmd: &str) -> String {
+        Theme::paint(&self.theme.command, cmd)
+    }
+
+    /// Formats secondary text, dimmed like hints.
+    pub fn hint(&self, msg: &str) -> String {
+        Theme::paint(&self.theme.hint, msg)
     }
 
     /// Formats informational text.
     #[allow(unused)]
-    pub fn info(msg: &str) -> String {
-        format!("{}{}{}", colors::BLUE, msg, colors::RESET)
+    pub fn info(&self, msg: &str) -> String {
+        Theme::paint(&self.theme.info, msg)
     }
 }
 
@@ -254,7 +409,7 @@ mod tests {
     fn setup_highlighter() -> PassmgrHighlighter {
         let mut registry = CommandRegistry::new();
         register_all(&mut registry);
-        PassmgrHighlighter::new(Arc::new(registry))
+        PassmgrHighlighter::new(Arc::new(registry), Theme::default())
     }
 
     #[test]
@@ -288,14 +443,14 @@ mod tests {
 
     #[test]
     fn test_output_highlighter_success() {
-        let result = OutputHighlighter::success("Done!");
+        let result = OutputHighlighter::new(Theme::default()).success("Done!");
         assert!(result.contains(colors::GREEN));
         assert!(result.contains("Done!"));
     }
 
     #[test]
     fn test_output_highlighter_error() {
-        let result = OutputHighlighter::error("Failed!");
+        let result = OutputHighlighter::new(Theme::default()).error("Failed!");
         assert!(result.contains(colors::BRIGHT_RED));
         assert!(result.contains("Failed!"));
     }
@@ -309,4 +464,98 @@ mod tests {
         let result = highlighter.highlight_line("   ");
         assert_eq!(result, "   ");
     }
+
+    #[test]
+    fn test_plain_theme_leaves_input_unchanged() {
+        let mut registry = CommandRegistry::new();
+        register_all(&mut registry);
+        let highlighter = PassmgrHighlighter::new(Arc::new(registry), Theme::plain());
+
+        for line in [
+            "add mykey mysecret",
+            "  get   github  ",
+            "help add",
+            "bogus arg",
+            "list --count-only",
+        ] {
+            assert_eq!(highlighter.highlight_line(line), line);
+        }
+        assert_eq!(highlighter.highlight_prompt("passmgr> ", true), "passmgr> ");
+        assert_eq!(
+            highlighter.highlight_prompt("[work:3]> ", true),
+            "[work:3]> "
+        );
+        assert_eq!(highlighter.highlight_hint(" <name>"), " <name>");
+        assert_eq!(
+            highlighter.highlight_candidate("github", rustyline::CompletionType::List),
+            "github"
+        );
+        assert_eq!(
+            highlighter.highlight_candidate("github (12 chars)", rustyline::CompletionType::List),
+            "github (12 chars)"
+        );
+    }
+
+    #[test]
+    fn test_candidate_annotation_is_dimmed() {
+        let highlighter = setup_highlighter();
+        let result =
+            highlighter.highlight_candidate("github (12 chars)", rustyline::CompletionType::List);
+
+        let theme = Theme::default();
+        assert_eq!(
+            result,
+            format!(
+                "{} {}",
+                Theme::paint(&theme.candidate, "github"),
+                Theme::paint(&theme.hint, "(12 chars)")
+            )
+        );
+    }
+
+    #[test]
+    fn test_plain_output_highlighter_unchanged() {
+        let output = OutputHighlighter::new(Theme::plain());
+        assert_eq!(output.error("Failed!"), "Failed!");
+        assert_eq!(output.warning("Careful"), "Careful");
+        assert_eq!(output.success("Done!"), "Done!");
+        assert_eq!(output.key("github"), "github");
+        assert_eq!(output.secret("hunter2"), "hunter2");
+        assert_eq!(output.command("add"), "add");
+        assert_eq!(output.info("Note"), "Note");
+    }
+
+    #[test]
+    fn test_theme_from_roles_overrides_roles() {
+        let roles = HashMap::from([
+            ("key".to_string(), "bold yellow".to_string()),
+            ("secret".to_string(), "none".to_string()),
+        ]);
+
+        let theme = Theme::from_roles(&roles).unwrap();
+        assert_eq!(theme.key, format!("{}{}", colors::BOLD, colors::YELLOW));
+        assert_eq!(theme.secret, "");
+        assert_eq!(theme.command, Theme::default().command);
+    }
+
+    #[test]
+    fn test_theme_from_no_roles_is_default() {
+        let theme = Theme::from_roles(&HashMap::new()).unwrap();
+        assert_eq!(theme, Theme::default());
+    }
+
+    #[test]
+    fn test_theme_rejects_unknown_role_and_style() {
+        let mut theme = Theme::default();
+        assert!(theme.set_role("banner", "red").is_err());
+        assert!(theme.set_role("key", "sparkly").is_err());
+    }
+
+    #[test]
+    fn test_no_color_requested() {
+        assert!(!no_color_requested(false, None));
+        assert!(!no_color_requested(false, Some(OsString::new())));
+        assert!(no_color_requested(false, Some(OsString::from("1"))));
+        assert!(no_color_requested(true, None));
+    }
 }
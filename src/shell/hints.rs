@@ -4,20 +4,44 @@
 
 use rustyline::Context;
 use rustyline::hint::Hinter;
-use std::sync::Arc;
+use std::sync::{Arc, RwLock};
 
 use crate::shell::command::CommandRegistry;
+use crate::trie::Trie;
 
-/// Hinter that provides command usage hints.
+/// Returns whether argument `arg_index` of `command` is a credential name.
+///
+/// `get` takes any number of names; the other commands take one, first.
+fn takes_key(command: &str, arg_index: usize) -> bool {
+    match command {
+        "get" => true,
+        "reveal" | "remove" | "rename" | "replace" | "touch" | "expire" => arg_index == 0,
+        _ => false,
+    }
+}
+
+/// Hinter that provides command usage and credential name hints.
 pub struct PassmgrHinter {
     /// Registry of available commands.
     registry: Arc<CommandRegistry>,
+    /// Trie containing credential keys (updated dynamically).
+    key_trie: Arc<RwLock<Trie>>,
 }
 
 impl PassmgrHinter {
     /// Creates a new hinter.
-    pub fn new(registry: Arc<CommandRegistry>) -> Self {
-        Self { registry }
+    pub fn new(registry: Arc<CommandRegistry>, key_trie: Arc<RwLock<Trie>>) -> Self {
+        Self { registry, key_trie }
+    }
+
+    /// Returns the rest of the alphabetically first key starting with
+    /// `partial`, skipping keys already given as arguments in `typed`.
+    fn key_hint(&self, partial: &str, typed: &[&str]) -> Option<String> {
+        let trie = self.key_trie.read().ok()?;
+        trie.completions(partial)
+            .into_iter()
+            .find(|key| key.len() > partial.len() && !typed.contains(&key.as_str()))
+            .map(|key| key[partial.len()..].to_string())
     }
 
     /// Gets a hint for the current input.
@@ -52,6 +76,20 @@ impl PassmgrHinter {
         // Show usage hint for the command
         if let Some(cmd) = self.registry.get(command) {
             let arg_count = parts.len() - 1;
+
+            // Suggest a stored key for arguments that name a credential
+            let (arg_index, partial) = if has_trailing_space {
+                (arg_count, "")
+            } else {
+                (arg_count - 1, parts[arg_count])
+            };
+            if takes_key(cmd.name(), arg_index)
+                && !partial.starts_with('-')
+                && let Some(hint) = self.key_hint(partial, &parts[1..])
+            {
+                return Some(hint);
+            }
+
             let min_args = cmd.min_args();
 
             if arg_count < min_args {
@@ -94,10 +132,19 @@ mod tests {
     use super::*;
     use crate::shell::commands::register_all;
 
-    fn setup_hinter() -> PassmgrHinter {
+    fn setup_hinter_with_keys(keys: &[&str]) -> PassmgrHinter {
         let mut registry = CommandRegistry::new();
         register_all(&mut registry);
-        PassmgrHinter::new(Arc::new(registry))
+
+        let mut key_trie = Trie::new();
+        for key in keys {
+            key_trie.insert(key);
+        }
+        PassmgrHinter::new(Arc::new(registry), Arc::new(RwLock::new(key_trie)))
+    }
+
+    fn setup_hinter() -> PassmgrHinter {
+        setup_hinter_with_keys(&[])
     }
 
     #[test]
@@ -128,6 +175,79 @@ mod tests {
         assert!(hint.is_none());
     }
 
+    #[test]
+    fn test_key_hint_for_partial_key() {
+        let hinter = setup_hinter_with_keys(&["github", "gitlab", "email"]);
+
+        assert_eq!(hinter.get_hint("get gith"), Some("ub".to_string()));
+        // Several matches hint the alphabetically first
+        assert_eq!(hinter.get_hint("rm git"), Some("hub".to_string()));
+        assert_eq!(hinter.get_hint("get em"), Some("ail".to_string()));
+        // A complete key or one matching nothing has no hint
+        assert_eq!(hinter.get_hint("get email"), None);
+        assert_eq!(hinter.get_hint("get bank"), None);
+    }
+
+    #[test]
+    fn test_key_hint_after_command() {
+        let hinter = setup_hinter_with_keys(&["github", "email"]);
+
+        assert_eq!(hinter.get_hint("get "), Some("email".to_string()));
+        // Keys already given are not suggested again
+        assert_eq!(hinter.get_hint("get email "), Some("github".to_string()));
+        assert_eq!(hinter.get_hint("get --show gi"), Some("thub".to_string()));
+        // Only the first argument of rename is an existing key
+        assert_eq!(hinter.get_hint("rename github gi"), None);
+        assert_eq!(
+            hinter.get_hint("rename github "),
+            Some(" <new>".to_string())
+        );
+    }
+
+    #[test]
+    fn test_key_hint_sees_trie_updates() {
+        let mut registry = CommandRegistry::new();
+        register_all(&mut registry);
+        let key_trie = Arc::new(RwLock::new(Trie::new()));
+        let hinter = PassmgrHinter::new(Arc::new(registry), Arc::clone(&key_trie));
+
+        assert_eq!(hinter.get_hint("get gi"), None);
+        key_trie.write().unwrap().insert("github");
+        assert_eq!(hinter.get_hint("get gi"), Some("thub".to_string()));
+    }
+
+    #[test]
+    fn test_no_key_hint_from_poisoned_trie() {
+        let mut registry = CommandRegistry::new();
+        register_all(&mut registry);
+        let mut trie = Trie::new();
+        trie.insert("github");
+        let key_trie = Arc::new(RwLock::new(trie));
+        let hinter = PassmgrHinter::new(Arc::new(registry), Arc::clone(&key_trie));
+
+        let poisoner = Arc::clone(&key_trie);
+        let _ = std::thread::spawn(move || {
+            let _guard = poisoner.write().unwrap();
+            panic!("poison the key trie");
+        })
+        .join();
+        assert!(key_trie.is_poisoned());
+
+        assert_eq!(hinter.get_hint("get gi"), None);
+        // Usage hints don't depend on the trie
+        assert_eq!(hinter.get_hint("touch "), Some(" <name>".to_string()));
+    }
+
+    #[test]
+    fn test_usage_hint_without_keys() {
+        let hinter = setup_hinter();
+        assert_eq!(hinter.get_hint("touch "), Some(" <name>".to_string()));
+
+        // New names are never hinted from stored keys
+        let hinter = setup_hinter_with_keys(&["github"]);
+        assert_eq!(hinter.get_hint("add gi"), None);
+    }
+
     #[test]
     fn test_empty_line_no_hint() {
         let hinter = setup_hinter();
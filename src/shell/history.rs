@@ -4,7 +4,79 @@
 
 use anyhow::Result;
 use rustyline::config::Configurer;
-use std::path::PathBuf;
+use rustyline::history::{FileHistory, History};
+use std::path::{Path, PathBuf};
+
+use crate::config::{DEFAULT_HISTORY_SIZE, HISTORY_FILE, app_dir_path};
+
+/// The shell's command history, as seen by the `history` command.
+pub trait CommandHistory {
+    /// Returns every entry, oldest first.
+    fn entries(&self) -> Vec<String>;
+
+    /// Removes every entry, both in memory and from the history file.
+    fn clear(&mut self) -> Result<()>;
+}
+
+/// The history of a running editor, persisted to `path`.
+pub struct EditorHistory<'a> {
+    history: &'a mut FileHistory,
+    path: &'a Path,
+}
+
+impl<'a> EditorHistory<'a> {
+    /// Wraps an editor's history and the file it is saved to.
+    pub fn new(history: &'a mut FileHistory, path: &'a Path) -> Self {
+        Self { history, path }
+    }
+}
+
+impl CommandHistory for EditorHistory<'_> {
+    fn entries(&self) -> Vec<String> {
+        self.history.iter().cloned().collect()
+    }
+
+    fn clear(&mut self) -> Result<()> {
+        History::clear(self.history)?;
+        // Saving an empty history is a no-op, so truncate the file now
+        if self.path.exists() {
+            std::fs::File::create(self.path)?;
+        }
+        log::info!("Cleared command history");
+        Ok(())
+    }
+}
+
+/// Saves `history` to `path` and syncs it to disk.
+///
+/// The parent directory is created if needed. After writing, the file is
+/// fsynced, and on Unix so is its directory, so a crash right after saving
+/// cannot lose the history or leave it partially written.
+pub fn save_history(history: &mut FileHistory, path: &Path) -> Result<()> {
+    if let Some(parent) = path.parent()
+        && !parent.exists()
+    {
+        std::fs::create_dir_all(parent)?;
+    }
+    history.save(path)?;
+    sync_file(path)?;
+    Ok(())
+}
+
+/// Flushes `path` and, on Unix, the directory entry pointing to it to disk.
+fn sync_file(path: &Path) -> std::io::Result<()> {
+    std::fs::File::open(path)?.sync_all()?;
+    #[cfg(unix)]
+    if let Some(parent) = path.parent() {
+        let dir = if parent.as_os_str().is_empty() {
+            Path::new(".")
+        } else {
+            parent
+        };
+        std::fs::File::open(dir)?.sync_all()?;
+    }
+    Ok(())
+}
 
 /// Configuration for command history.
 #[derive(Debug, Clone)]
@@ -14,25 +86,31 @@ pub struct HistoryConfig {
     /// Maximum number of entries to keep.
     pub max_entries: usize,
     /// Whether to ignore duplicate consecutive entries.
-    #[allow(unused)]
     pub ignore_dups: bool,
     /// Whether to ignore entries starting with whitespace.
-    #[allow(unused)]
     pub ignore_space: bool,
 }
 
 impl Default for HistoryConfig {
+    /// Keeps the history in `~/.passmgr`, next to the default vault.
+    ///
+    /// Without a home directory, it falls back to the current directory.
     fn default() -> Self {
+        Self::default_in_dir(&app_dir_path().unwrap_or_default())
+    }
+}
+
+impl HistoryConfig {
+    /// Creates the default config for keeping the history in `dir`.
+    pub fn default_in_dir(dir: &Path) -> Self {
         Self {
-            path: PathBuf::from("history"),
-            max_entries: 1000,
+            path: dir.join(HISTORY_FILE),
+            max_entries: DEFAULT_HISTORY_SIZE,
             ignore_dups: true,
             ignore_space: true,
         }
     }
-}
 
-impl HistoryConfig {
     /// Creates a new history config with the given path.
     pub fn new(path: PathBuf) -> Self {
         Self {
@@ -48,14 +126,12 @@ impl HistoryConfig {
     }
 
     /// Sets whether to ignore duplicate consecutive entries.
-    #[allow(unused)]
     pub fn with_ignore_dups(mut self, ignore: bool) -> Self {
         self.ignore_dups = ignore;
         self
     }
 
     /// Sets whether to ignore entries starting with whitespace.
-    #[allow(unused)]
     pub fn with_ignore_space(mut self, ignore: bool) -> Self {
         self.ignore_space = ignore;
         self
@@ -82,14 +158,7 @@ impl HistoryConfig {
     /// Saves history from a rustyline DefaultEditor.
     #[allow(unused)]
     pub fn save_from_default_editor(&self, editor: &mut rustyline::DefaultEditor) -> Result<()> {
-        // Ensure parent directory exists
-        if let Some(parent) = self.path.parent() {
-            if !parent.exists() {
-                std::fs::create_dir_all(parent)?;
-            }
-        }
-
-        editor.save_history(&self.path)?;
+        save_history(editor.history_mut(), &self.path)?;
         log::debug!("Saved history to {}", self.path.display());
 
         Ok(())
@@ -97,7 +166,9 @@ impl HistoryConfig {
 }
 
 /// Filters for determining what to add to history.
-#[allow(unused)]
+///
+/// The shell leaves filtering to this rather than to rustyline, so the
+/// `ignore_dups` and `ignore_space` settings apply the same way everywhere.
 pub struct HistoryFilter {
     /// Configuration to use for filtering.
     config: HistoryConfig,
@@ -115,28 +186,35 @@ impl HistoryFilter {
         }
     }
 
-    /// Determines if an entry should be added to history.
-    #[allow(unused)]
-    pub fn should_add(&mut self, entry: &str) -> bool {
-        let entry = entry.trim();
+    /// Creates a filter whose duplicate check continues from the last entry
+    /// of `history`, such as one loaded from the history file.
+    pub fn for_history(config: HistoryConfig, history: &FileHistory) -> Self {
+        Self {
+            config,
+            last_entry: history.iter().last().cloned(),
+        }
+    }
 
-        // Ignore empty entries
-        if entry.is_empty() {
+    /// Determines if an entry, as typed, should be added to history.
+    pub fn should_add(&mut self, entry: &str) -> bool {
+        // Ignore entries starting with whitespace (if configured), which is
+        // how a command is kept out of the history on purpose
+        if self.config.ignore_space && entry.starts_with(char::is_whitespace) {
             return false;
         }
 
-        // Ignore entries starting with whitespace (if configured)
-        if self.config.ignore_space && entry.starts_with(char::is_whitespace) {
+        // Ignore empty entries
+        let entry = entry.trim();
+        if entry.is_empty() {
             return false;
         }
 
         // Ignore duplicate consecutive entries (if configured)
-        if self.config.ignore_dups {
-            if let Some(ref last) = self.last_entry {
-                if last == entry {
-                    return false;
-                }
-            }
+        if self.config.ignore_dups
+            && let Some(ref last) = self.last_entry
+            && last == entry
+        {
+            return false;
         }
 
         // Update last entry
@@ -145,22 +223,104 @@ impl HistoryFilter {
     }
 
     /// Resets the filter state.
-    #[allow(unused)]
     pub fn reset(&mut self) {
         self.last_entry = None;
     }
 }
 
+/// Turns off rustyline's own history filtering, so only a [`HistoryFilter`]
+/// decides what is added.
+pub fn disable_editor_filtering(editor: &mut impl Configurer) -> Result<()> {
+    editor.set_history_ignore_dups(false)?;
+    editor.set_history_ignore_space(false);
+    Ok(())
+}
+
+/// Adds `line`, as typed, to `history` if `filter` lets it through, and
+/// returns whether it was added.
+///
+/// The entry is stored trimmed. `history` should not filter entries itself
+/// (see [`disable_editor_filtering`]), or its own settings would override
+/// the filter's.
+pub fn add_entry(history: &mut impl History, filter: &mut HistoryFilter, line: &str) -> bool {
+    if !filter.should_add(line) {
+        return false;
+    }
+    match history.add(line.trim()) {
+        Ok(added) => added,
+        Err(e) => {
+            log::warn!("Could not add history entry: {}", e);
+            false
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_editor_history_clear_truncates_file() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let path = temp_dir.path().join("history");
+
+        let mut history = FileHistory::new();
+        history.add("list").unwrap();
+        history.add("get github").unwrap();
+        history.save(&path).unwrap();
+        assert!(std::fs::metadata(&path).unwrap().len() > 0);
+
+        let mut editor_history = EditorHistory::new(&mut history, &path);
+        assert_eq!(editor_history.entries(), vec!["list", "get github"]);
+
+        editor_history.clear().unwrap();
+        assert!(editor_history.entries().is_empty());
+        assert_eq!(std::fs::metadata(&path).unwrap().len(), 0);
+
+        let mut reloaded = FileHistory::new();
+        reloaded.load(&path).unwrap();
+        assert_eq!(reloaded.len(), 0);
+    }
+
+    #[test]
+    fn test_save_history_writes_complete_file() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        // The parent directory does not exist yet
+        let path = temp_dir.path().join("profile").join("history");
+
+        let mut history = FileHistory::new();
+        for entry in ["list", "get github", "add \"my email\" secret"] {
+            history.add(entry).unwrap();
+        }
+        save_history(&mut history, &path).unwrap();
+
+        let mut reloaded = FileHistory::new();
+        reloaded.load(&path).unwrap();
+        assert_eq!(
+            reloaded.iter().cloned().collect::<Vec<_>>(),
+            vec!["list", "get github", "add \"my email\" secret"]
+        );
+    }
+
     #[test]
     fn test_history_config_default() {
         let config = HistoryConfig::default();
         assert_eq!(config.max_entries, 1000);
         assert!(config.ignore_dups);
         assert!(config.ignore_space);
+        if let Some(home) = dirs_next::home_dir() {
+            assert_eq!(config.path, home.join(".passmgr").join("history"));
+        }
+    }
+
+    #[test]
+    fn test_history_config_default_in_dir() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let config = HistoryConfig::default_in_dir(dir.path());
+
+        assert!(config.path.starts_with(dir.path()));
+        assert_eq!(config.path, dir.path().join("history"));
+        assert_eq!(config.max_entries, 1000);
     }
 
     #[test]
@@ -203,6 +363,30 @@ mod tests {
         assert!(filter.should_add("add key value")); // allowed when ignore_dups is false
     }
 
+    #[test]
+    fn test_history_filter_ignore_space() {
+        let mut filter = HistoryFilter::new(HistoryConfig::default());
+        assert!(!filter.should_add(" get github"));
+        assert!(filter.should_add("get github"));
+
+        let config = HistoryConfig::default().with_ignore_space(false);
+        let mut filter = HistoryFilter::new(config);
+        assert!(filter.should_add(" get github"));
+        // Stored trimmed, so this is a duplicate
+        assert!(!filter.should_add("get github"));
+    }
+
+    #[test]
+    fn test_history_filter_for_history_continues_from_last_entry() {
+        let mut history = FileHistory::new();
+        history.add("list").unwrap();
+        history.add("get github").unwrap();
+
+        let mut filter = HistoryFilter::for_history(HistoryConfig::default(), &history);
+        assert!(!filter.should_add("get github"));
+        assert!(filter.should_add("list"));
+    }
+
     #[test]
     fn test_history_filter_reset() {
         let config = HistoryConfig::default();
@@ -0,0 +1,322 @@
+//! Idle timer behind the shell's auto-lock, and the countdown shown in the
+//! prompt.
+//!
+//! Reading a line blocks until Enter is pressed, so the shell cannot check
+//! the timer while it waits for input. An [`IdleWatcher`] thread does, and
+//! interrupts the read once the timer expires so the shell can lock.
+//!
+//! Rustyline measures the prompt once, when reading a line starts, and then
+//! repaints it through the highlighter on every keystroke. So the prompt is
+//! rendered with the full timeout as a placeholder, and
+//! [`IdleTimer::refresh_countdown`] swaps in the time actually left, padded
+//! to the same width so the cursor stays in place.
+
+use std::borrow::Cow;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+/// How often an [`IdleWatcher`] checks an expired timer, until the shell
+/// waits for input again or resets it.
+const EXPIRED_POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Formats `remaining` as `M:SS`, or `H:MM:SS` from an hour up.
+///
+/// Partial seconds count as a whole second, so a countdown only shows
+/// `0:00` once it has run out.
+pub fn format_remaining(remaining: Duration) -> String {
+    let secs = remaining.as_secs() + u64::from(remaining.subsec_nanos() > 0);
+    let (hours, minutes, secs) = (secs / 3600, secs / 60 % 60, secs % 60);
+    if hours > 0 {
+        format!("{}:{:02}:{:02}", hours, minutes, secs)
+    } else {
+        format!("{}:{:02}", minutes, secs)
+    }
+}
+
+/// Inserts `countdown` into `prompt` before its closing `> `, as in
+/// `passmgr [4:59]> `, or appends it if the prompt does not end that way.
+pub fn insert_countdown(prompt: &str, countdown: &str) -> String {
+    match prompt.strip_suffix("> ") {
+        Some(head) => format!("{} [{}]> ", head, countdown),
+        None => format!("{}[{}] ", prompt, countdown),
+    }
+}
+
+/// Time since the last command, shared with the prompt highlighter.
+#[derive(Debug, Clone)]
+pub struct IdleTimer {
+    timeout: Duration,
+    last_activity: Arc<Mutex<Instant>>,
+}
+
+impl IdleTimer {
+    /// Creates a timer that expires after `timeout` without activity.
+    pub fn new(timeout: Duration) -> Self {
+        Self {
+            timeout,
+            last_activity: Arc::new(Mutex::new(Instant::now())),
+        }
+    }
+
+    /// Returns the inactivity after which the timer expires.
+    pub fn timeout(&self) -> Duration {
+        self.timeout
+    }
+
+    /// Restarts the timer after user activity.
+    pub fn reset(&self) {
+        if let Ok(mut last_activity) = self.last_activity.lock() {
+            *last_activity = Instant::now();
+        }
+    }
+
+    /// Returns how long is left before the timer expires.
+    pub fn remaining(&self) -> Duration {
+        let elapsed = self
+            .last_activity
+            .lock()
+            .map(|last_activity| last_activity.elapsed())
+            .unwrap_or_default();
+        self.timeout.saturating_sub(elapsed)
+    }
+
+    /// Returns whether the timeout has passed since the last activity.
+    pub fn expired(&self) -> bool {
+        self.remaining().is_zero()
+    }
+
+    /// Returns the countdown placeholder put into the prompt: the full
+    /// timeout, which is also the widest the countdown gets.
+    pub fn placeholder(&self) -> String {
+        format_remaining(self.timeout)
+    }
+
+    /// Replaces the placeholder in a prompt from [`insert_countdown`] with
+    /// the time left, keeping the prompt's width.
+    pub fn refresh_countdown<'p>(&self, prompt: &'p str) -> Cow<'p, str> {
+        let placeholder = format!("[{}]", self.placeholder());
+        let Some(start) = prompt.rfind(&placeholder) else {
+            return Cow::Borrowed(prompt);
+        };
+        let width = placeholder.len() - 2;
+        let countdown = format!("[{:>width$}]", format_remaining(self.remaining()));
+        let mut refreshed = prompt.to_string();
+        refreshed.replace_range(start..start + placeholder.len(), &countdown);
+        Cow::Owned(refreshed)
+    }
+}
+
+/// Calls back when an [`IdleTimer`] expires while the shell waits for input.
+///
+/// The watcher runs on its own thread, sleeping until the timer would expire.
+/// If the shell is then inside [`IdleWatcher::wait_for_input`], `on_expiry`
+/// is called once for that wait. The thread is stopped when the watcher is
+/// dropped.
+pub struct IdleWatcher {
+    waiting: Arc<AtomicBool>,
+    stop: Arc<AtomicBool>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl IdleWatcher {
+    /// Starts watching `timer`.
+    pub fn spawn(timer: IdleTimer, on_expiry: impl Fn() + Send + 'static) -> Self {
+        let waiting = Arc::new(AtomicBool::new(false));
+        let stop = Arc::new(AtomicBool::new(false));
+        let thread = {
+            let (waiting, stop) = (Arc::clone(&waiting), Arc::clone(&stop));
+            thread::spawn(move || {
+                while !stop.load(Ordering::SeqCst) {
+                    let remaining = timer.remaining();
+                    if !remaining.is_zero() {
+                        thread::park_timeout(remaining);
+                        continue;
+                    }
+                    if waiting.swap(false, Ordering::SeqCst) {
+                        on_expiry();
+                    }
+                    thread::park_timeout(EXPIRED_POLL_INTERVAL);
+                }
+            })
+        };
+        Self {
+            waiting,
+            stop,
+            thread: Some(thread),
+        }
+    }
+
+    /// Marks the shell as waiting for input until the returned guard is
+    /// dropped.
+    pub fn wait_for_input(&self) -> WaitingForInput<'_> {
+        self.waiting.store(true, Ordering::SeqCst);
+        WaitingForInput(&self.waiting)
+    }
+}
+
+impl Drop for IdleWatcher {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::SeqCst);
+        if let Some(thread) = self.thread.take() {
+            thread.thread().unpark();
+            let _ = thread.join();
+        }
+    }
+}
+
+/// Returned by [`IdleWatcher::wait_for_input`]; the wait ends when dropped.
+pub struct WaitingForInput<'a>(&'a AtomicBool);
+
+impl Drop for WaitingForInput<'_> {
+    fn drop(&mut self) {
+        self.0.store(false, Ordering::SeqCst);
+    }
+}
+
+/// Returns a callback that interrupts a line being read on the calling
+/// thread, as Ctrl-C does.
+///
+/// The line editor handles `SIGINT` while it reads from a terminal, and
+/// returns from the read when the signal arrives on its thread. The signal
+/// is only sent while some handler is installed, since by default it would
+/// end the process.
+#[cfg(unix)]
+pub fn interrupt_current_thread() -> impl Fn() + Send + 'static {
+    /// The thread to signal.
+    struct Target(libc::pthread_t);
+
+    // SAFETY: a pthread_t only identifies the thread; it is never dereferenced
+    unsafe impl Send for Target {}
+
+    impl Target {
+        fn interrupt(&self) {
+            if sigint_is_handled() {
+                // SAFETY: the shell's thread outlives the watcher calling this
+                unsafe { libc::pthread_kill(self.0, libc::SIGINT) };
+            }
+        }
+    }
+
+    // SAFETY: pthread_self has no preconditions
+    let target = Target(unsafe { libc::pthread_self() });
+    move || target.interrupt()
+}
+
+/// Returns a callback that does nothing: without signals, the idle lock
+/// only takes effect once a line has been entered.
+#[cfg(not(unix))]
+pub fn interrupt_current_thread() -> impl Fn() + Send + 'static {
+    || {}
+}
+
+/// Returns whether a handler other than the default is installed for `SIGINT`.
+#[cfg(unix)]
+fn sigint_is_handled() -> bool {
+    let mut current = std::mem::MaybeUninit::<libc::sigaction>::uninit();
+    // SAFETY: with a null new action, sigaction only writes the current one
+    if unsafe { libc::sigaction(libc::SIGINT, std::ptr::null(), current.as_mut_ptr()) } != 0 {
+        return false;
+    }
+    // SAFETY: sigaction succeeded, so it filled in `current`
+    let handler = unsafe { current.assume_init() }.sa_sigaction;
+    handler != libc::SIG_DFL && handler != libc::SIG_IGN
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_remaining() {
+        assert_eq!(format_remaining(Duration::from_secs(300)), "5:00");
+        assert_eq!(format_remaining(Duration::from_secs(299)), "4:59");
+        assert_eq!(format_remaining(Duration::from_millis(298_200)), "4:59");
+        assert_eq!(format_remaining(Duration::from_secs(9)), "0:09");
+        assert_eq!(format_remaining(Duration::from_millis(1)), "0:01");
+        assert_eq!(format_remaining(Duration::ZERO), "0:00");
+        assert_eq!(format_remaining(Duration::from_secs(600)), "10:00");
+        assert_eq!(format_remaining(Duration::from_secs(3661)), "1:01:01");
+    }
+
+    #[test]
+    fn test_insert_countdown() {
+        assert_eq!(insert_countdown("passmgr> ", "4:59"), "passmgr [4:59]> ");
+        assert_eq!(
+            insert_countdown("[{vault}:{count}]> ", "4:59"),
+            "[{vault}:{count}] [4:59]> "
+        );
+        assert_eq!(insert_countdown("$ ", "4:59"), "$ [4:59] ");
+    }
+
+    #[test]
+    fn test_refresh_countdown_keeps_width() {
+        let timer = IdleTimer::new(Duration::from_secs(600));
+        let prompt = insert_countdown("passmgr> ", &timer.placeholder());
+        assert_eq!(prompt, "passmgr [10:00]> ");
+
+        *timer.last_activity.lock().unwrap() -= Duration::from_secs(61);
+        let refreshed = timer.refresh_countdown(&prompt);
+        assert!(refreshed == "passmgr [ 8:59]> " || refreshed == "passmgr [ 8:58]> ");
+        assert_eq!(refreshed.len(), prompt.len());
+
+        // Prompts without the placeholder are left alone
+        assert_eq!(timer.refresh_countdown("passmgr> "), "passmgr> ");
+    }
+
+    #[test]
+    fn test_timer_expires_and_resets() {
+        let timer = IdleTimer::new(Duration::from_secs(60));
+        assert!(!timer.expired());
+
+        *timer.last_activity.lock().unwrap() -= Duration::from_secs(61);
+        assert!(timer.expired());
+        assert_eq!(timer.remaining(), Duration::ZERO);
+
+        timer.reset();
+        assert!(!timer.expired());
+        assert!(timer.remaining() > Duration::from_secs(59));
+    }
+
+    #[test]
+    fn test_watcher_calls_back_while_waiting() {
+        let timer = IdleTimer::new(Duration::from_millis(50));
+        let (sender, receiver) = std::sync::mpsc::channel();
+        let watcher = IdleWatcher::spawn(timer.clone(), move || {
+            let _ = sender.send(());
+        });
+
+        // Not waiting for input: nothing happens
+        thread::sleep(Duration::from_millis(150));
+        assert!(receiver.try_recv().is_err());
+
+        // Waiting once the timer has expired
+        timer.reset();
+        let waiting = watcher.wait_for_input();
+        assert!(receiver.recv_timeout(Duration::from_secs(5)).is_ok());
+        // Only once per wait
+        assert!(receiver.recv_timeout(Duration::from_millis(400)).is_err());
+        drop(waiting);
+
+        // After a reset the timer runs again
+        timer.reset();
+        let _waiting = watcher.wait_for_input();
+        assert!(receiver.recv_timeout(Duration::from_secs(5)).is_ok());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_interrupt_needs_a_handler() {
+        // Without a handler installed, SIGINT would end the test run
+        interrupt_current_thread()();
+    }
+
+    #[test]
+    fn test_watcher_stops_when_dropped() {
+        let watcher = IdleWatcher::spawn(IdleTimer::new(Duration::from_secs(3600)), || {});
+        let start = Instant::now();
+        drop(watcher);
+        assert!(start.elapsed() < Duration::from_secs(5));
+    }
+}
@@ -12,6 +12,11 @@ pub mod completer;
 pub mod highlighter;
 pub mod hints;
 pub mod history;
+pub mod idle;
+pub mod pager;
+pub mod session;
+pub mod suspend;
+pub mod undo;
 
 use anyhow::{Result, anyhow};
 use rustyline::completion::Completer;
@@ -19,26 +24,130 @@ use rustyline::config::Configurer;
 use rustyline::error::ReadlineError;
 use rustyline::highlight::Highlighter;
 use rustyline::hint::Hinter;
-use rustyline::history::FileHistory;
+use rustyline::history::{FileHistory, History};
 use rustyline::validate::{
     MatchingBracketValidator, ValidationContext, ValidationResult, Validator,
 };
-use rustyline::{Context, Editor, Helper};
+use rustyline::{CompletionType, Context, Editor, EventHandler, Helper, KeyEvent};
 use std::borrow::Cow;
-use std::sync::{Arc, RwLock};
+use std::io::IsTerminal;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, RwLock, RwLockWriteGuard};
+use std::time::Duration;
 
-use crate::credentials::Credentials;
-use crate::trie::Trie;
+use crate::credentials::{Credentials, NameNormalization};
+use crate::pwned::{self, RangeSource};
+use crate::trie::{Trie, keys_checksum};
 
-use command::{CommandRegistry, CommandResult, ShellContext};
+use command::{
+    CommandRegistry, CommandResult, OutputFormat, ShellContext, check_arg_count, split_line,
+};
 use commands::register_all;
-use completer::PassmgrCompleter;
-use highlighter::{OutputHighlighter, PassmgrHighlighter};
+use completer::{PassmgrCompleter, SecretLengths};
+use highlighter::{OutputHighlighter, PassmgrHighlighter, Theme};
 use hints::PassmgrHinter;
-use history::HistoryConfig;
+use history::{EditorHistory, HistoryConfig, HistoryFilter};
+use idle::{IdleTimer, IdleWatcher, insert_countdown, interrupt_current_thread};
+use pager::{Pager, SystemPager};
+use session::{SaveFnSession, Session};
+use suspend::SuspendSignal;
+use undo::UndoStack;
+
+/// The prompt displayed to the user unless another is configured.
+pub const PROMPT: &str = "passmgr> ";
+
+/// Name shown for `{vault}` when the vault in use is not a configured one.
+const UNNAMED_VAULT: &str = "passmgr";
+
+/// Reads an answer to a command's question, such as a confirmation.
+///
+/// Uses a separate editor so the answer is not added to the shell history.
+fn read_answer(prompt: &str) -> Option<String> {
+    let mut editor = rustyline::DefaultEditor::new().ok()?;
+    editor.readline(prompt).ok()
+}
 
-/// The prompt displayed to the user.
-const PROMPT: &str = "passmgr> ";
+/// Number of master passwords that may be tried to unlock the shell after `lock`.
+const UNLOCK_ATTEMPTS: usize = 3;
+
+/// What became of the shell after [`Shell::relock`].
+enum Relock {
+    /// The vault was locked and unlocked again.
+    Unlocked,
+    /// The session could not be locked; nothing was cleared.
+    NotLocked(anyhow::Error),
+    /// The vault was locked, and could not be unlocked again.
+    StayedLocked(anyhow::Error),
+}
+
+/// Reads the master password without echoing it.
+pub fn read_master_password(prompt: &str) -> Option<String> {
+    rpassword::prompt_password(prompt)
+        .ok()
+        .map(|pwd| pwd.trim().to_string())
+}
+
+/// Asks for the master password until `session` unlocks, returning the
+/// unlocked credentials.
+///
+/// Fails after [`UNLOCK_ATTEMPTS`] wrong passwords, if no password could be
+/// read, or if the session fails, e.g. because it is locked out.
+pub fn unlock_session(
+    session: &mut dyn Session,
+    read_password: &mut dyn FnMut(&str) -> Option<String>,
+) -> Result<Credentials> {
+    for attempt in 1..=UNLOCK_ATTEMPTS {
+        let password = read_password("Master Password: ")
+            .ok_or_else(|| anyhow!("Failed to read master password"))?;
+        if let Some(unlocked) = session.unlock(password)? {
+            return Ok(unlocked);
+        }
+        log::warn!(
+            "Invalid password attempt {} of {}",
+            attempt,
+            UNLOCK_ATTEMPTS
+        );
+        if attempt < UNLOCK_ATTEMPTS {
+            eprintln!("Invalid master password, please try again");
+        }
+    }
+    Err(anyhow!("Invalid master password"))
+}
+
+/// Reads a secret for a command without echoing it.
+fn read_secret(prompt: &str) -> Option<String> {
+    rpassword::prompt_password(prompt).ok()
+}
+
+/// Renders a prompt template, replacing `{vault}` with the vault name and
+/// `{count}` with the number of credentials.
+///
+/// Any other text, including unknown placeholders, is kept as is.
+///
+/// ```
+/// use passmgr::shell::render_prompt;
+///
+/// assert_eq!(render_prompt("[{vault}:{count}]> ", "work", 12), "[work:12]> ");
+/// ```
+pub fn render_prompt(template: &str, vault: &str, count: usize) -> String {
+    template
+        .replace("{vault}", vault)
+        .replace("{count}", &count.to_string())
+}
+
+/// Shown when the shell starts.
+const WELCOME: &str = "Unlocked. Type 'help' for available commands.";
+
+/// Shown instead of [`WELCOME`] the first time a new vault is opened.
+const ONBOARDING: &str = "Your vault is ready. A few commands to get started:\n\n  \
+    add <name> <secret>   Store a secret under a name\n  \
+    get <name>            Show a stored secret (masked unless --show)\n  \
+    list                  List the names of stored secrets\n  \
+    search <text>         Find names containing some text\n  \
+    remove <name>         Delete a secret\n  \
+    help [command]        Show all commands, or details for one\n  \
+    quit                  Leave the shell (Ctrl-D also works)\n\n\
+    Press Tab to complete commands and names. Changes are saved automatically.";
 
 /// Combined helper for rustyline that provides all shell features.
 pub struct PassmgrHelper {
@@ -46,18 +155,33 @@ pub struct PassmgrHelper {
     highlighter: PassmgrHighlighter,
     hinter: PassmgrHinter,
     validator: MatchingBracketValidator,
+    /// Keeps the prompt's countdown to the idle lock current, if shown.
+    idle_countdown: Option<IdleTimer>,
 }
 
 impl PassmgrHelper {
     /// Creates a new helper with all shell features.
-    pub fn new(registry: Arc<CommandRegistry>, key_trie: Arc<RwLock<Trie>>) -> Self {
+    pub fn new(
+        registry: Arc<CommandRegistry>,
+        key_trie: Arc<RwLock<Trie>>,
+        secret_lengths: SecretLengths,
+        theme: Theme,
+    ) -> Self {
         Self {
-            completer: PassmgrCompleter::new(Arc::clone(&registry), Arc::clone(&key_trie)),
-            highlighter: PassmgrHighlighter::new(Arc::clone(&registry)),
-            hinter: PassmgrHinter::new(Arc::clone(&registry)),
+            completer: PassmgrCompleter::new(Arc::clone(&registry), Arc::clone(&key_trie))
+                .with_secret_lengths(secret_lengths),
+            highlighter: PassmgrHighlighter::new(Arc::clone(&registry), theme),
+            hinter: PassmgrHinter::new(Arc::clone(&registry), Arc::clone(&key_trie)),
             validator: MatchingBracketValidator::new(),
+            idle_countdown: None,
         }
     }
+
+    /// Sets the timer whose countdown is shown in the prompt.
+    pub fn with_idle_countdown(mut self, timer: IdleTimer) -> Self {
+        self.idle_countdown = Some(timer);
+        self
+    }
 }
 
 // Implement all required traits for PassmgrHelper
@@ -85,7 +209,17 @@ impl Highlighter for PassmgrHelper {
         prompt: &'p str,
         default: bool,
     ) -> Cow<'b, str> {
-        self.highlighter.highlight_prompt(prompt, default)
+        match &self.idle_countdown {
+            Some(timer) => {
+                let prompt = timer.refresh_countdown(prompt);
+                Cow::Owned(
+                    self.highlighter
+                        .highlight_prompt(&prompt, default)
+                        .into_owned(),
+                )
+            }
+            None => self.highlighter.highlight_prompt(prompt, default),
+        }
     }
 
     fn highlight_hint<'h>(&self, hint: &'h str) -> Cow<'h, str> {
@@ -122,11 +256,43 @@ impl Validator for PassmgrHelper {
 impl Helper for PassmgrHelper {}
 
 /// Configuration for the shell.
+#[derive(Debug, Clone)]
 pub struct ShellConfig {
     /// History configuration.
     pub history: HistoryConfig,
     /// Whether to show the welcome message.
     pub show_welcome: bool,
+    /// Whether the welcome message introduces the core commands, for a vault
+    /// that was just created.
+    pub onboarding: bool,
+    /// Colors for input and output.
+    pub theme: Theme,
+    /// Inactivity after which the shell locks, even while waiting for input.
+    pub idle_timeout: Option<Duration>,
+    /// Whether the prompt shows the time left before the idle lock.
+    pub show_idle_countdown: bool,
+    /// Whether `get` prints secrets in plain text rather than masked.
+    pub reveal_secrets: bool,
+    /// File caching the key trie between runs, if enabled.
+    ///
+    /// The cache holds credential names unencrypted.
+    pub key_cache: Option<PathBuf>,
+    /// Output longer than this many lines is shown through the pager.
+    pub pager_threshold: Option<usize>,
+    /// How credential names are normalized on add and lookup.
+    pub name_normalization: NameNormalization,
+    /// Prompt template; see [`render_prompt`] for the placeholders.
+    pub prompt: String,
+    /// Whether destructive commands such as `remove` ask for confirmation.
+    pub confirm_destructive: bool,
+    /// Whether reading a secret records when and how often it was read.
+    ///
+    /// This makes reads change the vault, so they are saved.
+    pub track_access: bool,
+    /// Output format of commands that can print JSON.
+    pub output_format: OutputFormat,
+    /// Whether adding a weak secret prints a warning.
+    pub warn_weak_secrets: bool,
 }
 
 impl Default for ShellConfig {
@@ -134,18 +300,47 @@ impl Default for ShellConfig {
         Self {
             history: HistoryConfig::default(),
             show_welcome: true,
+            onboarding: false,
+            theme: Theme::default(),
+            idle_timeout: None,
+            show_idle_countdown: false,
+            reveal_secrets: false,
+            key_cache: None,
+            pager_threshold: None,
+            name_normalization: NameNormalization::None,
+            prompt: PROMPT.to_string(),
+            confirm_destructive: false,
+            track_access: false,
+            output_format: OutputFormat::Text,
+            warn_weak_secrets: true,
         }
     }
 }
 
+/// Why the interactive shell stopped.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ShellExit {
+    /// The user quit, or the session ended on its own.
+    Quit,
+    /// The user suspended the process; the host should lock the vault before
+    /// stopping and ask for the master password on resume.
+    Suspended,
+}
+
 /// The interactive shell.
 pub struct Shell {
     /// Command registry.
     registry: Arc<CommandRegistry>,
     /// Key trie for completion (shared with helper).
     key_trie: Arc<RwLock<Trie>>,
+    /// Secret lengths shown next to key completions (shared with helper).
+    secret_lengths: SecretLengths,
     /// Shell configuration.
     config: ShellConfig,
+    /// Shows output longer than `config.pager_threshold`.
+    pager: Box<dyn Pager>,
+    /// Where `pwned` looks up breached secrets, if this build can.
+    breach_source: Option<Box<dyn RangeSource>>,
 }
 
 impl Shell {
@@ -157,7 +352,10 @@ impl Shell {
         Self {
             registry: Arc::new(registry),
             key_trie: Arc::new(RwLock::new(Trie::new())),
+            secret_lengths: SecretLengths::default(),
             config: ShellConfig::default(),
+            pager: Box::new(SystemPager),
+            breach_source: pwned::default_source(),
         }
     }
 
@@ -169,38 +367,205 @@ impl Shell {
         Self {
             registry: Arc::new(registry),
             key_trie: Arc::new(RwLock::new(Trie::new())),
+            secret_lengths: SecretLengths::default(),
             config,
+            pager: Box::new(SystemPager),
+            breach_source: pwned::default_source(),
         }
     }
 
+    /// Replaces the pager used for long output.
+    #[allow(unused)]
+    pub fn with_pager(mut self, pager: Box<dyn Pager>) -> Self {
+        self.pager = pager;
+        self
+    }
+
+    /// Replaces where `pwned` looks up breached secrets.
+    #[allow(unused)]
+    pub fn with_breach_source(mut self, source: Box<dyn RangeSource>) -> Self {
+        self.breach_source = Some(source);
+        self
+    }
+
     /// Initializes the key trie from existing credentials.
+    ///
+    /// With a key cache configured, a cached trie built from the same keys is
+    /// reused; otherwise the trie is rebuilt and the cache refreshed.
     fn init_key_trie(&self, credentials: &Credentials) {
-        if let Ok(mut trie) = self.key_trie.write() {
-            trie.clear();
-            for key in credentials.list() {
-                trie.insert(key);
+        let mut trie = self.write_key_trie(credentials);
+        let checksum = keys_checksum(credentials.list().into_iter().map(String::as_str));
+
+        if let Some(path) = &self.config.key_cache
+            && let Some(cached) = load_key_cache(path, checksum)
+        {
+            *trie = cached;
+            log::debug!("Loaded key trie with {} entries from cache", trie.len());
+            return;
+        }
+
+        trie.clear();
+        for key in credentials.list() {
+            trie.insert(key);
+        }
+        log::debug!("Initialized key trie with {} entries", trie.len());
+
+        if let Some(path) = &self.config.key_cache
+            && let Err(e) = save_key_cache(path, checksum, &trie)
+        {
+            log::warn!("Could not write key cache: {}", e);
+        }
+    }
+
+    /// Locks the key trie for writing.
+    ///
+    /// If a command panicked while holding the lock, the trie may be half
+    /// updated, so the poison is cleared and the trie rebuilt from
+    /// `credentials` instead of ending the session.
+    fn write_key_trie(&self, credentials: &Credentials) -> RwLockWriteGuard<'_, Trie> {
+        match self.key_trie.write() {
+            Ok(trie) => trie,
+            Err(poisoned) => {
+                log::warn!("Key trie lock was poisoned, rebuilding the key trie");
+                let mut trie = poisoned.into_inner();
+                self.key_trie.clear_poison();
+                trie.clear();
+                for key in credentials.list() {
+                    trie.insert(key);
+                }
+                trie
             }
-            log::debug!("Initialized key trie with {} entries", trie.len());
+        }
+    }
+
+    /// Refreshes the secret lengths shown next to key completions.
+    fn refresh_secret_lengths(&self, credentials: &Credentials) {
+        if let Ok(mut lengths) = self.secret_lengths.write() {
+            *lengths = credentials
+                .iter()
+                .map(|(name, secret)| (name.clone(), secret.chars().count()))
+                .collect();
+        }
+    }
+
+    /// Locks `session` and wipes `credentials`, the key trie and the secret
+    /// lengths shown in completions.
+    ///
+    /// Nothing is cleared if the session cannot be locked.
+    fn lock(&self, credentials: &mut Credentials, session: &mut dyn Session) -> Result<()> {
+        session.lock()?;
+        credentials.wipe();
+        self.write_key_trie(credentials).clear();
+        self.refresh_secret_lengths(credentials);
+        log::info!("Shell locked");
+        Ok(())
+    }
+
+    /// Asks for the master password until `session` unlocks, then rebuilds
+    /// the key trie from the unlocked credentials.
+    ///
+    /// Fails as [`unlock_session`] does.
+    fn unlock(
+        &self,
+        credentials: &mut Credentials,
+        session: &mut dyn Session,
+        read_password: &mut dyn FnMut(&str) -> Option<String>,
+    ) -> Result<()> {
+        *credentials = unlock_session(session, read_password)?;
+        self.init_key_trie(credentials);
+        self.refresh_secret_lengths(credentials);
+        log::info!("Shell unlocked");
+        Ok(())
+    }
+
+    /// Locks the shell, announcing it with `reason`, then asks for the
+    /// master password until the vault is unlocked again.
+    ///
+    /// Secrets kept for undo are dropped with the rest.
+    fn relock(
+        &self,
+        reason: &str,
+        credentials: &mut Credentials,
+        session: &mut dyn Session,
+        undo: &mut UndoStack,
+        read_password: &mut dyn FnMut(&str) -> Option<String>,
+    ) -> Relock {
+        if let Err(e) = self.lock(credentials, session) {
+            return Relock::NotLocked(e);
+        }
+        // Replaced secrets kept for undo must not outlive the lock
+        undo.clear();
+        println!("{} Enter your MASTER password to unlock it again.", reason);
+        match self.unlock(credentials, session, read_password) {
+            Ok(()) => Relock::Unlocked,
+            Err(e) => Relock::StayedLocked(e),
         }
     }
 
     /// Runs the interactive shell with a save callback.
-    pub fn run_with_save<F>(&self, credentials: &mut Credentials, mut save_fn: F) -> Result<()>
+    #[allow(unused)]
+    pub fn run_with_save<F>(&self, credentials: &mut Credentials, save_fn: F) -> Result<ShellExit>
     where
         F: FnMut(&Credentials) -> Result<()>,
     {
+        let mut session = SaveFnSession::new(save_fn);
+        self.run_with_session(credentials, &mut session)
+    }
+
+    /// Runs the interactive shell, saving and delegating vault operations to `session`.
+    pub fn run_with_session(
+        &self,
+        credentials: &mut Credentials,
+        session: &mut dyn Session,
+    ) -> Result<ShellExit> {
         // Initialize key trie from existing credentials
         self.init_key_trie(credentials);
+        self.refresh_secret_lengths(credentials);
 
         // Create the helper
-        let helper = PassmgrHelper::new(Arc::clone(&self.registry), Arc::clone(&self.key_trie));
+        let idle = self.config.idle_timeout.map(IdleTimer::new);
+        let mut helper = PassmgrHelper::new(
+            Arc::clone(&self.registry),
+            Arc::clone(&self.key_trie),
+            Arc::clone(&self.secret_lengths),
+            self.config.theme.clone(),
+        );
+        if let Some(timer) = &idle
+            && self.config.show_idle_countdown
+        {
+            helper = helper.with_idle_countdown(timer.clone());
+        }
+        let output = OutputHighlighter::new(self.config.theme.clone());
+        // Interrupts reading a line once the idle timeout passes
+        let watcher = idle
+            .clone()
+            .map(|timer| IdleWatcher::spawn(timer, interrupt_current_thread()));
 
         // Create the editor with our custom helper
         let mut editor: Editor<PassmgrHelper, FileHistory> = Editor::new()?;
         editor.set_helper(Some(helper));
 
-        // Configure history
+        // List candidates (rather than cycling) so completion summaries are visible
+        editor.set_completion_type(CompletionType::List);
+
+        // Lock instead of stopping with the vault open on Ctrl-Z or SIGTSTP
+        let suspend = match SuspendSignal::install() {
+            Ok(suspend) => {
+                editor.bind_sequence(
+                    KeyEvent::ctrl('Z'),
+                    EventHandler::Conditional(Box::new(suspend.key_handler())),
+                );
+                Some(suspend)
+            }
+            Err(e) => {
+                log::warn!("Could not install suspend handler: {}", e);
+                None
+            }
+        };
+
+        // Configure history; what is added is decided by the history filter
         editor.set_max_history_size(self.config.history.max_entries)?;
+        history::disable_editor_filtering(&mut editor)?;
 
         // Load existing history if the file exists
         if self.config.history.path.exists() {
@@ -210,60 +575,172 @@ impl Shell {
                 log::debug!("Loaded history from {}", self.config.history.path.display());
             }
         }
+        let mut history_filter =
+            HistoryFilter::for_history(self.config.history.clone(), editor.history());
 
-        if self.config.show_welcome {
-            println!("Unlocked. Type 'help' for available commands.");
+        if let Some(message) = self.welcome_message() {
+            println!("{}", message);
         }
 
         log::info!("Shell started");
 
         // Main REPL loop
+        let mut exit = ShellExit::Quit;
+        let mut undo = UndoStack::default();
         loop {
-            match editor.readline(PROMPT) {
+            // The timeout may also pass while a command waits for an answer
+            let readline = if idle.as_ref().is_some_and(IdleTimer::expired) {
+                Err(ReadlineError::Interrupted)
+            } else {
+                let prompt = self.prompt(credentials, session);
+                let _waiting = watcher.as_ref().map(IdleWatcher::wait_for_input);
+                editor.readline(&prompt)
+            };
+            if suspend.as_ref().is_some_and(SuspendSignal::take) {
+                println!("Session locked on suspend.");
+                log::info!("Shell locked on suspend");
+                exit = ShellExit::Suspended;
+                break;
+            }
+
+            // Whatever was typed before the timeout passed is discarded
+            if let Some(timer) = &idle
+                && timer.expired()
+                && matches!(readline, Ok(_) | Err(ReadlineError::Interrupted))
+            {
+                let reason = format!(
+                    "Vault locked after {}s of inactivity.",
+                    timer.timeout().as_secs()
+                );
+                log::info!("Locking shell after idle timeout");
+                match self.relock(
+                    &reason,
+                    credentials,
+                    session,
+                    &mut undo,
+                    &mut read_master_password,
+                ) {
+                    Relock::Unlocked => {
+                        println!("{}", WELCOME);
+                        timer.reset();
+                        continue;
+                    }
+                    Relock::NotLocked(e) => {
+                        // Without a way to lock, close the shell rather than stay open
+                        log::warn!("Could not lock after idle timeout: {}", e);
+                        println!(
+                            "Session closed after {}s of inactivity.",
+                            timer.timeout().as_secs()
+                        );
+                    }
+                    Relock::StayedLocked(e) => {
+                        eprintln!("{}", output.error(&format!("Could not unlock: {}", e)));
+                        log::error!("Failed to unlock after idle timeout: {}", e);
+                    }
+                }
+                break;
+            }
+
+            match readline {
                 Ok(line) => {
+                    if let Some(timer) = &idle {
+                        timer.reset();
+                    }
+
+                    // Add to history, as typed so a leading space can keep it out
+                    history::add_entry(editor.history_mut(), &mut history_filter, &line);
+
                     let line = line.trim();
                     if line.is_empty() {
                         continue;
                     }
 
-                    // Add to history
-                    let _ = editor.add_history_entry(line);
-
                     // Parse and execute command
-                    let mut key_trie_guard = self
-                        .key_trie
-                        .write()
-                        .map_err(|e| anyhow!("Key trie lock poisoned: {}", e))?;
+                    let mut key_trie_guard = self.write_key_trie(credentials);
+                    let mut history =
+                        EditorHistory::new(editor.history_mut(), &self.config.history.path);
+                    let mut prompt = read_answer;
+                    let mut secret_reader = read_secret;
                     let mut ctx = ShellContext::new(credentials, &mut key_trie_guard)
-                        .with_registry(&self.registry);
+                        .with_registry(&self.registry)
+                        .with_session(&mut *session)
+                        .with_history(&mut history)
+                        .with_reveal_secrets(self.config.reveal_secrets)
+                        .with_name_normalization(self.config.name_normalization)
+                        .with_confirm_destructive(self.config.confirm_destructive)
+                        .with_prompt(&mut prompt)
+                        .with_secret_reader(&mut secret_reader)
+                        .with_undo(&mut undo)
+                        .with_track_access(self.config.track_access)
+                        .with_output_format(self.config.output_format)
+                        .with_warn_weak_secrets(self.config.warn_weak_secrets);
+                    if let Some(source) = self.breach_source.as_deref() {
+                        ctx = ctx.with_breach_source(source);
+                    }
 
                     let result = self.execute_with_context(line, &mut ctx);
                     let was_modified = ctx.modified;
+                    let warnings = std::mem::take(&mut ctx.warnings);
                     drop(key_trie_guard);
+                    if editor.history().is_empty() {
+                        // `history clear` forgot the entry duplicates are checked against
+                        history_filter.reset();
+                    }
+                    let mut lock = false;
 
                     match result {
                         CommandResult::Success(Some(msg)) => {
-                            println!("{}", msg);
+                            self.print_output(&msg);
                         }
                         CommandResult::Success(None) => {}
+                        CommandResult::Json(value) => {
+                            self.print_output(&format!("{:#}", value));
+                        }
                         CommandResult::Error(msg) => {
-                            eprintln!("{}", OutputHighlighter::error(&msg));
+                            eprintln!("{}", output.error(&msg));
                         }
                         CommandResult::Exit => {
                             log::info!("User requested exit");
                             break;
                         }
+                        CommandResult::Lock => lock = true,
                         CommandResult::Continue => {}
                     }
+                    for warning in &warnings {
+                        eprintln!("{}", output.hint(warning));
+                    }
+
+                    // Commands such as `use` replace credentials without modifying them
+                    self.refresh_secret_lengths(credentials);
 
                     // Save if credentials were modified
-                    if was_modified {
-                        if let Err(e) = save_fn(credentials) {
-                            eprintln!(
-                                "{}",
-                                OutputHighlighter::error(&format!("Failed to save: {}", e))
-                            );
-                            log::error!("Failed to save credentials: {}", e);
+                    if was_modified && let Err(e) = session.save(credentials) {
+                        eprintln!("{}", output.error(&format!("Failed to save: {}", e)));
+                        log::error!("Failed to save credentials: {}", e);
+                    }
+
+                    if lock {
+                        match self.relock(
+                            "Vault locked.",
+                            credentials,
+                            session,
+                            &mut undo,
+                            &mut read_master_password,
+                        ) {
+                            Relock::Unlocked => {
+                                println!("{}", WELCOME);
+                                if let Some(timer) = &idle {
+                                    timer.reset();
+                                }
+                            }
+                            Relock::NotLocked(e) => {
+                                eprintln!("{}", output.error(&format!("Could not lock: {}", e)));
+                            }
+                            Relock::StayedLocked(e) => {
+                                eprintln!("{}", output.error(&format!("Could not unlock: {}", e)));
+                                log::error!("Failed to unlock after lock: {}", e);
+                                break;
+                            }
                         }
                     }
                 }
@@ -278,58 +755,181 @@ impl Shell {
                     break;
                 }
                 Err(err) => {
-                    eprintln!("{}", OutputHighlighter::error(&format!("Error: {}", err)));
+                    eprintln!("{}", output.error(&format!("Error: {}", err)));
                     log::error!("Readline error: {}", err);
                     break;
                 }
             }
         }
 
-        // Save history
-        if let Some(parent) = self.config.history.path.parent() {
-            if !parent.exists() {
-                let _ = std::fs::create_dir_all(parent);
-            }
-        }
-        if let Err(e) = editor.save_history(&self.config.history.path) {
+        // Save history and sync it to disk
+        if let Err(e) = history::save_history(editor.history_mut(), &self.config.history.path) {
             log::warn!("Failed to save history: {}", e);
         } else {
             log::debug!("Saved history to {}", self.config.history.path.display());
         }
 
         log::info!("Shell exited");
-        Ok(())
+        Ok(exit)
+    }
+
+    /// Renders the configured prompt for the current vault and credentials.
+    fn prompt(&self, credentials: &Credentials, session: &dyn Session) -> String {
+        // Only ask the session for vaults when the name is shown
+        let vault = if self.config.prompt.contains("{vault}") {
+            session
+                .vaults()
+                .ok()
+                .and_then(|vaults| vaults.into_iter().find(|vault| vault.active))
+                .map(|vault| vault.name)
+        } else {
+            None
+        };
+        let prompt = render_prompt(
+            &self.config.prompt,
+            vault.as_deref().unwrap_or(UNNAMED_VAULT),
+            credentials.len(),
+        );
+        match self.config.idle_timeout {
+            // A placeholder the helper replaces with the time left
+            Some(timeout) if self.config.show_idle_countdown => {
+                insert_countdown(&prompt, &IdleTimer::new(timeout).placeholder())
+            }
+            _ => prompt,
+        }
+    }
+
+    /// Returns the message to show when the shell starts, if any.
+    fn welcome_message(&self) -> Option<&'static str> {
+        match (self.config.show_welcome, self.config.onboarding) {
+            (false, _) => None,
+            (true, true) => Some(ONBOARDING),
+            (true, false) => Some(WELCOME),
+        }
+    }
+
+    /// Prints a command's output, through the pager if it is long.
+    fn print_output(&self, text: &str) {
+        let stdout = std::io::stdout();
+        let is_terminal = stdout.is_terminal();
+        let result = pager::display(
+            text,
+            self.config.pager_threshold,
+            is_terminal,
+            self.pager.as_ref(),
+            &mut stdout.lock(),
+        );
+        if let Err(e) = result {
+            log::error!("Failed to print output: {}", e);
+        }
+    }
+
+    /// Executes a single command outside the interactive loop.
+    ///
+    /// `args` is the command name followed by its arguments, already split
+    /// (e.g. from the process command line). Secrets are printed in plain
+    /// text so the output can be piped. Credentials are saved through
+    /// `session` if the command modified them; a failed save is reported as
+    /// an error result.
+    pub fn run_once(
+        &self,
+        args: &[&str],
+        credentials: &mut Credentials,
+        session: &mut dyn Session,
+    ) -> CommandResult {
+        self.init_key_trie(credentials);
+
+        let mut key_trie_guard = self.write_key_trie(credentials);
+        let mut ctx = ShellContext::new(credentials, &mut key_trie_guard)
+            .with_registry(&self.registry)
+            .with_session(&mut *session)
+            .with_reveal_secrets(true)
+            .with_name_normalization(self.config.name_normalization)
+            .with_confirm_destructive(self.config.confirm_destructive)
+            .with_track_access(self.config.track_access)
+            .with_output_format(self.config.output_format)
+            .with_warn_weak_secrets(self.config.warn_weak_secrets);
+        if let Some(source) = self.breach_source.as_deref() {
+            ctx = ctx.with_breach_source(source);
+        }
+
+        let result = self.dispatch(args, &mut ctx);
+        let was_modified = ctx.modified;
+        let output = OutputHighlighter::new(self.config.theme.clone());
+        for warning in &ctx.warnings {
+            eprintln!("{}", output.hint(warning));
+        }
+        drop(key_trie_guard);
+
+        if was_modified && let Err(e) = session.save(credentials) {
+            log::error!("Failed to save credentials: {}", e);
+            return CommandResult::error(format!("Failed to save: {}", e));
+        }
+
+        result
     }
 
-    /// Parses and executes a command line.
+    /// Parses and executes a single command line, as typed in the shell.
+    ///
+    /// Quotes and escapes are honored as in the interactive loop. Changes
+    /// are made to `credentials` only; nothing is saved, so persisting them
+    /// is up to the caller.
+    ///
+    /// ```
+    /// use passmgr::shell::command::CommandResult;
+    /// use passmgr::{Credentials, Shell};
+    ///
+    /// let shell = Shell::new();
+    /// let mut credentials = Credentials::new();
+    ///
+    /// shell.run_command(r#"add "my email" hunter2"#, &mut credentials);
+    /// assert_eq!(credentials.get("my email"), Some(&"hunter2".to_string()));
+    ///
+    /// let result = shell.run_command("get --show 'my email'", &mut credentials);
+    /// assert!(matches!(result, CommandResult::Success(Some(_))));
+    /// ```
     #[allow(unused)]
-    fn execute_line(&self, line: &str, credentials: &mut Credentials) -> CommandResult {
-        let mut key_trie_guard = self.key_trie.write().unwrap();
-        let mut ctx =
-            ShellContext::new(credentials, &mut key_trie_guard).with_registry(&self.registry);
+    pub fn run_command(&self, line: &str, credentials: &mut Credentials) -> CommandResult {
+        self.init_key_trie(credentials);
+
+        let mut key_trie_guard = self.write_key_trie(credentials);
+        let mut ctx = ShellContext::new(credentials, &mut key_trie_guard)
+            .with_registry(&self.registry)
+            .with_reveal_secrets(self.config.reveal_secrets)
+            .with_name_normalization(self.config.name_normalization);
 
         self.execute_with_context(line, &mut ctx)
     }
 
     /// Executes a command with the given context.
     fn execute_with_context(&self, line: &str, ctx: &mut ShellContext) -> CommandResult {
-        // Parse the line into command and arguments
-        let parts: Vec<&str> = line.split_whitespace().collect();
+        // Parse the line into command and arguments, honoring quotes and escapes
+        let parts = match split_line(line) {
+            Ok(parts) => parts,
+            Err(e) => return CommandResult::error(format!("Could not parse command: {}", e)),
+        };
+
+        let parts: Vec<&str> = parts.iter().map(String::as_str).collect();
+        self.dispatch(&parts, ctx)
+    }
 
-        if parts.is_empty() {
+    /// Looks up and executes an already-split command line.
+    fn dispatch(&self, parts: &[&str], ctx: &mut ShellContext) -> CommandResult {
+        let Some((&cmd_name, args)) = parts.split_first() else {
             return CommandResult::Continue;
-        }
-
-        let cmd_name = parts[0];
-        let args: Vec<&str> = parts[1..].to_vec();
+        };
 
-        log::debug!("Executing command: {} with args: {:?}", cmd_name, args);
+        // Arguments may include secrets, so only their number is logged
+        log::debug!("Executing command: {} with {} args", cmd_name, args.len());
 
         // Look up the command
         match self.registry.get(cmd_name) {
             Some(cmd) => {
+                if let Err(usage) = check_arg_count(cmd.as_ref(), args) {
+                    return usage;
+                }
                 let start = std::time::Instant::now();
-                let result = cmd.execute(&args, ctx);
+                let result = cmd.execute(args, ctx);
                 let duration = start.elapsed();
                 log::debug!("Command '{}' completed in {:?}", cmd_name, duration);
                 result
@@ -348,9 +948,40 @@ impl Default for Shell {
     }
 }
 
+/// Reads a cached key trie, returning it only if it was built from keys with
+/// `checksum`.
+///
+/// The cache is the checksum as 8 little-endian bytes followed by the
+/// serialized trie. A missing or unreadable cache is treated as stale.
+fn load_key_cache(path: &Path, checksum: u64) -> Option<Trie> {
+    let bytes = std::fs::read(path).ok()?;
+    let (stored, trie) = bytes.split_first_chunk::<8>()?;
+    if u64::from_le_bytes(*stored) != checksum {
+        log::debug!("Key cache is stale");
+        return None;
+    }
+    Trie::from_bytes(trie)
+        .inspect_err(|e| log::warn!("Could not read key cache: {}", e))
+        .ok()
+}
+
+/// Writes `trie` to the key cache, tagged with the checksum of its keys.
+fn save_key_cache(path: &Path, checksum: u64, trie: &Trie) -> Result<()> {
+    if let Some(parent) = path.parent()
+        && !parent.as_os_str().is_empty()
+    {
+        std::fs::create_dir_all(parent)?;
+    }
+    let mut bytes = checksum.to_le_bytes().to_vec();
+    bytes.extend(trie.to_bytes());
+    std::fs::write(path, bytes)?;
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use undo::Change;
 
     #[test]
     fn test_shell_creation() {
@@ -359,47 +990,233 @@ mod tests {
     }
 
     #[test]
-    fn test_execute_line_unknown_command() {
+    fn test_render_prompt_placeholders() {
+        assert_eq!(
+            render_prompt("[{vault}:{count}]> ", "work", 3),
+            "[work:3]> "
+        );
+        assert_eq!(
+            render_prompt("{vault} {vault} ({count})> ", "home", 0),
+            "home home (0)> "
+        );
+        // Unknown placeholders are left alone
+        assert_eq!(render_prompt("{user}> ", "work", 3), "{user}> ");
+    }
+
+    #[test]
+    fn test_render_literal_prompt() {
+        assert_eq!(render_prompt(PROMPT, "work", 3), "passmgr> ");
+        assert_eq!(render_prompt("", "work", 3), "");
+    }
+
+    #[test]
+    fn test_prompt_uses_active_vault() {
+        struct VaultSession;
+        impl Session for VaultSession {
+            fn save(&mut self, _credentials: &Credentials) -> Result<()> {
+                Ok(())
+            }
+
+            fn vaults(&self) -> Result<Vec<crate::manager::VaultInfo>> {
+                Ok(["home", "work"]
+                    .into_iter()
+                    .map(|name| crate::manager::VaultInfo {
+                        name: name.to_string(),
+                        path: PathBuf::from(format!("{}.db", name)),
+                        active: name == "work",
+                    })
+                    .collect())
+            }
+        }
+
+        let shell = Shell::with_config(ShellConfig {
+            prompt: "[{vault}:{count}]> ".to_string(),
+            ..ShellConfig::default()
+        });
+        let mut credentials = Credentials::new();
+        credentials
+            .add("github".to_string(), "secret".to_string())
+            .unwrap();
+
+        assert_eq!(shell.prompt(&credentials, &VaultSession), "[work:1]> ");
+        // Sessions without vaults fall back to a fixed name
+        let save_session = SaveFnSession::new(|_: &Credentials| Ok(()));
+        assert_eq!(shell.prompt(&credentials, &save_session), "[passmgr:1]> ");
+    }
+
+    #[test]
+    fn test_prompt_idle_countdown() {
+        let session = SaveFnSession::new(|_: &Credentials| Ok(()));
+        let credentials = Credentials::new();
+        let shell = |idle_timeout, show_idle_countdown| {
+            Shell::with_config(ShellConfig {
+                idle_timeout,
+                show_idle_countdown,
+                ..ShellConfig::default()
+            })
+        };
+        let timeout = Some(Duration::from_secs(300));
+
+        assert_eq!(
+            shell(timeout, true).prompt(&credentials, &session),
+            "passmgr [5:00]> "
+        );
+        // Opt-in, and only with a timeout to count down
+        assert_eq!(
+            shell(timeout, false).prompt(&credentials, &session),
+            "passmgr> "
+        );
+        assert_eq!(
+            shell(None, true).prompt(&credentials, &session),
+            "passmgr> "
+        );
+
+        // The helper repaints the placeholder with the time left
+        let helper = PassmgrHelper::new(
+            Arc::new(CommandRegistry::new()),
+            Arc::new(RwLock::new(Trie::new())),
+            SecretLengths::default(),
+            Theme::plain(),
+        )
+        .with_idle_countdown(IdleTimer::new(Duration::from_secs(300)));
+        let painted = helper.highlight_prompt("passmgr [5:00]> ", true);
+        assert!(painted == "passmgr [5:00]> " || painted == "passmgr [4:59]> ");
+    }
+
+    #[test]
+    fn test_run_command_unknown_command() {
         let shell = Shell::new();
         let mut credentials = Credentials::new();
 
-        let result = shell.execute_line("unknown_cmd", &mut credentials);
+        let result = shell.run_command("unknown_cmd", &mut credentials);
         assert!(matches!(result, CommandResult::Error(_)));
     }
 
     #[test]
-    fn test_execute_line_help() {
+    fn test_run_command_help() {
         let shell = Shell::new();
         let mut credentials = Credentials::new();
 
-        let result = shell.execute_line("help", &mut credentials);
+        let result = shell.run_command("help", &mut credentials);
         assert!(matches!(result, CommandResult::Success(Some(_))));
     }
 
     #[test]
-    fn test_execute_line_quit() {
+    fn test_run_command_quit() {
         let shell = Shell::new();
         let mut credentials = Credentials::new();
 
-        let result = shell.execute_line("quit", &mut credentials);
+        let result = shell.run_command("quit", &mut credentials);
         assert!(matches!(result, CommandResult::Exit));
     }
 
     #[test]
-    fn test_execute_line_add_and_get() {
+    fn test_run_command_add_and_get() {
         let shell = Shell::new();
         let mut credentials = Credentials::new();
 
-        let result = shell.execute_line("add testkey testsecret", &mut credentials);
+        let result = shell.run_command("add testkey testsecret", &mut credentials);
         assert!(matches!(result, CommandResult::Success(_)));
 
-        let result = shell.execute_line("get testkey", &mut credentials);
+        let result = shell.run_command("get --show testkey", &mut credentials);
         match result {
             CommandResult::Success(Some(secret)) => assert_eq!(secret, "testsecret"),
             _ => panic!("Expected success with secret"),
         }
     }
 
+    #[test]
+    fn test_run_command_quoted_key() {
+        let shell = Shell::new();
+        let mut credentials = Credentials::new();
+
+        let result = shell.run_command(r#"add "my email" secret"#, &mut credentials);
+        assert!(matches!(result, CommandResult::Success(_)));
+        assert_eq!(credentials.get("my email"), Some(&"secret".to_string()));
+
+        let result = shell.run_command("get --show 'my email'", &mut credentials);
+        match result {
+            CommandResult::Success(Some(secret)) => assert_eq!(secret, "secret"),
+            _ => panic!("Expected success with secret"),
+        }
+    }
+
+    #[test]
+    fn test_run_command_quoted_secret() {
+        let shell = Shell::new();
+        let mut credentials = Credentials::new();
+
+        let result = shell.run_command(r#"add key "secret  with   spaces""#, &mut credentials);
+        assert!(matches!(result, CommandResult::Success(_)));
+        assert_eq!(
+            credentials.get("key"),
+            Some(&"secret  with   spaces".to_string())
+        );
+
+        let result = shell.run_command(r#"add other 'say "hi"' "it's""#, &mut credentials);
+        assert!(matches!(result, CommandResult::Success(_)));
+        assert_eq!(
+            credentials.get("other"),
+            Some(&r#"say "hi" it's"#.to_string())
+        );
+
+        let result = shell.run_command(r#"add escaped a\ b\"c"#, &mut credentials);
+        assert!(matches!(result, CommandResult::Success(_)));
+        assert_eq!(credentials.get("escaped"), Some(&r#"a b"c"#.to_string()));
+    }
+
+    #[test]
+    fn test_run_command_hash_is_not_a_comment() {
+        let shell = Shell::new();
+        let mut credentials = Credentials::new();
+
+        let result = shell.run_command("add k #abc", &mut credentials);
+        assert!(matches!(result, CommandResult::Success(_)));
+        assert_eq!(credentials.get("k"), Some(&"#abc".to_string()));
+
+        let result = shell.run_command("add #tag one # two", &mut credentials);
+        assert!(matches!(result, CommandResult::Success(_)));
+        assert_eq!(credentials.get("#tag"), Some(&"one # two".to_string()));
+    }
+
+    #[test]
+    fn test_run_command_unbalanced_quotes() {
+        let shell = Shell::new();
+        let mut credentials = Credentials::new();
+
+        let result = shell.run_command(r#"add "unterminated secret"#, &mut credentials);
+        assert!(matches!(result, CommandResult::Error(_)));
+        assert!(credentials.is_empty());
+    }
+
+    #[test]
+    fn test_recovers_from_poisoned_key_trie() {
+        let shell = Shell::new();
+        let mut credentials = Credentials::new();
+        credentials
+            .add("github".to_string(), "secret".to_string())
+            .unwrap();
+
+        // Panic while holding the lock, leaving a stale entry behind
+        let key_trie = Arc::clone(&shell.key_trie);
+        let _ = std::thread::spawn(move || {
+            let mut trie = key_trie.write().unwrap();
+            trie.insert("stale");
+            panic!("command panicked");
+        })
+        .join();
+        assert!(shell.key_trie.is_poisoned());
+
+        let result = shell.run_command("add email secret2", &mut credentials);
+        assert!(matches!(result, CommandResult::Success(_)));
+        assert!(!shell.key_trie.is_poisoned());
+
+        let trie = shell.key_trie.read().unwrap();
+        assert!(trie.contains("github"));
+        assert!(trie.contains("email"));
+        assert!(!trie.contains("stale"));
+    }
+
     #[test]
     fn test_key_trie_initialization() {
         let shell = Shell::new();
@@ -418,4 +1235,252 @@ mod tests {
         assert!(trie.contains("key2"));
         assert_eq!(trie.len(), 2);
     }
+
+    fn cached_shell(path: &Path) -> Shell {
+        Shell::with_config(ShellConfig {
+            key_cache: Some(path.to_path_buf()),
+            ..ShellConfig::default()
+        })
+    }
+
+    #[test]
+    fn test_key_cache_reused_when_keys_unchanged() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let path = temp_dir.path().join("keys.cache");
+        let mut credentials = Credentials::new();
+        credentials
+            .add("github".to_string(), "secret".to_string())
+            .unwrap();
+
+        cached_shell(&path).init_key_trie(&credentials);
+        assert!(path.exists());
+
+        // Plant a marker in the cache; it only shows up if the cache is used
+        let mut marked = Trie::new();
+        marked.insert("github");
+        marked.insert("marker");
+        let checksum = keys_checksum(["github"]);
+        save_key_cache(&path, checksum, &marked).unwrap();
+
+        let shell = cached_shell(&path);
+        shell.init_key_trie(&credentials);
+        assert!(shell.key_trie.read().unwrap().contains("marker"));
+    }
+
+    #[test]
+    fn test_key_cache_rebuilt_when_keys_change() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let path = temp_dir.path().join("keys.cache");
+        let mut credentials = Credentials::new();
+        credentials
+            .add("github".to_string(), "secret".to_string())
+            .unwrap();
+        cached_shell(&path).init_key_trie(&credentials);
+
+        credentials
+            .add("email".to_string(), "secret".to_string())
+            .unwrap();
+        let shell = cached_shell(&path);
+        shell.init_key_trie(&credentials);
+        assert!(shell.key_trie.read().unwrap().contains("email"));
+
+        // The refreshed cache matches the new keys
+        let cached = load_key_cache(&path, keys_checksum(["github", "email"])).unwrap();
+        assert_eq!(cached.completions(""), vec!["email", "github"]);
+    }
+
+    #[test]
+    fn test_key_cache_ignores_corrupt_file() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let path = temp_dir.path().join("keys.cache");
+        std::fs::write(&path, b"junk").unwrap();
+
+        let mut credentials = Credentials::new();
+        credentials
+            .add("github".to_string(), "secret".to_string())
+            .unwrap();
+        let shell = cached_shell(&path);
+        shell.init_key_trie(&credentials);
+        assert_eq!(
+            shell.key_trie.read().unwrap().completions(""),
+            vec!["github"]
+        );
+    }
+
+    #[test]
+    fn test_welcome_message() {
+        let shell = Shell::new();
+        assert_eq!(shell.welcome_message(), Some(WELCOME));
+    }
+
+    #[test]
+    fn test_onboarding_for_new_vault() {
+        let shell = Shell::with_config(ShellConfig {
+            onboarding: true,
+            ..ShellConfig::default()
+        });
+        let message = shell.welcome_message().unwrap();
+        assert!(message.contains("Your vault is ready"));
+        for command in ["add", "get", "list", "help", "quit"] {
+            assert!(shell.registry.get(command).is_some());
+            assert!(message.contains(command), "missing {}", command);
+        }
+    }
+
+    #[test]
+    fn test_no_welcome_suppresses_onboarding() {
+        for onboarding in [false, true] {
+            let shell = Shell::with_config(ShellConfig {
+                show_welcome: false,
+                onboarding,
+                ..ShellConfig::default()
+            });
+            assert_eq!(shell.welcome_message(), None);
+        }
+    }
+
+    /// Session whose vault unlocks with "master" and holds one credential.
+    #[derive(Default)]
+    struct LockingSession {
+        locked: bool,
+    }
+
+    impl Session for LockingSession {
+        fn save(&mut self, _credentials: &Credentials) -> Result<()> {
+            Ok(())
+        }
+
+        fn lock(&mut self) -> Result<()> {
+            self.locked = true;
+            Ok(())
+        }
+
+        fn unlock(&mut self, password: String) -> Result<Option<Credentials>> {
+            if password != "master" {
+                return Ok(None);
+            }
+            self.locked = false;
+            let mut credentials = Credentials::new();
+            credentials
+                .add("github".to_string(), "secret".to_string())
+                .unwrap();
+            Ok(Some(credentials))
+        }
+    }
+
+    /// Returns a password source answering with `passwords` in turn.
+    fn scripted(passwords: &[&str]) -> impl FnMut(&str) -> Option<String> {
+        let mut passwords: Vec<String> = passwords.iter().rev().map(|p| p.to_string()).collect();
+        move |_| passwords.pop()
+    }
+
+    #[test]
+    fn test_lock_then_unlock() {
+        let shell = Shell::new();
+        let mut session = LockingSession::default();
+        let mut credentials = session.unlock("master".to_string()).unwrap().unwrap();
+        shell.init_key_trie(&credentials);
+        shell.refresh_secret_lengths(&credentials);
+
+        shell.lock(&mut credentials, &mut session).unwrap();
+        assert!(session.locked);
+        assert!(credentials.is_empty());
+        assert!(!shell.key_trie.read().unwrap().contains("github"));
+        assert!(shell.secret_lengths.read().unwrap().is_empty());
+
+        let mut passwords = scripted(&["wrong", "master"]);
+        shell
+            .unlock(&mut credentials, &mut session, &mut passwords)
+            .unwrap();
+        assert!(!session.locked);
+        assert_eq!(credentials.get("github"), Some(&"secret".to_string()));
+        assert!(shell.key_trie.read().unwrap().contains("github"));
+        assert_eq!(shell.secret_lengths.read().unwrap().get("github"), Some(&6));
+    }
+
+    #[test]
+    fn test_unlock_gives_up() {
+        let shell = Shell::new();
+        let mut session = LockingSession::default();
+        let mut credentials = Credentials::new();
+        shell.lock(&mut credentials, &mut session).unwrap();
+
+        let mut passwords = scripted(&["a", "b", "c", "master"]);
+        assert!(
+            shell
+                .unlock(&mut credentials, &mut session, &mut passwords)
+                .is_err()
+        );
+        assert!(session.locked);
+        assert!(credentials.is_empty());
+
+        // Running out of input fails rather than retrying forever
+        let mut passwords = scripted(&[]);
+        assert!(
+            shell
+                .unlock(&mut credentials, &mut session, &mut passwords)
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_relock_clears_undo_and_unlocks() {
+        let shell = Shell::new();
+        let mut session = LockingSession::default();
+        let mut credentials = session.unlock("master".to_string()).unwrap().unwrap();
+        let mut undo = UndoStack::new(10);
+        undo.push(Change::Added(vec!["github".to_string()]));
+
+        let mut passwords = scripted(&["wrong", "master"]);
+        let relock = shell.relock(
+            "Vault locked.",
+            &mut credentials,
+            &mut session,
+            &mut undo,
+            &mut passwords,
+        );
+        assert!(matches!(relock, Relock::Unlocked));
+        assert!(undo.is_empty());
+        assert!(credentials.contains_key("github"));
+
+        let mut passwords = scripted(&[]);
+        let relock = shell.relock(
+            "Vault locked.",
+            &mut credentials,
+            &mut session,
+            &mut undo,
+            &mut passwords,
+        );
+        assert!(matches!(relock, Relock::StayedLocked(_)));
+        assert!(session.locked);
+        assert!(credentials.is_empty());
+
+        // A session that cannot lock is left as it was
+        let mut session = SaveFnSession::new(|_: &Credentials| Ok(()));
+        credentials
+            .add("github".to_string(), "secret".to_string())
+            .unwrap();
+        let relock = shell.relock(
+            "Vault locked.",
+            &mut credentials,
+            &mut session,
+            &mut undo,
+            &mut passwords,
+        );
+        assert!(matches!(relock, Relock::NotLocked(_)));
+        assert!(credentials.contains_key("github"));
+    }
+
+    #[test]
+    fn test_lock_unsupported_keeps_credentials() {
+        let shell = Shell::new();
+        let mut session = SaveFnSession::new(|_: &Credentials| Ok(()));
+        let mut credentials = Credentials::new();
+        credentials
+            .add("github".to_string(), "secret".to_string())
+            .unwrap();
+
+        assert!(shell.lock(&mut credentials, &mut session).is_err());
+        assert!(credentials.contains_key("github"));
+    }
 }
@@ -0,0 +1,159 @@
+//! Paging long command output.
+//!
+//! Output longer than the configured number of lines is piped through the
+//! system pager (`$PAGER`, or `less`) when stdout is a terminal. Launching the
+//! pager goes through the [`Pager`] trait so it can be replaced in tests.
+
+use std::io::{self, Write};
+use std::process::{Command, Stdio};
+
+/// Pager used when `$PAGER` is unset or empty.
+pub const DEFAULT_PAGER: &str = "less";
+
+/// Options passed to `less` through `$LESS` unless the user set their own:
+/// quit if the text fits on one screen, keep colors, and don't clear the screen.
+const DEFAULT_LESS_OPTIONS: &str = "FRX";
+
+/// Shows text to the user one screen at a time.
+pub trait Pager {
+    /// Displays `text`, returning once the user has closed the pager.
+    fn page(&self, text: &str) -> io::Result<()>;
+}
+
+/// Pages through the program named by `$PAGER`, or [`DEFAULT_PAGER`].
+pub struct SystemPager;
+
+impl Pager for SystemPager {
+    fn page(&self, text: &str) -> io::Result<()> {
+        let command = pager_command(std::env::var("PAGER").ok());
+        let parts = shell_words::split(&command)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+        let Some((program, args)) = parts.split_first() else {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, "empty pager"));
+        };
+
+        let mut child = Command::new(program);
+        child.args(args).stdin(Stdio::piped());
+        if std::env::var_os("LESS").is_none() {
+            child.env("LESS", DEFAULT_LESS_OPTIONS);
+        }
+        let mut child = child.spawn()?;
+
+        if let Some(mut stdin) = child.stdin.take() {
+            // The user may quit before reading everything
+            match stdin.write_all(text.as_bytes()) {
+                Err(e) if e.kind() != io::ErrorKind::BrokenPipe => return Err(e),
+                _ => {}
+            }
+        }
+        child.wait()?;
+        Ok(())
+    }
+}
+
+/// Returns the pager command line, falling back to [`DEFAULT_PAGER`] when
+/// `$PAGER` is unset or blank.
+pub fn pager_command(env_pager: Option<String>) -> String {
+    env_pager
+        .filter(|pager| !pager.trim().is_empty())
+        .unwrap_or_else(|| DEFAULT_PAGER.to_string())
+}
+
+/// Writes `text` to `out`, or through `pager` when it has more than
+/// `threshold` lines and the output is a terminal.
+///
+/// If the pager cannot be started, the text is written to `out` instead.
+pub fn display(
+    text: &str,
+    threshold: Option<usize>,
+    is_terminal: bool,
+    pager: &dyn Pager,
+    out: &mut dyn Write,
+) -> io::Result<()> {
+    if is_terminal && threshold.is_some_and(|max| text.lines().count() > max) {
+        match pager.page(text) {
+            Ok(()) => return Ok(()),
+            Err(e) => log::warn!("Could not run pager, printing directly: {}", e),
+        }
+    }
+    writeln!(out, "{}", text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+
+    /// Records what it was asked to page instead of running a program.
+    #[derive(Default)]
+    struct StubPager {
+        paged: RefCell<Vec<String>>,
+        fail: bool,
+    }
+
+    impl Pager for StubPager {
+        fn page(&self, text: &str) -> io::Result<()> {
+            if self.fail {
+                return Err(io::Error::new(io::ErrorKind::NotFound, "no pager"));
+            }
+            self.paged.borrow_mut().push(text.to_string());
+            Ok(())
+        }
+    }
+
+    const LONG: &str = "a\nb\nc\nd";
+
+    #[test]
+    fn test_over_threshold_goes_to_pager() {
+        let pager = StubPager::default();
+        let mut out = Vec::new();
+
+        display(LONG, Some(3), true, &pager, &mut out).unwrap();
+        assert_eq!(*pager.paged.borrow(), vec![LONG.to_string()]);
+        assert!(out.is_empty());
+    }
+
+    #[test]
+    fn test_under_threshold_prints_directly() {
+        let pager = StubPager::default();
+        let mut out = Vec::new();
+
+        display(LONG, Some(4), true, &pager, &mut out).unwrap();
+        assert!(pager.paged.borrow().is_empty());
+        assert_eq!(String::from_utf8(out).unwrap(), "a\nb\nc\nd\n");
+    }
+
+    #[test]
+    fn test_no_pager_without_terminal_or_threshold() {
+        let pager = StubPager::default();
+
+        let mut out = Vec::new();
+        display(LONG, Some(1), false, &pager, &mut out).unwrap();
+        assert!(!out.is_empty());
+
+        let mut out = Vec::new();
+        display(LONG, None, true, &pager, &mut out).unwrap();
+        assert!(!out.is_empty());
+
+        assert!(pager.paged.borrow().is_empty());
+    }
+
+    #[test]
+    fn test_failing_pager_falls_back_to_printing() {
+        let pager = StubPager {
+            fail: true,
+            ..StubPager::default()
+        };
+        let mut out = Vec::new();
+
+        display(LONG, Some(1), true, &pager, &mut out).unwrap();
+        assert_eq!(String::from_utf8(out).unwrap(), "a\nb\nc\nd\n");
+    }
+
+    #[test]
+    fn test_pager_command() {
+        assert_eq!(pager_command(None), DEFAULT_PAGER);
+        assert_eq!(pager_command(Some("  ".to_string())), DEFAULT_PAGER);
+        assert_eq!(pager_command(Some("more".to_string())), "more");
+    }
+}
@@ -0,0 +1,141 @@
+//! Host-side session operations available to commands.
+//!
+//! Commands only see the in-memory credentials through [`ShellContext`].
+//! Anything that needs the vault file or the master password goes through
+//! the [`Session`] trait, which the password manager implements.
+//!
+//! [`ShellContext`]: crate::shell::command::ShellContext
+
+use anyhow::{Result, anyhow};
+
+use crate::backup::BackupReport;
+use crate::credentials::Credentials;
+use crate::crypto::KdfParams;
+use crate::manager::{VaultInfo, VerifyReport};
+use crate::storage::CryptoInfo;
+
+/// Operations the shell host provides to the REPL and its commands.
+///
+/// Only [`Session::save`] is required; the other operations default to an
+/// "unsupported" error so lightweight hosts (and tests) can ignore them.
+pub trait Session {
+    /// Persists the given credentials.
+    fn save(&mut self, credentials: &Credentials) -> Result<()>;
+
+    /// Attempts to decrypt every backup with the current master password.
+    fn verify_backups(&self) -> Result<Vec<BackupReport>> {
+        Err(anyhow!("Backups are not available in this session"))
+    }
+
+    /// Replaces the vault's contents with a backup that decrypts with
+    /// `password`, and returns the restored credentials.
+    fn restore_backup(&mut self, _name: &str, _password: String) -> Result<Credentials> {
+        Err(anyhow!("Backups are not available in this session"))
+    }
+
+    /// Lists the vaults that can be switched to.
+    fn vaults(&self) -> Result<Vec<VaultInfo>> {
+        Err(anyhow!("Vaults are not available in this session"))
+    }
+
+    /// Unlocks the named vault and returns its credentials.
+    ///
+    /// The previous vault's credentials and master password must be cleared.
+    fn switch_vault(&mut self, _name: &str, _password: String) -> Result<Credentials> {
+        Err(anyhow!("Vaults are not available in this session"))
+    }
+
+    /// Re-reads the vault file with the current master password and returns
+    /// its credentials, discarding any that were not saved.
+    fn reload(&mut self) -> Result<Credentials> {
+        Err(anyhow!("Reloading is not available in this session"))
+    }
+
+    /// Returns whether the credentials have changes that were not saved,
+    /// e.g. because saving failed.
+    fn has_unsaved_changes(&self) -> bool {
+        false
+    }
+
+    /// Checks that the vault file on disk is intact.
+    fn verify(&self) -> Result<VerifyReport> {
+        Err(anyhow!("Verification is not available in this session"))
+    }
+
+    /// Describes how the vault file is encrypted.
+    fn crypto_info(&self) -> Result<CryptoInfo> {
+        Err(anyhow!(
+            "Encryption details are not available in this session"
+        ))
+    }
+
+    /// Saves `credentials` with the master password derived using `params`
+    /// from now on.
+    fn reencrypt_with_params(
+        &mut self,
+        _params: KdfParams,
+        _credentials: &Credentials,
+    ) -> Result<()> {
+        Err(anyhow!(
+            "Changing encryption parameters is not available in this session"
+        ))
+    }
+
+    /// Clears the master password and any keys from memory, so the vault has
+    /// to be unlocked again with [`Session::unlock`].
+    fn lock(&mut self) -> Result<()> {
+        Err(anyhow!("Locking is not available in this session"))
+    }
+
+    /// Unlocks the vault after [`Session::lock`] and returns its credentials,
+    /// or `None` if `password` is not the master password.
+    fn unlock(&mut self, _password: String) -> Result<Option<Credentials>> {
+        Err(anyhow!("Locking is not available in this session"))
+    }
+}
+
+/// A session that only knows how to save, backed by a callback.
+pub struct SaveFnSession<F> {
+    save_fn: F,
+}
+
+impl<F> SaveFnSession<F>
+where
+    F: FnMut(&Credentials) -> Result<()>,
+{
+    /// Wraps a save callback.
+    pub fn new(save_fn: F) -> Self {
+        Self { save_fn }
+    }
+}
+
+impl<F> Session for SaveFnSession<F>
+where
+    F: FnMut(&Credentials) -> Result<()>,
+{
+    fn save(&mut self, credentials: &Credentials) -> Result<()> {
+        (self.save_fn)(credentials)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_save_fn_session_calls_callback() {
+        let mut saved = 0;
+        {
+            let mut session = SaveFnSession::new(|_: &Credentials| {
+                saved += 1;
+                Ok(())
+            });
+
+            session.save(&Credentials::new()).unwrap();
+            session.save(&Credentials::new()).unwrap();
+            assert!(session.verify_backups().is_err());
+        }
+
+        assert_eq!(saved, 2);
+    }
+}
@@ -0,0 +1,107 @@
+//! Locking the shell when the process is suspended.
+//!
+//! A stopped process can be inspected while it still holds decrypted
+//! secrets. On Unix, [`SuspendSignal`] catches `SIGTSTP` (and the shell binds
+//! Ctrl-Z to it) so the shell can lock the vault first, then stop the process
+//! with [`suspend_process`]. The master password must be entered again on
+//! resume.
+
+use std::io;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use rustyline::{Cmd, ConditionalEventHandler, Event, EventContext, RepeatCount};
+
+/// Records suspend requests until the REPL gets to act on them.
+///
+/// While installed, `SIGTSTP` no longer stops the process; it only sets the
+/// flag. The handler is removed when this is dropped.
+pub struct SuspendSignal {
+    requested: Arc<AtomicBool>,
+    #[cfg(unix)]
+    id: signal_hook::SigId,
+}
+
+impl SuspendSignal {
+    /// Installs the `SIGTSTP` handler.
+    ///
+    /// On platforms without job control nothing is installed and the flag is
+    /// only set through [`SuspendSignal::request`].
+    pub fn install() -> io::Result<Self> {
+        let requested = Arc::new(AtomicBool::new(false));
+
+        #[cfg(unix)]
+        let id = signal_hook::flag::register(signal_hook::consts::SIGTSTP, Arc::clone(&requested))?;
+
+        Ok(Self {
+            requested,
+            #[cfg(unix)]
+            id,
+        })
+    }
+
+    /// Marks a suspend as requested.
+    #[allow(unused)]
+    pub fn request(&self) {
+        self.requested.store(true, Ordering::SeqCst);
+    }
+
+    /// Returns whether a suspend was requested, resetting the flag.
+    pub fn take(&self) -> bool {
+        self.requested.swap(false, Ordering::SeqCst)
+    }
+
+    /// Returns a readline handler that turns Ctrl-Z into a suspend request.
+    pub fn key_handler(&self) -> SuspendKeyHandler {
+        SuspendKeyHandler {
+            requested: Arc::clone(&self.requested),
+        }
+    }
+}
+
+impl Drop for SuspendSignal {
+    fn drop(&mut self) {
+        #[cfg(unix)]
+        signal_hook::low_level::unregister(self.id);
+    }
+}
+
+/// Readline handler for Ctrl-Z.
+///
+/// Instead of letting readline stop the process with the vault unlocked, it
+/// records a suspend request and interrupts the current line.
+pub struct SuspendKeyHandler {
+    requested: Arc<AtomicBool>,
+}
+
+impl ConditionalEventHandler for SuspendKeyHandler {
+    fn handle(&self, _: &Event, _: RepeatCount, _: bool, _: &EventContext) -> Option<Cmd> {
+        self.requested.store(true, Ordering::SeqCst);
+        Some(Cmd::Interrupt)
+    }
+}
+
+/// Stops the process as the default `SIGTSTP` action would.
+///
+/// Returns once the process is resumed. Does nothing on platforms without job
+/// control.
+pub fn suspend_process() -> io::Result<()> {
+    #[cfg(unix)]
+    signal_hook::low_level::emulate_default_handler(signal_hook::consts::SIGTSTP)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_take_resets_request() {
+        let signal = SuspendSignal::install().unwrap();
+        assert!(!signal.take());
+
+        signal.request();
+        assert!(signal.take());
+        assert!(!signal.take());
+    }
+}
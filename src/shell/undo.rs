@@ -0,0 +1,279 @@
+//! Undo of credential changes made in the shell.
+//!
+//! Commands that change credentials record a [`Change`] holding what is
+//! needed to reverse it, such as the secret of a replaced credential. The
+//! stack only lives as long as the shell session.
+
+use std::collections::VecDeque;
+
+use crate::credentials::Credentials;
+use crate::trie::Trie;
+
+/// Number of changes kept for `undo` by default.
+pub const DEFAULT_UNDO_LIMIT: usize = 50;
+
+/// A change to the credentials that can be reverted.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Change {
+    /// Credentials were added under these names.
+    Added(Vec<String>),
+    /// Credentials were moved to the trash.
+    Trashed(Vec<String>),
+    /// A credential was renamed.
+    Renamed {
+        /// The name before the rename.
+        old: String,
+        /// The name after the rename.
+        new: String,
+    },
+    /// A credential was replaced with one under a new name and secret.
+    Replaced {
+        /// The replaced credential's name.
+        old: String,
+        /// Its secret, restored by undo.
+        secret: String,
+        /// The name of the credential that replaced it.
+        new: String,
+    },
+    /// A credential was tagged.
+    Tagged {
+        /// The tagged credential.
+        name: String,
+        /// The tag added to it.
+        tag: String,
+    },
+    /// A tag was removed from a credential.
+    Untagged {
+        /// The credential the tag was removed from.
+        name: String,
+        /// The removed tag, added back by undo.
+        tag: String,
+    },
+    /// A tag was renamed on every credential that had it.
+    ///
+    /// Only credentials outside the trash are recorded; trashed ones keep
+    /// the new tag.
+    TagRenamed {
+        /// The tag before the rename.
+        old: String,
+        /// The tag after the rename.
+        new: String,
+        /// Credentials that had `old` but not `new`.
+        renamed: Vec<String>,
+        /// Credentials that had both tags, which were merged into `new`.
+        merged: Vec<String>,
+    },
+}
+
+impl Change {
+    /// Applies the inverse of this change to `credentials` and `key_trie`,
+    /// returning a description of what was undone.
+    pub fn revert(
+        self,
+        credentials: &mut Credentials,
+        key_trie: &mut Trie,
+    ) -> Result<String, String> {
+        match self {
+            Change::Added(names) => {
+                for name in &names {
+                    credentials.remove(name);
+                    key_trie.remove(name);
+                }
+                match names.as_slice() {
+                    [name] => Ok(format!("Undid adding '{}'", name)),
+                    _ => Ok(format!("Undid adding {} credentials", names.len())),
+                }
+            }
+            Change::Trashed(names) => {
+                for name in &names {
+                    credentials
+                        .restore_from_trash(name)
+                        .map_err(|e| e.to_string())?;
+                    key_trie.insert(name);
+                }
+                match names.as_slice() {
+                    [name] => Ok(format!("Undid removing '{}'", name)),
+                    _ => Ok(format!("Undid removing {} credentials", names.len())),
+                }
+            }
+            Change::Renamed { old, new } => {
+                credentials.rename(&new, &old).map_err(|e| e.to_string())?;
+                key_trie.remove(&new);
+                key_trie.insert(&old);
+                Ok(format!("Undid renaming '{}' to '{}'", old, new))
+            }
+            Change::Replaced { old, secret, new } => {
+                if !credentials.remove(&new) {
+                    return Err(format!("'{}' not found", new));
+                }
+                key_trie.remove(&new);
+                credentials
+                    .add(old.clone(), secret)
+                    .map_err(|e| e.to_string())?;
+                key_trie.insert(&old);
+                Ok(format!("Undid replacing '{}' with '{}'", old, new))
+            }
+            Change::Tagged { name, tag } => {
+                credentials
+                    .remove_tag(&name, &tag)
+                    .map_err(|e| e.to_string())?;
+                Ok(format!("Undid tagging '{}' with '{}'", name, tag))
+            }
+            Change::Untagged { name, tag } => {
+                credentials
+                    .add_tag(&name, &tag)
+                    .map_err(|e| e.to_string())?;
+                Ok(format!("Undid removing tag '{}' from '{}'", tag, name))
+            }
+            Change::TagRenamed {
+                old,
+                new,
+                renamed,
+                merged,
+            } => {
+                for name in &renamed {
+                    credentials
+                        .remove_tag(name, &new)
+                        .map_err(|e| e.to_string())?;
+                }
+                for name in renamed.iter().chain(&merged) {
+                    credentials.add_tag(name, &old).map_err(|e| e.to_string())?;
+                }
+                Ok(format!("Undid renaming tag '{}' to '{}'", old, new))
+            }
+        }
+    }
+}
+
+/// Bounded stack of changes, most recent last.
+#[derive(Debug)]
+pub struct UndoStack {
+    changes: VecDeque<Change>,
+    limit: usize,
+}
+
+impl UndoStack {
+    /// Creates a stack keeping at most `limit` changes; older ones are dropped.
+    pub fn new(limit: usize) -> Self {
+        Self {
+            changes: VecDeque::new(),
+            limit,
+        }
+    }
+
+    /// Records a change, dropping the oldest one if the stack is full.
+    pub fn push(&mut self, change: Change) {
+        if self.limit == 0 {
+            return;
+        }
+        if self.changes.len() == self.limit {
+            self.changes.pop_front();
+        }
+        self.changes.push_back(change);
+    }
+
+    /// Removes and returns the most recent change.
+    pub fn pop(&mut self) -> Option<Change> {
+        self.changes.pop_back()
+    }
+
+    /// Forgets every change, e.g. when the credentials are replaced as a whole.
+    pub fn clear(&mut self) {
+        self.changes.clear();
+    }
+
+    /// Returns the number of changes that can be undone.
+    #[allow(unused)]
+    pub fn len(&self) -> usize {
+        self.changes.len()
+    }
+
+    /// Returns whether there is nothing to undo.
+    #[allow(unused)]
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+}
+
+impl Default for UndoStack {
+    fn default() -> Self {
+        Self::new(DEFAULT_UNDO_LIMIT)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stack_is_bounded() {
+        let mut stack = UndoStack::new(2);
+        for name in ["a", "b", "c"] {
+            stack.push(Change::Added(vec![name.to_string()]));
+        }
+
+        assert_eq!(stack.len(), 2);
+        assert_eq!(stack.pop(), Some(Change::Added(vec!["c".to_string()])));
+        assert_eq!(stack.pop(), Some(Change::Added(vec!["b".to_string()])));
+        assert_eq!(stack.pop(), None);
+    }
+
+    #[test]
+    fn test_revert_rename_and_replace() {
+        let mut credentials = Credentials::new();
+        credentials
+            .add("gitlab".to_string(), "secret".to_string())
+            .unwrap();
+        let mut trie = Trie::new();
+        trie.insert("gitlab");
+
+        let change = Change::Renamed {
+            old: "github".to_string(),
+            new: "gitlab".to_string(),
+        };
+        assert!(change.revert(&mut credentials, &mut trie).is_ok());
+        assert_eq!(credentials.get("github"), Some(&"secret".to_string()));
+        assert!(trie.contains("github"));
+        assert!(!trie.contains("gitlab"));
+
+        let change = Change::Replaced {
+            old: "email".to_string(),
+            secret: "old".to_string(),
+            new: "github".to_string(),
+        };
+        assert!(change.revert(&mut credentials, &mut trie).is_ok());
+        assert_eq!(credentials.get("email"), Some(&"old".to_string()));
+        assert!(!credentials.contains_key("github"));
+        assert!(trie.contains("email"));
+        assert!(!trie.contains("github"));
+    }
+
+    #[test]
+    fn test_revert_tag_rename() {
+        let mut credentials = Credentials::new();
+        for name in ["github", "gitlab", "bank"] {
+            credentials
+                .add(name.to_string(), "secret".to_string())
+                .unwrap();
+        }
+        credentials.add_tag("github", "job").unwrap();
+        credentials.add_tag("gitlab", "job").unwrap();
+        credentials.add_tag("bank", "job").unwrap();
+        let mut trie = Trie::new();
+
+        // github had 'work', gitlab had both, bank only had 'job'
+        let change = Change::TagRenamed {
+            old: "work".to_string(),
+            new: "job".to_string(),
+            renamed: vec!["github".to_string()],
+            merged: vec!["gitlab".to_string()],
+        };
+        assert!(change.revert(&mut credentials, &mut trie).is_ok());
+        assert_eq!(credentials.metadata("github").unwrap().tags, ["work"]);
+        assert_eq!(
+            credentials.metadata("gitlab").unwrap().tags,
+            ["job", "work"]
+        );
+        assert_eq!(credentials.metadata("bank").unwrap().tags, ["job"]);
+    }
+}
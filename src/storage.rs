@@ -1,8 +1,77 @@
 use anyhow::{Result, anyhow};
 use base64::{Engine as _, engine::general_purpose};
 use serde::{Deserialize, Serialize};
+use std::fmt;
 use std::fs;
-use std::path::Path;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use crate::crypto::{Cipher, KDF_NAME, KEY_WRAP_CIPHER, KdfParams, NONCE_LEN, SALT_LEN};
+
+/// Errors in the contents of a store file.
+///
+/// These are returned wrapped in [`anyhow::Error`]; use `downcast_ref` to inspect them.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StorageError {
+    /// A decoded field has the wrong length.
+    BadField {
+        /// The field's path in the store, e.g. `recovery.key.nonce`.
+        field: &'static str,
+        /// Length the field must have, in bytes.
+        expected: usize,
+        /// Length the field has, in bytes.
+        actual: usize,
+    },
+}
+
+impl fmt::Display for StorageError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StorageError::BadField {
+                field,
+                expected,
+                actual,
+            } => write!(f, "{} must be {} bytes, found {}", field, expected, actual),
+        }
+    }
+}
+
+impl std::error::Error for StorageError {}
+
+/// The store format version written by this build.
+///
+/// - Version 1: salt, nonce and ciphertext only (default Argon2id parameters implied).
+/// - Version 2: adds the Argon2id parameters used to derive the key.
+/// - Version 3: adds optional recovery key support. When a recovery key is set
+///   up, the data is encrypted under a random data key, which is stored
+///   wrapped under both the password-derived key and the recovery key.
+/// - Version 4: the data is always encrypted under a random data key wrapped
+///   under the password-derived key, so changing the password only re-wraps
+///   the data key. A recovery key, if set up, wraps the same data key.
+/// - Version 5: records the cipher the credentials are encrypted with.
+///   Older stores are always ChaCha20-Poly1305.
+/// - Version 6: the encrypted data holds each credential's metadata (tags,
+///   timestamps and rotation interval) next to the secrets. Older stores
+///   hold the secrets only.
+/// - Version 7: the encrypted data also holds the trash, credentials removed
+///   but not yet purged. Older stores have no trash.
+pub const STORE_VERSION: u8 = 7;
+
+/// A data key encrypted under another key.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WrappedKey {
+    pub nonce: String,       // Base64 encoded
+    pub wrapped_key: String, // Base64 encoded
+}
+
+/// The data key wrapped under a key derived from the recovery key.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RecoverySlot {
+    pub argon2_salt: String, // Base64 encoded
+    pub kdf_params: KdfParams,
+    pub key: WrappedKey,
+}
 
 #[derive(Serialize, Deserialize)]
 pub struct EncryptedStore {
@@ -10,6 +79,81 @@ pub struct EncryptedStore {
     pub argon2_salt: String,      // Base64 encoded
     pub encryption_nonce: String, // Base64 encoded
     pub encrypted_data: String,   // Base64 encoded
+    /// Cipher of `encrypted_data` (absent before version 5, meaning ChaCha20-Poly1305).
+    #[serde(default)]
+    pub cipher: Cipher,
+    /// Argon2id parameters (absent in version 1 stores).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub kdf_params: Option<KdfParams>,
+    /// Data key wrapped under the password-derived key (absent before version 3,
+    /// and in version 3 stores without a recovery key).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub data_key: Option<WrappedKey>,
+    /// Data key wrapped under the recovery key (recovery-enabled stores only).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub recovery: Option<RecoverySlot>,
+    /// Consecutive failed unlock attempts, counted only with a lockout policy.
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub failed_attempts: u32,
+    /// Unix time until which unlocking is refused after too many failed attempts.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub locked_until: Option<u64>,
+}
+
+fn is_zero(n: &u32) -> bool {
+    *n == 0
+}
+
+/// Non-secret description of how a store is encrypted, for diagnostics.
+///
+/// Sizes are those of the decoded values; no key material is included.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CryptoInfo {
+    /// Store format version.
+    pub version: u8,
+    /// Key derivation function used for the master password.
+    pub kdf: &'static str,
+    /// Argon2id parameters (the defaults for version 1 stores).
+    pub kdf_params: KdfParams,
+    /// Length of the Argon2id salt in bytes.
+    pub salt_len: usize,
+    /// Cipher used for the credentials.
+    pub cipher: &'static str,
+    /// Cipher used for the wrapped data key.
+    pub key_cipher: &'static str,
+    /// Length of the data nonce in bytes.
+    pub nonce_len: usize,
+    /// Length of the wrapped data key in bytes, if the data key is wrapped
+    /// under the password-derived key.
+    pub wrapped_key_len: Option<usize>,
+    /// Whether a recovery key also wraps the data key.
+    pub recovery_key: bool,
+}
+
+impl EncryptedStore {
+    /// Describes how this store is encrypted.
+    pub fn crypto_info(&self) -> Result<CryptoInfo> {
+        let wrapped_key_len = match &self.data_key {
+            Some(wrapped) => Some(
+                general_purpose::STANDARD
+                    .decode(&wrapped.wrapped_key)?
+                    .len(),
+            ),
+            None => None,
+        };
+
+        Ok(CryptoInfo {
+            version: self.version,
+            kdf: KDF_NAME,
+            kdf_params: self.kdf_params.unwrap_or_default(),
+            salt_len: decode_salt("argon2_salt", &self.argon2_salt)?.len(),
+            cipher: self.cipher.name(),
+            key_cipher: KEY_WRAP_CIPHER.name(),
+            nonce_len: decode_nonce("encryption_nonce", &self.encryption_nonce)?.len(),
+            wrapped_key_len,
+            recovery_key: self.recovery.is_some(),
+        })
+    }
 }
 
 pub fn load_encrypted_store(path: &Path) -> Result<EncryptedStore> {
@@ -27,12 +171,149 @@ pub fn save_encrypted_store(path: &Path, store: &EncryptedStore) -> Result<()> {
     Ok(())
 }
 
-pub fn decode_salt(encoded: &str) -> Result<Vec<u8>> {
-    Ok(general_purpose::STANDARD.decode(encoded)?)
+/// Where a vault's encrypted store is kept.
+///
+/// Backups and the lock file are only made for backends with a
+/// [`path`](StorageBackend::path).
+pub trait StorageBackend {
+    /// Reads the store.
+    fn load(&self) -> Result<EncryptedStore>;
+
+    /// Writes the store, replacing the previous one.
+    fn save(&self, store: &EncryptedStore) -> Result<()>;
+
+    /// Returns whether a store has been saved.
+    fn exists(&self) -> bool;
+
+    /// Deletes the store.
+    fn remove(&self) -> Result<()>;
+
+    /// Returns the file the store is kept in, if it is kept in a file.
+    fn path(&self) -> Option<&Path> {
+        None
+    }
 }
 
-pub fn decode_nonce(encoded: &str) -> Result<Vec<u8>> {
-    Ok(general_purpose::STANDARD.decode(encoded)?)
+/// Keeps the store in a JSON file; the default backend.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileBackend {
+    /// The store file.
+    path: PathBuf,
+}
+
+impl FileBackend {
+    /// Creates a backend for the store file at `path`.
+    pub fn new(path: PathBuf) -> Self {
+        Self { path }
+    }
+}
+
+impl StorageBackend for FileBackend {
+    fn load(&self) -> Result<EncryptedStore> {
+        load_encrypted_store(&self.path)
+    }
+
+    fn save(&self, store: &EncryptedStore) -> Result<()> {
+        save_encrypted_store(&self.path, store)
+    }
+
+    /// An empty file counts as no store, so a new vault can be created by
+    /// touching it.
+    fn exists(&self) -> bool {
+        fs::metadata(&self.path).is_ok_and(|m| m.len() > 0)
+    }
+
+    fn remove(&self) -> Result<()> {
+        Ok(fs::remove_file(&self.path)?)
+    }
+
+    fn path(&self) -> Option<&Path> {
+        Some(&self.path)
+    }
+}
+
+/// Keeps the store in memory, serialized as it would be in a file, for tests
+/// and for embedding where there is no filesystem.
+///
+/// Clones share the same store, so a test can keep one to inspect or
+/// tamper with what a [`Manager`](crate::manager::Manager) saved.
+#[allow(unused)]
+#[derive(Debug, Clone, Default)]
+pub struct InMemoryBackend {
+    /// The serialized store, if one has been saved.
+    json: Arc<Mutex<Option<String>>>,
+}
+
+#[allow(unused)]
+impl InMemoryBackend {
+    /// Creates a backend with no store.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the store as it would be written to a file, if there is one.
+    pub fn contents(&self) -> Option<String> {
+        self.json.lock().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
+    /// Replaces the store with `json`, which need not be a valid store.
+    pub fn set_contents(&self, json: impl Into<String>) {
+        *self.json.lock().unwrap_or_else(|e| e.into_inner()) = Some(json.into());
+    }
+}
+
+impl StorageBackend for InMemoryBackend {
+    fn load(&self) -> Result<EncryptedStore> {
+        let json = self
+            .contents()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "no store has been saved"))?;
+        Ok(serde_json::from_str(&json)?)
+    }
+
+    fn save(&self, store: &EncryptedStore) -> Result<()> {
+        self.set_contents(serde_json::to_string_pretty(store)?);
+        Ok(())
+    }
+
+    fn exists(&self) -> bool {
+        self.contents().is_some()
+    }
+
+    fn remove(&self) -> Result<()> {
+        *self.json.lock().unwrap_or_else(|e| e.into_inner()) = None;
+        Ok(())
+    }
+}
+
+/// Decodes the salt stored in `field`, checking that it is [`SALT_LEN`] bytes.
+pub fn decode_salt(field: &'static str, encoded: &str) -> Result<[u8; SALT_LEN]> {
+    decode_fixed(field, encoded)
+}
+
+/// Decodes the nonce stored in `field`, checking that it is [`NONCE_LEN`]
+/// bytes, as both ciphers require.
+pub fn decode_nonce(field: &'static str, encoded: &str) -> Result<[u8; NONCE_LEN]> {
+    decode_fixed(field, encoded)
+}
+
+/// Decodes a base64 field that must be exactly `N` bytes long.
+fn decode_fixed<const N: usize>(field: &'static str, encoded: &str) -> Result<[u8; N]> {
+    let bytes = general_purpose::STANDARD.decode(encoded)?;
+    let actual = bytes.len();
+    bytes.try_into().map_err(|_| {
+        StorageError::BadField {
+            field,
+            expected: N,
+            actual,
+        }
+        .into()
+    })
+}
+
+/// Decodes a base64 field without checking its length, returning the
+/// number of bytes it holds.
+pub fn decoded_len(encoded: &str) -> Result<usize> {
+    Ok(general_purpose::STANDARD.decode(encoded)?.len())
 }
 
 pub fn decode_encrypted_data(encoded: &str) -> Result<Vec<u8>> {
@@ -0,0 +1,250 @@
+//! Password strength estimation.
+//!
+//! By default this is a deliberately simple heuristic based on length,
+//! character variety and a short list of very common passwords. With the
+//! `zxcvbn` feature enabled, estimates come from the zxcvbn library instead,
+//! which also reports crack-time estimates and the patterns it matched. Either
+//! way the result is meant for warnings and summaries, not as a security
+//! guarantee.
+
+/// Coarse strength rating for a secret.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Strength {
+    VeryWeak,
+    Weak,
+    Fair,
+    Strong,
+    VeryStrong,
+}
+
+impl Strength {
+    /// Returns true for ratings that should be flagged to the user.
+    pub fn is_weak(self) -> bool {
+        self <= Strength::Weak
+    }
+
+    /// Returns a human-readable label.
+    pub fn label(self) -> &'static str {
+        match self {
+            Strength::VeryWeak => "very weak",
+            Strength::Weak => "weak",
+            Strength::Fair => "fair",
+            Strength::Strong => "strong",
+            Strength::VeryStrong => "very strong",
+        }
+    }
+}
+
+/// Result of estimating the strength of a secret.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StrengthReport {
+    /// Coarse rating of the secret.
+    pub strength: Strength,
+    /// Estimated time to crack the secret offline against a slow hash, if the
+    /// backend provides one.
+    pub crack_time: Option<String>,
+    /// Names of the patterns the backend recognised in the secret.
+    pub patterns: Vec<String>,
+}
+
+impl StrengthReport {
+    /// Returns true for ratings that should be flagged to the user.
+    pub fn is_weak(&self) -> bool {
+        self.strength.is_weak()
+    }
+}
+
+/// Estimates the strength of a secret.
+#[cfg(not(feature = "zxcvbn"))]
+pub fn estimate_strength(secret: &str) -> StrengthReport {
+    StrengthReport {
+        strength: simple_strength(secret),
+        crack_time: None,
+        patterns: Vec::new(),
+    }
+}
+
+/// Estimates the strength of a secret using zxcvbn.
+#[cfg(feature = "zxcvbn")]
+pub fn estimate_strength(secret: &str) -> StrengthReport {
+    use zxcvbn::Score;
+    use zxcvbn::matching::patterns::MatchPattern;
+
+    let entropy = zxcvbn::zxcvbn(secret, &[]);
+    let strength = match entropy.score() {
+        Score::Zero => Strength::VeryWeak,
+        Score::One => Strength::Weak,
+        Score::Two => Strength::Fair,
+        Score::Three => Strength::Strong,
+        _ => Strength::VeryStrong,
+    };
+
+    let mut patterns: Vec<String> = Vec::new();
+    for m in entropy.sequence() {
+        let name = match m.pattern {
+            MatchPattern::Dictionary(_) => "dictionary",
+            MatchPattern::Spatial(_) => "keyboard",
+            MatchPattern::Repeat(_) => "repeat",
+            MatchPattern::Sequence(_) => "sequence",
+            MatchPattern::Regex(_) => "regex",
+            MatchPattern::Date(_) => "date",
+            MatchPattern::BruteForce => "bruteforce",
+        };
+        if !patterns.iter().any(|p| p == name) {
+            patterns.push(name.to_string());
+        }
+    }
+
+    StrengthReport {
+        strength,
+        crack_time: Some(
+            entropy
+                .crack_times()
+                .offline_slow_hashing_1e4_per_second()
+                .to_string(),
+        ),
+        patterns,
+    }
+}
+
+/// Passwords that are always rated very weak, regardless of their shape.
+const COMMON_PASSWORDS: &[&str] = &[
+    "123456",
+    "12345678",
+    "123456789",
+    "1234567890",
+    "111111",
+    "000000",
+    "123123",
+    "654321",
+    "password",
+    "password1",
+    "passw0rd",
+    "qwerty",
+    "qwertyuiop",
+    "abc123",
+    "letmein",
+    "welcome",
+    "iloveyou",
+    "admin",
+    "monkey",
+    "dragon",
+    "football",
+    "baseball",
+    "sunshine",
+    "princess",
+    "trustno1",
+    "hunter2",
+];
+
+/// Estimates the strength of a secret with the built-in heuristic.
+#[cfg_attr(feature = "zxcvbn", allow(dead_code))]
+fn simple_strength(secret: &str) -> Strength {
+    let len = secret.chars().count();
+    if len < 6 || COMMON_PASSWORDS.contains(&secret.to_lowercase().as_str()) {
+        return Strength::VeryWeak;
+    }
+
+    let has_lower = secret.chars().any(|c| c.is_lowercase());
+    let has_upper = secret.chars().any(|c| c.is_uppercase());
+    let has_digit = secret.chars().any(|c| c.is_ascii_digit());
+    let has_other = secret.chars().any(|c| !c.is_alphanumeric());
+    let classes = [has_lower, has_upper, has_digit, has_other]
+        .iter()
+        .filter(|&&present| present)
+        .count();
+
+    let mut score = classes.saturating_sub(1);
+    for threshold in [8, 12, 16] {
+        if len >= threshold {
+            score += 1;
+        }
+    }
+
+    match score {
+        0 => Strength::VeryWeak,
+        1 => Strength::Weak,
+        2 | 3 => Strength::Fair,
+        4 | 5 => Strength::Strong,
+        _ => Strength::VeryStrong,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_short_and_common_are_very_weak() {
+        assert_eq!(simple_strength(""), Strength::VeryWeak);
+        assert_eq!(simple_strength("abc"), Strength::VeryWeak);
+        assert_eq!(simple_strength("123456"), Strength::VeryWeak);
+        assert_eq!(simple_strength("Password"), Strength::VeryWeak);
+    }
+
+    #[test]
+    fn test_single_class_is_weak() {
+        assert!(simple_strength("abcdefgh").is_weak());
+    }
+
+    #[test]
+    fn test_mixed_classes_are_stronger() {
+        assert_eq!(simple_strength("Tr0ub4dor&3"), Strength::Strong);
+        assert_eq!(
+            simple_strength("correct horse battery staple"),
+            Strength::Strong
+        );
+        assert_eq!(
+            simple_strength("x7#Kq9!mZ2@pL5$wR8^t"),
+            Strength::VeryStrong
+        );
+    }
+
+    #[cfg(not(feature = "zxcvbn"))]
+    #[test]
+    fn test_default_report_has_no_extras() {
+        let report = estimate_strength("Tr0ub4dor&3");
+        assert_eq!(report.strength, Strength::Strong);
+        assert_eq!(report.crack_time, None);
+        assert!(report.patterns.is_empty());
+    }
+
+    #[cfg(feature = "zxcvbn")]
+    #[test]
+    fn test_zxcvbn_weak_inputs() {
+        for secret in ["password", "123456", "qwerty", "aaaaaaaa"] {
+            let report = estimate_strength(secret);
+            assert!(report.is_weak(), "{secret} rated {:?}", report.strength);
+            assert!(report.crack_time.is_some());
+            assert!(!report.patterns.is_empty());
+        }
+    }
+
+    #[cfg(feature = "zxcvbn")]
+    #[test]
+    fn test_zxcvbn_strong_inputs() {
+        for secret in ["x7#Kq9!mZ2@pL5$wR8^t", "correct horse battery staple"] {
+            let report = estimate_strength(secret);
+            assert!(
+                report.strength >= Strength::Strong,
+                "{secret} rated {:?}",
+                report.strength
+            );
+        }
+    }
+
+    #[cfg(feature = "zxcvbn")]
+    #[test]
+    fn test_zxcvbn_reports_dictionary_pattern() {
+        let report = estimate_strength("password");
+        assert_eq!(report.strength, Strength::VeryWeak);
+        assert!(report.patterns.iter().any(|p| p == "dictionary"));
+    }
+
+    #[test]
+    fn test_ordering() {
+        assert!(Strength::VeryWeak < Strength::Weak);
+        assert!(Strength::Strong < Strength::VeryStrong);
+        assert!(!Strength::Fair.is_weak());
+    }
+}
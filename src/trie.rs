@@ -3,10 +3,11 @@
 //! This module provides a trie implementation optimized for command and
 //! credential key autocompletion in the shell.
 
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
 /// A node in the trie structure.
-#[derive(Debug, Default)]
+#[derive(Debug, Default, Serialize, Deserialize)]
 struct TrieNode {
     children: HashMap<char, TrieNode>,
     is_end_of_word: bool,
@@ -19,6 +20,73 @@ impl TrieNode {
             is_end_of_word: false,
         }
     }
+
+    /// Returns the children in ascending character order.
+    fn sorted_children(&self) -> Vec<(char, &TrieNode)> {
+        let mut children: Vec<(char, &TrieNode)> = self
+            .children
+            .iter()
+            .map(|(ch, child)| (*ch, child))
+            .collect();
+        children.sort_unstable_by_key(|(ch, _)| *ch);
+        children
+    }
+}
+
+/// Iterator over the words below a node, in sorted order.
+///
+/// Walks the trie depth first with an explicit stack, so deep words cannot
+/// overflow the call stack, and only does the work needed for each word.
+struct Words<'a> {
+    /// Nodes still to visit, the next one last, each with the length in
+    /// bytes of the word leading to its parent and the character leading
+    /// to it.
+    stack: Vec<(usize, char, &'a TrieNode)>,
+    /// The word leading to the node visited last.
+    word: String,
+    /// Whether the starting node is itself a word not yet returned.
+    pending: bool,
+}
+
+impl<'a> Words<'a> {
+    fn new(node: &'a TrieNode, prefix: &str) -> Self {
+        let mut words = Self {
+            stack: Vec::new(),
+            word: prefix.to_string(),
+            pending: node.is_end_of_word,
+        };
+        words.push_children(node);
+        words
+    }
+
+    /// Queues the children of `node`, which the current word leads to, so
+    /// that the smallest is visited first.
+    fn push_children(&mut self, node: &'a TrieNode) {
+        let len = self.word.len();
+        for (ch, child) in node.sorted_children().into_iter().rev() {
+            self.stack.push((len, ch, child));
+        }
+    }
+}
+
+impl Iterator for Words<'_> {
+    type Item = String;
+
+    fn next(&mut self) -> Option<String> {
+        if std::mem::take(&mut self.pending) {
+            return Some(self.word.clone());
+        }
+
+        while let Some((parent_len, ch, node)) = self.stack.pop() {
+            self.word.truncate(parent_len);
+            self.word.push(ch);
+            self.push_children(node);
+            if node.is_end_of_word {
+                return Some(self.word.clone());
+            }
+        }
+        None
+    }
 }
 
 /// A trie (prefix tree) for efficient string completion.
@@ -38,7 +106,7 @@ impl TrieNode {
 /// assert!(trie.contains("add"));
 /// assert!(!trie.contains("unknown"));
 /// ```
-#[derive(Debug, Default)]
+#[derive(Debug, Default, Serialize, Deserialize)]
 pub struct Trie {
     root: TrieNode,
     count: usize,
@@ -63,7 +131,7 @@ impl Trie {
 
         let mut current = &mut self.root;
         for ch in word.chars() {
-            current = current.children.entry(ch).or_insert_with(TrieNode::new);
+            current = current.children.entry(ch).or_default();
         }
 
         if !current.is_end_of_word {
@@ -72,6 +140,18 @@ impl Trie {
         }
     }
 
+    /// Inserts a word, rejecting words that cannot be indexed.
+    ///
+    /// Unlike [`Trie::insert`], an empty word is an error rather than a no-op,
+    /// so callers keeping the trie in sync with another collection can detect it.
+    pub fn try_insert(&mut self, word: &str) -> Result<(), String> {
+        if word.is_empty() {
+            return Err("Cannot index an empty name".to_string());
+        }
+        self.insert(word);
+        Ok(())
+    }
+
     /// Removes a word from the trie.
     ///
     /// Returns `true` if the word was found and removed, `false` otherwise.
@@ -126,29 +206,110 @@ impl Trie {
     ///
     /// The results are sorted alphabetically.
     pub fn completions(&self, prefix: &str) -> Vec<String> {
-        let mut results = Vec::new();
+        self.completions_limited(prefix, usize::MAX)
+    }
 
-        // Navigate to the prefix node
-        let mut current = &self.root;
-        for ch in prefix.chars() {
-            match current.children.get(&ch) {
-                Some(node) => current = node,
-                None => return results, // Prefix not found
-            }
+    /// Calls `f` with each word, in sorted order.
+    ///
+    /// Unlike [`Trie::all_words`], no list of words is built, so large tries
+    /// can be streamed.
+    ///
+    /// ```
+    /// use passmgr::trie::Trie;
+    ///
+    /// let mut trie = Trie::new();
+    /// trie.insert("gitlab");
+    /// trie.insert("github");
+    ///
+    /// let mut words = Vec::new();
+    /// trie.for_each(|word| words.push(word.len()));
+    /// assert_eq!(words, vec![6, 6]);
+    /// ```
+    #[allow(unused)]
+    pub fn for_each<F: FnMut(&str)>(&self, f: F) {
+        self.for_each_with_prefix("", f);
+    }
+
+    /// Calls `f` with each word starting with `prefix`, in sorted order.
+    pub fn for_each_with_prefix<F: FnMut(&str)>(&self, prefix: &str, mut f: F) {
+        if let Some(node) = self.find(prefix) {
+            Self::visit(node, &mut prefix.to_string(), &mut f);
         }
+    }
 
-        // Collect all words from this node
-        self.collect_words(current, &mut prefix.to_string(), &mut results);
+    /// Returns at most `limit` words starting with `prefix`: the
+    /// alphabetically first of those [`Trie::completions`] would return.
+    ///
+    /// Words past the limit are never visited.
+    pub fn completions_limited(&self, prefix: &str, limit: usize) -> Vec<String> {
+        let mut results = Vec::new();
+        if let Some(node) = self.find(prefix) {
+            Self::collect_words(node, &mut prefix.to_string(), &mut results, limit);
+        }
+        results
+    }
+
+    /// Returns the number of words starting with `prefix`.
+    pub fn count_with_prefix(&self, prefix: &str) -> usize {
+        let mut count = 0;
+        self.for_each_with_prefix(prefix, |_| count += 1);
+        count
+    }
+
+    /// Returns the words within `max_distance` edits of `word`.
+    ///
+    /// Distance is the Levenshtein distance over characters. Results are
+    /// sorted by distance, then alphabetically. Branches of the trie that
+    /// cannot come within `max_distance` are not visited.
+    pub fn closest(&self, word: &str, max_distance: usize) -> Vec<String> {
+        let target: Vec<char> = word.chars().collect();
+        let first_row: Vec<usize> = (0..=target.len()).collect();
+
+        let mut results = Vec::new();
+        let mut prefix = String::new();
+        for (ch, child) in &self.root.children {
+            prefix.push(*ch);
+            Self::collect_closest(
+                child,
+                *ch,
+                &target,
+                &first_row,
+                max_distance,
+                &mut prefix,
+                &mut results,
+            );
+            prefix.pop();
+        }
 
-        // Sort results alphabetically
         results.sort();
-        results
+        results.into_iter().map(|(_, word)| word).collect()
     }
 
-    /// Returns all words in the trie.
+    /// Returns all words in the trie, sorted.
     #[allow(unused)]
     pub fn all_words(&self) -> Vec<String> {
-        self.completions("")
+        self.iter().collect()
+    }
+
+    /// Returns an iterator over the words in sorted order.
+    ///
+    /// Words are produced as the iterator is advanced, so taking the first
+    /// few of a large trie is cheap.
+    ///
+    /// ```
+    /// use passmgr::trie::Trie;
+    ///
+    /// let mut trie = Trie::new();
+    /// for word in ["gitlab", "github", "email"] {
+    ///     trie.insert(word);
+    /// }
+    ///
+    /// let first: Vec<String> = trie.iter().take(2).collect();
+    /// assert_eq!(first, vec!["email", "github"]);
+    /// ```
+    #[allow(unused)]
+    pub fn iter(&self) -> impl Iterator<Item = String> + '_ {
+        Words::new(&self.root, "")
     }
 
     /// Returns the number of words in the trie.
@@ -168,20 +329,128 @@ impl Trie {
         self.count = 0;
     }
 
-    /// Helper function to collect all words from a given node.
-    fn collect_words(&self, node: &TrieNode, prefix: &mut String, results: &mut Vec<String>) {
+    /// Serializes the trie, e.g. to cache it between runs.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        serde_json::to_vec(self).expect("trie serialization cannot fail")
+    }
+
+    /// Restores a trie serialized with [`Trie::to_bytes`].
+    pub fn from_bytes(bytes: &[u8]) -> serde_json::Result<Self> {
+        serde_json::from_slice(bytes)
+    }
+
+    /// Returns the node reached by following `prefix` from the root.
+    fn find(&self, prefix: &str) -> Option<&TrieNode> {
+        let mut current = &self.root;
+        for ch in prefix.chars() {
+            current = current.children.get(&ch)?;
+        }
+        Some(current)
+    }
+
+    /// Helper for [`Trie::for_each_with_prefix`]: calls `f` with every word
+    /// below `node`, visiting children in character order so words come out
+    /// sorted.
+    fn visit<F: FnMut(&str)>(node: &TrieNode, prefix: &mut String, f: &mut F) {
+        if node.is_end_of_word {
+            f(prefix);
+        }
+
+        for (ch, child) in node.sorted_children() {
+            prefix.push(ch);
+            Self::visit(child, prefix, f);
+            prefix.pop();
+        }
+    }
+
+    /// Helper for [`Trie::closest`]: extends the edit-distance row for `prev_row`
+    /// by the character `ch` leading to `node`, then recurses.
+    fn collect_closest(
+        node: &TrieNode,
+        ch: char,
+        target: &[char],
+        prev_row: &[usize],
+        max_distance: usize,
+        prefix: &mut String,
+        results: &mut Vec<(usize, String)>,
+    ) {
+        let mut row = Vec::with_capacity(prev_row.len());
+        row.push(prev_row[0] + 1);
+        for i in 1..=target.len() {
+            let substitution = prev_row[i - 1] + usize::from(target[i - 1] != ch);
+            let insertion = row[i - 1] + 1;
+            let deletion = prev_row[i] + 1;
+            row.push(substitution.min(insertion).min(deletion));
+        }
+
+        let distance = row[target.len()];
+        if node.is_end_of_word && distance <= max_distance {
+            results.push((distance, prefix.clone()));
+        }
+
+        // Every longer word passing through this node is at least this far away
+        if row.iter().min().is_some_and(|&min| min <= max_distance) {
+            for (next, child) in &node.children {
+                prefix.push(*next);
+                Self::collect_closest(child, *next, target, &row, max_distance, prefix, results);
+                prefix.pop();
+            }
+        }
+    }
+
+    /// Helper function to collect the words below a given node, in sorted
+    /// order, until `results` holds `limit` words.
+    ///
+    /// Children are visited in character order, so once the limit is
+    /// reached every word not yet visited sorts after those collected, and
+    /// the rest of the subtree is skipped.
+    fn collect_words(
+        node: &TrieNode,
+        prefix: &mut String,
+        results: &mut Vec<String>,
+        limit: usize,
+    ) {
+        if results.len() >= limit {
+            return;
+        }
         if node.is_end_of_word {
             results.push(prefix.clone());
         }
 
-        for (ch, child) in &node.children {
-            prefix.push(*ch);
-            self.collect_words(child, prefix, results);
+        for (ch, child) in node.sorted_children() {
+            if results.len() >= limit {
+                return;
+            }
+            prefix.push(ch);
+            Self::collect_words(child, prefix, results, limit);
             prefix.pop();
         }
     }
 }
 
+/// Returns a checksum of a set of keys, independent of their order.
+///
+/// A cached trie is only valid for the keys it was built from; comparing
+/// checksums detects any added, removed or renamed key. This is FNV-1a, which
+/// is stable across builds, unlike the standard library's hasher.
+pub fn keys_checksum<'a>(keys: impl IntoIterator<Item = &'a str>) -> u64 {
+    const OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+    const PRIME: u64 = 0x0100_0000_01b3;
+
+    let mut keys: Vec<&str> = keys.into_iter().collect();
+    keys.sort_unstable();
+
+    let mut hash = OFFSET_BASIS;
+    for key in keys {
+        // Terminate each key so ["ab", "c"] and ["a", "bc"] differ
+        for byte in key.bytes().chain(std::iter::once(0)) {
+            hash ^= u64::from(byte);
+            hash = hash.wrapping_mul(PRIME);
+        }
+    }
+    hash
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -274,6 +543,144 @@ mod tests {
         assert_eq!(all, vec!["apple", "mango", "zebra"]);
     }
 
+    #[test]
+    fn test_for_each_matches_all_words() {
+        let mut trie = Trie::new();
+        for word in [
+            "zebra",
+            "apple",
+            "app",
+            "mango",
+            "Apple",
+            "café",
+            "cafe",
+            "日本語",
+            "a-b",
+            "a",
+        ] {
+            trie.insert(word);
+        }
+
+        let mut visited = Vec::new();
+        trie.for_each(|word| visited.push(word.to_string()));
+        assert_eq!(visited, trie.all_words());
+
+        let mut count = 0;
+        Trie::new().for_each(|_| count += 1);
+        assert_eq!(count, 0);
+    }
+
+    #[test]
+    fn test_for_each_with_prefix() {
+        let mut trie = Trie::new();
+        for word in ["help", "helper", "helping", "hello", "world"] {
+            trie.insert(word);
+        }
+
+        for prefix in ["", "hel", "help", "helpe", "w", "x", "helpers"] {
+            let mut visited = Vec::new();
+            trie.for_each_with_prefix(prefix, |word| visited.push(word.to_string()));
+            assert_eq!(visited, trie.completions(prefix), "{}", prefix);
+        }
+    }
+
+    #[test]
+    fn test_iter_matches_all_words() {
+        let mut trie = Trie::new();
+        for word in [
+            "zebra",
+            "apple",
+            "app",
+            "mango",
+            "Apple",
+            "café",
+            "cafe",
+            "日本語",
+            "a-b",
+            "a",
+        ] {
+            trie.insert(word);
+        }
+
+        let mut expected = trie.completions("");
+        expected.sort();
+        assert_eq!(trie.iter().collect::<Vec<_>>(), expected);
+        assert_eq!(trie.all_words(), expected);
+        assert_eq!(trie.iter().take(3).collect::<Vec<_>>(), expected[..3]);
+
+        trie.remove("app");
+        assert!(!trie.iter().any(|word| word == "app"));
+        assert_eq!(Trie::new().iter().next(), None);
+    }
+
+    #[test]
+    fn test_iter_long_word() {
+        let word = "x".repeat(1_000);
+        let mut trie = Trie::new();
+        trie.insert(&word);
+        trie.insert("x");
+
+        let mut words = trie.iter();
+        assert_eq!(words.next(), Some("x".to_string()));
+        assert_eq!(words.next().map(|w| w.len()), Some(word.len()));
+        assert_eq!(words.next(), None);
+    }
+
+    #[test]
+    fn test_completions_limited() {
+        let mut trie = Trie::new();
+        for word in ["help", "helper", "helping", "hello", "world", "held"] {
+            trie.insert(word);
+        }
+
+        assert_eq!(trie.completions_limited("hel", 2), vec!["held", "hello"]);
+        assert_eq!(trie.completions_limited("hel", 10), trie.completions("hel"));
+        assert_eq!(trie.completions_limited("help", 1), vec!["help"]);
+        assert!(trie.completions_limited("hel", 0).is_empty());
+        assert!(trie.completions_limited("x", 5).is_empty());
+
+        assert_eq!(trie.count_with_prefix("hel"), 5);
+        assert_eq!(trie.count_with_prefix(""), 6);
+        assert_eq!(trie.count_with_prefix("x"), 0);
+    }
+
+    #[test]
+    fn test_completions_limited_matches_sorted_completions() {
+        let mut trie = Trie::new();
+        let mut words: Vec<String> = Vec::new();
+        for i in 0..300u32 {
+            // Mix lengths, shared prefixes, case and non-ASCII characters
+            let word = format!(
+                "{}{}",
+                ["a", "ab", "B", "é", "a-", "zz"][i as usize % 6],
+                i * 7919 % 1000
+            );
+            trie.insert(&word);
+            words.push(word);
+        }
+        words.sort();
+        words.dedup();
+
+        for prefix in ["", "a", "ab", "a-", "B", "é", "zz9", "q"] {
+            let expected: Vec<String> = words
+                .iter()
+                .filter(|w| w.starts_with(prefix))
+                .cloned()
+                .collect();
+            assert_eq!(trie.completions(prefix), expected, "{}", prefix);
+            for limit in [0, 1, 2, 10, 99, expected.len(), expected.len() + 1] {
+                let limited = trie.completions_limited(prefix, limit);
+                assert_eq!(
+                    limited,
+                    expected[..limit.min(expected.len())],
+                    "{} {}",
+                    prefix,
+                    limit
+                );
+            }
+        }
+    }
+
     #[test]
     fn test_duplicate_insert() {
         let mut trie = Trie::new();
@@ -286,6 +693,14 @@ mod tests {
         assert!(trie.contains("test"));
     }
 
+    #[test]
+    fn test_try_insert() {
+        let mut trie = Trie::new();
+        assert!(trie.try_insert("hello").is_ok());
+        assert!(trie.try_insert("").is_err());
+        assert_eq!(trie.len(), 1);
+    }
+
     #[test]
     fn test_special_characters() {
         let mut trie = Trie::new();
@@ -357,4 +772,79 @@ mod tests {
         let completions = trie.completions("help");
         assert_eq!(completions, vec!["helper", "helping"]);
     }
+
+    #[test]
+    fn test_closest_near_miss() {
+        let mut trie = Trie::new();
+        trie.insert("github");
+        trie.insert("gitlab");
+        trie.insert("email");
+
+        assert_eq!(trie.closest("githb", 1), vec!["github"]);
+        assert_eq!(trie.closest("githb", 2), vec!["github", "gitlab"]);
+        assert_eq!(trie.closest("gihtub", 2), vec!["github"]);
+        assert_eq!(trie.closest("emial", 2), vec!["email"]);
+        assert_eq!(trie.closest("github", 1), vec!["github"]);
+    }
+
+    #[test]
+    fn test_closest_orders_by_distance() {
+        let mut trie = Trie::new();
+        trie.insert("gitlab");
+        trie.insert("github");
+        trie.insert("gitla");
+
+        assert_eq!(trie.closest("gitlab", 1), vec!["gitlab", "gitla"]);
+        assert_eq!(trie.closest("gitlab", 2), vec!["gitlab", "gitla", "github"]);
+        assert_eq!(trie.closest("gitxab", 3), vec!["gitlab", "github", "gitla"]);
+    }
+
+    #[test]
+    fn test_closest_no_match() {
+        let mut trie = Trie::new();
+        trie.insert("github");
+        trie.insert("email");
+
+        assert!(trie.closest("zzzzzzzzzz", 2).is_empty());
+        assert!(trie.closest("bank", 1).is_empty());
+        assert!(Trie::new().closest("github", 2).is_empty());
+    }
+
+    #[test]
+    fn test_closest_unicode() {
+        let mut trie = Trie::new();
+        trie.insert("café");
+
+        assert_eq!(trie.closest("cafe", 1), vec!["café"]);
+    }
+
+    #[test]
+    fn test_bytes_round_trip() {
+        let mut trie = Trie::new();
+        for word in ["github", "gitlab", "git", "email", "café", "with space"] {
+            trie.insert(word);
+        }
+
+        let restored = Trie::from_bytes(&trie.to_bytes()).unwrap();
+        assert_eq!(restored.len(), trie.len());
+        for prefix in ["", "g", "git", "gith", "e", "caf", "w", "x"] {
+            assert_eq!(restored.completions(prefix), trie.completions(prefix));
+        }
+        assert!(restored.contains("café"));
+        assert!(!restored.contains("gi"));
+    }
+
+    #[test]
+    fn test_from_bytes_rejects_garbage() {
+        assert!(Trie::from_bytes(b"not a trie").is_err());
+    }
+
+    #[test]
+    fn test_keys_checksum() {
+        let a = keys_checksum(["github", "email"]);
+        assert_eq!(a, keys_checksum(["email", "github"]));
+        assert_ne!(a, keys_checksum(["github"]));
+        assert_ne!(a, keys_checksum(["github", "emails"]));
+        assert_ne!(keys_checksum(["ab", "c"]), keys_checksum(["a", "bc"]));
+    }
 }
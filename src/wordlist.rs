@@ -0,0 +1,484 @@
+//! Word list for generated passphrases.
+//!
+//! 4096 common English words of 4 to 8 lowercase letters, so each word adds
+//! exactly 12 bits of entropy. This is not the EFF long list, whose 7776
+//! words are meant for dice rather than a power of two. The words come from
+//! the zxcvbn frequency lists of television and film subtitles and English
+//! Wikipedia, checked by hand to leave out names of people and places,
+//! nationalities, months and days, profanity, interjections and clipped
+//! spellings such as "nothin".
+
+/// The words, sorted and without duplicates.
+pub static WORDS: [&str; 4096] = [
+    "abandon", "ability", "able", "aboard", "about", "above", "abroad", "absence", "absolute",
+    "absurd", "abuse", "academic", "academy", "accent", "accept", "accepted", "accident",
+    "account", "accounts", "accurate", "accuse", "accused", "accusing", "achieve", "achieved",
+    "acid", "acoustic", "acquired", "acres", "across", "acted", "acting", "actions", "active",
+    "actively", "activist", "activity", "actor", "actors", "actress", "acts", "actual", "actually",
+    "adapted", "added", "adding", "addition", "address", "adjacent", "admiral", "admire", "admit",
+    "admitted", "adopt", "adopted", "adoption", "adorable", "adore", "adult", "adults", "advance",
+    "advanced", "advice", "advise", "advisor", "advisory", "advocate", "affair", "affairs",
+    "affect", "affected", "afford", "afraid", "after", "again", "against", "aged", "agencies",
+    "agency", "agenda", "agent", "agents", "ages", "agree", "agreed", "ahead", "aimed", "aims",
+    "aircraft", "aired", "airfield", "airline", "airlines", "airport", "aisle", "alarm", "album",
+    "albums", "alcohol", "alert", "alien", "alike", "alive", "alleged", "allergic", "alliance",
+    "allied", "allies", "allow", "allowed", "allowing", "allows", "ally", "almost", "alone",
+    "along", "already", "alright", "also", "altar", "although", "altitude", "alumni", "always",
+    "amazing", "amen", "amnesia", "among", "amount", "amounts", "amusing", "analysis", "anchor",
+    "ancient", "anger", "angry", "animals", "animated", "ankle", "announce", "annoying", "annual",
+    "annually", "another", "answer", "answered", "answers", "anxious", "anybody", "anyhow",
+    "anymore", "anyone", "anything", "anytime", "anyway", "anyways", "anywhere", "apart",
+    "apology", "apparent", "appeal", "appeals", "appear", "appeared", "appears", "appetite",
+    "applied", "apply", "approach", "approval", "approve", "approved", "arch", "archives", "area",
+    "areas", "arena", "argue", "argued", "argues", "arguing", "argument", "armed", "armies",
+    "arms", "army", "around", "arrange", "arranged", "arrest", "arrested", "arrival", "arrive",
+    "arrived", "arrives", "arriving", "arrogant", "arson", "article", "articles", "artist",
+    "artistic", "artists", "arts", "artwork", "ashamed", "ashes", "aside", "asked", "asking",
+    "asks", "asleep", "aspect", "aspects", "assault", "assembly", "assets", "assigned", "assist",
+    "assisted", "assists", "assume", "assumed", "assuming", "assure", "assured", "athlete",
+    "athletes", "athletic", "attached", "attack", "attacked", "attacks", "attempt", "attend",
+    "attended", "attic", "attitude", "attorney", "auction", "audience", "audio", "audition",
+    "aunt", "author", "authored", "authors", "auto", "avenue", "average", "aviation", "avoid",
+    "avoiding", "awake", "award", "awarded", "awards", "aware", "away", "awful", "awfully",
+    "awhile", "awkward", "axis", "babies", "baby", "bachelor", "back", "backed", "backing",
+    "backs", "backup", "badge", "badly", "bags", "bail", "bait", "baked", "balance", "balcony",
+    "bald", "ballet", "band", "bands", "bank", "banking", "banned", "barbecue", "bare", "barely",
+    "bargain", "barn", "barrel", "bars", "base", "based", "basement", "bases", "basic", "basis",
+    "bath", "bathroom", "battery", "batting", "battle", "beacon", "beans", "bearing", "bears",
+    "beat", "beaten", "beating", "beats", "became", "because", "become", "becomes", "becoming",
+    "bedroom", "beef", "been", "before", "began", "begged", "begging", "begin", "begins", "begun",
+    "behalf", "behave", "behavior", "behind", "being", "belief", "beliefs", "believe", "believed",
+    "believes", "bells", "belly", "belong", "belonged", "belongs", "beloved", "below", "belt",
+    "bench", "bend", "beneath", "benefit", "benefits", "bent", "beside", "besides", "best", "beta",
+    "betray", "betrayed", "better", "between", "beyond", "bigger", "biggest", "bike", "billion",
+    "binding", "biology", "birds", "birth", "bishops", "bite", "bits", "bitter", "bizarre",
+    "blame", "blamed", "blaming", "blanket", "blast", "bleed", "bleeding", "bless", "blew",
+    "blind", "block", "blocks", "blond", "blood", "bloody", "blow", "blowing", "blown", "blows",
+    "board", "boarding", "boards", "boat", "boats", "bodies", "body", "bomb", "bombing", "bone",
+    "bonus", "book", "booked", "books", "boom", "boot", "booze", "border", "bored", "boring",
+    "born", "borough", "borrow", "borrowed", "boss", "both", "bother", "bothered", "bothers",
+    "bottle", "bottles", "bottom", "bought", "bound", "boundary", "bout", "bowl", "boxes", "boys",
+    "bracelet", "brains", "branches", "brand", "brands", "brass", "brat", "brave", "bread",
+    "break", "breaking", "breaks", "breath", "breathe", "breed", "breeding", "bribe", "brick",
+    "bride", "bridge", "brief", "briefly", "brigade", "bring", "bringing", "brings", "broad",
+    "broke", "broken", "bronze", "brother", "brothers", "brought", "brush", "bucks", "buddies",
+    "budget", "bugging", "bugs", "build", "building", "built", "bullets", "bump", "bureau",
+    "burial", "buried", "burn", "burned", "burning", "burst", "bury", "buses", "business", "bust",
+    "busted", "busy", "butters", "button", "buying", "buzz", "cabin", "cabinet", "cable", "cafe",
+    "cage", "cake", "calendar", "call", "called", "caller", "calling", "calls", "calm", "came",
+    "camera", "cameras", "camp", "campaign", "camping", "camps", "campus", "canal", "cancel",
+    "canceled", "candles", "cannot", "canyon", "capable", "capacity", "cape", "capital", "capture",
+    "captured", "carbon", "card", "cards", "care", "cared", "career", "careful", "cares", "cargo",
+    "caring", "carried", "carries", "carry", "carrying", "cars", "cascade", "case", "cases",
+    "cast", "castle", "casual", "catch", "catching", "category", "cattle", "caught", "cause",
+    "caused", "causes", "causing", "cavalry", "cave", "ceased", "ceiling", "cell", "cells",
+    "cemetery", "census", "center", "centered", "centers", "central", "centre", "centres", "cents",
+    "century", "ceremony", "certain", "chain", "chair", "chairman", "chairs", "chamber", "chance",
+    "chances", "change", "changed", "changes", "changing", "channel", "channels", "chapel",
+    "chapter", "chapters", "charge", "charged", "charges", "charm", "charming", "chart", "charter",
+    "charts", "chasing", "chat", "cheap", "cheat", "cheated", "cheating", "check", "checked",
+    "checking", "checks", "cheer", "chef", "chemical", "chess", "chest", "chick", "chief", "child",
+    "children", "chili", "chill", "chip", "chips", "choice", "choices", "choir", "choose", "chop",
+    "chorus", "chose", "chosen", "church", "churches", "cigar", "cinema", "circle", "circles",
+    "circuit", "circular", "circus", "cited", "cities", "citing", "citizen", "citizens", "city",
+    "civic", "civil", "civilian", "claim", "claimed", "claiming", "claims", "clan", "class",
+    "classes", "clean", "cleaned", "cleaning", "clear", "cleared", "clearly", "clerk", "clever",
+    "client", "clients", "climate", "climb", "clinic", "clinical", "clock", "close", "closed",
+    "closely", "closer", "closest", "closet", "closing", "closure", "clothes", "clothing", "clown",
+    "club", "clubs", "clue", "coach", "coached", "coaches", "coaching", "coal", "coast", "coastal",
+    "coat", "cocktail", "code", "coins", "cold", "collapse", "collar", "collect", "college",
+    "colleges", "colonel", "colonial", "colonies", "colony", "color", "colored", "colour",
+    "colours", "column", "columns", "coma", "combat", "combined", "come", "comedy", "comes",
+    "comfort", "comic", "comics", "coming", "command", "comment", "comments", "commerce", "commit",
+    "common", "commonly", "commons", "commune", "compact", "company", "compare", "compared",
+    "compete", "competed", "compiled", "complain", "complete", "complex", "composed", "composer",
+    "compound", "concept", "concepts", "concern", "concerns", "concert", "concerts", "conduct",
+    "confess", "confirm", "conflict", "confront", "confused", "congress", "connects", "conquest",
+    "consider", "consist", "consists", "constant", "contact", "contacts", "contain", "contains",
+    "content", "contest", "context", "continue", "contract", "contrary", "contrast", "control",
+    "controls", "convince", "convoy", "cooked", "cooking", "cool", "copies", "cops", "copy",
+    "core", "cork", "corn", "corner", "corps", "correct", "cost", "costs", "costume", "cottage",
+    "cough", "could", "council", "counsel", "count", "counter", "counties", "counting", "country",
+    "county", "coup", "couple", "couples", "courage", "course", "courses", "court", "courtesy",
+    "courts", "cousin", "cover", "coverage", "covered", "covering", "covers", "cows", "cozy",
+    "crack", "cracked", "crane", "cranes", "crash", "crashed", "crashing", "crawl", "crawling",
+    "crazy", "cream", "create", "created", "creates", "creating", "creation", "creator",
+    "creature", "credit", "credited", "credits", "creek", "creep", "creeps", "creepy", "crew",
+    "cried", "crime", "crimes", "criminal", "crisis", "criteria", "critic", "critical", "critics",
+    "crops", "crossed", "crosses", "crossing", "crowd", "crown", "cruel", "crush", "crushed",
+    "crying", "cult", "cultural", "culture", "cultures", "cure", "current", "curse", "custody",
+    "customer", "customs", "cute", "cuts", "cutting", "cycle", "cylinder", "daddy", "daily",
+    "damage", "damaged", "dame", "dance", "dancing", "dare", "dark", "darling", "darn", "data",
+    "database", "date", "dated", "dates", "dating", "daughter", "days", "deacon", "deadly", "deaf",
+    "deal", "dealer", "dealing", "deals", "dealt", "dear", "dearest", "deaths", "debate", "debt",
+    "debut", "debuted", "decade", "decades", "decent", "decide", "decided", "decides", "decision",
+    "deck", "declared", "decline", "declined", "deed", "deemed", "deep", "deeper", "deeply",
+    "defeat", "defeated", "defence", "defend", "defended", "defense", "defined", "degree",
+    "degrees", "delay", "delayed", "delicate", "deliver", "delivery", "demand", "demanded",
+    "demands", "democrat", "demon", "demons", "denial", "denied", "density", "dentist", "deny",
+    "denying", "departed", "depend", "depends", "depicted", "deployed", "deposit", "depot",
+    "depth", "deputy", "derby", "derived", "descent", "describe", "deserve", "deserved",
+    "deserves", "design", "designed", "designs", "desk", "despite", "dessert", "destroy", "detail",
+    "detailed", "details", "develop", "device", "devices", "devoted", "dialect", "dialogue",
+    "diameter", "diary", "died", "dies", "diet", "digging", "dignity", "dime", "diner", "dining",
+    "dinner", "diocese", "direct", "directed", "directly", "director", "dirt", "dirty", "disagree",
+    "disaster", "disc", "discuss", "disease", "diseases", "dish", "dishes", "disorder", "display",
+    "displays", "dispute", "distance", "distinct", "distract", "district", "disturb", "ditch",
+    "dive", "diverse", "divided", "division", "divorce", "divorced", "dizzy", "dock", "docks",
+    "doctors", "doctrine", "document", "does", "doing", "doll", "dollars", "domain", "domestic",
+    "dominant", "donated", "done", "donor", "doomed", "door", "doors", "dorm", "double", "doubles",
+    "doubt", "doubts", "dough", "down", "downtown", "dozen", "draft", "drafted", "drag", "dragged",
+    "dragging", "drama", "dramatic", "drank", "draw", "drawer", "drawing", "drawn", "dream",
+    "dreamed", "dreaming", "dress", "dressed", "dresses", "dressing", "drill", "drink", "drinking",
+    "drinks", "drive", "driven", "drivers", "drives", "driving", "drop", "dropped", "dropping",
+    "drops", "drove", "drown", "drowned", "drowning", "drug", "drugged", "drum", "drums", "drunk",
+    "dual", "dubbed", "dude", "dull", "dumb", "dump", "dumped", "during", "dust", "duties", "duty",
+    "dynamic", "dynasty", "each", "eager", "earlier", "earliest", "early", "earn", "earned",
+    "earning", "ears", "earth", "ease", "easier", "easily", "east", "eastern", "easy", "eaten",
+    "eating", "eats", "economic", "economy", "edge", "edited", "edition", "editions", "editor",
+    "educated", "effect", "effects", "effort", "efforts", "eggs", "eight", "eighteen", "eighty",
+    "either", "elders", "eldest", "elected", "election", "elements", "elevated", "elevator",
+    "eleven", "eligible", "elite", "else", "embassy", "emerged", "emerging", "emotion", "emotions",
+    "emperor", "emphasis", "employed", "employee", "empty", "enabled", "ended", "endemic",
+    "ending", "ends", "enemies", "enemy", "energy", "engaged", "engine", "engines", "enjoy",
+    "enjoyed", "enjoying", "enlisted", "enormous", "enough", "enrolled", "ensemble", "ensure",
+    "entered", "entering", "enters", "entire", "entirely", "entitled", "entity", "entrance",
+    "entry", "envelope", "envy", "enzyme", "epic", "episode", "episodes", "equal", "equation",
+    "equipped", "erase", "erected", "error", "escape", "escaped", "essay", "estate", "estates",
+    "ethics", "ethnic", "even", "evening", "event", "events", "eventual", "ever", "every",
+    "everyday", "everyone", "evidence", "evil", "evolved", "exact", "exactly", "exam", "examine",
+    "example", "examples", "except", "exchange", "excited", "exciting", "excuse", "excuses",
+    "executed", "exercise", "exhibit", "exile", "exist", "existed", "existing", "exists", "exit",
+    "expand", "expanded", "expect", "expected", "expense", "expert", "experts", "explain",
+    "explains", "explode", "explore", "export", "expose", "exposed", "exposure", "extend",
+    "extended", "extends", "extent", "external", "extinct", "extra", "eyed", "eyes", "fabulous",
+    "face", "faced", "faces", "facility", "facing", "fact", "factor", "factors", "factory",
+    "facts", "faculty", "fail", "failed", "failing", "failure", "fair", "fairly", "fairy",
+    "faithful", "fake", "faking", "fall", "falling", "falls", "false", "fame", "familiar",
+    "families", "family", "famous", "fancy", "fans", "farm", "farmers", "farming", "farms",
+    "fashion", "fast", "fastest", "fate", "father", "fathers", "fault", "favor", "favorite",
+    "favors", "favour", "fear", "fears", "feature", "featured", "features", "federal", "feed",
+    "feeding", "feel", "feeling", "feelings", "feels", "feet", "fell", "fellow", "felt", "female",
+    "females", "fence", "ferry", "festival", "fever", "fewer", "fiancee", "fiction", "field",
+    "fifteen", "fifth", "fifty", "fight", "fighters", "fighting", "figure", "figured", "figures",
+    "file", "filed", "files", "fill", "filled", "filling", "film", "filmed", "filming", "films",
+    "filthy", "final", "finally", "finals", "finance", "find", "finding", "finds", "fine",
+    "finest", "fingers", "finish", "finished", "fired", "fires", "firing", "firm", "first", "fits",
+    "fitted", "five", "fixed", "fixing", "flag", "flat", "fled", "fleet", "flesh", "flew", "flies",
+    "flight", "flip", "flirting", "floating", "floor", "flow", "flows", "fluid", "flying", "focus",
+    "focused", "focuses", "focusing", "fold", "folk", "folks", "follow", "followed", "follows",
+    "fond", "food", "foods", "fool", "fooled", "fooling", "foolish", "fools", "foot", "footage",
+    "forbid", "force", "forced", "forces", "forcing", "foreign", "forests", "forget", "forgive",
+    "forgiven", "forgot", "fork", "form", "formal", "formally", "format", "formed", "former",
+    "formerly", "forming", "forms", "fort", "forth", "fortress", "fortune", "forty", "forum",
+    "forward", "fought", "foul", "found", "founded", "founder", "founders", "founding", "four",
+    "fourteen", "fourth", "fragile", "frame", "frankly", "fraud", "freak", "freaked", "freaking",
+    "free", "freeze", "freezing", "freight", "frequent", "fresh", "freshman", "fridge", "fried",
+    "friend", "friendly", "friends", "fries", "from", "front", "frozen", "fruit", "fuel", "full",
+    "fully", "function", "fund", "funded", "funding", "funds", "funeral", "funny", "furious",
+    "further", "future", "gain", "gained", "gaining", "gallery", "gambling", "game", "gameplay",
+    "games", "gang", "garage", "garbage", "gardens", "gate", "gather", "gathered", "gauge", "gave",
+    "gear", "geek", "gender", "general", "generous", "genetic", "genre", "gentle", "genuine",
+    "genus", "gesture", "gets", "getting", "ghosts", "giant", "gift", "gifts", "girl", "give",
+    "given", "gives", "giving", "glacier", "glad", "glasses", "global", "globe", "glorious",
+    "gloves", "glow", "goal", "goals", "gods", "goes", "going", "gold", "gone", "good", "goodbye",
+    "goodness", "goods", "goody", "gorgeous", "gospel", "gossip", "gotten", "governor", "gown",
+    "grab", "grabbed", "grade", "grades", "graduate", "grammar", "grams", "grand", "grandma",
+    "grandpa", "grandson", "granted", "grants", "graphic", "graphics", "grass", "grateful",
+    "grave", "great", "greater", "greatest", "greatly", "grew", "grey", "grid", "grief", "grip",
+    "groom", "ground", "grounded", "grounds", "group", "groups", "grow", "growing", "grown",
+    "grows", "growth", "guard", "guards", "guess", "guessed", "guessing", "guest", "guests",
+    "guidance", "guide", "guilt", "guilty", "gulf", "guns", "guts", "guys", "habit", "habitat",
+    "hair", "haircut", "half", "halfway", "hallway", "hand", "handed", "handle", "handled",
+    "handling", "hands", "handsome", "hang", "hanging", "happen", "happened", "happens", "happier",
+    "happiest", "happily", "happy", "harbor", "harbour", "hard", "harder", "hardest", "hardly",
+    "hardware", "harm", "harmless", "harmony", "harsh", "hate", "hated", "hates", "hating", "hats",
+    "haul", "have", "having", "head", "headache", "headed", "heading", "heads", "heal", "healing",
+    "health", "healthy", "hear", "heard", "hearing", "hears", "heart", "heat", "heavens",
+    "heavily", "heavy", "heels", "height", "heir", "held", "hell", "help", "helped", "helpful",
+    "helping", "helpless", "helps", "hence", "here", "heritage", "hero", "heroes", "hers",
+    "herself", "hide", "hiding", "high", "higher", "highest", "highly", "highness", "highway",
+    "highways", "hike", "hills", "himself", "hint", "hire", "hired", "hiring", "historic",
+    "history", "hits", "hitting", "hold", "holding", "holds", "hole", "holes", "holidays",
+    "hollow", "holy", "home", "homeless", "homes", "homework", "homicide", "honest", "honestly",
+    "honesty", "honey", "honor", "honorary", "honored", "honors", "honour", "honours", "hook",
+    "hooked", "hope", "hoped", "hopeless", "hopes", "hoping", "horrible", "horror", "hospital",
+    "host", "hostage", "hosted", "hostile", "hosts", "hotel", "hotels", "hour", "hours", "house",
+    "housed", "houses", "housing", "however", "huge", "human", "humans", "humor", "hunch",
+    "hundred", "hundreds", "hungry", "hurry", "hurt", "hurting", "hurts", "husband", "hush",
+    "hybrid", "idea", "ideal", "ideas", "identify", "identity", "idiot", "idiots", "ignore",
+    "ignoring", "illegal", "illness", "image", "images", "imagine", "imagined", "impact",
+    "imperial", "impress", "improve", "improved", "inch", "inches", "incident", "include",
+    "included", "includes", "income", "increase", "indeed", "index", "indicate", "indoor",
+    "inducted", "industry", "infantry", "informed", "initial", "injured", "injuries", "injury",
+    "inner", "innings", "innocent", "input", "insanity", "insecure", "inside", "insist",
+    "insisted", "inspired", "instance", "instant", "instead", "instinct", "insult", "intend",
+    "intended", "intense", "interest", "interior", "internal", "intimate", "into", "invasion",
+    "invented", "invite", "invited", "inviting", "involve", "involved", "involves", "iron",
+    "ironic", "island", "islands", "isle", "isolated", "issue", "issued", "issues", "item",
+    "items", "itself", "jacket", "jacks", "jail", "jazz", "jealous", "jealousy", "jerk", "jersey",
+    "jewelry", "jobs", "join", "joined", "joining", "joint", "joke", "jokes", "joking", "journal",
+    "journals", "journey", "judge", "judges", "judging", "judgment", "judicial", "jump", "jumped",
+    "jumping", "junction", "junk", "jury", "just", "justify", "keep", "keeping", "keeps", "kept",
+    "kick", "kicked", "kicking", "kicks", "kidding", "kiddo", "kidnap", "kidney", "kids", "kind",
+    "kindly", "kinds", "kingdom", "kings", "kiss", "kissed", "kissing", "kitchen", "knee", "knees",
+    "knew", "knife", "knock", "knocked", "knocking", "know", "knowing", "known", "knows", "label",
+    "labor", "labour", "lack", "ladder", "ladies", "lady", "laid", "lake", "lakes", "lame", "lamp",
+    "land", "landed", "landing", "landmark", "lands", "language", "large", "largely", "larger",
+    "largest", "larvae", "last", "lasted", "late", "lately", "later", "latest", "latter", "laugh",
+    "laughed", "laughing", "laughs", "launch", "launched", "laundry", "lawn", "laws", "lawsuit",
+    "lawyer", "lawyers", "layer", "laying", "layout", "lazy", "lead", "leader", "leaders",
+    "leading", "leads", "leaf", "league", "leagues", "leak", "leap", "learn", "learned",
+    "learning", "least", "leave", "leaves", "leaving", "lecture", "lectures", "leery", "left",
+    "legal", "legally", "legs", "less", "lesser", "lesson", "lessons", "lets", "letter", "letters",
+    "letting", "level", "levels", "liar", "liberal", "library", "license", "licensed", "lied",
+    "lies", "life", "lifetime", "lift", "light", "lighten", "lighting", "like", "liked", "likely",
+    "likes", "liking", "limit", "limited", "limits", "limo", "line", "linear", "lines", "linked",
+    "links", "lions", "lips", "lipstick", "liquor", "list", "listed", "listen", "listened",
+    "listing", "lists", "literacy", "literary", "little", "live", "lived", "liver", "lives",
+    "living", "load", "loaded", "loan", "lobby", "local", "locally", "locate", "located",
+    "location", "lock", "locked", "locker", "lodge", "loft", "logic", "logical", "logo", "lonely",
+    "longer", "longest", "look", "looked", "looking", "looks", "loop", "loose", "lord", "lords",
+    "lose", "loses", "losing", "loss", "losses", "lost", "lots", "loud", "lousy", "loved", "loves",
+    "loving", "lower", "lowest", "loyal", "loyalty", "luck", "luggage", "lunatic", "lunch",
+    "lungs", "lying", "lyrics", "machines", "madam", "made", "magazine", "magical", "magnetic",
+    "maid", "mail", "main", "mainland", "mainly", "maintain", "majesty", "major", "majority",
+    "make", "makes", "makeup", "making", "male", "males", "mall", "mama", "manage", "managed",
+    "manager", "manages", "managing", "manga", "manner", "manners", "manor", "mansion", "many",
+    "maps", "march", "margin", "maritime", "marked", "market", "markets", "marriage", "married",
+    "marry", "marrying", "martial", "mask", "mass", "massacre", "massage", "match", "matches",
+    "mate", "material", "math", "matter", "matters", "maximum", "maybe", "mayor", "meal", "mean",
+    "meaning", "means", "meant", "meantime", "measure", "measured", "measures", "meat", "medal",
+    "medals", "media", "median", "medical", "medicine", "medieval", "medium", "meet", "meeting",
+    "meetings", "meets", "member", "members", "memorial", "memories", "memory", "mental",
+    "mention", "menu", "merely", "merged", "merger", "mess", "message", "messages", "messed",
+    "messing", "messy", "metal", "meter", "meters", "method", "methods", "metre", "metres",
+    "metro", "middle", "might", "mile", "military", "militia", "milk", "mill", "million",
+    "millions", "mind", "minded", "minds", "mine", "mines", "mini", "minimum", "mining",
+    "minister", "ministry", "minority", "minus", "minute", "minutes", "miracle", "miracles",
+    "mirror", "misery", "miss", "missed", "misses", "missile", "missing", "mission", "missions",
+    "mistake", "mistaken", "mistakes", "mixed", "mixture", "mobile", "mode", "model", "models",
+    "moderate", "modern", "modified", "moment", "moments", "mommy", "monsters", "month",
+    "monument", "mood", "moral", "more", "moreover", "morning", "moron", "mortal", "mosque",
+    "most", "mostly", "motel", "moth", "mothers", "motion", "motive", "motor", "mount", "mounted",
+    "mouth", "move", "moved", "movement", "moves", "movie", "movies", "moving", "much", "multiple",
+    "munich", "murderer", "museum", "museums", "music", "musical", "musician", "must", "mutual",
+    "myself", "mystery", "nail", "nails", "name", "named", "namely", "names", "nanny", "narrow",
+    "nation", "national", "nations", "native", "natural", "nature", "naval", "navy", "near",
+    "nearby", "nearest", "nearly", "neat", "neck", "necklace", "need", "needed", "needing",
+    "needle", "needs", "negative", "neighbor", "neither", "nephew", "nerve", "nerves", "nervous",
+    "nest", "network", "networks", "never", "newly", "news", "next", "nice", "nicely", "nicer",
+    "nickname", "niece", "night", "nine", "nineteen", "ninety", "ninth", "nobody", "noise", "none",
+    "nonsense", "noon", "normal", "normally", "north", "northern", "nose", "notable", "notably",
+    "note", "noted", "notes", "nothing", "notice", "noticed", "novel", "novels", "nowhere",
+    "nuclear", "number", "numbered", "numerous", "nurse", "nurses", "nuts", "oath", "object",
+    "objects", "observed", "obsessed", "obtain", "obtained", "obvious", "occasion", "occupied",
+    "occur", "occurred", "occurs", "ocean", "odds", "offense", "offer", "offered", "offering",
+    "offers", "office", "officer", "officers", "offices", "official", "often", "older", "oldest",
+    "once", "ones", "ongoing", "only", "onto", "open", "opened", "opening", "opens", "opera",
+    "operate", "operated", "operates", "opinion", "opponent", "opposed", "opposite", "optical",
+    "option", "options", "oral", "orbit", "ordained", "order", "ordered", "ordering", "orders",
+    "ordinary", "organ", "organic", "oriented", "origin", "original", "origins", "orthodox",
+    "other", "others", "ought", "ours", "outbreak", "outdoor", "outer", "outfit", "output",
+    "outside", "oven", "over", "overall", "overseas", "overview", "owes", "owned", "owner",
+    "owners", "owns", "oxygen", "pack", "package", "packed", "packing", "pages", "paid", "pain",
+    "painful", "paint", "painted", "painting", "pair", "pairs", "palace", "pale", "palm",
+    "pancakes", "panel", "panic", "pants", "paper", "papers", "parade", "parallel", "paranoid",
+    "pardon", "parent", "parents", "parish", "park", "parked", "parking", "parole", "part",
+    "partial", "parties", "partly", "partner", "partners", "parts", "party", "passage", "passed",
+    "passes", "passing", "past", "patch", "patent", "path", "pathetic", "patient", "patients",
+    "patrol", "patron", "pattern", "patterns", "payback", "paying", "payment", "pays", "peace",
+    "peaceful", "peak", "peaked", "peer", "penalty", "people", "percent", "perfect", "perform",
+    "perfume", "perhaps", "period", "periods", "person", "personal", "persons", "phase", "phone",
+    "phones", "phony", "photo", "phrase", "physical", "physics", "piano", "pick", "picked",
+    "picking", "picks", "picnic", "picture", "pictures", "piece", "pieces", "pier", "pigs", "pile",
+    "pill", "pills", "pilot", "pilots", "pine", "pipe", "pitch", "pitched", "pitcher", "pity",
+    "place", "placed", "places", "placing", "plain", "plains", "plan", "plane", "planned",
+    "planning", "plans", "plant", "planted", "plants", "plate", "plates", "platform", "play",
+    "played", "players", "playing", "playoff", "playoffs", "plays", "plaza", "plea", "plead",
+    "pleasant", "please", "pleased", "pleasure", "plenty", "plot", "plug", "plus", "pocket",
+    "poem", "poems", "poet", "poetry", "point", "pointed", "pointing", "points", "poker", "pole",
+    "policies", "policy", "polish", "polite", "politics", "poll", "pool", "poor", "pops",
+    "popular", "porch", "port", "portion", "portions", "portrait", "ports", "position", "positive",
+    "possible", "possibly", "post", "posted", "poster", "posts", "potatoes", "potion", "pound",
+    "pounds", "pour", "poverty", "power", "powered", "powerful", "practice", "praise", "praised",
+    "pray", "prayer", "prayers", "praying", "prefer", "pregnant", "premier", "premiere", "prepare",
+    "prepared", "presence", "present", "presents", "preserve", "press", "pressing", "pressure",
+    "pretend", "pretty", "prevent", "previous", "prices", "pride", "priests", "primary", "prime",
+    "print", "printed", "printing", "prints", "prior", "priority", "prison", "prisoner", "privacy",
+    "prize", "probably", "problem", "problems", "proceed", "process", "produce", "produced",
+    "producer", "produces", "product", "products", "profile", "profit", "program", "programs",
+    "progress", "project", "projects", "prom", "promise", "promised", "promises", "promote",
+    "promoted", "proof", "proper", "properly", "property", "proposal", "propose", "proposed",
+    "protect", "protein", "proteins", "protest", "protests", "protocol", "proud", "prove",
+    "proved", "proven", "proves", "provide", "provided", "provides", "province", "psychic",
+    "public", "publicly", "pull", "pulled", "pulling", "pulls", "pulse", "pump", "punch", "punish",
+    "punished", "punk", "pupils", "purchase", "pure", "purpose", "purposes", "purse", "pursue",
+    "push", "pushed", "pushing", "puts", "putting", "puzzle", "qualify", "quality", "quarter",
+    "quarters", "queen", "question", "quick", "quickly", "quiet", "quietly", "quit", "quite",
+    "quiz", "quote", "quoted", "rabbi", "race", "races", "racial", "rack", "radar", "radical",
+    "radio", "rage", "raid", "rail", "railroad", "railway", "railways", "rain", "raise", "raised",
+    "raising", "rally", "ranch", "range", "ranges", "ranging", "rank", "ranked", "ranking",
+    "ranks", "rapid", "rapidly", "rare", "rarely", "rate", "rated", "rates", "rather", "rating",
+    "ratings", "ratio", "rational", "rats", "reach", "reached", "reaches", "reaching", "react",
+    "reaction", "read", "readers", "reading", "ready", "real", "reality", "realize", "realized",
+    "realizes", "really", "realm", "rear", "reason", "reasons", "rebuilt", "recall", "recalled",
+    "receipt", "receive", "received", "receiver", "receives", "recent", "recently", "recipe",
+    "record", "recorded", "records", "recover", "recovery", "reduce", "reduced", "reducing",
+    "refer", "referred", "refers", "reflect", "reform", "reformed", "reforms", "refuge",
+    "refugees", "refuse", "refused", "regard", "regarded", "regime", "regiment", "region",
+    "regional", "regions", "register", "regret", "regrets", "regular", "rehab", "reign",
+    "rejected", "related", "relating", "relation", "relative", "relax", "relaxed", "relay",
+    "release", "released", "releases", "relevant", "relief", "relieved", "religion", "remain",
+    "remained", "remains", "remember", "remind", "reminded", "reminds", "remote", "removal",
+    "remove", "removed", "renamed", "renowned", "rent", "rented", "repair", "repay", "repeat",
+    "repeated", "replace", "replaced", "report", "reported", "reporter", "reports", "republic",
+    "request", "require", "required", "requires", "research", "resent", "reserve", "reserves",
+    "resident", "residing", "resigned", "resist", "resort", "resource", "respect", "respects",
+    "respond", "response", "rest", "restored", "result", "resulted", "results", "resumed",
+    "retail", "retain", "retained", "retired", "retiring", "retreat", "return", "returned",
+    "returns", "reunion", "reveal", "revealed", "reveals", "revenge", "revenue", "reverend",
+    "reverse", "review", "reviewed", "reviews", "revised", "revival", "reward", "rhythm", "ride",
+    "riders", "ridge", "riding", "rifle", "right", "ring", "ringing", "rings", "riot", "ripped",
+    "rise", "rising", "risk", "risks", "risky", "ritual", "rival", "rivals", "river", "road",
+    "roads", "roast", "robbed", "robbery", "robe", "rock", "role", "roles", "roll", "rolled",
+    "rolling", "rolls", "romance", "romantic", "roof", "room", "roommate", "rooms", "roots",
+    "rope", "roses", "roster", "rough", "roughly", "round", "route", "routes", "routine", "royal",
+    "rude", "rugby", "ruin", "ruined", "ruining", "ruins", "rule", "ruled", "ruler", "rules",
+    "ruling", "rumor", "rumors", "runners", "running", "runs", "rural", "rushed", "sack", "sacred",
+    "safe", "safely", "safer", "safety", "said", "sail", "sailed", "saint", "sake", "sakes",
+    "salad", "salary", "sale", "sales", "salesman", "salt", "same", "sand", "sandwich", "sane",
+    "sauce", "save", "saved", "saving", "saying", "says", "scale", "scam", "scan", "scandal",
+    "scar", "scare", "scared", "scares", "scarf", "scaring", "scary", "scenario", "scene",
+    "scenes", "schedule", "scheme", "scholar", "scholars", "school", "schools", "science",
+    "sciences", "score", "scored", "scores", "scoring", "scratch", "screen", "screw", "screwed",
+    "screwing", "script", "scum", "sealed", "search", "season", "seasons", "seat", "seats",
+    "second", "seconds", "secrets", "section", "sections", "sector", "secure", "secured", "seduce",
+    "seed", "seeds", "seeing", "seek", "seeking", "seem", "seemed", "seems", "seen", "sees",
+    "segment", "segments", "selected", "self", "selfish", "sell", "selling", "semester",
+    "seminary", "senate", "senator", "send", "sending", "sends", "senior", "sense", "senses",
+    "sent", "sentence", "separate", "sequel", "sequence", "sergeant", "serial", "series",
+    "serious", "serve", "served", "serves", "service", "services", "serving", "session",
+    "sessions", "sets", "setting", "settle", "settled", "settlers", "setup", "seven", "seventh",
+    "seventy", "several", "severe", "severely", "shadows", "shake", "shaking", "shall", "shallow",
+    "shame", "shape", "shaped", "share", "shared", "shares", "sharing", "shave", "shed", "sheep",
+    "sheet", "shelter", "sheriff", "shield", "shift", "shine", "shining", "ship", "shipping",
+    "ships", "shirt", "shirts", "shock", "shocked", "shoe", "shoes", "shoot", "shooting", "shop",
+    "shopping", "shops", "shore", "short", "shortly", "shorts", "shot", "shots", "should",
+    "shoulder", "shove", "show", "showed", "shower", "showing", "shown", "shows", "shrink", "shut",
+    "sick", "sickness", "side", "sides", "siege", "sight", "sign", "signal", "signals", "signed",
+    "signing", "signs", "silence", "silent", "silk", "silly", "similar", "simply", "since",
+    "sincere", "sing", "singer", "singers", "singing", "single", "singles", "sink", "sins",
+    "sister", "sisters", "site", "sites", "sits", "sitting", "situated", "sixteen", "sixth",
+    "sixty", "size", "sized", "skating", "skill", "skills", "skin", "skip", "skirt", "skull",
+    "slap", "slavery", "slaves", "sleep", "sleeping", "slept", "slice", "slide", "slight", "slip",
+    "slipped", "slow", "slowly", "small", "smaller", "smart", "smarter", "smell", "smelled",
+    "smells", "smile", "smiling", "smoking", "snack", "snap", "sneak", "sneaking", "snuck", "soap",
+    "social", "society", "sock", "socks", "soda", "sofa", "soft", "soil", "solar", "sold",
+    "soldiers", "sole", "solid", "solo", "solution", "solve", "solved", "some", "somebody",
+    "someday", "somehow", "someone", "sometime", "somewhat", "song", "songs", "sons", "soon",
+    "sooner", "sore", "sorry", "sort", "sorts", "sought", "soul", "souls", "sound", "sounded",
+    "sounds", "soup", "source", "sources", "south", "southern", "space", "spaces", "span", "spare",
+    "speak", "speakers", "speaking", "speaks", "special", "species", "specific", "speech", "speed",
+    "spell", "spells", "spend", "spending", "spent", "spill", "spilled", "spin", "spinning",
+    "spirits", "spit", "spite", "split", "spoil", "spoiled", "spoke", "spoken", "sport",
+    "sporting", "sports", "spot", "spots", "spotted", "spray", "spread", "springs", "spying",
+    "squad", "squadron", "square", "squeeze", "stab", "stabbed", "stable", "stadium", "staff",
+    "stage", "stages", "stairs", "stake", "stakes", "stalking", "stall", "stamp", "stand",
+    "standard", "standing", "stands", "stare", "staring", "starred", "starring", "stars", "start",
+    "started", "starters", "starting", "starts", "starving", "state", "stated", "states",
+    "stating", "station", "stations", "statue", "status", "stay", "stayed", "staying", "stays",
+    "steady", "steak", "steal", "stealing", "steam", "steel", "stem", "step", "stepped", "steps",
+    "stick", "sticking", "stiff", "still", "stink", "stinks", "stir", "stock", "stole", "stolen",
+    "stomach", "stood", "stop", "stopped", "stopping", "stops", "storage", "store", "stored",
+    "stores", "stories", "story", "straight", "strange", "strategy", "stream", "street", "streets",
+    "stress", "stressed", "stretch", "strictly", "strikes", "string", "strings", "strip", "stroke",
+    "stronger", "strongly", "struck", "struggle", "stubborn", "stuck", "student", "students",
+    "studied", "studies", "studio", "studios", "study", "studying", "stuff", "stuffed", "stunt",
+    "style", "styles", "subject", "subjects", "subtle", "suburb", "suburban", "suburbs", "succeed",
+    "such", "sudden", "suddenly", "suffer", "suffered", "suggest", "suggests", "suit", "suitable",
+    "suitcase", "suite", "suits", "summary", "superior", "supper", "supplied", "supplies",
+    "supply", "support", "supports", "suppose", "supposed", "supreme", "sure", "surely", "surface",
+    "surgeon", "surgery", "surname", "surprise", "survey", "survival", "survive", "survived",
+    "suspect", "suspects", "swear", "sweat", "sweater", "sweep", "sweet", "sweetie", "swell",
+    "swim", "swing", "switch", "switched", "swore", "sworn", "symbol", "sympathy", "symphony",
+    "symptoms", "syndrome", "system", "systems", "tabby", "table", "tables", "tactical", "tail",
+    "take", "taken", "takes", "taking", "tale", "talent", "talented", "tales", "talk", "talked",
+    "talking", "talks", "tall", "tank", "tanks", "tape", "tapes", "targets", "task", "tasks",
+    "taste", "tastes", "taught", "taxes", "taxi", "teach", "teachers", "teaching", "team", "teams",
+    "tear", "tearing", "tears", "tech", "teenage", "teenager", "teeth", "tell", "telling", "tells",
+    "temper", "temple", "temples", "tend", "tense", "tension", "tent", "tenth", "tenure", "term",
+    "terminal", "terminus", "terms", "terrible", "terribly", "terrific", "tested", "testify",
+    "tests", "text", "texts", "than", "thank", "thankful", "thanking", "thanks", "that", "theater",
+    "theatre", "thee", "their", "theirs", "them", "theme", "themes", "then", "theology", "theorem",
+    "theories", "theory", "therapy", "there", "thereby", "these", "they", "thick", "thief",
+    "thing", "things", "think", "thinking", "thinks", "third", "thirsty", "thirty", "this",
+    "those", "thou", "though", "thought", "thousand", "threat", "threaten", "threats", "three",
+    "threw", "thrilled", "throat", "throne", "through", "throw", "throwing", "thrown", "throws",
+    "thumb", "thus", "ticket", "tickets", "tied", "tier", "ties", "tight", "till", "time", "times",
+    "timing", "tiny", "tips", "tire", "tired", "tissue", "title", "titled", "titles", "toast",
+    "today", "toes", "together", "toilet", "told", "tomb", "tomorrow", "tone", "tongue", "tonight",
+    "tons", "took", "tool", "tools", "tooth", "topic", "topics", "tops", "tore", "torn", "torture",
+    "toss", "total", "totally", "touch", "touched", "touching", "tough", "tour", "toured",
+    "touring", "tourism", "tourist", "tours", "toward", "towards", "towel", "towels", "tower",
+    "towers", "town", "towns", "toys", "trace", "track", "tracked", "tracking", "tracks", "trade",
+    "traded", "trading", "traffic", "tragedy", "tragic", "trail", "train", "trained", "training",
+    "trains", "tramp", "transfer", "transit", "trap", "trapped", "trash", "trauma", "traveled",
+    "travels", "treat", "treated", "treating", "treats", "treaty", "tree", "trees", "trial",
+    "trials", "tribal", "tribe", "tribes", "tribute", "trick", "tricked", "tricks", "tried",
+    "tries", "trio", "trip", "triple", "troops", "trophy", "tropical", "troubles", "true", "truly",
+    "trunk", "trust", "trusted", "trustees", "trusting", "truth", "trying", "tube", "tumor",
+    "tuna", "tune", "tunnel", "turn", "turned", "turning", "turns", "twelve", "twenty", "twice",
+    "twin", "twins", "twist", "type", "types", "typical", "ugly", "unable", "uncle", "under",
+    "unfair", "unhappy", "uniform", "union", "unions", "unique", "unit", "united", "units",
+    "unity", "universe", "unknown", "unless", "unlike", "until", "unusual", "upcoming", "updated",
+    "upon", "upper", "upset", "upside", "upstairs", "urban", "urgent", "usage", "used", "useful",
+    "useless", "user", "users", "uses", "using", "usual", "usually", "valley", "valuable", "value",
+    "values", "vampires", "vanquish", "variable", "variant", "variants", "varied", "varies",
+    "variety", "various", "vary", "vast", "vault", "vehicle", "vehicles", "venture", "venue",
+    "venues", "verdict", "verse", "version", "versions", "versus", "vertical", "very", "vessel",
+    "vessels", "veteran", "veterans", "vibe", "vice", "vicious", "victim", "victims", "video",
+    "videos", "view", "viewed", "viewers", "views", "village", "villages", "violence", "violent",
+    "virtual", "virus", "visible", "visions", "visit", "visited", "visiting", "visitor",
+    "visitors", "visits", "visual", "vocal", "vocalist", "vocals", "vodka", "voice", "voiced",
+    "voices", "volume", "volumes", "vote", "voted", "voters", "votes", "voting", "vows", "wait",
+    "waited", "waiter", "waiting", "waitress", "wake", "wakes", "waking", "walk", "walked",
+    "walking", "walks", "wallet", "want", "wanted", "wanting", "wants", "wardrobe", "warfare",
+    "warm", "warn", "warned", "warning", "warrant", "wars", "wash", "washed", "waste", "wasted",
+    "wasting", "watch", "watched", "watching", "water", "wave", "waves", "ways", "weak",
+    "weakness", "wealth", "wealthy", "weapon", "weapons", "wear", "wearing", "wears", "weather",
+    "website", "wedding", "weddings", "week", "weekend", "weekly", "weight", "weird", "welfare",
+    "well", "went", "were", "western", "whack", "what", "wheel", "when", "whenever", "where",
+    "whereas", "wherever", "whether", "which", "while", "whilst", "whip", "whistle", "whoever",
+    "whole", "whom", "whose", "wickets", "wide", "widely", "wider", "widow", "wife", "wild",
+    "wildlife", "will", "willing", "wind", "window", "winds", "wine", "wing", "wings", "winners",
+    "winning", "wins", "wipe", "wiped", "wire", "wish", "wished", "wishes", "wishing", "witch",
+    "witches", "with", "within", "without", "witness", "wives", "woke", "woman", "women", "wonder",
+    "wondered", "wooden", "word", "words", "wore", "work", "worked", "worker", "workers",
+    "working", "works", "world", "worn", "worried", "worries", "worry", "worrying", "worse",
+    "worship", "worst", "worth", "would", "wound", "wounded", "wounds", "wrap", "wrapped", "wreck",
+    "wrist", "write", "writer", "writers", "writes", "writing", "writings", "written", "wrong",
+    "wrote", "yacht", "yard", "yards", "year", "years", "yell", "yelling", "younger", "youngest",
+    "your", "yours", "yourself", "youth", "zero", "zone",
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_words_are_sorted_and_unique() {
+        assert!(WORDS.windows(2).all(|pair| pair[0] < pair[1]));
+    }
+
+    #[test]
+    fn test_words_are_short_lowercase() {
+        for word in WORDS {
+            assert!(
+                (4..=8).contains(&word.len()) && word.bytes().all(|b| b.is_ascii_lowercase()),
+                "unexpected word '{}'",
+                word
+            );
+        }
+    }
+}
@@ -2,11 +2,26 @@
 //!
 //! These tests verify the complete workflow of the password manager.
 
-use passmgr::credentials::Credentials;
-use passmgr::manager::Manager;
-use passmgr::shell::command::{CommandRegistry, CommandResult, ShellContext};
+use chrono::{TimeZone, Utc};
+use log::LevelFilter;
+use passmgr::backup::BackupStatus;
+use passmgr::credentials::{
+    Conflict, CredentialError, CredentialLimits, Credentials, MergePolicy, MergeReport,
+    NameNormalization, mask_secret,
+};
+use passmgr::logging::JsonLogger;
+use passmgr::manager::{CheckStatus, LoadError, Manager};
+use passmgr::shell::Shell;
+use passmgr::shell::command::{CommandRegistry, CommandResult, ShellContext, check_arg_count};
 use passmgr::shell::commands::register_all;
+use passmgr::shell::session::SaveFnSession;
+use passmgr::shell::suspend::SuspendSignal;
+use passmgr::storage::{InMemoryBackend, StorageBackend, StorageError, decode_nonce, decode_salt};
 use passmgr::trie::Trie;
+use std::fs;
+use std::io::Write;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
 use tempfile::TempDir;
 
 /// Creates a test environment with a temporary directory.
@@ -20,6 +35,22 @@ fn setup_test_env() -> (Manager, TempDir) {
     (manager, temp_dir)
 }
 
+/// Creates a manager storing its vault in memory, along with a handle to
+/// that storage for opening it again or inspecting it.
+fn setup_in_memory() -> (Manager, InMemoryBackend) {
+    let backend = InMemoryBackend::new();
+    let mut manager = Manager::new();
+    manager.set_backend(Box::new(backend.clone()));
+    (manager, backend)
+}
+
+/// Creates a manager opening the vault stored in `backend`.
+fn manager_for(backend: &InMemoryBackend) -> Manager {
+    let mut manager = Manager::new();
+    manager.set_backend(Box::new(backend.clone()));
+    manager
+}
+
 /// Creates a command registry for testing.
 fn create_registry() -> CommandRegistry {
     let mut registry = CommandRegistry::new();
@@ -33,7 +64,7 @@ fn create_registry() -> CommandRegistry {
 
 #[test]
 fn test_manager_new_user_setup() {
-    let (mut manager, _temp_dir) = setup_test_env();
+    let (mut manager, _backend) = setup_in_memory();
 
     assert!(manager.is_new_user(), "Should be a new user initially");
 
@@ -45,18 +76,13 @@ fn test_manager_new_user_setup() {
 
 #[test]
 fn test_manager_password_validation() {
-    let temp_dir = TempDir::new().expect("Failed to create temp dir");
-    let db_path = temp_dir.path().join("test_passwords.db");
-
-    let mut manager = Manager::new();
-    manager.set_db_path(db_path.clone());
+    let (mut manager, backend) = setup_in_memory();
     manager
         .setup_new_user("correct_password".to_string())
         .expect("Setup failed");
 
     // Create a new manager instance pointing to the same database
-    let mut manager2 = Manager::new();
-    manager2.set_db_path(db_path);
+    let mut manager2 = manager_for(&backend);
 
     // Validate with correct password
     let valid = manager2
@@ -65,8 +91,7 @@ fn test_manager_password_validation() {
     assert!(valid, "Correct password should validate");
 
     // Validate with wrong password (need fresh manager)
-    let mut manager3 = Manager::new();
-    manager3.set_db_path(temp_dir.path().join("test_passwords.db"));
+    let mut manager3 = manager_for(&backend);
     let valid = manager3
         .validate_master_password("wrong_password".to_string())
         .expect("Validation should not error");
@@ -74,14 +99,325 @@ fn test_manager_password_validation() {
 }
 
 #[test]
-fn test_manager_credential_persistence() {
-    let temp_dir = TempDir::new().expect("Failed to create temp dir");
+fn test_manager_verify_backups() {
+    let (mut manager, temp_dir) = setup_test_env();
+    let db_path = temp_dir.path().join("test_passwords.db");
+    let backup_dir = temp_dir.path().join("backups");
+    fs::create_dir(&backup_dir).unwrap();
+
+    manager
+        .setup_new_user("correct_password".to_string())
+        .expect("Setup failed");
+    manager
+        .credentials_mut()
+        .add("github".to_string(), "secret1".to_string())
+        .unwrap();
+    manager
+        .credentials_mut()
+        .add("email".to_string(), "secret2".to_string())
+        .unwrap();
+    manager.save_credentials().expect("Save failed");
+
+    // A good backup is a copy of the live store
+    fs::copy(&db_path, backup_dir.join("1-good.db")).unwrap();
+
+    // A corrupted backup has its ciphertext tampered with
+    let mut store: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(&db_path).unwrap()).unwrap();
+    store["encrypted_data"] = serde_json::Value::String("AAAAAAAAAAAAAAAAAAAAAAAA".to_string());
+    fs::write(backup_dir.join("2-corrupted.db"), store.to_string()).unwrap();
+
+    // A truncated backup is not valid JSON at all
+    fs::write(backup_dir.join("3-truncated.db"), "{\"version\": 2,").unwrap();
+
+    // A backup encrypted with another password
+    let mut other = Manager::new();
+    other.set_db_path(backup_dir.join("4-other-password.db"));
+    other
+        .setup_new_user("other_password".to_string())
+        .expect("Setup failed");
+
+    let reports = manager.verify_backups().expect("Verification failed");
+    let summary: Vec<(String, Option<usize>)> = reports
+        .iter()
+        .map(|r| {
+            let name = r.path.file_name().unwrap().to_string_lossy().into_owned();
+            match r.status {
+                BackupStatus::Valid { credentials } => (name, Some(credentials)),
+                BackupStatus::Invalid { .. } => (name, None),
+            }
+        })
+        .collect();
+
+    assert_eq!(
+        summary,
+        vec![
+            // The copy made before the last save, when the vault was empty
+            ("test_passwords.db.bak".to_string(), Some(0)),
+            ("1-good.db".to_string(), Some(2)),
+            ("2-corrupted.db".to_string(), None),
+            ("3-truncated.db".to_string(), None),
+            ("4-other-password.db".to_string(), None),
+        ]
+    );
+}
+
+#[test]
+fn test_save_keeps_previous_store_as_backup() {
+    let (mut manager, temp_dir) = setup_test_env();
     let db_path = temp_dir.path().join("test_passwords.db");
+    let bak_path = temp_dir.path().join("test_passwords.db.bak");
+
+    manager
+        .setup_new_user("correct_password".to_string())
+        .expect("Setup failed");
+    manager
+        .credentials_mut()
+        .add("github".to_string(), "secret1".to_string())
+        .unwrap();
+    manager.save_credentials().expect("Save failed");
+    let before = fs::read_to_string(&db_path).unwrap();
+
+    manager
+        .credentials_mut()
+        .add("email".to_string(), "secret2".to_string())
+        .unwrap();
+    manager.save_credentials().expect("Save failed");
+
+    // The backup holds exactly the store as it was before the last save
+    assert_eq!(fs::read_to_string(&bak_path).unwrap(), before);
+
+    let mut restored = Manager::new();
+    restored.set_db_path(bak_path);
+    assert!(
+        restored
+            .validate_master_password("correct_password".to_string())
+            .unwrap()
+    );
+    assert_eq!(restored.credentials().list(), vec!["github"]);
+
+    // No timestamped backups unless configured
+    assert!(!temp_dir.path().join("backups").exists());
+}
+
+#[test]
+fn test_save_rotates_timestamped_backups() {
+    let (mut manager, _temp_dir) = setup_test_env();
+    manager.set_backup_count(2);
+    manager
+        .setup_new_user("correct_password".to_string())
+        .expect("Setup failed");
+
+    for i in 0..4 {
+        manager
+            .credentials_mut()
+            .add(format!("key{}", i), "secret".to_string())
+            .unwrap();
+        manager.save_credentials().expect("Save failed");
+        // Timestamps have millisecond resolution
+        std::thread::sleep(std::time::Duration::from_millis(5));
+    }
+
+    let reports = manager.verify_backups().expect("Verification failed");
+    let counts: Vec<Option<usize>> = reports
+        .iter()
+        .map(|r| match r.status {
+            BackupStatus::Valid { credentials } => Some(credentials),
+            BackupStatus::Invalid { .. } => None,
+        })
+        .collect();
+    // `.bak`, then the two newest timestamped backups
+    assert_eq!(counts, vec![Some(3), Some(2), Some(3)]);
+}
+
+#[test]
+fn test_manager_restore_backup() {
+    let (mut manager, temp_dir) = setup_test_env();
+    manager
+        .setup_new_user("correct_password".to_string())
+        .expect("Setup failed");
+    manager
+        .credentials_mut()
+        .add("github".to_string(), "secret1".to_string())
+        .unwrap();
+    manager.save_credentials().expect("Save failed");
+    manager.credentials_mut().remove("github");
+    manager.save_credentials().expect("Save failed");
+
+    let err = manager
+        .restore_backup("test_passwords.db.bak", "wrong_password".to_string())
+        .unwrap_err();
+    assert!(err.to_string().contains("Could not decrypt"));
+    assert!(manager.credentials().is_empty());
+
+    assert!(
+        manager
+            .restore_backup("../test_passwords.db", "correct_password".to_string())
+            .is_err()
+    );
+    assert!(
+        manager
+            .restore_backup("missing.db", "correct_password".to_string())
+            .is_err()
+    );
+
+    manager
+        .restore_backup("test_passwords.db.bak", "correct_password".to_string())
+        .expect("Restore failed");
+    assert_eq!(
+        manager.credentials().get("github"),
+        Some(&"secret1".to_string())
+    );
+
+    // The restored credentials were saved
+    let mut reloaded = Manager::new();
+    reloaded.set_db_path(temp_dir.path().join("test_passwords.db"));
+    assert!(
+        reloaded
+            .validate_master_password("correct_password".to_string())
+            .unwrap()
+    );
+    assert!(reloaded.credentials().get("github").is_some());
+}
+
+#[test]
+fn test_manager_verify_healthy_vault() {
+    let (mut manager, _backend) = setup_in_memory();
+    manager
+        .setup_new_user("correct_password".to_string())
+        .expect("Setup failed");
+    manager.enable_recovery().expect("Recovery failed");
+
+    let report = manager.verify().expect("Verification failed");
+    assert!(report.passed(), "{:?}", report);
+    assert_eq!(report.checks.len(), 6);
+}
+
+#[test]
+fn test_manager_verify_reports_damage() {
+    let (mut manager, backend) = setup_in_memory();
+    manager
+        .setup_new_user("correct_password".to_string())
+        .expect("Setup failed");
+    let original = backend.contents().unwrap();
+
+    let statuses = |manager: &Manager| -> Vec<CheckStatus> {
+        manager
+            .verify()
+            .expect("Verification failed")
+            .checks
+            .into_iter()
+            .map(|check| check.status)
+            .collect()
+    };
+
+    // A short nonce fails its check, and the later checks are skipped
+    let mut store: serde_json::Value = serde_json::from_str(&original).unwrap();
+    store["encryption_nonce"] = serde_json::Value::String("AAAA".to_string());
+    backend.set_contents(store.to_string());
+    let statuses_after = statuses(&manager);
+    assert_eq!(
+        statuses_after[..2],
+        [CheckStatus::Passed, CheckStatus::Passed]
+    );
+    assert_eq!(
+        statuses_after[2],
+        CheckStatus::Failed("found a 3-byte nonce".to_string())
+    );
+    assert!(
+        statuses_after[3..]
+            .iter()
+            .all(|s| *s == CheckStatus::Skipped)
+    );
+
+    // Tampered ciphertext fails decryption
+    let mut store: serde_json::Value = serde_json::from_str(&original).unwrap();
+    store["encrypted_data"] = serde_json::Value::String("AAAAAAAAAAAAAAAAAAAAAAAA".to_string());
+    backend.set_contents(store.to_string());
+    let statuses_after = statuses(&manager);
+    assert!(matches!(statuses_after[4], CheckStatus::Failed(_)));
+    assert_eq!(statuses_after[5], CheckStatus::Skipped);
+
+    // Invalid base64
+    let mut store: serde_json::Value = serde_json::from_str(&original).unwrap();
+    store["argon2_salt"] = serde_json::Value::String("not base64!".to_string());
+    backend.set_contents(store.to_string());
+    assert!(matches!(statuses(&manager)[1], CheckStatus::Failed(_)));
+
+    // Verifying never modifies the file
+    let damaged = backend.contents();
+    manager.verify().unwrap();
+    assert_eq!(backend.contents(), damaged);
+}
+
+#[test]
+fn test_manager_switch_vault_clears_previous() {
+    let (mut manager, temp_dir) = setup_test_env();
+    let personal_path = temp_dir.path().join("test_passwords.db");
+    let work_path = temp_dir.path().join("work.db");
+
+    // Prepare an independent work vault
+    let mut work = Manager::new();
+    work.set_db_path(work_path.clone());
+    work.setup_new_user("work_password".to_string())
+        .expect("Setup failed");
+    work.credentials_mut()
+        .add("jira".to_string(), "work-secret".to_string())
+        .unwrap();
+    work.save_credentials().expect("Save failed");
+
+    manager
+        .setup_new_user("personal_password".to_string())
+        .expect("Setup failed");
+    manager
+        .credentials_mut()
+        .add("github".to_string(), "personal-secret".to_string())
+        .unwrap();
+    manager.save_credentials().expect("Save failed");
+    manager.add_vault("default", personal_path);
+    manager.add_vault("work", work_path);
+
+    let names: Vec<String> = manager.vaults().into_iter().map(|v| v.name).collect();
+    assert_eq!(names, vec!["default", "work"]);
+
+    manager
+        .switch_vault("work", "work_password".to_string())
+        .expect("Switch failed");
+
+    assert_eq!(manager.active_vault(), Some("work"));
+    assert!(manager.credentials().get("github").is_none());
+    assert_eq!(
+        manager.credentials().get("jira"),
+        Some(&"work-secret".to_string())
+    );
+
+    // The personal vault's password no longer unlocks anything
+    assert!(
+        manager
+            .switch_vault("default", "work_password".to_string())
+            .is_err()
+    );
+    assert_eq!(manager.active_vault(), Some("work"));
+}
+
+#[test]
+fn test_manager_verify_backups_no_directory() {
+    let (mut manager, _temp_dir) = setup_test_env();
+    manager
+        .setup_new_user("correct_password".to_string())
+        .expect("Setup failed");
+
+    let reports = manager.verify_backups().expect("Verification failed");
+    assert!(reports.is_empty());
+}
+
+#[test]
+fn test_manager_credential_persistence() {
+    let backend = InMemoryBackend::new();
 
     // Create and populate first manager
     {
-        let mut manager = Manager::new();
-        manager.set_db_path(db_path.clone());
+        let mut manager = manager_for(&backend);
         manager
             .setup_new_user("test_password".to_string())
             .expect("Setup failed");
@@ -101,8 +437,7 @@ fn test_manager_credential_persistence() {
 
     // Create second manager and verify persistence
     {
-        let mut manager2 = Manager::new();
-        manager2.set_db_path(db_path);
+        let mut manager2 = manager_for(&backend);
 
         let valid = manager2
             .validate_master_password("test_password".to_string())
@@ -121,17 +456,105 @@ fn test_manager_credential_persistence() {
 }
 
 #[test]
-fn test_manager_wrong_password() {
-    let (mut manager, temp_dir) = setup_test_env();
+fn test_manager_in_memory_backend() {
+    let (mut manager, backend) = setup_in_memory();
+    assert!(manager.is_new_user());
+    manager.setup_new_user("password".to_string()).unwrap();
+    assert!(backend.contents().unwrap().contains("\"encrypted_data\""));
+    assert!(manager.db_path().is_none());
+
+    // Backups are only kept for vaults stored in a file
+    assert!(manager.verify_backups().is_err());
+    assert!(
+        manager
+            .restore_backup("test.db.bak", "password".to_string())
+            .is_err()
+    );
+
+    manager
+        .credentials_mut()
+        .add("github".to_string(), "secret".to_string())
+        .unwrap();
+    manager.save_credentials().unwrap();
+    let mut reopened = manager_for(&backend);
+    assert!(
+        reopened
+            .validate_master_password("password".to_string())
+            .unwrap()
+    );
+    assert_eq!(
+        reopened.credentials().get("github"),
+        Some(&"secret".to_string())
+    );
+}
+
+/// Runs the same unlock, save, reopen and password change through a manager,
+/// returning what was observed; `open` creates a manager for the same vault.
+fn vault_round_trip(open: impl Fn() -> Manager) -> Vec<String> {
+    let mut observed = Vec::new();
+    let mut manager = open();
+    observed.push(format!("new user: {}", manager.is_new_user()));
+    manager.setup_new_user("first".to_string()).unwrap();
+    manager
+        .credentials_mut()
+        .add("github".to_string(), "secret".to_string())
+        .unwrap();
+    manager.save_credentials().unwrap();
+    manager
+        .change_master_password("second".to_string())
+        .unwrap();
+
+    let mut reopened = open();
+    for password in ["first", "second"] {
+        let unlocked = reopened.validate_master_password(password.to_string());
+        observed.push(format!(
+            "{}: {:?}",
+            password,
+            unlocked.map_err(|e| e.to_string())
+        ));
+    }
+    observed.push(format!("{:?}", reopened.credentials().get("github")));
+    observed.push(format!("{:?}", reopened.verify().unwrap().passed()));
+    observed.push(format!("{:?}", reopened.crypto_info().unwrap()));
+    observed
+}
+
+#[test]
+fn test_in_memory_backend_matches_file() {
+    let temp_dir = TempDir::new().unwrap();
     let db_path = temp_dir.path().join("test_passwords.db");
+    let from_file = vault_round_trip(|| {
+        let mut manager = Manager::new();
+        manager.set_db_path(db_path.clone());
+        manager
+    });
+
+    let backend = InMemoryBackend::new();
+    let in_memory = vault_round_trip(|| manager_for(&backend));
+
+    assert_eq!(from_file, in_memory);
+    assert!(in_memory.contains(&"second: Ok(true)".to_string()));
+    // Both hold the store in the same format
+    let file_store: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(&db_path).unwrap()).unwrap();
+    let memory_store: serde_json::Value =
+        serde_json::from_str(&backend.contents().unwrap()).unwrap();
+    let keys = |store: &serde_json::Value| -> Vec<String> {
+        store.as_object().unwrap().keys().cloned().collect()
+    };
+    assert_eq!(keys(&file_store), keys(&memory_store));
+}
+
+#[test]
+fn test_manager_wrong_password() {
+    let (mut manager, backend) = setup_in_memory();
 
     manager
         .setup_new_user("correct_password".to_string())
         .expect("Setup failed");
 
     // Create a new manager and try wrong password
-    let mut manager2 = Manager::new();
-    manager2.set_db_path(db_path);
+    let mut manager2 = manager_for(&backend);
 
     let valid = manager2
         .validate_master_password("wrong_password".to_string())
@@ -139,6 +562,121 @@ fn test_manager_wrong_password() {
     assert!(!valid, "Wrong password should not validate");
 }
 
+#[test]
+fn test_store_fields_with_wrong_lengths() {
+    let (mut manager, backend) = setup_in_memory();
+    manager
+        .setup_new_user("correct_password".to_string())
+        .expect("Setup failed");
+    let original = backend.contents().unwrap();
+
+    // A 4-byte salt is rejected as soon as it is decoded
+    let mut store = backend.load().unwrap();
+    store.argon2_salt = "AAAAAA==".to_string();
+    let err = decode_salt("argon2_salt", &store.argon2_salt).unwrap_err();
+    assert_eq!(
+        err.downcast_ref::<StorageError>(),
+        Some(&StorageError::BadField {
+            field: "argon2_salt",
+            expected: 16,
+            actual: 4,
+        })
+    );
+    backend.save(&store).unwrap();
+    let err = manager
+        .validate_master_password("correct_password".to_string())
+        .unwrap_err();
+    assert_eq!(
+        err.downcast_ref::<LoadError>().map(ToString::to_string),
+        Some("vault file is damaged: argon2_salt must be 16 bytes, found 4".to_string())
+    );
+
+    // So is a 16-byte nonce
+    let mut store: serde_json::Value = serde_json::from_str(&original).unwrap();
+    store["encryption_nonce"] = serde_json::Value::String("A".repeat(22) + "==");
+    backend.set_contents(store.to_string());
+    let store = backend.load().unwrap();
+    let err = decode_nonce("encryption_nonce", &store.encryption_nonce).unwrap_err();
+    assert_eq!(
+        err.downcast_ref::<StorageError>(),
+        Some(&StorageError::BadField {
+            field: "encryption_nonce",
+            expected: 12,
+            actual: 16,
+        })
+    );
+    let err = manager
+        .validate_master_password("correct_password".to_string())
+        .unwrap_err();
+    assert_eq!(
+        err.downcast_ref::<LoadError>().map(ToString::to_string),
+        Some("vault file is damaged: encryption_nonce must be 12 bytes, found 16".to_string())
+    );
+}
+
+/// Log sink shared between the global logger and the test reading it.
+#[derive(Clone, Default)]
+struct SharedLog(Arc<Mutex<Vec<u8>>>);
+
+impl Write for SharedLog {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.lock().unwrap().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+#[test]
+fn test_json_log_never_contains_secrets() {
+    // The only test in this binary that installs a logger
+    let sink = SharedLog::default();
+    log::set_boxed_logger(JsonLogger::new(LevelFilter::Trace, sink.clone())).unwrap();
+    log::set_max_level(LevelFilter::Trace);
+
+    let (mut manager, _backend) = setup_in_memory();
+    manager
+        .setup_new_user("master-pw-7f3a".to_string())
+        .expect("Setup failed");
+    manager
+        .validate_master_password("master-pw-7f3a".to_string())
+        .unwrap();
+
+    let shell = Shell::new();
+    let credentials = manager.credentials_mut();
+    for line in [
+        "add github s3cret-9d1c",
+        "get --show github",
+        "get --json --reveal github",
+        "replace github gitlab n3w-s3cret-4e2b",
+        "rename gitlab github",
+        "add github again-s3cret-77aa",
+        "remove github",
+    ] {
+        shell.run_command(line, credentials);
+    }
+    manager.save_credentials().unwrap();
+
+    let output = String::from_utf8(sink.0.lock().unwrap().clone()).unwrap();
+    assert!(output.contains("github"));
+    for secret in [
+        "master-pw-7f3a",
+        "s3cret-9d1c",
+        "n3w-s3cret-4e2b",
+        "again-s3cret-77aa",
+    ] {
+        assert!(!output.contains(secret), "log contains '{}'", secret);
+    }
+    for line in output.lines() {
+        let record: serde_json::Value = serde_json::from_str(line).unwrap();
+        for field in ["timestamp", "level", "target", "message"] {
+            assert!(record[field].is_string(), "missing {} in {}", field, line);
+        }
+    }
+}
+
 // ============================================================================
 // Trie Tests
 // ============================================================================
@@ -230,7 +768,6 @@ fn test_add_command() {
 
     assert!(matches!(result, CommandResult::Success(_)));
     assert!(ctx.modified);
-    drop(ctx);
     assert_eq!(credentials.get("testkey"), Some(&"testsecret".to_string()));
 }
 
@@ -266,10 +803,81 @@ fn test_get_command() {
     let get_cmd = registry.get("get").expect("Get command should exist");
     let result = get_cmd.execute(&["mykey"], &mut ctx);
 
+    match result {
+        CommandResult::Success(Some(masked)) => assert_eq!(masked, "••••••••"),
+        _ => panic!("Expected success with masked secret"),
+    }
+
+    let result = get_cmd.execute(&["--show", "mykey"], &mut ctx);
     match result {
         CommandResult::Success(Some(secret)) => assert_eq!(secret, "mysecret"),
         _ => panic!("Expected success with secret"),
     }
+
+    let reveal_cmd = registry.get("reveal").expect("Reveal command should exist");
+    match reveal_cmd.execute(&["mykey"], &mut ctx) {
+        CommandResult::Success(Some(secret)) => assert_eq!(secret, "mysecret"),
+        _ => panic!("Expected success with secret"),
+    }
+}
+
+#[test]
+fn test_slugified_names_on_add_and_get() {
+    let mut credentials = Credentials::new();
+    let mut trie = Trie::new();
+    let registry = create_registry();
+    let mut ctx = ShellContext::new(&mut credentials, &mut trie)
+        .with_registry(&registry)
+        .with_name_normalization(NameNormalization::Slugify);
+
+    let add_cmd = registry.get("add").expect("Add command should exist");
+    match add_cmd.execute(&["My GitHub", "s3cret"], &mut ctx) {
+        CommandResult::Success(Some(msg)) => assert_eq!(msg, "Added 'my-github'"),
+        _ => panic!("Expected success"),
+    }
+    assert!(ctx.key_trie.contains("my-github"));
+
+    let get_cmd = registry.get("get").expect("Get command should exist");
+    match get_cmd.execute(&["--show", "My GitHub"], &mut ctx) {
+        CommandResult::Success(Some(secret)) => assert_eq!(secret, "s3cret"),
+        _ => panic!("Expected success with secret"),
+    }
+
+    let remove_cmd = registry.get("remove").expect("Remove command should exist");
+    let result = remove_cmd.execute(&["MY  GITHUB"], &mut ctx);
+    assert!(matches!(result, CommandResult::Success(_)));
+
+    assert_eq!(credentials.list(), Vec::<&String>::new());
+}
+
+#[test]
+fn test_names_unchanged_without_normalization() {
+    let mut credentials = Credentials::new();
+    let mut trie = Trie::new();
+    let registry = create_registry();
+    let mut ctx = ShellContext::new(&mut credentials, &mut trie).with_registry(&registry);
+
+    let add_cmd = registry.get("add").expect("Add command should exist");
+    add_cmd.execute(&["My GitHub", "s3cret"], &mut ctx);
+
+    assert!(credentials.get("My GitHub").is_some());
+    assert!(credentials.get("my-github").is_none());
+}
+
+#[test]
+fn test_name_normalization_apply() {
+    assert_eq!(NameNormalization::None.apply("My GitHub"), "My GitHub");
+    assert_eq!(NameNormalization::Lowercase.apply("My GitHub"), "my github");
+    assert_eq!(
+        NameNormalization::Slugify.apply("  My   GitHub "),
+        "my-github"
+    );
+    assert_eq!(
+        NameNormalization::Slugify.apply("Ünïcode Name"),
+        "ünïcode-name"
+    );
+    assert_eq!("slugify".parse(), Ok(NameNormalization::Slugify));
+    assert!("shout".parse::<NameNormalization>().is_err());
 }
 
 #[test]
@@ -303,15 +911,18 @@ fn test_remove_command() {
 
     assert!(matches!(result, CommandResult::Success(_)));
     assert!(ctx.modified);
-    drop(ctx);
     assert!(credentials.get("toremove").is_none());
 }
 
 #[test]
 fn test_list_command() {
     let mut credentials = Credentials::new();
-    credentials.add("key1".to_string(), "val1".to_string()).unwrap();
-    credentials.add("key2".to_string(), "val2".to_string()).unwrap();
+    credentials
+        .add("key1".to_string(), "val1".to_string())
+        .unwrap();
+    credentials
+        .add("key2".to_string(), "val2".to_string())
+        .unwrap();
     let mut trie = Trie::new();
     let registry = create_registry();
 
@@ -406,6 +1017,130 @@ fn test_credentials_basic_operations() {
     assert!(!creds.remove("key1")); // Already removed
 }
 
+#[test]
+fn test_credentials_len() {
+    let mut creds = Credentials::new();
+    assert_eq!(creds.len(), 0);
+
+    creds.add("key1".to_string(), "val1".to_string()).unwrap();
+    creds.add("key2".to_string(), "val2".to_string()).unwrap();
+    assert_eq!(creds.len(), 2);
+
+    // A rejected duplicate does not count
+    assert!(creds.add("key1".to_string(), "other".to_string()).is_err());
+    assert_eq!(creds.len(), 2);
+
+    creds.rename("key1", "key3").unwrap();
+    assert_eq!(creds.len(), 2);
+
+    creds.remove("key2");
+    assert_eq!(creds.len(), 1);
+    assert_eq!(creds.len(), creds.list().len());
+}
+
+#[test]
+fn test_credentials_contains_key() {
+    let mut creds = Credentials::new();
+    assert!(!creds.contains_key("key"));
+
+    creds.add("key".to_string(), "val".to_string()).unwrap();
+    assert!(creds.contains_key("key"));
+    // Names are matched exactly
+    assert!(!creds.contains_key("Key"));
+    assert!(!creds.contains_key("ke"));
+
+    creds.rename("key", "renamed").unwrap();
+    assert!(!creds.contains_key("key"));
+    assert!(creds.contains_key("renamed"));
+
+    creds.remove("renamed");
+    assert!(!creds.contains_key("renamed"));
+}
+
+#[test]
+fn test_credentials_matching() {
+    let mut creds = Credentials::new();
+    for name in [
+        "test-1",
+        "test-22",
+        "Test-3",
+        "my test",
+        "github",
+        "ünïcode",
+    ] {
+        creds.add(name.to_string(), "secret".to_string()).unwrap();
+    }
+
+    // Without wildcards, names containing the pattern match, ignoring case
+    assert_eq!(
+        creds.matching("test"),
+        vec!["Test-3", "my test", "test-1", "test-22"]
+    );
+    // With wildcards, the whole name must match
+    assert_eq!(
+        creds.matching("test-*"),
+        vec!["Test-3", "test-1", "test-22"]
+    );
+    assert_eq!(creds.matching("test-?"), vec!["Test-3", "test-1"]);
+    assert_eq!(creds.matching("*hub"), vec!["github"]);
+    assert_eq!(creds.matching("g*t*b"), vec!["github"]);
+    assert_eq!(creds.matching("ü?ïcode"), vec!["ünïcode"]);
+    assert_eq!(creds.matching("*").len(), 6);
+    assert!(creds.matching("test").len() < creds.len());
+    assert!(creds.matching("git?").is_empty());
+    // An empty pattern never matches everything
+    assert!(creds.matching("").is_empty());
+}
+
+#[test]
+fn test_credentials_wipe() {
+    let mut creds = Credentials::new();
+    creds
+        .add("github".to_string(), "secret".to_string())
+        .unwrap();
+    creds
+        .add("email".to_string(), "secret2".to_string())
+        .unwrap();
+    creds.move_to_trash("email", Utc::now());
+
+    creds.wipe();
+
+    assert!(creds.is_empty());
+    assert!(creds.trashed().is_empty());
+    assert!(creds.get("github").is_none());
+}
+
+#[test]
+fn test_credentials_search_regex() {
+    let mut creds = Credentials::new();
+    for name in [
+        "aws-eu-prod",
+        "aws-us-prod",
+        "aws-prod-backup",
+        "AWS-eu-prod",
+        "gcp-prod",
+    ] {
+        creds.add(name.to_string(), "secret".to_string()).unwrap();
+    }
+
+    // Anchored patterns must match the whole name
+    assert_eq!(
+        creds.search_regex("^aws-.*-prod$").unwrap(),
+        vec!["aws-eu-prod", "aws-us-prod"]
+    );
+    // Unanchored patterns match anywhere, case sensitively by default
+    assert_eq!(
+        creds.search_regex("prod-").unwrap(),
+        vec!["aws-prod-backup"]
+    );
+    assert_eq!(creds.search_regex("(?i)^aws-eu").unwrap().len(), 2);
+    assert!(creds.search_regex("^azure").unwrap().is_empty());
+
+    // An invalid pattern is an error, not an empty result
+    assert!(creds.search_regex("aws-(prod").is_err());
+    assert!(creds.search_regex("*prod").is_err());
+}
+
 #[test]
 fn test_credentials_duplicate_prevention() {
     let mut creds = Credentials::new();
@@ -413,10 +1148,1035 @@ fn test_credentials_duplicate_prevention() {
     creds.add("key".to_string(), "val1".to_string()).unwrap();
     let result = creds.add("key".to_string(), "val2".to_string());
 
-    assert!(result.is_err());
+    assert_eq!(result, Err(CredentialError::Duplicate("key".to_string())));
+    assert_eq!(result.unwrap_err().to_string(), "'key' already exists.");
     assert_eq!(creds.get("key"), Some(&"val1".to_string())); // Original value preserved
 }
 
+#[test]
+fn test_credentials_length_limits() {
+    let mut creds = Credentials::new();
+    assert_eq!(creds.limits(), CredentialLimits::default());
+    creds.set_limits(CredentialLimits {
+        max_key_len: 8,
+        max_secret_len: 16,
+        ..CredentialLimits::default()
+    });
+
+    // Exactly at the limits is fine; one byte over is not
+    assert!(creds.add("a".repeat(8), "s".repeat(16)).is_ok());
+    let err = creds.add("b".repeat(9), "secret".to_string()).unwrap_err();
+    assert_eq!(
+        err,
+        CredentialError::TooLong {
+            field: "Name",
+            len: 9,
+            max: 8
+        }
+    );
+    assert_eq!(err.to_string(), "Name is 9 bytes long; the limit is 8.");
+    let err = creds.add("b".to_string(), "s".repeat(17)).unwrap_err();
+    assert_eq!(
+        err,
+        CredentialError::TooLong {
+            field: "Secret",
+            len: 17,
+            max: 16
+        }
+    );
+    assert_eq!(err.to_string(), "Secret is 17 bytes long; the limit is 16.");
+
+    // Limits count bytes, not characters
+    assert!(creds.add("ü".repeat(4), "secret".to_string()).is_ok());
+    assert!(creds.add("é".repeat(5), "secret".to_string()).is_err());
+
+    // Renaming checks the new name too, and leaves the old entry alone
+    let err = creds.rename(&"a".repeat(8), &"c".repeat(9)).unwrap_err();
+    assert!(matches!(err, CredentialError::TooLong { max: 8, .. }));
+    assert!(creds.get(&"a".repeat(8)).is_some());
+
+    // The defaults are generous
+    let mut creds = Credentials::new();
+    assert!(creds.add("key".to_string(), "s".repeat(4096)).is_ok());
+    assert!(creds.add("key2".to_string(), "s".repeat(4097)).is_err());
+}
+
+#[test]
+fn test_credentials_reject_confusing_names() {
+    let mut creds = Credentials::new();
+    for name in [
+        "tab\there",
+        "new\nline",
+        "bell\u{7}",
+        " leading",
+        "trailing ",
+        "\t",
+        "",
+    ] {
+        assert!(
+            creds.add(name.to_string(), "secret".to_string()).is_err(),
+            "{:?} should be rejected",
+            name
+        );
+    }
+    assert_eq!(
+        creds.add("a\u{1b}[31m".to_string(), "s".to_string()),
+        Err(CredentialError::ControlCharacters)
+    );
+    assert_eq!(
+        creds.add(" github".to_string(), "s".to_string()),
+        Err(CredentialError::SurroundingWhitespace)
+    );
+    assert_eq!(
+        creds.add(String::new(), "s".to_string()),
+        Err(CredentialError::EmptyName)
+    );
+    assert!(creds.is_empty());
+
+    // Inner spaces and control characters in secrets are fine
+    creds
+        .add("my email".to_string(), "line1\nline2".to_string())
+        .unwrap();
+    assert!(creds.rename("my email", "my\temail").is_err());
+    assert!(creds.get("my email").is_some());
+}
+
+#[test]
+fn test_credentials_rename() {
+    let mut creds = Credentials::new();
+    creds
+        .add("github".to_string(), "secret1".to_string())
+        .unwrap();
+    creds
+        .add("email".to_string(), "secret2".to_string())
+        .unwrap();
+
+    assert!(creds.rename("github", "gh").is_ok());
+    assert_eq!(creds.get("gh"), Some(&"secret1".to_string()));
+    assert!(creds.get("github").is_none());
+
+    // Failed renames leave the store untouched
+    assert_eq!(
+        creds.rename("gh", "email"),
+        Err(CredentialError::Duplicate("email".to_string()))
+    );
+    assert_eq!(
+        creds.rename("missing", "other"),
+        Err(CredentialError::NotFound("missing".to_string()))
+    );
+    assert_eq!(creds.rename("gh", ""), Err(CredentialError::EmptyName));
+    assert_eq!(creds.list().len(), 2);
+    assert_eq!(creds.get("email"), Some(&"secret2".to_string()));
+}
+
+#[test]
+fn test_credentials_update() {
+    let mut creds = Credentials::new();
+    creds.set_limits(CredentialLimits {
+        max_key_len: 16,
+        max_secret_len: 8,
+        ..CredentialLimits::default()
+    });
+    creds.add("github".to_string(), "old".to_string()).unwrap();
+    let rotated = Utc.with_ymd_and_hms(2026, 1, 1, 0, 0, 0).unwrap();
+    creds.touch("github", rotated);
+
+    creds.update("github", "new".to_string()).unwrap();
+    assert_eq!(creds.get("github"), Some(&"new".to_string()));
+    assert!(creds.metadata("github").unwrap().updated_at > Some(rotated));
+
+    // Failed updates leave the secret untouched
+    match creds.update("github", "far too long".to_string()) {
+        Err(CredentialError::TooLong { field, len, max }) => {
+            assert_eq!((field, len, max), ("Secret", 12, 8))
+        }
+        other => panic!("Expected TooLong, got {:?}", other),
+    }
+    assert_eq!(
+        creds.update("missing", "new".to_string()),
+        Err(CredentialError::NotFound("missing".to_string()))
+    );
+    assert_eq!(creds.get("github"), Some(&"new".to_string()));
+    assert!(!creds.contains_key("missing"));
+    // Variants are displayed for the shell
+    assert_eq!(
+        creds
+            .update("missing", "x".to_string())
+            .unwrap_err()
+            .to_string(),
+        "'missing' not found"
+    );
+}
+
+#[test]
+fn test_credentials_tags() {
+    let mut creds = Credentials::new();
+    creds
+        .add("github".to_string(), "secret".to_string())
+        .unwrap();
+
+    assert_eq!(creds.add_tag("github", "work"), Ok(true));
+    assert_eq!(creds.add_tag("github", "dev"), Ok(true));
+    assert_eq!(creds.add_tag("github", "work"), Ok(false));
+    assert_eq!(creds.metadata("github").unwrap().tags, ["dev", "work"]);
+
+    assert_eq!(
+        creds.add_tag("missing", "work"),
+        Err(CredentialError::NotFound("missing".to_string()))
+    );
+    for tag in ["", "two words", "tab\there"] {
+        assert_eq!(
+            creds.add_tag("github", tag),
+            Err(CredentialError::InvalidTag(tag.to_string()))
+        );
+    }
+
+    assert_eq!(creds.remove_tag("github", "dev"), Ok(true));
+    assert_eq!(creds.remove_tag("github", "dev"), Ok(false));
+    assert_eq!(creds.entries()[0].tags, ["work"]);
+
+    // Tags follow a renamed credential and are saved with it
+    creds.rename("github", "gh").unwrap();
+    let reloaded = Credentials::from_json(&creds.to_json().unwrap()).unwrap();
+    assert_eq!(reloaded.metadata("gh").unwrap().tags, ["work"]);
+
+    // Stores written before tags existed load without any
+    let old = Credentials::from_json(br#"{"github":"secret"}"#).unwrap();
+    assert_eq!(old.get("github"), Some(&"secret".to_string()));
+    assert!(old.tag_counts().is_empty());
+}
+
+#[test]
+fn test_credentials_rename_tag() {
+    let mut creds = Credentials::new();
+    for name in ["github", "gitlab", "bank", "old"] {
+        creds.add(name.to_string(), "secret".to_string()).unwrap();
+    }
+    creds.add_tag("github", "work").unwrap();
+    creds.add_tag("gitlab", "work").unwrap();
+    creds.add_tag("gitlab", "job").unwrap();
+    creds.add_tag("bank", "home").unwrap();
+    creds.add_tag("old", "work").unwrap();
+    creds.move_to_trash("old", Utc::now());
+
+    assert_eq!(creds.rename_tag("work", "job"), Ok(2));
+    assert_eq!(creds.metadata("github").unwrap().tags, ["job"]);
+    // Both tags on one entry merge into one
+    assert_eq!(creds.metadata("gitlab").unwrap().tags, ["job"]);
+    assert_eq!(creds.metadata("bank").unwrap().tags, ["home"]);
+    let job = "job".to_string();
+    let home = "home".to_string();
+    assert_eq!(creds.tag_counts(), vec![(&home, 1), (&job, 2)]);
+
+    // Trashed credentials come back with the new tag
+    creds.restore_from_trash("old").unwrap();
+    assert_eq!(creds.metadata("old").unwrap().tags, ["job"]);
+
+    assert_eq!(creds.rename_tag("work", "job"), Ok(0));
+    // Renaming a tag to itself changes nothing
+    assert_eq!(creds.rename_tag("job", "job"), Ok(0));
+    assert_eq!(
+        creds.rename_tag("job", "no good"),
+        Err(CredentialError::InvalidTag("no good".to_string()))
+    );
+    assert_eq!(creds.metadata("github").unwrap().tags, ["job"]);
+}
+
+#[test]
+fn test_credentials_reject_empty_secrets() {
+    let mut creds = Credentials::new();
+    assert_eq!(
+        creds.add("github".to_string(), String::new()),
+        Err(CredentialError::InvalidSecret)
+    );
+    assert!(creds.is_empty());
+
+    creds
+        .add("github".to_string(), "secret".to_string())
+        .unwrap();
+    assert_eq!(
+        creds.update("github", String::new()),
+        Err(CredentialError::InvalidSecret)
+    );
+    assert_eq!(creds.get("github"), Some(&"secret".to_string()));
+    assert_eq!(
+        CredentialError::InvalidSecret.to_string(),
+        "Secret cannot be empty."
+    );
+}
+
+#[test]
+fn test_credentials_count_limit() {
+    let mut creds = Credentials::new();
+    creds.set_limits(CredentialLimits {
+        max_credentials: 2,
+        ..CredentialLimits::default()
+    });
+    creds.add("a".to_string(), "s".to_string()).unwrap();
+    creds.add("b".to_string(), "s".to_string()).unwrap();
+
+    let err = creds.add("c".to_string(), "s".to_string()).unwrap_err();
+    assert_eq!(err, CredentialError::LimitReached(2));
+    assert_eq!(
+        err.to_string(),
+        "The vault is full; the limit is 2 credentials."
+    );
+    assert_eq!(creds.len(), 2);
+    // Existing credentials can still be updated, and removing one makes room
+    creds.update("a", "new".to_string()).unwrap();
+    creds.remove("b");
+    creds.add("c".to_string(), "s".to_string()).unwrap();
+}
+
+#[test]
+fn test_credentials_default_and_clone() {
+    assert!(Credentials::default().is_empty());
+
+    let mut creds = Credentials::new();
+    creds
+        .add("github".to_string(), "secret".to_string())
+        .unwrap();
+    creds.add("email".to_string(), "other".to_string()).unwrap();
+    creds.move_to_trash("email", Utc::now());
+
+    let mut copy = creds.clone();
+    assert_eq!(copy.get("github"), Some(&"secret".to_string()));
+    assert_eq!(copy.trashed().len(), 1);
+
+    // Changes to the copy leave the original alone
+    copy.update("github", "changed".to_string()).unwrap();
+    copy.add("bank".to_string(), "pin".to_string()).unwrap();
+    assert_eq!(creds.get("github"), Some(&"secret".to_string()));
+    assert!(!creds.contains_key("bank"));
+    assert_eq!(creds.len(), 1);
+}
+
+#[test]
+fn test_credentials_debug_hides_secrets() {
+    let mut creds = Credentials::new();
+    creds
+        .add("github".to_string(), "hunter2".to_string())
+        .unwrap();
+
+    creds.add("aws".to_string(), "s3cr3t".to_string()).unwrap();
+    creds.move_to_trash("aws", Utc::now());
+    creds
+        .add("email".to_string(), "letmein".to_string())
+        .unwrap();
+
+    let debug = format!("{:?}", creds);
+    assert_eq!(
+        debug,
+        r#"Credentials { count: 2, keys: ["email", "github"] }"#
+    );
+    for secret in ["hunter2", "s3cr3t", "letmein"] {
+        assert!(!debug.contains(secret));
+        assert!(!format!("{:#?}", creds).contains(secret));
+    }
+}
+
+#[test]
+fn test_credentials_stats() {
+    let mut creds = Credentials::new();
+    creds.add("a".to_string(), "1234".to_string()).unwrap();
+    creds.add("b".to_string(), "abcdefgh".to_string()).unwrap();
+    creds.add("c".to_string(), "abcdefgh".to_string()).unwrap();
+    creds
+        .add("d".to_string(), "x7#Kq9!mZ2@pL5$wR8^t".to_string())
+        .unwrap();
+
+    let stats = creds.stats();
+    assert_eq!(stats.total, 4);
+    assert_eq!(stats.average_secret_len, 10.0);
+    assert_eq!(stats.median_secret_len, 8.0);
+    assert_eq!(stats.weak_count, 3);
+    assert_eq!(stats.duplicate_count, 2);
+    assert_eq!(stats.tag_count, 0);
+
+    creds.add_tag("a", "work").unwrap();
+    creds.add_tag("b", "work").unwrap();
+    creds.add_tag("b", "home").unwrap();
+    assert_eq!(creds.stats().tag_count, 2);
+
+    let empty = Credentials::new().stats();
+    assert_eq!(empty.total, 0);
+    assert_eq!(empty.average_secret_len, 0.0);
+    assert_eq!(empty.median_secret_len, 0.0);
+    assert_eq!(empty.tag_count, 0);
+    assert_eq!(empty.oldest_created_at, None);
+    assert_eq!(empty.newest_created_at, None);
+    assert_eq!(empty.oldest_updated_at, None);
+    assert_eq!(empty.newest_updated_at, None);
+}
+
+#[test]
+fn test_credentials_stats_entry_range() {
+    let mut creds = Credentials::from_json(
+        br#"{
+            "secrets": {"a": "secret", "b": "secret", "c": "secret"},
+            "metadata": {
+                "a": {"created_at": "2025-06-01T00:00:00Z"},
+                "b": {"created_at": "2024-03-01T00:00:00Z"},
+                "c": {"created_at": "2026-01-01T00:00:00Z"}
+            }
+        }"#,
+    )
+    .unwrap();
+
+    let stats = creds.stats();
+    assert_eq!(
+        stats.oldest_created_at,
+        Some(Utc.with_ymd_and_hms(2024, 3, 1, 0, 0, 0).unwrap())
+    );
+    assert_eq!(
+        stats.newest_created_at,
+        Some(Utc.with_ymd_and_hms(2026, 1, 1, 0, 0, 0).unwrap())
+    );
+
+    // Updating a secret does not change when it was added
+    creds.update("b", "rotated".to_string()).unwrap();
+    assert_eq!(
+        creds.stats().oldest_created_at,
+        Some(Utc.with_ymd_and_hms(2024, 3, 1, 0, 0, 0).unwrap())
+    );
+
+    // Trashed credentials are not counted
+    creds.move_to_trash("b", Utc::now());
+    assert_eq!(
+        creds.stats().oldest_created_at,
+        Some(Utc.with_ymd_and_hms(2025, 6, 1, 0, 0, 0).unwrap())
+    );
+}
+
+#[test]
+fn test_credentials_stats_update_range() {
+    let mut creds = Credentials::new();
+    for name in ["a", "b", "c", "d"] {
+        creds.add(name.to_string(), "secret".to_string()).unwrap();
+    }
+    let oldest = Utc.with_ymd_and_hms(2024, 3, 1, 0, 0, 0).unwrap();
+    let newest = Utc.with_ymd_and_hms(2026, 1, 1, 0, 0, 0).unwrap();
+    creds.touch("a", newest);
+    creds.touch("b", oldest);
+    creds.touch("c", Utc.with_ymd_and_hms(2025, 6, 1, 0, 0, 0).unwrap());
+    creds.touch("d", Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap());
+
+    let stats = creds.stats();
+    assert_eq!(stats.oldest_updated_at, Some(oldest));
+    assert_eq!(stats.newest_updated_at, Some(newest));
+
+    // Trashed credentials are not counted
+    creds.move_to_trash("b", newest);
+    assert_eq!(
+        creds.stats().oldest_updated_at,
+        Some(Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap())
+    );
+}
+
+#[test]
+fn test_mask_secret_counts_characters() {
+    assert_eq!(mask_secret(""), "");
+    assert_eq!(mask_secret("abc"), "•••");
+    // Multi-byte characters are masked once each, not once per byte
+    assert_eq!(mask_secret("pässwörd"), "••••••••");
+    assert_eq!(mask_secret("密码"), "••");
+    assert_eq!(mask_secret("🔑🔒x"), "•••");
+    assert_eq!(
+        mask_secret("日本語のパスワード").chars().count(),
+        "日本語のパスワード".chars().count()
+    );
+}
+
+#[test]
+fn test_credentials_duplicates() {
+    let mut creds = Credentials::new();
+    creds.add("zeta".to_string(), "same".to_string()).unwrap();
+    creds.add("alpha".to_string(), "same".to_string()).unwrap();
+    creds.add("mid".to_string(), "same".to_string()).unwrap();
+    creds
+        .add("lonely".to_string(), "other".to_string())
+        .unwrap();
+
+    let groups = creds.duplicates();
+    assert_eq!(groups, vec![vec!["alpha", "mid", "zeta"]]);
+
+    // Ordering is deterministic across calls
+    assert_eq!(creds.duplicates(), groups);
+
+    assert!(Credentials::new().duplicates().is_empty());
+}
+
+#[test]
+fn test_credentials_get_or_insert_with_existing() {
+    let mut creds = Credentials::new();
+    creds
+        .add("github".to_string(), "original".to_string())
+        .unwrap();
+
+    let secret = creds
+        .get_or_insert_with("github", || panic!("must not be called"))
+        .unwrap();
+    assert_eq!(secret, "original");
+    assert_eq!(creds.list().len(), 1);
+    assert_eq!(creds.get("github"), Some(&"original".to_string()));
+}
+
+#[test]
+fn test_credentials_get_or_insert_with_missing() {
+    let mut creds = Credentials::new();
+
+    let secret = creds
+        .get_or_insert_with("github", || "generated".to_string())
+        .unwrap();
+    assert_eq!(secret, "generated");
+    assert_eq!(creds.get("github"), Some(&"generated".to_string()));
+    let meta = creds.metadata("github").unwrap();
+    assert!(meta.created_at.is_some());
+    assert_eq!(meta.updated_at, meta.created_at);
+
+    // The inserted entry is a regular credential
+    assert!(
+        creds
+            .add("github".to_string(), "other".to_string())
+            .is_err()
+    );
+    assert_eq!(creds.search("git"), vec!["github"]);
+}
+
+#[test]
+fn test_credentials_get_or_insert_with_validates() {
+    let mut creds = Credentials::new();
+    creds.set_limits(CredentialLimits {
+        max_key_len: 8,
+        max_secret_len: 4,
+        ..CredentialLimits::default()
+    });
+
+    for (name, error) in [
+        ("", CredentialError::EmptyName),
+        ("a\nb", CredentialError::ControlCharacters),
+        (" padded", CredentialError::SurroundingWhitespace),
+        (
+            "much-too-long",
+            CredentialError::TooLong {
+                field: "Name",
+                len: 13,
+                max: 8,
+            },
+        ),
+    ] {
+        assert_eq!(
+            creds.get_or_insert_with(name, || "abc".to_string()),
+            Err(error)
+        );
+    }
+    assert_eq!(
+        creds.get_or_insert_with("github", || "abcdef".to_string()),
+        Err(CredentialError::TooLong {
+            field: "Secret",
+            len: 6,
+            max: 4,
+        })
+    );
+    assert!(creds.is_empty());
+    assert!(creds.metadata("github").is_none());
+}
+
+#[test]
+fn test_credentials_iter() {
+    let mut creds = Credentials::new();
+    creds.add("zeta".to_string(), "z".to_string()).unwrap();
+    creds.add("alpha".to_string(), "a".to_string()).unwrap();
+
+    let mut pairs: Vec<(&String, &String)> = creds.iter().collect();
+    pairs.sort();
+    assert_eq!(pairs, creds.iter_sorted());
+    assert_eq!(
+        creds.iter_sorted(),
+        vec![
+            (&"alpha".to_string(), &"a".to_string()),
+            (&"zeta".to_string(), &"z".to_string())
+        ]
+    );
+
+    let mut count = 0;
+    for (name, secret) in &creds {
+        assert_eq!(creds.get(name), Some(secret));
+        count += 1;
+    }
+    assert_eq!(count, 2);
+
+    assert!(Credentials::new().iter_sorted().is_empty());
+}
+
+#[test]
+fn test_shell_run_command() {
+    let shell = Shell::new();
+    let mut credentials = Credentials::new();
+
+    let result = shell.run_command(r#"add "my email" "secret with spaces""#, &mut credentials);
+    assert!(matches!(result, CommandResult::Success(_)));
+    assert_eq!(
+        credentials.get("my email"),
+        Some(&"secret with spaces".to_string())
+    );
+
+    // Credentials changed outside the shell are picked up by the next command
+    credentials
+        .add("github".to_string(), "hunter2".to_string())
+        .unwrap();
+    match shell.run_command("get githb", &mut credentials) {
+        CommandResult::Error(msg) => assert!(msg.contains("Did you mean 'github'?")),
+        _ => panic!("Expected not found error"),
+    }
+
+    match shell.run_command("list", &mut credentials) {
+        CommandResult::Success(Some(msg)) => assert_eq!(msg, "github\nmy email"),
+        _ => panic!("Expected list"),
+    }
+
+    assert!(matches!(
+        shell.run_command(r#"add "unterminated"#, &mut credentials),
+        CommandResult::Error(_)
+    ));
+    assert!(matches!(
+        shell.run_command("quit", &mut credentials),
+        CommandResult::Exit
+    ));
+}
+
+#[test]
+fn test_credentials_entries() {
+    let mut creds = Credentials::new();
+    creds
+        .add("github".to_string(), "hunter2".to_string())
+        .unwrap();
+    creds
+        .add("email".to_string(), "pässwörd".to_string())
+        .unwrap();
+
+    let entries = creds.entries();
+    let names: Vec<&str> = entries.iter().map(|entry| entry.name).collect();
+    assert_eq!(names, ["email", "github"]);
+
+    let github = &entries[1];
+    assert!(github.tags.is_empty());
+    assert!(github.created_at.is_some());
+    assert_eq!(github.updated_at, github.created_at);
+    assert_eq!(github.rotate_after, None);
+    assert_eq!(github.masked_secret(), mask_secret("hunter2"));
+    assert_eq!(entries[0].masked_secret().chars().count(), 8);
+
+    // The plaintext only comes out through reveal
+    let debug = format!("{:?}", entries);
+    assert!(!debug.contains("hunter2"));
+    assert!(!debug.contains("pässwörd"));
+    assert!(debug.contains("github"));
+    assert_eq!(github.reveal(), "hunter2");
+
+    assert!(Credentials::new().entries().is_empty());
+}
+
+#[test]
+fn test_credentials_expired() {
+    let day = Duration::from_secs(24 * 60 * 60);
+    let now = Utc.with_ymd_and_hms(2026, 6, 1, 12, 0, 0).unwrap();
+    let days_ago = |days: i64| now - chrono::Duration::days(days);
+
+    let mut creds = Credentials::new();
+    for name in ["bank", "email", "github", "wifi"] {
+        creds.add(name.to_string(), "secret".to_string()).unwrap();
+    }
+    creds.touch("bank", days_ago(100));
+    creds.touch("email", days_ago(20));
+    creds.touch("github", days_ago(40));
+    creds.touch("wifi", days_ago(1000));
+
+    // Nothing expires without a rotation interval
+    assert!(creds.expired(now).is_empty());
+
+    creds.set_default_rotate_after(Some(90 * day));
+    assert_eq!(creds.expired(now), ["bank", "wifi"]);
+
+    // A credential's own interval overrides the default
+    assert!(creds.set_rotate_after("github", Some(30 * day)));
+    assert!(creds.set_rotate_after("wifi", Some(10_000 * day)));
+    assert_eq!(creds.expired(now), ["bank", "github"]);
+
+    // Exactly at the interval is not yet expired
+    assert!(creds.expired(days_ago(10)).iter().all(|n| *n != "bank"));
+
+    // Marking as rotated resets the age
+    assert!(creds.touch("bank", now));
+    assert_eq!(creds.expired(now), ["github"]);
+
+    assert!(!creds.touch("missing", now));
+    assert!(!creds.set_rotate_after("missing", Some(day)));
+}
+
+#[test]
+fn test_credential_metadata_survives_rename_and_save() {
+    let rotated = Utc.with_ymd_and_hms(2026, 1, 1, 0, 0, 0).unwrap();
+
+    let (mut manager, backend) = setup_in_memory();
+    manager.setup_new_user("password".to_string()).unwrap();
+    let creds = manager.credentials_mut();
+    creds
+        .add("github".to_string(), "secret".to_string())
+        .unwrap();
+    let created_at = creds.metadata("github").unwrap().created_at;
+    creds.touch("github", rotated);
+    creds.set_rotate_after("github", Some(Duration::from_secs(3600)));
+    creds.rename("github", "gitlab").unwrap();
+    assert!(creds.metadata("github").is_none());
+    manager.save_credentials().unwrap();
+
+    let mut reloaded = manager_for(&backend);
+    assert!(
+        reloaded
+            .validate_master_password("password".to_string())
+            .unwrap()
+    );
+    let meta = reloaded.credentials().metadata("gitlab").unwrap();
+    assert_eq!(meta.created_at, created_at);
+    assert_eq!(meta.updated_at, Some(rotated));
+    assert_eq!(meta.rotate_after, Some(Duration::from_secs(3600)));
+}
+
+#[test]
+fn test_credentials_trash() {
+    let deleted_at = Utc.with_ymd_and_hms(2026, 1, 1, 0, 0, 0).unwrap();
+    let mut creds = Credentials::new();
+    creds
+        .add("github".to_string(), "secret".to_string())
+        .unwrap();
+    let meta = creds.metadata("github").cloned();
+
+    assert!(creds.move_to_trash("github", deleted_at));
+    assert!(!creds.move_to_trash("github", deleted_at));
+    assert!(!creds.contains_key("github"));
+    assert!(creds.list().is_empty());
+    assert_eq!(creds.stats().total, 0);
+    let trashed = creds.trashed();
+    assert_eq!(trashed.len(), 1);
+    assert_eq!(trashed[0].0, "github");
+    assert_eq!(trashed[0].1.deleted_at, deleted_at);
+
+    creds.restore_from_trash("github").unwrap();
+    assert_eq!(creds.get("github"), Some(&"secret".to_string()));
+    assert_eq!(creds.metadata("github").cloned(), meta);
+    assert!(creds.trashed().is_empty());
+    assert_eq!(
+        creds.restore_from_trash("github"),
+        Err(CredentialError::Duplicate("github".to_string()))
+    );
+
+    creds.move_to_trash("github", deleted_at);
+    assert_eq!(creds.purge_trash(), 1);
+    assert_eq!(
+        creds.restore_from_trash("github"),
+        Err(CredentialError::NotFound("github".to_string()))
+    );
+}
+
+#[test]
+fn test_credentials_trash_survives_save() {
+    let (mut manager, backend) = setup_in_memory();
+    manager.setup_new_user("password".to_string()).unwrap();
+    let creds = manager.credentials_mut();
+    creds
+        .add("github".to_string(), "secret".to_string())
+        .unwrap();
+    creds.move_to_trash("github", Utc::now());
+    manager.save_credentials().unwrap();
+
+    let mut reloaded = manager_for(&backend);
+    assert!(
+        reloaded
+            .validate_master_password("password".to_string())
+            .unwrap()
+    );
+    let creds = reloaded.credentials_mut();
+    assert!(creds.is_empty());
+    creds.restore_from_trash("github").unwrap();
+    assert_eq!(creds.get("github"), Some(&"secret".to_string()));
+}
+
+/// Builds credentials from `(name, secret, days before 2026-06-01)`
+/// entries; entries without an age have no update time.
+fn dated_credentials(entries: &[(&str, &str, Option<i64>)]) -> Credentials {
+    let now = Utc.with_ymd_and_hms(2026, 6, 1, 12, 0, 0).unwrap();
+    let undated = entries
+        .iter()
+        .filter(|(_, _, age)| age.is_none())
+        .map(|(name, secret, _)| (name.to_string(), secret.to_string()))
+        .collect();
+    let mut creds = Credentials::from_map(undated);
+    for (name, secret, age) in entries {
+        if let Some(days) = age {
+            creds.add(name.to_string(), secret.to_string()).unwrap();
+            creds.touch(name, now - chrono::Duration::days(*days));
+        }
+    }
+    creds
+}
+
+/// Local and incoming sets sharing `github` (same secret) and `email`
+/// (different secrets, incoming newer), with `bank` only local and `wifi`
+/// only incoming.
+fn merge_fixture() -> (Credentials, Credentials) {
+    let local = dated_credentials(&[
+        ("bank", "local-bank", Some(5)),
+        ("email", "local-email", Some(30)),
+        ("github", "same", Some(10)),
+    ]);
+    let incoming = dated_credentials(&[
+        ("email", "incoming-email", Some(2)),
+        ("github", "same", Some(1)),
+        ("wifi", "incoming-wifi", Some(3)),
+    ]);
+    (local, incoming)
+}
+
+#[test]
+fn test_credentials_merge_keep_existing() {
+    let (mut local, incoming) = merge_fixture();
+
+    let report = local.merge(incoming, MergePolicy::KeepExisting);
+
+    assert_eq!(report.added, ["wifi"]);
+    assert!(report.overwritten.is_empty());
+    assert_eq!(report.skipped, ["email", "github"]);
+    assert!(report.conflicted.is_empty());
+    assert_eq!(local.get("email"), Some(&"local-email".to_string()));
+    assert_eq!(local.get("wifi"), Some(&"incoming-wifi".to_string()));
+    assert_eq!(local.get("bank"), Some(&"local-bank".to_string()));
+    assert_eq!(local.len(), 4);
+}
+
+#[test]
+fn test_credentials_merge_overwrite() {
+    let (mut local, incoming) = merge_fixture();
+    let incoming_updated = incoming.metadata("email").unwrap().updated_at;
+
+    let report = local.merge(incoming, MergePolicy::Overwrite);
+
+    assert_eq!(report.added, ["wifi"]);
+    assert_eq!(report.overwritten, ["email"]);
+    // An identical secret is left alone even when overwriting
+    assert_eq!(report.skipped, ["github"]);
+    assert!(report.conflicted.is_empty());
+    assert_eq!(local.get("email"), Some(&"incoming-email".to_string()));
+    // The incoming metadata comes along with the secret
+    assert_eq!(
+        local.metadata("email").unwrap().updated_at,
+        incoming_updated
+    );
+    assert_eq!(local.get("bank"), Some(&"local-bank".to_string()));
+}
+
+#[test]
+fn test_credentials_merge_keep_newer() {
+    let (mut local, incoming) = merge_fixture();
+    let report = local.merge(incoming, MergePolicy::KeepNewer);
+    assert_eq!(report.overwritten, ["email"]);
+    assert_eq!(local.get("email"), Some(&"incoming-email".to_string()));
+
+    // An older incoming secret is skipped
+    let (mut local, _) = merge_fixture();
+    let older = dated_credentials(&[("bank", "old-bank", Some(50))]);
+    let report = local.merge(older, MergePolicy::KeepNewer);
+    assert_eq!(report.skipped, ["bank"]);
+    assert_eq!(local.get("bank"), Some(&"local-bank".to_string()));
+}
+
+#[test]
+fn test_credentials_merge_keep_newer_conflicts() {
+    let (mut local, _) = merge_fixture();
+    local
+        .to_map_mut()
+        .insert("legacy".to_string(), "local-legacy".to_string());
+
+    // Without both update times, or with equal ones, the secrets can't be ordered
+    let incoming = dated_credentials(&[
+        ("bank", "incoming-bank", None),
+        ("email", "incoming-email", Some(30)),
+        ("legacy", "incoming-legacy", Some(1)),
+    ]);
+    let report = local.merge(incoming, MergePolicy::KeepNewer);
+
+    assert!(report.added.is_empty());
+    assert!(report.overwritten.is_empty());
+    assert!(report.skipped.is_empty());
+    assert_eq!(report.conflicted, ["bank", "email", "legacy"]);
+    assert_eq!(local.get("bank"), Some(&"local-bank".to_string()));
+    assert_eq!(local.get("email"), Some(&"local-email".to_string()));
+    assert_eq!(local.get("legacy"), Some(&"local-legacy".to_string()));
+}
+
+#[test]
+fn test_credentials_merge_ignores_incoming_trash() {
+    let mut local = Credentials::new();
+    let mut incoming = Credentials::new();
+    incoming
+        .add("github".to_string(), "secret".to_string())
+        .unwrap();
+    incoming.move_to_trash("github", Utc::now());
+
+    let report = local.merge(incoming, MergePolicy::Overwrite);
+
+    assert_eq!(report, MergeReport::default());
+    assert!(local.is_empty());
+    assert!(local.trashed().is_empty());
+}
+
+/// Builds credentials from `(name, secret)` pairs.
+fn credentials_of(entries: &[(&str, &str)]) -> Credentials {
+    let mut creds = Credentials::new();
+    for (name, secret) in entries {
+        creds.add(name.to_string(), secret.to_string()).unwrap();
+    }
+    creds
+}
+
+fn conflict(name: &str, local: Option<&str>, remote: Option<&str>) -> Conflict {
+    Conflict {
+        name: name.to_string(),
+        local: local.map(str::to_string),
+        remote: remote.map(str::to_string),
+    }
+}
+
+#[test]
+fn test_credentials_merge3_resolves_one_sided_changes() {
+    let base = credentials_of(&[
+        ("bank", "b1"),
+        ("email", "e1"),
+        ("github", "g1"),
+        ("wifi", "w1"),
+    ]);
+    // Local edits bank, deletes wifi and adds jira
+    let local = credentials_of(&[
+        ("bank", "b2"),
+        ("email", "e1"),
+        ("github", "g1"),
+        ("jira", "j1"),
+    ]);
+    // Remote edits email, deletes github, adds vpn and makes the same edit to bank
+    let remote = credentials_of(&[
+        ("bank", "b2"),
+        ("email", "e2"),
+        ("vpn", "v1"),
+        ("wifi", "w1"),
+    ]);
+
+    let (merged, conflicts) = Credentials::merge3(&base, &local, &remote);
+
+    assert!(conflicts.is_empty());
+    let mut entries: Vec<(&str, &str)> = merged
+        .iter()
+        .map(|(name, secret)| (name.as_str(), secret.as_str()))
+        .collect();
+    entries.sort();
+    assert_eq!(
+        entries,
+        [
+            ("bank", "b2"),
+            ("email", "e2"),
+            ("jira", "j1"),
+            ("vpn", "v1")
+        ]
+    );
+}
+
+#[test]
+fn test_credentials_merge3_add_add() {
+    let base = Credentials::new();
+    let local = credentials_of(&[("github", "local"), ("email", "same")]);
+    let remote = credentials_of(&[("github", "remote"), ("email", "same")]);
+
+    let (merged, conflicts) = Credentials::merge3(&base, &local, &remote);
+
+    // The same credential added on both sides is not a conflict
+    assert_eq!(
+        conflicts,
+        [conflict("github", Some("local"), Some("remote"))]
+    );
+    assert_eq!(merged.get("github"), Some(&"local".to_string()));
+    assert_eq!(merged.get("email"), Some(&"same".to_string()));
+}
+
+#[test]
+fn test_credentials_merge3_edit_edit() {
+    let base = credentials_of(&[("github", "old")]);
+    let local = credentials_of(&[("github", "local-secret")]);
+    let remote = credentials_of(&[("github", "remote-secret")]);
+
+    let (merged, conflicts) = Credentials::merge3(&base, &local, &remote);
+
+    assert_eq!(
+        conflicts,
+        [conflict(
+            "github",
+            Some("local-secret"),
+            Some("remote-secret")
+        )]
+    );
+    assert_eq!(merged.get("github"), Some(&"local-secret".to_string()));
+    // Secrets are masked when a conflict is printed
+    let debug = format!("{:?}", conflicts[0]);
+    assert!(!debug.contains("secret"));
+}
+
+#[test]
+fn test_credentials_merge3_edit_delete() {
+    let base = credentials_of(&[("email", "old"), ("github", "old")]);
+    // email is edited locally and deleted remotely; github the other way around
+    let local = credentials_of(&[("email", "new")]);
+    let remote = credentials_of(&[("github", "new")]);
+
+    let (merged, conflicts) = Credentials::merge3(&base, &local, &remote);
+
+    assert_eq!(
+        conflicts,
+        [
+            conflict("email", Some("new"), None),
+            conflict("github", None, Some("new")),
+        ]
+    );
+    // The edited secret is kept whichever side deleted it
+    assert_eq!(merged.get("email"), Some(&"new".to_string()));
+    assert_eq!(merged.get("github"), Some(&"new".to_string()));
+}
+
+#[test]
+fn test_credentials_merge3_keeps_metadata_and_trash() {
+    let rotated = Utc.with_ymd_and_hms(2026, 6, 1, 12, 0, 0).unwrap();
+    let base = credentials_of(&[("email", "old"), ("github", "secret")]);
+    let local = credentials_of(&[("email", "old"), ("github", "secret")]);
+    let mut remote = credentials_of(&[("email", "new"), ("github", "secret")]);
+    remote.touch("email", rotated);
+    remote.move_to_trash("github", rotated);
+
+    let (merged, conflicts) = Credentials::merge3(&base, &local, &remote);
+
+    assert!(conflicts.is_empty());
+    assert_eq!(merged.metadata("email").unwrap().updated_at, Some(rotated));
+    assert!(!merged.contains_key("github"));
+    let trashed: Vec<&String> = merged.trashed().into_iter().map(|(name, _)| name).collect();
+    assert_eq!(trashed, ["github"]);
+}
+
+#[test]
+fn test_suspend_handler_installs() {
+    let signal = SuspendSignal::install().expect("Failed to install suspend handler");
+    assert!(!signal.take());
+    drop(signal);
+
+    // Installing again after the previous handler was removed also works
+    assert!(SuspendSignal::install().is_ok());
+}
+
 // ============================================================================
 // Command Registry Tests
 // ============================================================================
@@ -435,3 +2195,96 @@ fn test_registry_completions() {
     let completions = registry.completions("");
     assert!(completions.len() >= 6); // At least the main commands
 }
+
+#[test]
+fn test_arg_count_checked_for_every_command() {
+    let registry = create_registry();
+
+    for cmd in registry.commands() {
+        let too_many: Vec<&str> = match cmd.max_args() {
+            Some(max) => vec!["x"; max + 1],
+            None => continue,
+        };
+        match check_arg_count(cmd.as_ref(), &too_many) {
+            Err(CommandResult::Error(msg)) => {
+                assert!(msg.contains("Too many arguments"), "{}", cmd.name());
+                assert!(msg.contains(cmd.usage()), "{}", cmd.name());
+            }
+            _ => panic!("'{}' accepted too many arguments", cmd.name()),
+        }
+    }
+
+    for cmd in registry.commands().filter(|cmd| cmd.min_args() > 0) {
+        let too_few = vec!["x"; cmd.min_args() - 1];
+        match check_arg_count(cmd.as_ref(), &too_few) {
+            Err(CommandResult::Error(msg)) => {
+                assert!(msg.contains("Missing required arguments"), "{}", cmd.name());
+            }
+            _ => panic!("'{}' accepted too few arguments", cmd.name()),
+        }
+    }
+
+    for cmd in registry.commands() {
+        let enough = vec!["x"; cmd.min_args()];
+        assert!(
+            check_arg_count(cmd.as_ref(), &enough).is_ok(),
+            "{}",
+            cmd.name()
+        );
+    }
+}
+
+#[test]
+fn test_dispatcher_rejects_bad_arg_counts() {
+    let shell = Shell::new();
+    let mut credentials = Credentials::new();
+    let mut session = SaveFnSession::new(|_: &Credentials| Ok(()));
+
+    for args in [
+        &["add", "only_name"][..],
+        &["remove"][..],
+        &["rename", "old"][..],
+        &["stats", "extra"][..],
+        &["quit", "now"][..],
+        &["remove", "a", "b"][..],
+    ] {
+        let result = shell.run_once(args, &mut credentials, &mut session);
+        match result {
+            CommandResult::Error(msg) => assert!(msg.starts_with("Usage: "), "{:?}", args),
+            _ => panic!("{:?} should be rejected", args),
+        }
+    }
+    assert!(credentials.is_empty());
+}
+
+#[test]
+fn test_shell_history_filtering() {
+    use passmgr::shell::history::{
+        HistoryConfig, HistoryFilter, add_entry, disable_editor_filtering,
+    };
+    use rustyline::history::History;
+
+    let temp_dir = TempDir::new().unwrap();
+    let config = HistoryConfig::default_in_dir(temp_dir.path());
+    let mut editor = rustyline::DefaultEditor::new().unwrap();
+    disable_editor_filtering(&mut editor).unwrap();
+
+    let mut filter = HistoryFilter::for_history(config.clone(), editor.history());
+    for line in ["list", "list", " get github", "get email  ", "list"] {
+        add_entry(editor.history_mut(), &mut filter, line);
+    }
+    let entries: Vec<&String> = editor.history().iter().collect();
+    assert_eq!(entries, ["list", "get email", "list"]);
+
+    // With both settings off, everything but empty lines is kept
+    let config = config.with_ignore_dups(false).with_ignore_space(false);
+    let mut editor = rustyline::DefaultEditor::new().unwrap();
+    disable_editor_filtering(&mut editor).unwrap();
+    let mut filter = HistoryFilter::for_history(config, editor.history());
+    for line in ["list", "list", " get github", "   "] {
+        add_entry(editor.history_mut(), &mut filter, line);
+    }
+    assert_eq!(editor.history().len(), 3);
+    let entries: Vec<&String> = editor.history().iter().collect();
+    assert_eq!(entries, ["list", "list", "get github"]);
+}